@@ -6,6 +6,8 @@ Douyin Link Parser - Simple HTTP API Server
 
 import re
 import json
+import os
+from pathlib import Path
 from http.server import HTTPServer, BaseHTTPRequestHandler
 import urllib.parse
 import requests
@@ -21,8 +23,49 @@ HEADERS = {
 }
 
 
-def parse_douyin_link(share_text: str) -> dict:
+def request_options(client_headers) -> dict:
+    """根据客户端转发的请求头构造 requests 参数（登录 Cookie、代理、CA 证书）"""
+    headers = dict(HEADERS)
+    options = {"headers": headers}
+
+    cookie = client_headers.get("X-Douyin-Cookie", "")
+    if cookie:
+        headers["Cookie"] = cookie
+
+    proxy = client_headers.get("X-Proxy-Url", "")
+    if proxy:
+        options["proxies"] = {"http": proxy, "https": proxy}
+
+    ca_bundle = client_headers.get("X-CA-Bundle", "")
+    if ca_bundle:
+        options["verify"] = ca_bundle
+
+    return options
+
+
+# 登录 Cookie 只发送给这些域名（及其子域名）
+COOKIE_DOMAINS = ("douyin.com", "iesdouyin.com")
+
+
+def cookie_allowed(url: str) -> bool:
+    """判断目标地址是否可以携带登录 Cookie（视频 / 图片 CDN 等其他主机不携带）"""
+    host = (urllib.parse.urlparse(url).hostname or "").lower()
+    return any(host == d or host.endswith("." + d) for d in COOKIE_DOMAINS)
+
+
+def options_for(options: dict, url: str) -> dict:
+    """按目标地址返回 requests 参数，非抖音域名时去掉 Cookie 请求头"""
+    headers = options.get("headers", {})
+    if "Cookie" not in headers or cookie_allowed(url):
+        return options
+    scoped = dict(options)
+    scoped["headers"] = {k: v for k, v in headers.items() if k != "Cookie"}
+    return scoped
+
+
+def parse_douyin_link(share_text: str, options: dict = None) -> dict:
     """解析抖音分享链接，返回视频信息"""
+    options = options or {"headers": HEADERS}
     # 1. 提取 URL
     urls = re.findall(r'http[s]?://[^\s]+', share_text)
     if not urls:
@@ -31,12 +74,12 @@ def parse_douyin_link(share_text: str) -> dict:
     share_url = urls[0]
     
     # 2. 跟随重定向获取视频 ID
-    resp = requests.get(share_url, headers=HEADERS, timeout=10)
+    resp = requests.get(share_url, timeout=10, **options_for(options, share_url))
     video_id = resp.url.split("?")[0].strip("/").split("/")[-1]
     
     # 3. 访问分享页面
     share_page_url = f"https://www.iesdouyin.com/share/video/{video_id}"
-    response = requests.get(share_page_url, headers=HEADERS, timeout=15)
+    response = requests.get(share_page_url, timeout=15, **options_for(options, share_page_url))
     response.raise_for_status()
 
     # 4. 解析 _ROUTER_DATA
@@ -58,8 +101,17 @@ def parse_douyin_link(share_text: str) -> dict:
     else:
         raise ValueError("无法解析视频信息：找不到 loaderData")
 
-    # 6. 提取字段
-    video_url = data["video"]["play_addr"]["url_list"][0].replace("playwm", "play")
+    # 6. 提取字段（图文作品没有播放地址）
+    play_urls = (data.get("video") or {}).get("play_addr", {}).get("url_list") or [""]
+    video_url = play_urls[0].replace("playwm", "play")
+    images = []
+    for image in data.get("images") or []:
+        url_list = image.get("url_list") or []
+        # 优先使用无水印地址
+        clean = [url for url in url_list if "water" not in url]
+        if clean or url_list:
+            images.append((clean or url_list)[0])
+    music_urls = ((data.get("music") or {}).get("play_url") or {}).get("url_list") or [""]
     title = data.get("desc", "").strip() or f"douyin_{video_id}"
     title = re.sub(r'[\\/:*?"<>|]', '_', title)
     
@@ -75,9 +127,153 @@ def parse_douyin_link(share_text: str) -> dict:
         "comments": statistics.get("comment_count", 0),
         "shares": statistics.get("share_count", 0),
         "cover": data.get("video", {}).get("cover", {}).get("url_list", [""])[0],
+        # 图文作品的图片地址和背景音乐
+        "images": images,
+        "music_url": music_urls[0],
+        # 原始作品数据，供客户端提取话题、音乐、码率档位等完整信息
+        "raw": data,
     }
 
 
+# -------------------- 列表接口签名 --------------------
+# 搜索 / 用户作品等列表接口校验 a_bogus 签名，由页面内的安全 SDK 在发出 XHR 时自动附加，
+# 因此在无头浏览器中打开抖音首页，再从页面内发起请求
+
+BASE_DIR = Path(__file__).parent.resolve()
+DOUYIN_WEB = "https://www.douyin.com"
+# 允许代为签名请求的接口前缀
+SIGNED_API_PREFIX = "/aweme/v1/web/"
+DESKTOP_UA = (
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 "
+    "(KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"
+)
+
+# 在页面内用 XHR 请求接口（页面 SDK 会拦截 XHR 并附加签名参数）
+XHR_SCRIPT = """
+(url) => new Promise((resolve) => {
+    const xhr = new XMLHttpRequest();
+    xhr.open("GET", url, true);
+    xhr.withCredentials = true;
+    xhr.setRequestHeader("Accept", "application/json");
+    xhr.onload = () => resolve({ status: xhr.status, body: xhr.responseText });
+    xhr.onerror = () => resolve({ status: 0, body: "" });
+    xhr.ontimeout = () => resolve({ status: 0, body: "" });
+    xhr.timeout = 30000;
+    xhr.send();
+})
+"""
+
+
+def parse_cookie_header(cookie: str) -> list:
+    """将 Cookie 请求头拆成 Playwright 的 Cookie 列表（作用于 .douyin.com）"""
+    cookies = []
+    for pair in cookie.split(";"):
+        name, sep, value = pair.strip().partition("=")
+        if sep and name:
+            cookies.append({"name": name, "value": value, "domain": ".douyin.com", "path": "/"})
+    return cookies
+
+
+class SignedFetcher:
+    """持有一个打开抖音首页的无头浏览器，代为发起需要签名的列表请求
+
+    HTTPServer 单线程处理请求，同步版 Playwright 只在该线程中使用
+    """
+
+    def __init__(self):
+        self.playwright = None
+        self.browser = None
+        self.proxy = ""
+        self.context = None
+        self.cookie = None
+        self.page = None
+
+    def launch(self, proxy: str):
+        """启动浏览器；未安装 Playwright 自带的 Chromium 时依次尝试系统 Edge / Chrome"""
+        if self.browser is not None and self.proxy == proxy:
+            return
+        self.close()
+        from playwright.sync_api import sync_playwright
+
+        self.playwright = sync_playwright().start()
+        options = {"headless": True}
+        if proxy:
+            options["proxy"] = {"server": proxy}
+        last_error = None
+        for channel in (None, "msedge", "chrome"):
+            try:
+                if channel:
+                    self.browser = self.playwright.chromium.launch(channel=channel, **options)
+                else:
+                    self.browser = self.playwright.chromium.launch(**options)
+                break
+            except Exception as e:
+                last_error = e
+        if self.browser is None:
+            self.close()
+            raise RuntimeError(f"无法启动浏览器: {last_error}")
+        self.proxy = proxy
+
+    def open_page(self, cookie: str):
+        """按 Cookie 创建浏览器上下文并打开抖音首页（Cookie 变化时重建）"""
+        if self.page is not None and self.cookie == cookie:
+            return
+        if self.context is not None:
+            self.context.close()
+            self.context = None
+            self.page = None
+
+        self.context = self.browser.new_context(user_agent=DESKTOP_UA, locale="zh-CN")
+        self.context.add_init_script(path=str(BASE_DIR / "stealth.min.js"))
+        if cookie:
+            self.context.add_cookies(parse_cookie_header(cookie))
+        self.page = self.context.new_page()
+        self.page.goto(DOUYIN_WEB + "/", wait_until="domcontentloaded", timeout=30000)
+        try:
+            # 等待安全 SDK 加载完成，超时不影响后续请求
+            self.page.wait_for_load_state("networkidle", timeout=10000)
+        except Exception:
+            pass
+        self.cookie = cookie
+
+    def fetch(self, path_and_query: str, client_headers) -> dict:
+        """在页面内请求列表接口，返回 {status, body}；失败时重建页面重试一次"""
+        proxy = client_headers.get("X-Proxy-Url", "")
+        cookie = client_headers.get("X-Douyin-Cookie", "")
+        for attempt in range(2):
+            try:
+                self.launch(proxy)
+                self.open_page(cookie)
+                return self.page.evaluate(XHR_SCRIPT, DOUYIN_WEB + path_and_query)
+            except Exception as e:
+                print(f"[Signed] 请求失败（第 {attempt + 1} 次）: {e}")
+                if attempt == 1:
+                    raise
+                self.page = None
+                self.cookie = None
+
+    def close(self):
+        """关闭浏览器并释放 Playwright"""
+        for close in (
+            self.context and self.context.close,
+            self.browser and self.browser.close,
+            self.playwright and self.playwright.stop,
+        ):
+            try:
+                if close:
+                    close()
+            except Exception:
+                pass
+        self.playwright = None
+        self.browser = None
+        self.context = None
+        self.page = None
+        self.cookie = None
+
+
+signed_fetcher = SignedFetcher()
+
+
 class DouyinAPIHandler(BaseHTTPRequestHandler):
     """HTTP 请求处理器"""
     
@@ -99,12 +295,40 @@ class DouyinAPIHandler(BaseHTTPRequestHandler):
                 return
             
             try:
-                result = parse_douyin_link(link)
+                result = parse_douyin_link(link, request_options(self.headers))
                 self.send_json({"status": "success", "data": result})
             except Exception as e:
                 self.send_json({"status": "error", "error": str(e)}, 500)
+
+        elif parsed.path.startswith("/douyin_web" + SIGNED_API_PREFIX):
+            # 代为签名并请求抖音列表接口，原样返回上游状态码与响应体
+            self.handle_signed_api(parsed)
         else:
             self.send_json({"status": "error", "error": "未知路径"}, 404)
+
+    def handle_signed_api(self, parsed):
+        path_and_query = parsed.path[len("/douyin_web"):]
+        if parsed.query:
+            path_and_query += "?" + parsed.query
+
+        try:
+            result = signed_fetcher.fetch(path_and_query, self.headers)
+        except Exception as e:
+            # Sidecar 自身出错（浏览器不可用等），与上游响应区分开
+            self.send_json({"status": "error", "error": str(e)}, 502, {"X-Sidecar-Error": "1"})
+            return
+
+        status = result.get("status") or 0
+        if status == 0:
+            self.send_json({"status": "error", "error": "页面内请求失败"}, 502, {"X-Sidecar-Error": "1"})
+            return
+
+        body = (result.get("body") or "").encode("utf-8")
+        self.send_response(status)
+        self.send_header("Content-Type", "application/json; charset=utf-8")
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
     
     def do_POST(self):
         """处理 POST 请求"""
@@ -130,7 +354,7 @@ class DouyinAPIHandler(BaseHTTPRequestHandler):
             return
         
         try:
-            result = parse_douyin_link(link)
+            result = parse_douyin_link(link, request_options(self.headers))
             self.send_json({"status": "success", "data": result})
         except Exception as e:
             self.send_json({"status": "error", "error": str(e)}, 500)
@@ -154,7 +378,8 @@ class DouyinAPIHandler(BaseHTTPRequestHandler):
         try:
             # 使用流式下载
             print(f"[Download] Downloading to {save_path}")
-            response = requests.get(url, headers=HEADERS, stream=True, timeout=60)
+            options = options_for(request_options(self.headers), url)
+            response = requests.get(url, stream=True, timeout=60, **options)
             response.raise_for_status()
             
             with open(save_path, 'wb') as f:
@@ -167,11 +392,13 @@ class DouyinAPIHandler(BaseHTTPRequestHandler):
             print(f"[Download] Error: {e}")
             self.send_json({"status": "error", "error": str(e)}, 500)
 
-    def send_json(self, data, status=200):
+    def send_json(self, data, status=200, extra_headers=None):
         """发送 JSON 响应"""
         self.send_response(status)
         self.send_header("Content-Type", "application/json; charset=utf-8")
         self.send_header("Access-Control-Allow-Origin", "*")
+        for name, value in (extra_headers or {}).items():
+            self.send_header(name, value)
         self.end_headers()
         self.wfile.write(json.dumps(data, ensure_ascii=False).encode("utf-8"))
     
@@ -180,8 +407,6 @@ class DouyinAPIHandler(BaseHTTPRequestHandler):
         print(f"[API] {args[0]}")
 
 
-import os
-
 # 配置
 SIDECAR_PORT = int(os.environ.get("SIDECAR_PORT", "38080"))
 SIDECAR_HOST = os.environ.get("SIDECAR_HOST", "127.0.0.1")
@@ -192,8 +417,12 @@ def main():
     server = HTTPServer((SIDECAR_HOST, SIDECAR_PORT), DouyinAPIHandler)
     print(f"[Douyin API] 服务器启动在 http://{SIDECAR_HOST}:{SIDECAR_PORT}")
     print(f"[Douyin API] 解析接口: GET /parse?link=<抖音链接>")
+    print(f"[Douyin API] 列表接口签名代理: GET /douyin_web{SIGNED_API_PREFIX}...")
     print(f"[Douyin API] 健康检查: GET /health")
-    server.serve_forever()
+    try:
+        server.serve_forever()
+    finally:
+        signed_fetcher.close()
 
 
 if __name__ == "__main__":
@@ -2,5 +2,6 @@
 
 pub mod knowledge_base;
 pub mod onnx_embedder;
+pub mod onnx_ocr;
 pub mod service;
 pub mod vector_db;
@@ -0,0 +1,498 @@
+//! PP-OCRv4 文字识别模型
+//! 检测模型 (DB) 定位文字区域，识别模型 (CRNN + CTC) 逐行识别文字
+
+use crate::core::asr_engine::ModelInfo;
+use ort::session::{builder::GraphOptimizationLevel, Session};
+use ort::value::Tensor;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+
+/// 检测模型文件名
+pub const DET_MODEL_FILE: &str = "ch_PP-OCRv4_det_infer.onnx";
+/// 识别模型文件名
+pub const REC_MODEL_FILE: &str = "ch_PP-OCRv4_rec_infer.onnx";
+/// 字符字典文件名
+pub const KEYS_FILE: &str = "ppocr_keys_v1.txt";
+
+/// 检测输入最长边
+pub const DET_MAX_SIDE: u32 = 960;
+/// 识别输入高度
+const REC_HEIGHT: usize = 48;
+/// 识别输入最大宽度
+const REC_MAX_WIDTH: usize = 960;
+/// 概率图二值化阈值
+const DET_BINARY_THRESHOLD: f32 = 0.3;
+/// 文字框平均得分阈值
+const DET_BOX_THRESHOLD: f32 = 0.6;
+/// 文字框外扩比例
+const DET_UNCLIP_RATIO: f32 = 1.5;
+/// 最小文字框边长（像素）
+const DET_MIN_SIZE: usize = 3;
+
+/// 检测模型归一化参数
+const DET_MEAN: [f32; 3] = [0.485, 0.456, 0.406];
+const DET_STD: [f32; 3] = [0.229, 0.224, 0.225];
+
+/// 获取 PP-OCRv4 文字识别模型信息
+pub fn model_info() -> ModelInfo {
+    ModelInfo {
+        name: "PP-OCRv4".to_string(),
+        description: "百度飞桨，中英文文字检测与识别，用于提取画面字幕和标题".to_string(),
+        size_mb: 16,
+        languages: vec!["中文".into(), "英语".into()],
+        download_url: "https://hf-mirror.com/SWHL/RapidOCR/resolve/main/PP-OCRv4".to_string(),
+        model_dir: "pp-ocr".to_string(),
+        is_installed: false,
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OcrError {
+    #[error("模型加载失败: {0}")]
+    ModelLoad(String),
+    #[error("字典加载失败: {0}")]
+    KeysLoad(String),
+    #[error("推理失败: {0}")]
+    Inference(String),
+    #[error("图像数据无效: {0}")]
+    InvalidImage(String),
+}
+
+/// BGR 图像帧
+#[derive(Debug, Clone)]
+pub struct BgrFrame {
+    pub width: usize,
+    pub height: usize,
+    /// 按行排列的 BGR24 像素
+    pub data: Vec<u8>,
+}
+
+/// 文字区域（轴对齐矩形，像素坐标）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TextBox {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    pub score: f32,
+}
+
+/// 单行识别结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrLine {
+    pub text: String,
+    pub confidence: f32,
+    pub text_box: TextBox,
+}
+
+pub struct OnnxOcr {
+    det: Session,
+    rec: Session,
+    /// 识别类别，下标 0 为 CTC blank
+    charset: Vec<String>,
+}
+
+impl OnnxOcr {
+    /// 从模型目录加载检测模型、识别模型和字典
+    pub fn new(model_dir: &Path) -> Result<Self, OcrError> {
+        let det = Self::load_session(&model_dir.join(DET_MODEL_FILE))?;
+        let rec = Self::load_session(&model_dir.join(REC_MODEL_FILE))?;
+
+        let keys = std::fs::read_to_string(model_dir.join(KEYS_FILE))
+            .map_err(|e| OcrError::KeysLoad(e.to_string()))?;
+        let charset = build_charset(&keys);
+
+        Ok(Self { det, rec, charset })
+    }
+
+    /// 检查模型文件是否齐全
+    pub fn is_installed(model_dir: &Path) -> bool {
+        [DET_MODEL_FILE, REC_MODEL_FILE, KEYS_FILE]
+            .iter()
+            .all(|f| model_dir.join(f).exists())
+    }
+
+    fn load_session(path: &Path) -> Result<Session, OcrError> {
+        Session::builder()
+            .map_err(|e| OcrError::ModelLoad(e.to_string()))?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(|e| OcrError::ModelLoad(e.to_string()))?
+            .commit_from_file(path)
+            .map_err(|e| OcrError::ModelLoad(format!("{}: {}", path.display(), e)))
+    }
+
+    /// 识别一帧中的所有文字行（从上到下）
+    ///
+    /// 帧尺寸需为 32 的倍数，可用 [`detection_size`] 计算
+    pub fn recognize(&mut self, frame: &BgrFrame) -> Result<Vec<OcrLine>, OcrError> {
+        if frame.data.len() < frame.width * frame.height * 3 {
+            return Err(OcrError::InvalidImage(format!(
+                "数据长度不足: {}x{}",
+                frame.width, frame.height
+            )));
+        }
+
+        let boxes = self.detect(frame)?;
+        let mut lines = Vec::with_capacity(boxes.len());
+        for text_box in boxes {
+            let (text, confidence) = self.recognize_box(frame, &text_box)?;
+            if !text.trim().is_empty() {
+                lines.push(OcrLine {
+                    text,
+                    confidence,
+                    text_box,
+                });
+            }
+        }
+        Ok(lines)
+    }
+
+    /// 运行检测模型，返回文字框
+    fn detect(&mut self, frame: &BgrFrame) -> Result<Vec<TextBox>, OcrError> {
+        let (w, h) = (frame.width, frame.height);
+        let plane = w * h;
+        let mut input = vec![0.0f32; plane * 3];
+        for (i, pixel) in frame.data.chunks_exact(3).take(plane).enumerate() {
+            for c in 0..3 {
+                input[c * plane + i] = (pixel[c] as f32 / 255.0 - DET_MEAN[c]) / DET_STD[c];
+            }
+        }
+
+        let tensor = Tensor::from_array((vec![1usize, 3, h, w], input.into_boxed_slice()))
+            .map_err(|e| OcrError::Inference(e.to_string()))?;
+
+        let (prob_map, map_w, map_h) = {
+            let outputs = self
+                .det
+                .run(ort::inputs![tensor])
+                .map_err(|e| OcrError::Inference(format!("检测模型推理失败: {}", e)))?;
+            let (shape, data) = outputs[0]
+                .try_extract_tensor::<f32>()
+                .map_err(|e| OcrError::Inference(e.to_string()))?;
+            let dims: Vec<usize> = shape.iter().map(|&d| d as usize).collect();
+            if dims.len() < 2 {
+                return Err(OcrError::Inference(format!("检测输出维度异常: {:?}", dims)));
+            }
+            (data.to_vec(), dims[dims.len() - 1], dims[dims.len() - 2])
+        };
+
+        Ok(find_text_boxes(&prob_map, map_w, map_h))
+    }
+
+    /// 裁剪文字框并运行识别模型
+    fn recognize_box(
+        &mut self,
+        frame: &BgrFrame,
+        text_box: &TextBox,
+    ) -> Result<(String, f32), OcrError> {
+        let crop = match crop_frame(frame, text_box) {
+            Some(crop) => crop,
+            None => return Ok((String::new(), 0.0)),
+        };
+        let target_w = ((crop.width as f32 * REC_HEIGHT as f32 / crop.height.max(1) as f32).round()
+            as usize)
+            .clamp(REC_HEIGHT / 3, REC_MAX_WIDTH);
+        let resized = resize_bilinear(&crop, target_w, REC_HEIGHT);
+
+        let plane = target_w * REC_HEIGHT;
+        let mut input = vec![0.0f32; plane * 3];
+        for (i, pixel) in resized.data.chunks_exact(3).enumerate() {
+            for c in 0..3 {
+                input[c * plane + i] = (pixel[c] as f32 / 255.0 - 0.5) / 0.5;
+            }
+        }
+
+        let tensor = Tensor::from_array((
+            vec![1usize, 3, REC_HEIGHT, target_w],
+            input.into_boxed_slice(),
+        ))
+        .map_err(|e| OcrError::Inference(e.to_string()))?;
+
+        let outputs = self
+            .rec
+            .run(ort::inputs![tensor])
+            .map_err(|e| OcrError::Inference(format!("识别模型推理失败: {}", e)))?;
+        let (shape, data) = outputs[0]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| OcrError::Inference(e.to_string()))?;
+
+        // 输出形状 [1, T, C]
+        let classes = shape.last().copied().unwrap_or(0) as usize;
+        Ok(ctc_greedy_decode(data, classes, &self.charset))
+    }
+}
+
+/// 构造识别类别表：blank + 字典 + 空格
+pub fn build_charset(keys: &str) -> Vec<String> {
+    let mut charset = vec![String::new()];
+    charset.extend(
+        keys.lines()
+            .map(|l| l.trim_end_matches('\r').to_string())
+            .filter(|l| !l.is_empty()),
+    );
+    charset.push(" ".to_string());
+    charset
+}
+
+/// 计算检测输入尺寸：最长边不超过 max_side，宽高均为 32 的倍数
+pub fn detection_size(width: u32, height: u32, max_side: u32) -> (u32, u32) {
+    let longest = width.max(height).max(1);
+    let scale = if longest > max_side {
+        max_side as f32 / longest as f32
+    } else {
+        1.0
+    };
+    let round32 = |v: u32| ((((v as f32 * scale) / 32.0).round() as u32) * 32).max(32);
+    (round32(width), round32(height))
+}
+
+/// DB 后处理：二值化概率图、连通域分析、按得分过滤并外扩
+pub fn find_text_boxes(prob_map: &[f32], width: usize, height: usize) -> Vec<TextBox> {
+    let mut visited = vec![false; width * height];
+    let mut boxes = Vec::new();
+    let mut queue = VecDeque::new();
+
+    for start in 0..(width * height).min(prob_map.len()) {
+        if visited[start] || prob_map[start] <= DET_BINARY_THRESHOLD {
+            continue;
+        }
+
+        // 4 邻域广度优先遍历
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (width, height, 0, 0);
+        let (mut count, mut score_sum) = (0usize, 0.0f32);
+        visited[start] = true;
+        queue.push_back(start);
+        while let Some(idx) = queue.pop_front() {
+            let (x, y) = (idx % width, idx / width);
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_y = min_y.min(y);
+            max_y = max_y.max(y);
+            count += 1;
+            score_sum += prob_map[idx];
+
+            let neighbors = [
+                (x > 0).then(|| idx - 1),
+                (x + 1 < width).then(|| idx + 1),
+                (y > 0).then(|| idx - width),
+                (y + 1 < height).then(|| idx + width),
+            ];
+            for n in neighbors.into_iter().flatten() {
+                if !visited[n] && prob_map[n] > DET_BINARY_THRESHOLD {
+                    visited[n] = true;
+                    queue.push_back(n);
+                }
+            }
+        }
+
+        let box_w = max_x - min_x + 1;
+        let box_h = max_y - min_y + 1;
+        let score = score_sum / count as f32;
+        if box_w.min(box_h) < DET_MIN_SIZE || score < DET_BOX_THRESHOLD {
+            continue;
+        }
+
+        // 外扩：偏移量 = 面积 * ratio / 周长
+        let offset = ((box_w * box_h) as f32 * DET_UNCLIP_RATIO / (2 * (box_w + box_h)) as f32)
+            .round() as usize;
+        let x0 = min_x.saturating_sub(offset);
+        let y0 = min_y.saturating_sub(offset);
+        let x1 = (max_x + offset + 1).min(width);
+        let y1 = (max_y + offset + 1).min(height);
+
+        boxes.push(TextBox {
+            x: x0,
+            y: y0,
+            width: x1 - x0,
+            height: y1 - y0,
+            score,
+        });
+    }
+
+    boxes.sort_by_key(|b| (b.y, b.x));
+    boxes
+}
+
+/// CTC 贪心解码，返回文本和平均置信度
+pub fn ctc_greedy_decode(logits: &[f32], classes: usize, charset: &[String]) -> (String, f32) {
+    if classes == 0 {
+        return (String::new(), 0.0);
+    }
+
+    let mut text = String::new();
+    let mut scores = Vec::new();
+    let mut previous = 0usize;
+    for step in logits.chunks_exact(classes) {
+        let (index, prob) = step
+            .iter()
+            .copied()
+            .enumerate()
+            .fold(
+                (0, f32::MIN),
+                |best, (i, p)| if p > best.1 { (i, p) } else { best },
+            );
+
+        if index != 0 && index != previous {
+            if let Some(ch) = charset.get(index) {
+                text.push_str(ch);
+                scores.push(prob);
+            }
+        }
+        previous = index;
+    }
+
+    let confidence = if scores.is_empty() {
+        0.0
+    } else {
+        scores.iter().sum::<f32>() / scores.len() as f32
+    };
+    (text, confidence)
+}
+
+/// 裁剪文字框区域，文字框会被限制在画面范围内；裁剪后为空时返回 None
+fn crop_frame(frame: &BgrFrame, text_box: &TextBox) -> Option<BgrFrame> {
+    if frame.data.len() < frame.width * frame.height * 3 {
+        return None;
+    }
+
+    let x0 = text_box.x.min(frame.width);
+    let y0 = text_box.y.min(frame.height);
+    let x1 = text_box.x.saturating_add(text_box.width).min(frame.width);
+    let y1 = text_box.y.saturating_add(text_box.height).min(frame.height);
+    if x1 <= x0 || y1 <= y0 {
+        return None;
+    }
+
+    let (width, height) = (x1 - x0, y1 - y0);
+    let mut data = Vec::with_capacity(width * height * 3);
+    for y in y0..y1 {
+        let row = (y * frame.width + x0) * 3;
+        data.extend_from_slice(&frame.data[row..row + width * 3]);
+    }
+    Some(BgrFrame {
+        width,
+        height,
+        data,
+    })
+}
+
+/// 双线性插值缩放
+pub fn resize_bilinear(frame: &BgrFrame, width: usize, height: usize) -> BgrFrame {
+    let mut data = vec![0u8; width * height * 3];
+    let scale_x = frame.width as f32 / width as f32;
+    let scale_y = frame.height as f32 / height as f32;
+
+    for y in 0..height {
+        let sy = ((y as f32 + 0.5) * scale_y - 0.5).clamp(0.0, (frame.height - 1) as f32);
+        let (y0, fy) = (sy.floor() as usize, sy.fract());
+        let y1 = (y0 + 1).min(frame.height - 1);
+        for x in 0..width {
+            let sx = ((x as f32 + 0.5) * scale_x - 0.5).clamp(0.0, (frame.width - 1) as f32);
+            let (x0, fx) = (sx.floor() as usize, sx.fract());
+            let x1 = (x0 + 1).min(frame.width - 1);
+            for c in 0..3 {
+                let p = |px: usize, py: usize| frame.data[(py * frame.width + px) * 3 + c] as f32;
+                let top = p(x0, y0) * (1.0 - fx) + p(x1, y0) * fx;
+                let bottom = p(x0, y1) * (1.0 - fx) + p(x1, y1) * fx;
+                data[(y * width + x) * 3 + c] = (top * (1.0 - fy) + bottom * fy).round() as u8;
+            }
+        }
+    }
+
+    BgrFrame {
+        width,
+        height,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detection_size() {
+        assert_eq!(detection_size(1080, 1920, 960), (544, 960));
+        assert_eq!(detection_size(100, 50, 960), (96, 64));
+        assert_eq!(detection_size(10, 10, 960), (32, 32));
+    }
+
+    #[test]
+    fn test_crop_frame_clamps_to_frame() {
+        let frame = BgrFrame {
+            width: 4,
+            height: 3,
+            data: (0..36).collect(),
+        };
+
+        let inside = TextBox {
+            x: 1,
+            y: 1,
+            width: 2,
+            height: 1,
+            score: 1.0,
+        };
+        let crop = crop_frame(&frame, &inside).unwrap();
+        assert_eq!((crop.width, crop.height), (2, 1));
+        assert_eq!(crop.data, (15..21).collect::<Vec<u8>>());
+
+        // 超出画面的部分被裁掉
+        let overflow = TextBox {
+            x: 3,
+            y: 2,
+            width: 10,
+            height: 10,
+            score: 1.0,
+        };
+        let crop = crop_frame(&frame, &overflow).unwrap();
+        assert_eq!((crop.width, crop.height), (1, 1));
+
+        // 完全在画面外
+        let outside = TextBox {
+            x: 8,
+            y: 0,
+            width: 2,
+            height: 2,
+            score: 1.0,
+        };
+        assert!(crop_frame(&frame, &outside).is_none());
+    }
+
+    #[test]
+    fn test_find_text_boxes() {
+        let (w, h) = (20, 10);
+        let mut map = vec![0.0f32; w * h];
+        for y in 4..7 {
+            for x in 2..12 {
+                map[y * w + x] = 0.9;
+            }
+        }
+        // 噪点：得分低且尺寸过小
+        map[w + 18] = 0.5;
+
+        let boxes = find_text_boxes(&map, w, h);
+        assert_eq!(boxes.len(), 1);
+        let b = boxes[0];
+        assert!(b.x < 2 && b.y < 4);
+        assert!(b.x + b.width > 12 && b.y + b.height > 7);
+    }
+
+    #[test]
+    fn test_ctc_greedy_decode() {
+        let charset = build_charset("你\n好\n");
+        assert_eq!(charset.len(), 4);
+
+        // 时间步: 你 你 blank 好 好 空格
+        let steps = [1usize, 1, 0, 2, 2, 3];
+        let mut logits = Vec::new();
+        for index in steps {
+            let mut step = vec![0.0f32; 4];
+            step[index] = 0.9;
+            logits.extend(step);
+        }
+        let (text, confidence) = ctc_greedy_decode(&logits, 4, &charset);
+        assert_eq!(text, "你好 ");
+        assert!((confidence - 0.9).abs() < 1e-6);
+    }
+}
@@ -1,10 +1,17 @@
 // AI 服务适配器
-// 支持豆包、ChatGPT、LM Studio
+// 统一的 LlmProvider 接口，内置豆包、ChatGPT、DeepSeek、LM Studio，并支持自定义 OpenAI 兼容服务
 
-use reqwest::Client;
+use futures_util::future::{BoxFuture, FutureExt};
+use futures_util::StreamExt;
+use reqwest::{Client, RequestBuilder, Response};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::utils::http_client::{self, HttpService};
+
 #[derive(Error, Debug)]
 pub enum AiError {
     #[error("API 调用失败: {0}")]
@@ -15,6 +22,10 @@ pub enum AiError {
     ServiceUnavailable(String),
     #[error("响应解析失败: {0}")]
     ParseError(String),
+    #[error("配置无效: {0}")]
+    InvalidConfig(String),
+    #[error("已取消")]
+    Cancelled,
     #[error("网络错误: {0}")]
     NetworkError(#[from] reqwest::Error),
 }
@@ -60,102 +71,23 @@ pub struct KnowledgeReference {
     pub snippet: String,
 }
 
-// OpenAI 兼容的请求/响应结构
-#[derive(Debug, Serialize)]
-struct ChatRequest {
-    model: String,
-    messages: Vec<ChatMessage>,
-    temperature: f32,
-}
-
-// ... (Existing code)
-
+/// 对话消息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: String,
     pub content: String,
 }
 
-// ... (Existing structs)
-
-// ... Update all providers to include `chat` method ...
-
-impl DoubaoProvider {
-    // ... existing methods ...
-
-    pub async fn chat(&self, messages: Vec<ChatMessage>) -> Result<String, AiError> {
-        let request = ChatRequest {
-            model: self.model.clone(),
-            messages,
-            temperature: 0.7,
-        };
-        self.send_chat_request(&request).await
-    }
-
-    async fn send_chat_request(&self, request: &ChatRequest) -> Result<String, AiError> {
-        let response = self
-            .client
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .timeout(std::time::Duration::from_secs(60)) // 防止无限等待
-            .json(request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(AiError::ApiCallFailed(format!(
-                "状态码: {}, 响应: {}",
-                status, text
-            )));
-        }
-
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .map_err(|e| AiError::ParseError(e.to_string()))?;
-
-        chat_response
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| AiError::ParseError("空响应".to_string()))
-    }
-}
-
-// ... Repeat for OpenAiProvider, DeepSeekProvider ...
-
-impl AiService {
-    // ... existing methods ...
-
-    pub async fn chat(&self, messages: Vec<ChatMessage>) -> Result<String, AiError> {
-        match self.provider_type {
-            AiProviderType::Doubao => {
-                let api_key = self.doubao_api_key.clone().ok_or(AiError::InvalidApiKey)?;
-                let provider = DoubaoProvider::new(api_key);
-                provider.chat(messages).await
-            }
-            AiProviderType::OpenAi => {
-                let api_key = self.openai_api_key.clone().ok_or(AiError::InvalidApiKey)?;
-                let provider = OpenAiProvider::new(api_key);
-                provider.chat(messages).await
-            }
-            AiProviderType::DeepSeek => {
-                let api_key = self
-                    .deepseek_api_key
-                    .clone()
-                    .ok_or(AiError::InvalidApiKey)?;
-                let provider = DeepSeekProvider::new(api_key);
-                provider.chat(messages).await
-            }
-            AiProviderType::LmStudio => {
-                let provider = LmStudioProvider::new(self.lm_studio_url.clone());
-                provider.chat(messages).await
-            }
-        }
-    }
+// OpenAI 兼容的请求/响应结构
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -199,332 +131,443 @@ const ANALYSIS_PROMPT: &str = r#"你是一个专业的短视频内容分析师
 视频文案：
 "#;
 
-/// 豆包 API 提供者
-pub struct DoubaoProvider {
-    client: Client,
-    api_key: String,
-    model: String,
-    base_url: String,
-}
-
-impl DoubaoProvider {
-    pub fn new(api_key: String) -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(std::time::Duration::from_secs(120))
-                .build()
-                .unwrap_or_default(),
-            api_key,
-            model: "doubao-seed-1-8-251228".to_string(), // 豆包最新 seed 模型
-            base_url: "https://ark.cn-beijing.volces.com/api/v3".to_string(),
+/// 大模型提供者
+///
+/// 只需实现 `chat`，内容分析默认复用对话接口并解析返回的 JSON
+pub trait LlmProvider: Send + Sync {
+    /// 显示名称
+    fn name(&self) -> &str;
+
+    /// 多轮对话，返回助手回复
+    fn chat<'a>(&'a self, messages: Vec<ChatMessage>) -> BoxFuture<'a, Result<String, AiError>>;
+
+    /// 流式对话：每收到一段增量回复调用 `on_delta`，`cancel` 置位后尽快停止，返回完整回复
+    ///
+    /// 默认实现不支持流式，完整回复作为一段增量推送
+    fn chat_stream<'a>(
+        &'a self,
+        messages: Vec<ChatMessage>,
+        on_delta: &'a mut (dyn FnMut(&str) + Send),
+        cancel: &'a AtomicBool,
+    ) -> BoxFuture<'a, Result<String, AiError>> {
+        async move {
+            let reply = self.chat(messages).await?;
+            if cancel.load(Ordering::SeqCst) {
+                return Err(AiError::Cancelled);
+            }
+            on_delta(&reply);
+            Ok(reply)
         }
+        .boxed()
     }
 
-    pub fn with_model(api_key: String, model: String) -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(std::time::Duration::from_secs(120))
-                .build()
-                .unwrap_or_default(),
-            api_key,
-            model,
-            base_url: "https://ark.cn-beijing.volces.com/api/v3".to_string(),
+    /// 分析视频文案结构
+    fn analyze<'a>(
+        &'a self,
+        content: &'a str,
+        context: &'a str,
+    ) -> BoxFuture<'a, Result<AnalysisResult, AiError>> {
+        let messages = analysis_messages(content, context);
+        async move {
+            let reply = self.chat(messages).await?;
+            parse_analysis_result(&reply)
         }
+        .boxed()
     }
 
-    pub fn name(&self) -> &str {
-        "豆包"
+    /// 流式分析：模型的原始回复以增量推送，结束后再解析为分析结果
+    fn analyze_stream<'a>(
+        &'a self,
+        content: &'a str,
+        context: &'a str,
+        on_delta: &'a mut (dyn FnMut(&str) + Send),
+        cancel: &'a AtomicBool,
+    ) -> BoxFuture<'a, Result<AnalysisResult, AiError>> {
+        let messages = analysis_messages(content, context);
+        async move {
+            let reply = self.chat_stream(messages, on_delta, cancel).await?;
+            parse_analysis_result(&reply)
+        }
+        .boxed()
     }
+}
 
-    pub async fn analyze(&self, content: &str, context: &str) -> Result<AnalysisResult, AiError> {
-        let prompt = format!("{}{}\n\n参考知识：\n{}", ANALYSIS_PROMPT, content, context);
+/// 文案分析请求的消息
+fn analysis_messages(content: &str, context: &str) -> Vec<ChatMessage> {
+    vec![ChatMessage {
+        role: "user".to_string(),
+        content: format!("{}{}\n\n参考知识：\n{}", ANALYSIS_PROMPT, content, context),
+    }]
+}
 
-        let request = ChatRequest {
-            model: self.model.clone(),
-            messages: vec![ChatMessage {
-                role: "user".to_string(),
-                content: prompt,
-            }],
-            temperature: 0.7,
-        };
+/// 流式读取时检查取消标记的间隔
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
 
-        let response = self
-            .client
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+/// 等待取消标记被置位
+async fn wait_cancelled(cancel: &AtomicBool) {
+    while !cancel.load(Ordering::SeqCst) {
+        tokio::time::sleep(CANCEL_POLL_INTERVAL).await;
+    }
+}
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(AiError::ApiCallFailed(format!(
-                "状态码: {}, 响应: {}",
-                status, text
-            )));
-        }
+/// 流式响应事件
+#[derive(Debug, PartialEq)]
+enum SseEvent {
+    /// 增量回复
+    Delta(String),
+    /// 回复结束（`data: [DONE]`）
+    Done,
+}
 
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .map_err(|e| AiError::ParseError(e.to_string()))?;
+/// SSE 解码器：缓存不完整的行，逐行解析 `data:` 中的增量回复
+#[derive(Default)]
+struct SseDecoder {
+    buffer: Vec<u8>,
+}
 
-        let content = chat_response
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| AiError::ParseError("空响应".to_string()))?;
+impl SseDecoder {
+    /// 写入一个数据块，返回其中完整行解析出的事件
+    fn push(&mut self, chunk: &[u8]) -> Vec<Result<SseEvent, AiError>> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            // 按整行解码，多字节字符不会被数据块边界截断
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            if let Some(event) = parse_sse_line(String::from_utf8_lossy(&line).trim()) {
+                events.push(event);
+            }
+        }
+        events
+    }
+}
 
-        parse_analysis_result(&content)
+/// 解析一行 SSE 数据，非 `data:` 行与没有内容的增量返回 None
+fn parse_sse_line(line: &str) -> Option<Result<SseEvent, AiError>> {
+    let data = line.strip_prefix("data:")?.trim();
+    if data == "[DONE]" {
+        return Some(Ok(SseEvent::Done));
     }
+
+    let value: serde_json::Value = match serde_json::from_str(data) {
+        Ok(value) => value,
+        Err(e) => return Some(Err(AiError::ParseError(format!("流式响应解析失败: {}", e)))),
+    };
+    if let Some(error) = value.get("error") {
+        let message = error
+            .get("message")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .unwrap_or_else(|| error.to_string());
+        return Some(Err(AiError::ApiCallFailed(message)));
+    }
+
+    value
+        .pointer("/choices/0/delta/content")
+        .and_then(|v| v.as_str())
+        .filter(|delta| !delta.is_empty())
+        .map(|delta| Ok(SseEvent::Delta(delta.to_string())))
 }
 
-/// OpenAI API 提供者
-pub struct OpenAiProvider {
-    client: Client,
-    api_key: String,
-    model: String,
-    base_url: String,
+/// OpenAI 兼容服务配置
+///
+/// 豆包、ChatGPT、DeepSeek、LM Studio 都是预设配置；自定义配置可接入 Qwen、Moonshot、
+/// vLLM、Ollama 或内部网关等任意兼容 `/chat/completions` 的服务
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OpenAiCompatibleConfig {
+    /// 显示名称
+    pub name: String,
+    /// 接口基础地址（包含版本路径，如 `https://api.openai.com/v1`）
+    pub base_url: String,
+    /// API Key，本地服务可留空
+    pub api_key: Option<String>,
+    pub model: String,
+    /// 附加请求头（如网关鉴权、组织 ID）
+    pub headers: BTreeMap<String, String>,
+    pub temperature: f32,
+    /// 最大生成 token 数，未设置时使用服务端默认值
+    pub max_tokens: Option<u32>,
+    /// 请求超时（秒）
+    pub timeout_secs: u64,
 }
 
-impl OpenAiProvider {
-    pub fn new(api_key: String) -> Self {
+impl Default for OpenAiCompatibleConfig {
+    fn default() -> Self {
         Self {
-            client: Client::new(),
-            api_key,
-            model: "gpt-4".to_string(),
-            base_url: "https://api.openai.com/v1".to_string(),
+            name: "自定义".to_string(),
+            base_url: String::new(),
+            api_key: None,
+            model: String::new(),
+            headers: BTreeMap::new(),
+            temperature: 0.7,
+            max_tokens: None,
+            timeout_secs: 120,
         }
     }
+}
 
-    pub fn with_model(api_key: String, model: String) -> Self {
+impl OpenAiCompatibleConfig {
+    /// 豆包（火山方舟）
+    pub fn doubao(api_key: String) -> Self {
         Self {
-            client: Client::new(),
-            api_key,
-            model,
-            base_url: "https://api.openai.com/v1".to_string(),
+            name: "豆包".to_string(),
+            base_url: "https://ark.cn-beijing.volces.com/api/v3".to_string(),
+            api_key: Some(api_key),
+            model: "doubao-seed-1-8-251228".to_string(), // 豆包最新 seed 模型
+            ..Self::default()
         }
     }
 
-    pub fn name(&self) -> &str {
-        "ChatGPT"
+    /// ChatGPT
+    pub fn openai(api_key: String) -> Self {
+        Self {
+            name: "ChatGPT".to_string(),
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: Some(api_key),
+            model: "gpt-4".to_string(),
+            ..Self::default()
+        }
     }
 
-    pub async fn analyze(&self, content: &str, context: &str) -> Result<AnalysisResult, AiError> {
-        let prompt = format!("{}{}\n\n参考知识：\n{}", ANALYSIS_PROMPT, content, context);
-
-        let request = ChatRequest {
-            model: self.model.clone(),
-            messages: vec![ChatMessage {
-                role: "user".to_string(),
-                content: prompt,
-            }],
-            temperature: 0.7,
-        };
-
-        let response = self
-            .client
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            if status.as_u16() == 401 {
-                return Err(AiError::InvalidApiKey);
-            }
-            return Err(AiError::ApiCallFailed(format!(
-                "状态码: {}, 响应: {}",
-                status, text
-            )));
+    /// DeepSeek
+    pub fn deepseek(api_key: String) -> Self {
+        Self {
+            name: "DeepSeek".to_string(),
+            base_url: "https://api.deepseek.com/v1".to_string(),
+            api_key: Some(api_key),
+            model: "deepseek-chat".to_string(),
+            ..Self::default()
         }
-
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .map_err(|e| AiError::ParseError(e.to_string()))?;
-
-        let content = chat_response
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| AiError::ParseError("空响应".to_string()))?;
-
-        parse_analysis_result(&content)
     }
 
-    pub async fn chat(&self, messages: Vec<ChatMessage>) -> Result<String, AiError> {
-        let request = ChatRequest {
-            model: self.model.clone(),
-            messages,
-            temperature: 0.7,
-        };
-
-        let response = self
-            .client
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+    /// LM Studio 本地服务，`url` 为不带 `/v1` 的服务地址
+    pub fn lm_studio(url: &str) -> Self {
+        Self {
+            name: "LM Studio".to_string(),
+            base_url: format!("{}/v1", url.trim_end_matches('/')),
+            // LM Studio 会忽略这个值，使用当前加载的模型
+            model: "default".to_string(),
+            ..Self::default()
+        }
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            if status.as_u16() == 401 {
-                return Err(AiError::InvalidApiKey);
-            }
-            return Err(AiError::ApiCallFailed(format!(
-                "状态码: {}, 响应: {}",
-                status, text
+    /// 检查配置是否完整
+    pub fn validate(&self) -> Result<(), AiError> {
+        if !self.base_url.starts_with("http://") && !self.base_url.starts_with("https://") {
+            return Err(AiError::InvalidConfig(format!(
+                "接口地址需以 http:// 或 https:// 开头: {}",
+                self.base_url
             )));
         }
-
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .map_err(|e| AiError::ParseError(e.to_string()))?;
-
-        chat_response
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| AiError::ParseError("空响应".to_string()))
+        if self.model.trim().is_empty() {
+            return Err(AiError::InvalidConfig("模型名称不能为空".to_string()));
+        }
+        if !(0.0..=2.0).contains(&self.temperature) {
+            return Err(AiError::InvalidConfig(format!(
+                "temperature 需在 0 到 2 之间: {}",
+                self.temperature
+            )));
+        }
+        Ok(())
     }
 }
 
-/// LM Studio 本地模型提供者
-pub struct LmStudioProvider {
+/// OpenAI 兼容接口提供者
+pub struct OpenAiCompatibleProvider {
     client: Client,
-    base_url: String,
+    /// 流式请求使用的客户端：不限制总时长，超时按两次数据之间的间隔计算
+    stream_client: Client,
+    config: OpenAiCompatibleConfig,
 }
 
-impl LmStudioProvider {
-    pub fn new(base_url: String) -> Self {
-        // 创建带超时的 client，本地模型推理可能需要较长时间
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(120)) // 2分钟超时
+impl OpenAiCompatibleProvider {
+    pub fn new(config: OpenAiCompatibleConfig) -> Self {
+        // 本地模型推理可能需要较长时间，超时由配置决定
+        let timeout = Duration::from_secs(config.timeout_secs.max(1));
+        let client = http_client::client_builder(HttpService::Ai)
+            .timeout(timeout)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        let stream_client = http_client::client_builder(HttpService::Ai)
+            .read_timeout(timeout)
             .build()
             .unwrap_or_else(|_| Client::new());
 
-        Self { client, base_url }
+        Self {
+            client,
+            stream_client,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &OpenAiCompatibleConfig {
+        &self.config
     }
 
-    pub fn default_url() -> Self {
-        Self::new("http://localhost:1234".to_string())
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/{}", self.config.base_url.trim_end_matches('/'), path)
     }
 
-    pub fn name(&self) -> &str {
-        "LM Studio"
+    /// 附加鉴权与自定义请求头
+    fn with_headers(&self, mut request: RequestBuilder) -> RequestBuilder {
+        if let Some(api_key) = self.config.api_key.as_deref().filter(|k| !k.is_empty()) {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+        for (name, value) in &self.config.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        request
     }
 
-    /// 检测 LM Studio 是否运行
+    /// 检测服务是否可用（请求模型列表）
     pub async fn is_running(&self) -> bool {
-        match Client::new()
-            .get(format!("{}/v1/models", self.base_url))
-            .timeout(std::time::Duration::from_secs(3))
-            .send()
-            .await
-        {
+        let request = self
+            .with_headers(self.client.get(self.endpoint("models")))
+            .timeout(std::time::Duration::from_secs(3));
+        match request.send().await {
             Ok(response) => response.status().is_success(),
             Err(_) => false,
         }
     }
 
-    pub async fn analyze(&self, content: &str, context: &str) -> Result<AnalysisResult, AiError> {
-        if !self.is_running().await {
-            return Err(AiError::ServiceUnavailable("LM Studio 未运行".to_string()));
-        }
-
-        let prompt = format!("{}{}\n\n参考知识：\n{}", ANALYSIS_PROMPT, content, context);
-
-        // LM Studio 兼容 OpenAI API，model 字段可以留空或使用任意值
-        // LM Studio 会自动使用当前加载的模型
+    /// 发送对话请求并检查状态码
+    async fn post_chat(
+        &self,
+        client: &Client,
+        messages: Vec<ChatMessage>,
+        stream: bool,
+    ) -> Result<Response, AiError> {
         let request = ChatRequest {
-            model: "default".to_string(), // LM Studio 会忽略这个值，使用当前加载的模型
-            messages: vec![ChatMessage {
-                role: "user".to_string(),
-                content: prompt,
-            }],
-            temperature: 0.7,
+            model: self.config.model.clone(),
+            messages,
+            temperature: self.config.temperature,
+            max_tokens: self.config.max_tokens,
+            stream,
         };
 
         let response = self
-            .client
-            .post(format!("{}/v1/chat/completions", self.base_url))
+            .with_headers(client.post(self.endpoint("chat/completions")))
             .header("Content-Type", "application/json")
             .json(&request)
             .send()
-            .await?;
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    AiError::ServiceUnavailable(format!("无法连接 {}: {}", self.config.name, e))
+                } else {
+                    AiError::NetworkError(e)
+                }
+            })?;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
+            if status.as_u16() == 401 {
+                return Err(AiError::InvalidApiKey);
+            }
             return Err(AiError::ApiCallFailed(format!(
                 "状态码: {}, 响应: {}",
                 status, text
             )));
         }
 
+        Ok(response)
+    }
+
+    /// 读取非流式响应中的回复
+    async fn read_reply(response: Response) -> Result<String, AiError> {
         let chat_response: ChatResponse = response
             .json()
             .await
             .map_err(|e| AiError::ParseError(e.to_string()))?;
 
-        let content = chat_response
+        chat_response
             .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| AiError::ParseError("空响应".to_string()))?;
-
-        parse_analysis_result(&content)
+            .into_iter()
+            .next()
+            .map(|c| c.message.content)
+            .ok_or_else(|| AiError::ParseError("空响应".to_string()))
     }
 
-    pub async fn chat(&self, messages: Vec<ChatMessage>) -> Result<String, AiError> {
-        if !self.is_running().await {
-            return Err(AiError::ServiceUnavailable("LM Studio 未运行".to_string()));
-        }
+    async fn send_chat(&self, messages: Vec<ChatMessage>) -> Result<String, AiError> {
+        let response = self.post_chat(&self.client, messages, false).await?;
+        Self::read_reply(response).await
+    }
 
-        let request = ChatRequest {
-            model: "default".to_string(),
-            messages,
-            temperature: 0.7,
+    async fn send_chat_stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        on_delta: &mut (dyn FnMut(&str) + Send),
+        cancel: &AtomicBool,
+    ) -> Result<String, AiError> {
+        // 取消优先：等待响应和读取数据时都能及时停止
+        let response = tokio::select! {
+            biased;
+            _ = wait_cancelled(cancel) => return Err(AiError::Cancelled),
+            response = self.post_chat(&self.stream_client, messages, true) => response?,
         };
 
-        let response = self
-            .client
-            .post(format!("{}/v1/chat/completions", self.base_url))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+        // 不支持流式的服务会忽略 stream 参数，直接返回完整回复
+        let is_event_stream = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        if !is_event_stream {
+            let reply = tokio::select! {
+                biased;
+                _ = wait_cancelled(cancel) => return Err(AiError::Cancelled),
+                reply = Self::read_reply(response) => reply?,
+            };
+            on_delta(&reply);
+            return Ok(reply);
+        }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(AiError::ApiCallFailed(format!(
-                "状态码: {}, 响应: {}",
-                status, text
-            )));
+        let mut stream = response.bytes_stream();
+        let mut decoder = SseDecoder::default();
+        let mut reply = String::new();
+        loop {
+            let chunk = tokio::select! {
+                biased;
+                _ = wait_cancelled(cancel) => return Err(AiError::Cancelled),
+                chunk = stream.next() => match chunk {
+                    Some(chunk) => chunk?,
+                    None => break,
+                },
+            };
+
+            for event in decoder.push(&chunk) {
+                match event? {
+                    SseEvent::Delta(delta) => {
+                        reply.push_str(&delta);
+                        on_delta(&delta);
+                    }
+                    SseEvent::Done => return Ok(reply),
+                }
+            }
         }
 
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .map_err(|e| AiError::ParseError(e.to_string()))?;
+        // 部分服务不发送 [DONE]，连接关闭即视为结束
+        Ok(reply)
+    }
+}
 
-        chat_response
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| AiError::ParseError("空响应".to_string()))
+impl LlmProvider for OpenAiCompatibleProvider {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn chat<'a>(&'a self, messages: Vec<ChatMessage>) -> BoxFuture<'a, Result<String, AiError>> {
+        self.send_chat(messages).boxed()
+    }
+    fn chat_stream<'a>(
+        &'a self,
+        messages: Vec<ChatMessage>,
+        on_delta: &'a mut (dyn FnMut(&str) + Send),
+        cancel: &'a AtomicBool,
+    ) -> BoxFuture<'a, Result<String, AiError>> {
+        self.send_chat_stream(messages, on_delta, cancel).boxed()
     }
 }
 
@@ -629,116 +672,6 @@ fn extract_json(content: &str) -> String {
     content.to_string()
 }
 
-/// DeepSeek API 提供者
-pub struct DeepSeekProvider {
-    client: Client,
-    api_key: String,
-    model: String,
-    base_url: String,
-}
-
-impl DeepSeekProvider {
-    pub fn new(api_key: String) -> Self {
-        Self {
-            client: Client::new(),
-            api_key,
-            model: "deepseek-chat".to_string(),
-            base_url: "https://api.deepseek.com/v1".to_string(), // 修正：需要 /v1 路径
-        }
-    }
-
-    pub fn name(&self) -> &str {
-        "DeepSeek"
-    }
-
-    pub async fn analyze(&self, content: &str, context: &str) -> Result<AnalysisResult, AiError> {
-        let prompt = format!("{}{}\n\n参考知识：\n{}", ANALYSIS_PROMPT, content, context);
-
-        let request = ChatRequest {
-            model: self.model.clone(),
-            messages: vec![ChatMessage {
-                role: "user".to_string(),
-                content: prompt,
-            }],
-            temperature: 0.7,
-        };
-
-        let response = self
-            .client
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            if status.as_u16() == 401 {
-                return Err(AiError::InvalidApiKey);
-            }
-            return Err(AiError::ApiCallFailed(format!(
-                "状态码: {}, 响应: {}",
-                status, text
-            )));
-        }
-
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .map_err(|e| AiError::ParseError(e.to_string()))?;
-
-        let content = chat_response
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| AiError::ParseError("空响应".to_string()))?;
-
-        parse_analysis_result(&content)
-    }
-
-    pub async fn chat(&self, messages: Vec<ChatMessage>) -> Result<String, AiError> {
-        let request = ChatRequest {
-            model: self.model.clone(),
-            messages,
-            temperature: 0.7,
-        };
-
-        let response = self
-            .client
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            if status.as_u16() == 401 {
-                return Err(AiError::InvalidApiKey);
-            }
-            return Err(AiError::ApiCallFailed(format!(
-                "状态码: {}, 响应: {}",
-                status, text
-            )));
-        }
-
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .map_err(|e| AiError::ParseError(e.to_string()))?;
-
-        chat_response
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| AiError::ParseError("空响应".to_string()))
-    }
-}
-
 /// AI 服务管理器
 #[derive(Clone)]
 pub struct AiService {
@@ -747,6 +680,8 @@ pub struct AiService {
     pub openai_api_key: Option<String>,
     pub deepseek_api_key: Option<String>,
     pub lm_studio_url: String,
+    /// 自定义 OpenAI 兼容服务
+    pub custom_provider: OpenAiCompatibleConfig,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -755,6 +690,32 @@ pub enum AiProviderType {
     OpenAi,
     DeepSeek,
     LmStudio,
+    /// 自定义 OpenAI 兼容服务
+    Custom,
+}
+
+impl AiProviderType {
+    /// 从设置中的提供者标识解析，未知标识回退到 LM Studio
+    pub fn from_key(key: &str) -> Self {
+        match key {
+            "doubao" => Self::Doubao,
+            "openai" => Self::OpenAi,
+            "deepseek" => Self::DeepSeek,
+            "custom" => Self::Custom,
+            _ => Self::LmStudio,
+        }
+    }
+
+    /// 设置中使用的提供者标识
+    pub fn as_key(self) -> &'static str {
+        match self {
+            Self::Doubao => "doubao",
+            Self::OpenAi => "openai",
+            Self::DeepSeek => "deepseek",
+            Self::LmStudio => "lmstudio",
+            Self::Custom => "custom",
+        }
+    }
 }
 
 impl Default for AiService {
@@ -765,6 +726,7 @@ impl Default for AiService {
             openai_api_key: None,
             deepseek_api_key: None,
             lm_studio_url: "http://localhost:1234".to_string(),
+            custom_provider: OpenAiCompatibleConfig::default(),
         }
     }
 }
@@ -794,35 +756,285 @@ impl AiService {
         self.lm_studio_url = url;
     }
 
-    pub async fn analyze(&self, content: &str, context: &str) -> Result<AnalysisResult, AiError> {
+    pub fn set_custom_provider(&mut self, config: OpenAiCompatibleConfig) {
+        self.custom_provider = config;
+    }
+
+    /// 当前提供者的接口配置
+    pub fn provider_config(&self) -> Result<OpenAiCompatibleConfig, AiError> {
         match self.provider_type {
             AiProviderType::Doubao => {
                 let api_key = self.doubao_api_key.clone().ok_or(AiError::InvalidApiKey)?;
-                let provider = DoubaoProvider::new(api_key);
-                provider.analyze(content, context).await
+                Ok(OpenAiCompatibleConfig::doubao(api_key))
             }
             AiProviderType::OpenAi => {
                 let api_key = self.openai_api_key.clone().ok_or(AiError::InvalidApiKey)?;
-                let provider = OpenAiProvider::new(api_key);
-                provider.analyze(content, context).await
+                Ok(OpenAiCompatibleConfig::openai(api_key))
             }
             AiProviderType::DeepSeek => {
                 let api_key = self
                     .deepseek_api_key
                     .clone()
                     .ok_or(AiError::InvalidApiKey)?;
-                let provider = DeepSeekProvider::new(api_key);
-                provider.analyze(content, context).await
+                Ok(OpenAiCompatibleConfig::deepseek(api_key))
             }
-            AiProviderType::LmStudio => {
-                let provider = LmStudioProvider::new(self.lm_studio_url.clone());
-                provider.analyze(content, context).await
+            AiProviderType::LmStudio => Ok(OpenAiCompatibleConfig::lm_studio(&self.lm_studio_url)),
+            AiProviderType::Custom => {
+                self.custom_provider.validate()?;
+                Ok(self.custom_provider.clone())
             }
         }
     }
 
+    /// 创建当前提供者
+    pub fn provider(&self) -> Result<Box<dyn LlmProvider>, AiError> {
+        Ok(Box::new(OpenAiCompatibleProvider::new(
+            self.provider_config()?,
+        )))
+    }
+
+    pub async fn analyze(&self, content: &str, context: &str) -> Result<AnalysisResult, AiError> {
+        self.provider()?.analyze(content, context).await
+    }
+
+    pub async fn chat(&self, messages: Vec<ChatMessage>) -> Result<String, AiError> {
+        self.provider()?.chat(messages).await
+    }
+
+    /// 流式对话，增量回复通过 `on_delta` 推送
+    pub async fn chat_stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        on_delta: &mut (dyn FnMut(&str) + Send),
+        cancel: &AtomicBool,
+    ) -> Result<String, AiError> {
+        self.provider()?
+            .chat_stream(messages, on_delta, cancel)
+            .await
+    }
+
+    /// 流式分析，模型回复通过 `on_delta` 推送，结束后返回解析好的分析结果
+    pub async fn analyze_stream(
+        &self,
+        content: &str,
+        context: &str,
+        on_delta: &mut (dyn FnMut(&str) + Send),
+        cancel: &AtomicBool,
+    ) -> Result<AnalysisResult, AiError> {
+        self.provider()?
+            .analyze_stream(content, context, on_delta, cancel)
+            .await
+    }
+
     pub async fn check_lm_studio(&self) -> bool {
-        let provider = LmStudioProvider::new(self.lm_studio_url.clone());
+        let provider =
+            OpenAiCompatibleProvider::new(OpenAiCompatibleConfig::lm_studio(&self.lm_studio_url));
         provider.is_running().await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_config() {
+        let mut service = AiService::new();
+        let config = service.provider_config().unwrap();
+        assert_eq!(config.base_url, "http://localhost:1234/v1");
+        assert_eq!(config.api_key, None);
+
+        service.set_provider(AiProviderType::DeepSeek);
+        assert!(matches!(
+            service.provider_config(),
+            Err(AiError::InvalidApiKey)
+        ));
+        service.set_deepseek_key("sk-test".to_string());
+        assert_eq!(service.provider_config().unwrap().model, "deepseek-chat");
+
+        // 自定义服务需要填写地址和模型
+        service.set_provider(AiProviderType::Custom);
+        assert!(matches!(
+            service.provider_config(),
+            Err(AiError::InvalidConfig(_))
+        ));
+        service.set_custom_provider(OpenAiCompatibleConfig {
+            name: "Ollama".to_string(),
+            base_url: "http://localhost:11434/v1".to_string(),
+            model: "qwen2.5:7b".to_string(),
+            ..OpenAiCompatibleConfig::default()
+        });
+        assert_eq!(service.provider().unwrap().name(), "Ollama");
+    }
+
+    #[test]
+    fn test_provider_type_keys() {
+        for provider in [
+            AiProviderType::Doubao,
+            AiProviderType::OpenAi,
+            AiProviderType::DeepSeek,
+            AiProviderType::LmStudio,
+            AiProviderType::Custom,
+        ] {
+            assert_eq!(AiProviderType::from_key(provider.as_key()), provider);
+        }
+        assert_eq!(
+            AiProviderType::from_key("unknown"),
+            AiProviderType::LmStudio
+        );
+    }
+
+    #[test]
+    fn test_chat_request_body() {
+        let config = OpenAiCompatibleConfig {
+            base_url: "https://gateway.example.com/v1/".to_string(),
+            model: "moonshot-v1-8k".to_string(),
+            max_tokens: Some(2048),
+            temperature: 0.3,
+            ..OpenAiCompatibleConfig::default()
+        };
+        let provider = OpenAiCompatibleProvider::new(config.clone());
+        assert_eq!(
+            provider.endpoint("chat/completions"),
+            "https://gateway.example.com/v1/chat/completions"
+        );
+
+        let body = serde_json::to_value(ChatRequest {
+            model: config.model,
+            messages: Vec::new(),
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            stream: false,
+        })
+        .unwrap();
+        assert_eq!(body["max_tokens"], 2048);
+        let body = serde_json::to_value(ChatRequest {
+            model: "default".to_string(),
+            messages: Vec::new(),
+            temperature: 0.7,
+            max_tokens: None,
+            stream: true,
+        })
+        .unwrap();
+        assert!(body.get("max_tokens").is_none());
+        assert_eq!(body["stream"], true);
+    }
+
+    #[test]
+    fn test_sse_decoder() {
+        let mut decoder = SseDecoder::default();
+        let body = "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n\
+                    data: {\"choices\":[{\"delta\":{\"content\":\"开头\"}}]}\n\n\
+                    : keep-alive\n\
+                    data: {\"choices\":[{\"delta\":{\"content\":\"用悬念\"}}]}\n\n\
+                    data: [DONE]\n\n";
+        // 按字节拆成小块，模拟多字节字符被数据块边界截断
+        let mut events = Vec::new();
+        for chunk in body.as_bytes().chunks(7) {
+            events.extend(decoder.push(chunk).into_iter().map(Result::unwrap));
+        }
+        assert_eq!(
+            events,
+            vec![
+                SseEvent::Delta("开头".to_string()),
+                SseEvent::Delta("用悬念".to_string()),
+                SseEvent::Done,
+            ]
+        );
+
+        let error = parse_sse_line("data: {\"error\":{\"message\":\"quota exceeded\"}}").unwrap();
+        assert!(matches!(error, Err(AiError::ApiCallFailed(msg)) if msg == "quota exceeded"));
+    }
+
+    #[tokio::test]
+    async fn test_chat_stream() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            let body = "data: {\"choices\":[{\"delta\":{\"content\":\"你好\"}}]}\n\n\
+                        data: {\"choices\":[{\"delta\":{\"content\":\"，创作者\"}}]}\n\n\
+                        data: [DONE]\n\n";
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(head.as_bytes());
+            let _ = stream.write_all(body.as_bytes());
+        });
+
+        let provider = OpenAiCompatibleProvider::new(OpenAiCompatibleConfig {
+            base_url,
+            model: "qwen2.5:7b".to_string(),
+            ..OpenAiCompatibleConfig::default()
+        });
+        let mut deltas = Vec::new();
+        let reply = provider
+            .chat_stream(
+                Vec::new(),
+                &mut |delta: &str| deltas.push(delta.to_string()),
+                &AtomicBool::new(false),
+            )
+            .await
+            .unwrap();
+        assert_eq!(reply, "你好，创作者");
+        assert_eq!(deltas, vec!["你好", "，创作者"]);
+
+        // 已取消时不再读取
+        let cancelled = provider
+            .chat_stream(Vec::new(), &mut |_: &str| {}, &AtomicBool::new(true))
+            .await;
+        assert!(matches!(cancelled, Err(AiError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_chat_stream_cancel_non_stream_reply() {
+        use std::io::{Read, Write};
+        use std::sync::Arc;
+
+        // 不支持流式的服务返回完整 JSON，响应体迟迟不到时也能取消
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            let head =
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 64\r\n\r\n";
+            let _ = stream.write_all(head.as_bytes());
+            std::thread::sleep(std::time::Duration::from_secs(5));
+        });
+
+        let provider = OpenAiCompatibleProvider::new(OpenAiCompatibleConfig {
+            base_url,
+            model: "qwen2.5:7b".to_string(),
+            ..OpenAiCompatibleConfig::default()
+        });
+        let cancel = Arc::new(AtomicBool::new(false));
+        let flag = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            flag.store(true, std::sync::atomic::Ordering::SeqCst);
+        });
+
+        let started = std::time::Instant::now();
+        let result = provider
+            .chat_stream(Vec::new(), &mut |_: &str| {}, &cancel)
+            .await;
+        assert!(matches!(result, Err(AiError::Cancelled)));
+        assert!(started.elapsed() < std::time::Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_parse_analysis_result() {
+        let reply = "分析如下：\n```json\n{\"hook\": {\"text\": \"你敢信？\", \"technique\": \"悬念\", \"effectiveness\": \"强\"}, \"buildup\": [{\"text\": \"铺垫\", \"purpose\": \"引出\"}], \"climax\": null, \"ending\": null}\n```";
+        let result = parse_analysis_result(reply).unwrap();
+        assert_eq!(result.hook.unwrap().technique, "悬念");
+        assert_eq!(result.buildup.len(), 1);
+        assert!(result.climax.is_none());
+    }
+}
@@ -1,14 +1,21 @@
 // AI 相关命令
 
 use crate::ai::knowledge_base::{Document, KnowledgeBase, SearchResult};
-use crate::ai::service::{AiProviderType, AiService, AnalysisResult, ChatMessage};
+use crate::ai::onnx_ocr::{self, OnnxOcr, DET_MODEL_FILE, KEYS_FILE, REC_MODEL_FILE};
+use crate::ai::service::{
+    AiError, AiProviderType, AiService, AnalysisResult, ChatMessage, OpenAiCompatibleConfig,
+    OpenAiCompatibleProvider,
+};
+use crate::utils::http_client::{self, HttpService};
 use crate::utils::paths::get_app_paths;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tauri::{Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 use tracing::{error, info, warn};
 
 // 全局知识库实例
@@ -27,6 +34,9 @@ pub struct AiSettings {
     pub openai_api_key: Option<String>,
     pub deepseek_api_key: Option<String>,
     pub lm_studio_url: String,
+    /// 自定义 OpenAI 兼容服务，未传入时保留当前配置
+    #[serde(default)]
+    pub custom_provider: Option<OpenAiCompatibleConfig>,
 }
 
 impl Default for AiSettings {
@@ -37,6 +47,7 @@ impl Default for AiSettings {
             openai_api_key: None,
             deepseek_api_key: None,
             lm_studio_url: "http://localhost:1234".to_string(),
+            custom_provider: None,
         }
     }
 }
@@ -206,13 +217,7 @@ pub async fn update_ai_settings(settings: AiSettings) -> Result<(), String> {
     let mut service = AI_SERVICE.lock();
 
     // 设置提供者类型
-    let provider_type = match settings.provider.as_str() {
-        "doubao" => AiProviderType::Doubao,
-        "openai" => AiProviderType::OpenAi,
-        "deepseek" => AiProviderType::DeepSeek,
-        _ => AiProviderType::LmStudio,
-    };
-    service.set_provider(provider_type);
+    service.set_provider(AiProviderType::from_key(&settings.provider));
 
     // 设置 API Keys
     if let Some(key) = settings.doubao_api_key {
@@ -236,6 +241,11 @@ pub async fn update_ai_settings(settings: AiSettings) -> Result<(), String> {
         service.set_lm_studio_url(settings.lm_studio_url);
     }
 
+    // 设置自定义 OpenAI 兼容服务
+    if let Some(config) = settings.custom_provider {
+        service.set_custom_provider(config);
+    }
+
     Ok(())
 }
 
@@ -244,27 +254,18 @@ pub async fn update_ai_settings(settings: AiSettings) -> Result<(), String> {
 pub async fn get_ai_settings() -> Result<AiSettings, String> {
     let service = AI_SERVICE.lock();
 
-    let provider = match service.provider_type {
-        AiProviderType::Doubao => "doubao",
-        AiProviderType::OpenAi => "openai",
-        AiProviderType::DeepSeek => "deepseek",
-        AiProviderType::LmStudio => "lmstudio",
-    };
-
     Ok(AiSettings {
-        provider: provider.to_string(),
+        provider: service.provider_type.as_key().to_string(),
         doubao_api_key: service.doubao_api_key.clone(),
         openai_api_key: service.openai_api_key.clone(),
         deepseek_api_key: service.deepseek_api_key.clone(),
         lm_studio_url: service.lm_studio_url.clone(),
+        custom_provider: Some(service.custom_provider.clone()),
     })
 }
 
-/// AI 内容分析
-#[tauri::command]
-pub async fn analyze_content(content: String) -> Result<AnalysisResult, String> {
-    info!("开始 AI 内容分析，内容长度: {} 字符", content.len());
-
+/// 从知识库检索与文案相关的参考内容，用于内容分析
+async fn analysis_context(content: &str) -> String {
     // 获取知识库实例（克隆后立即释放锁）
     let kb = {
         let kb_guard = KNOWLEDGE_BASE.lock();
@@ -272,9 +273,9 @@ pub async fn analyze_content(content: String) -> Result<AnalysisResult, String>
     };
 
     // 获取知识库上下文
-    let context = if let Some(kb) = kb {
+    if let Some(kb) = kb {
         // 搜索相关知识
-        match kb.search(&content, 3).await {
+        match kb.search(content, 3).await {
             Ok(results) => {
                 info!("从知识库找到 {} 条相关内容", results.len());
                 results
@@ -290,7 +291,15 @@ pub async fn analyze_content(content: String) -> Result<AnalysisResult, String>
         }
     } else {
         String::new()
-    };
+    }
+}
+
+/// AI 内容分析
+#[tauri::command]
+pub async fn analyze_content(content: String) -> Result<AnalysisResult, String> {
+    info!("开始 AI 内容分析，内容长度: {} 字符", content.len());
+
+    let context = analysis_context(&content).await;
 
     // 调用 AI 服务
     let service = {
@@ -319,6 +328,15 @@ pub async fn check_lm_studio() -> Result<bool, String> {
     Ok(service.check_lm_studio().await)
 }
 
+/// 检查自定义 OpenAI 兼容服务是否可用（请求模型列表）
+#[tauri::command]
+pub async fn check_custom_ai_provider(config: OpenAiCompatibleConfig) -> Result<bool, String> {
+    config
+        .validate()
+        .map_err(|e| format!("检查 AI 服务失败: {}", e))?;
+    Ok(OpenAiCompatibleProvider::new(config).is_running().await)
+}
+
 // ========== 嵌入模型管理 ==========
 
 /// 嵌入模型信息
@@ -389,113 +407,187 @@ pub async fn download_embedding_model(app: tauri::AppHandle) -> Result<(), Strin
         ("config.json", format!("{}/config.json", root_url)),
     ];
 
-    let client = reqwest::Client::new();
+    let client = http_client::build_client(HttpService::ModelDownload);
 
     for (file_name, url) in files {
         let dest_path = model_path.join(file_name);
+        // 对于可选文件（如 quantized），下载失败时跳过
+        let optional = file_name.contains("quantized");
+        download_model_file(
+            &app,
+            &client,
+            "embedding-model-progress",
+            file_name,
+            &url,
+            &dest_path,
+            optional,
+        )
+        .await?;
+    }
+
+    // 下载完成后重新初始化知识库，以使用新的 ONNX 嵌入器
+    let _ = init_knowledge_base(app, String::new()).await;
+
+    Ok(())
+}
 
-        // 发送开始下载事件
+/// 流式下载单个模型文件，并通过 `event` 发送进度事件
+///
+/// `optional` 为 true 时，HTTP 状态错误不视为失败
+async fn download_model_file(
+    app: &tauri::AppHandle,
+    client: &reqwest::Client,
+    event: &str,
+    file_name: &str,
+    url: &str,
+    dest_path: &std::path::Path,
+    optional: bool,
+) -> Result<(), String> {
+    // 发送开始下载事件
+    let _ = app.emit(
+        event,
+        EmbeddingModelProgress {
+            file_name: file_name.to_string(),
+            downloaded_bytes: 0,
+            total_bytes: 0,
+            progress: 0.0,
+            status: "downloading".to_string(),
+        },
+    );
+
+    // 下载文件
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+
+    if !response.status().is_success() {
+        if optional {
+            return Ok(());
+        }
         let _ = app.emit(
-            "embedding-model-progress",
+            event,
             EmbeddingModelProgress {
                 file_name: file_name.to_string(),
                 downloaded_bytes: 0,
                 total_bytes: 0,
                 progress: 0.0,
-                status: "downloading".to_string(),
+                status: "failed".to_string(),
             },
         );
+        return Err(format!(
+            "下载 {} 失败: HTTP {}",
+            file_name,
+            response.status()
+        ));
+    }
 
-        // 下载文件
-        let response = client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| format!("请求失败: {}", e))?;
-
-        if !response.status().is_success() {
-            // 对于可选文件（如 quantized），跳过错误
-            if file_name.contains("quantized") {
-                continue;
-            }
-            let _ = app.emit(
-                "embedding-model-progress",
-                EmbeddingModelProgress {
-                    file_name: file_name.to_string(),
-                    downloaded_bytes: 0,
-                    total_bytes: 0,
-                    progress: 0.0,
-                    status: "failed".to_string(),
-                },
-            );
-            return Err(format!(
-                "下载 {} 失败: HTTP {}",
-                file_name,
-                response.status()
-            ));
-        }
-
-        let total_size = response.content_length().unwrap_or(0);
-        let mut downloaded: u64 = 0;
-
-        // 创建文件
-        let mut file = tokio::fs::File::create(&dest_path)
-            .await
-            .map_err(|e| format!("创建文件失败: {}", e))?;
-
-        // 流式下载
-        use futures_util::StreamExt;
-        use tokio::io::AsyncWriteExt;
-
-        let mut stream = response.bytes_stream();
+    let total_size = response.content_length().unwrap_or(0);
 
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|e| format!("下载错误: {}", e))?;
-            file.write_all(&chunk)
-                .await
-                .map_err(|e| format!("写入失败: {}", e))?;
+    // 先写入临时文件，完整下载后再改名，避免中断留下的残缺文件被当作已安装
+    let mut part_name = dest_path.as_os_str().to_owned();
+    part_name.push(".part");
+    let part_path = PathBuf::from(part_name);
 
-            downloaded += chunk.len() as u64;
-            let progress = if total_size > 0 {
-                downloaded as f32 / total_size as f32
+    let written = write_model_stream(app, event, file_name, response, &part_path, total_size)
+        .await
+        .and_then(|downloaded| {
+            if total_size > 0 && downloaded != total_size {
+                Err(format!(
+                    "下载 {} 不完整: {}/{} 字节",
+                    file_name, downloaded, total_size
+                ))
             } else {
-                0.0
-            };
-
-            // 发送进度事件（每 100KB 更新一次）
-            if downloaded % (100 * 1024) < chunk.len() as u64 || downloaded == total_size {
-                let _ = app.emit(
-                    "embedding-model-progress",
-                    EmbeddingModelProgress {
-                        file_name: file_name.to_string(),
-                        downloaded_bytes: downloaded,
-                        total_bytes: total_size,
-                        progress,
-                        status: "downloading".to_string(),
-                    },
-                );
+                Ok(())
             }
-        }
-
-        // 发送完成事件
+        });
+    let result = match written {
+        Ok(()) => tokio::fs::rename(&part_path, dest_path)
+            .await
+            .map_err(|e| format!("保存文件失败: {}", e)),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        let _ = tokio::fs::remove_file(&part_path).await;
         let _ = app.emit(
-            "embedding-model-progress",
+            event,
             EmbeddingModelProgress {
                 file_name: file_name.to_string(),
-                downloaded_bytes: total_size,
+                downloaded_bytes: 0,
                 total_bytes: total_size,
-                progress: 1.0,
-                status: "completed".to_string(),
+                progress: 0.0,
+                status: "failed".to_string(),
             },
         );
+        return Err(e);
     }
 
-    // 下载完成后重新初始化知识库，以使用新的 ONNX 嵌入器
-    let _ = init_knowledge_base(app, String::new()).await;
+    // 发送完成事件
+    let _ = app.emit(
+        event,
+        EmbeddingModelProgress {
+            file_name: file_name.to_string(),
+            downloaded_bytes: total_size,
+            total_bytes: total_size,
+            progress: 1.0,
+            status: "completed".to_string(),
+        },
+    );
 
     Ok(())
 }
 
+/// 将响应流写入文件并发送进度事件，返回写入的字节数
+async fn write_model_stream(
+    app: &tauri::AppHandle,
+    event: &str,
+    file_name: &str,
+    response: reqwest::Response,
+    path: &std::path::Path,
+    total_size: u64,
+) -> Result<u64, String> {
+    use futures_util::StreamExt;
+    use tokio::io::AsyncWriteExt;
+
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|e| format!("创建文件失败: {}", e))?;
+    let mut downloaded: u64 = 0;
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("下载错误: {}", e))?;
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("写入失败: {}", e))?;
+
+        downloaded += chunk.len() as u64;
+        let progress = if total_size > 0 {
+            downloaded as f32 / total_size as f32
+        } else {
+            0.0
+        };
+
+        // 发送进度事件（每 100KB 更新一次）
+        if downloaded % (100 * 1024) < chunk.len() as u64 || downloaded == total_size {
+            let _ = app.emit(
+                event,
+                EmbeddingModelProgress {
+                    file_name: file_name.to_string(),
+                    downloaded_bytes: downloaded,
+                    total_bytes: total_size,
+                    progress,
+                    status: "downloading".to_string(),
+                },
+            );
+        }
+    }
+
+    file.flush().await.map_err(|e| format!("写入失败: {}", e))?;
+    Ok(downloaded)
+}
+
 /// 获取嵌入模型目录
 ///
 /// 使用 AppPaths 统一管理路径
@@ -571,18 +663,81 @@ pub async fn open_embedding_models_dir(app: tauri::AppHandle) -> Result<(), Stri
     Ok(())
 }
 
-/// AI 对话
+// ========== OCR 模型管理 ==========
+
+/// 获取 OCR 模型目录
+pub fn get_ocr_model_dir(app: &tauri::AppHandle) -> PathBuf {
+    let model_info = onnx_ocr::model_info();
+    get_embedding_models_dir(app).join(model_info.model_dir)
+}
+
+/// 获取 OCR 模型状态
 #[tauri::command]
-pub async fn chat_with_ai(
-    mut messages: Vec<ChatMessage>,
-    context_content: String,
-) -> Result<String, String> {
-    info!(
-        "AI 对话请求，消息数: {}, 上下文长度: {}",
-        messages.len(),
-        context_content.len()
-    );
+pub async fn get_ocr_model_status(app: tauri::AppHandle) -> Result<EmbeddingModelInfo, String> {
+    let model_info = onnx_ocr::model_info();
+
+    Ok(EmbeddingModelInfo {
+        name: model_info.name,
+        description: model_info.description,
+        size_mb: model_info.size_mb,
+        is_installed: OnnxOcr::is_installed(&get_ocr_model_dir(&app)),
+    })
+}
 
+/// 下载 OCR 模型（检测模型、识别模型和字典）
+#[tauri::command]
+pub async fn download_ocr_model(app: tauri::AppHandle) -> Result<(), String> {
+    let model_path = get_ocr_model_dir(&app);
+    std::fs::create_dir_all(&model_path).map_err(|e| format!("创建目录失败: {}", e))?;
+
+    let model_info = onnx_ocr::model_info();
+    let base_url = &model_info.download_url;
+    let files = vec![
+        (DET_MODEL_FILE, format!("{}/{}", base_url, DET_MODEL_FILE)),
+        (REC_MODEL_FILE, format!("{}/{}", base_url, REC_MODEL_FILE)),
+        // 字典文件由 PaddleOCR 仓库提供
+        (
+            KEYS_FILE,
+            "https://raw.githubusercontent.com/PaddlePaddle/PaddleOCR/release/2.7/ppocr/utils/ppocr_keys_v1.txt"
+                .to_string(),
+        ),
+    ];
+
+    let client = http_client::build_client(HttpService::ModelDownload);
+    for (file_name, url) in files {
+        download_model_file(
+            &app,
+            &client,
+            "ocr-model-progress",
+            file_name,
+            &url,
+            &model_path.join(file_name),
+            false,
+        )
+        .await?;
+    }
+
+    info!("OCR 模型下载完成");
+    Ok(())
+}
+
+/// 删除 OCR 模型
+#[tauri::command]
+pub async fn delete_ocr_model(app: tauri::AppHandle) -> Result<(), String> {
+    let model_path = get_ocr_model_dir(&app);
+
+    if model_path.exists() {
+        std::fs::remove_dir_all(&model_path).map_err(|e| format!("删除模型失败: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// 为对话补充系统提示词：当前视频文案与知识库检索结果（RAG）
+async fn build_chat_messages(
+    mut messages: Vec<ChatMessage>,
+    context_content: &str,
+) -> Vec<ChatMessage> {
     // 1. 获取最后一条用户消息用于 RAG 搜索
     let last_user_message = messages
         .last()
@@ -639,7 +794,7 @@ pub async fn chat_with_ai(
         system_prompt.push_str(&truncated_content);
         system_prompt.push_str("\n...(文案过长已截断)...");
     } else {
-        system_prompt.push_str(&context_content);
+        system_prompt.push_str(context_content);
     }
 
     if let Some(rag) = rag_context {
@@ -657,7 +812,24 @@ pub async fn chat_with_ai(
         },
     );
 
-    // 5. 调用 AI 服务
+    messages
+}
+
+/// AI 对话
+#[tauri::command]
+pub async fn chat_with_ai(
+    messages: Vec<ChatMessage>,
+    context_content: String,
+) -> Result<String, String> {
+    info!(
+        "AI 对话请求，消息数: {}, 上下文长度: {}",
+        messages.len(),
+        context_content.len()
+    );
+
+    let messages = build_chat_messages(messages, &context_content).await;
+
+    // 调用 AI 服务
     info!("准备调用 AI 服务进行对话...");
     let service = {
         let guard = AI_SERVICE.lock();
@@ -675,3 +847,169 @@ pub async fn chat_with_ai(
         }
     }
 }
+
+// ========== 流式输出 ==========
+
+/// 流式输出的增量事件
+#[derive(Debug, Clone, Serialize)]
+pub struct AiStreamDelta {
+    pub request_id: String,
+    pub delta: String,
+}
+
+/// 流式输出的结束事件
+#[derive(Debug, Clone, Serialize)]
+pub struct AiStreamFinished {
+    pub request_id: String,
+    /// completed / cancelled / failed
+    pub status: String,
+    pub error: Option<String>,
+}
+
+/// 进行中的流式请求及其取消标记
+static AI_STREAMS: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 登记流式请求，结束（包括出错或前端断开）时自动注销
+struct StreamRegistration {
+    request_id: String,
+    cancel: Arc<AtomicBool>,
+}
+
+impl StreamRegistration {
+    fn new(request_id: &str) -> Result<Self, String> {
+        let mut streams = AI_STREAMS.lock();
+        if streams.contains_key(request_id) {
+            return Err(format!("请求 {} 正在进行中", request_id));
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        streams.insert(request_id.to_string(), cancel.clone());
+        Ok(Self {
+            request_id: request_id.to_string(),
+            cancel,
+        })
+    }
+}
+
+impl Drop for StreamRegistration {
+    fn drop(&mut self) {
+        AI_STREAMS.lock().remove(&self.request_id);
+    }
+}
+
+/// 发送增量事件的回调
+fn delta_emitter(app: &AppHandle, request_id: &str) -> impl FnMut(&str) + Send {
+    let app = app.clone();
+    let request_id = request_id.to_string();
+    move |delta: &str| {
+        let _ = app.emit(
+            "ai:stream-delta",
+            AiStreamDelta {
+                request_id: request_id.clone(),
+                delta: delta.to_string(),
+            },
+        );
+    }
+}
+
+/// 发送结束事件，并把错误转换为命令返回值
+fn finish_stream<T>(
+    app: &AppHandle,
+    request_id: &str,
+    action: &str,
+    result: Result<T, AiError>,
+) -> Result<T, String> {
+    let (status, error) = match &result {
+        Ok(_) => ("completed", None),
+        Err(AiError::Cancelled) => ("cancelled", None),
+        Err(e) => ("failed", Some(e.to_string())),
+    };
+    let _ = app.emit(
+        "ai:stream-finished",
+        AiStreamFinished {
+            request_id: request_id.to_string(),
+            status: status.to_string(),
+            error,
+        },
+    );
+
+    result.map_err(|e| {
+        if !matches!(e, AiError::Cancelled) {
+            error!("{}失败 ({}): {}", action, request_id, e);
+        }
+        format!("{}失败: {}", action, e)
+    })
+}
+
+/// 流式 AI 对话
+///
+/// 回复片段通过 `ai:stream-delta` 事件推送，结束时发送 `ai:stream-finished`；返回完整回复
+#[tauri::command]
+pub async fn chat_with_ai_stream(
+    app: AppHandle,
+    request_id: String,
+    messages: Vec<ChatMessage>,
+    context_content: String,
+) -> Result<String, String> {
+    info!(
+        "AI 流式对话请求 {}，消息数: {}, 上下文长度: {}",
+        request_id,
+        messages.len(),
+        context_content.len()
+    );
+    let registration = StreamRegistration::new(&request_id)?;
+
+    let messages = build_chat_messages(messages, &context_content).await;
+    let service = {
+        let guard = AI_SERVICE.lock();
+        guard.clone()
+    };
+
+    let mut on_delta = delta_emitter(&app, &request_id);
+    let result = service
+        .chat_stream(messages, &mut on_delta, &registration.cancel)
+        .await;
+    finish_stream(&app, &request_id, "AI 对话", result)
+}
+
+/// 流式 AI 内容分析
+///
+/// 模型回复片段通过 `ai:stream-delta` 事件推送，结束后返回解析好的分析结果
+#[tauri::command]
+pub async fn analyze_content_stream(
+    app: AppHandle,
+    request_id: String,
+    content: String,
+) -> Result<AnalysisResult, String> {
+    info!(
+        "开始 AI 流式内容分析 {}，内容长度: {} 字符",
+        request_id,
+        content.len()
+    );
+    let registration = StreamRegistration::new(&request_id)?;
+
+    let context = analysis_context(&content).await;
+    let service = {
+        let guard = AI_SERVICE.lock();
+        guard.clone()
+    };
+
+    let mut on_delta = delta_emitter(&app, &request_id);
+    let result = service
+        .analyze_stream(&content, &context, &mut on_delta, &registration.cancel)
+        .await;
+    finish_stream(&app, &request_id, "AI 分析", result)
+}
+
+/// 取消流式请求，返回请求是否仍在进行
+#[tauri::command]
+pub async fn cancel_ai_stream(request_id: String) -> Result<bool, String> {
+    match AI_STREAMS.lock().get(&request_id) {
+        Some(cancel) => {
+            cancel.store(true, Ordering::SeqCst);
+            info!("取消 AI 流式请求: {}", request_id);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
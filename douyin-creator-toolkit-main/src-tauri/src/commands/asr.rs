@@ -181,7 +181,9 @@ pub async fn download_model(app: AppHandle) -> Result<(), String> {
         .map_err(|e| format!("创建目录失败: {}", e))?;
 
     let files = manager.get_download_files();
-    let client = reqwest::Client::new();
+    let client = crate::utils::http_client::build_client(
+        crate::utils::http_client::HttpService::ModelDownload,
+    );
 
     for (url, dest_path) in files {
         let file_name = dest_path
@@ -0,0 +1,304 @@
+// 话题 / 音乐作品采集命令
+// 抓取话题（挑战）和背景音乐下的热门 / 最新作品，保存到资料库后可批量下载音频并加入转写队列
+
+use crate::commands::settings::get_database;
+use crate::core::douyin_parser::{ChallengeSort, DouyinParser, QualityPreference};
+use crate::core::mcp_client::{DouyinVideoInfo, McpClient, SearchResult, DEFAULT_USER_VIDEO_COUNT};
+use crate::data::task_queue::TaskType;
+use crate::data::CollectedVideoRecord;
+use crate::utils::paths::get_app_paths;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+use tracing::{error, info, warn};
+
+/// 来源类型：话题
+const SOURCE_CHALLENGE: &str = "challenge";
+/// 来源类型：背景音乐
+const SOURCE_MUSIC: &str = "music";
+
+/// 采集结果：本页作品与解析出的来源 ID
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionPage {
+    pub source_type: String,
+    pub source_id: String,
+    #[serde(flatten)]
+    pub result: SearchResult,
+    /// 本次保存到资料库的作品数
+    pub saved: usize,
+}
+
+/// 批量转写采集作品的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionTranscribeResult {
+    pub total: usize,
+    /// 已下载音频并加入转写队列
+    pub queued: usize,
+    /// 图文作品，无需转写
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// 采集作品的下载目录：导出目录下的 collections/<来源>_<ID>
+fn collection_output_dir(source_type: &str, source_id: &str) -> PathBuf {
+    let folder = format!("{}_{}", source_type, source_id);
+    let export_path = crate::commands::settings::get_config_manager()
+        .map(|m| m.get().default_export_path)
+        .unwrap_or_default();
+    if !export_path.is_empty() {
+        return PathBuf::from(export_path).join("collections").join(folder);
+    }
+
+    get_app_paths()
+        .map(|paths| paths.data_dir.join("collections").join(&folder))
+        .unwrap_or_else(|_| PathBuf::from("collections").join(folder))
+}
+
+/// 把一页作品保存到资料库，返回保存条数
+fn save_page(source_type: &str, source_id: &str, videos: &[DouyinVideoInfo]) -> usize {
+    let db = match get_database() {
+        Some(db) => db,
+        None => return 0,
+    };
+
+    let records: Vec<CollectedVideoRecord> = videos
+        .iter()
+        .filter(|v| !v.aweme_id.is_empty())
+        .map(|v| CollectedVideoRecord {
+            source_type: source_type.to_string(),
+            source_id: source_id.to_string(),
+            aweme_id: v.aweme_id.clone(),
+            title: v.title.clone(),
+            author: v.author.clone(),
+            video_url: v.video_url.clone(),
+            cover_url: v.cover_url.clone(),
+            create_time: v.create_time.map(|t| t as i64),
+            likes: v.likes as i64,
+            status: "new".to_string(),
+            task_id: None,
+            collected_at: String::new(),
+        })
+        .collect();
+
+    match db.save_collected_videos(&records) {
+        Ok(saved) => saved,
+        Err(e) => {
+            error!("保存采集作品失败 {}/{}: {}", source_type, source_id, e);
+            0
+        }
+    }
+}
+
+/// 分页获取话题下的作品，默认按热度排序并保存到资料库
+///
+/// `challenge` 可以是话题 ID、话题页链接或分享短链接；翻页时传入上一页的 `cursor`
+#[tauri::command]
+pub async fn fetch_challenge_videos(
+    challenge: String,
+    sort: Option<ChallengeSort>,
+    cursor: Option<u64>,
+    count: Option<u32>,
+    save: Option<bool>,
+) -> Result<CollectionPage, String> {
+    let source_id = DouyinParser::new()
+        .resolve_challenge_id(&challenge)
+        .await
+        .map_err(|e| format!("获取话题作品失败: {}", e))?;
+
+    let result = McpClient::default()
+        .get_challenge_videos(
+            &source_id,
+            sort.unwrap_or_default(),
+            cursor.unwrap_or(0),
+            count.unwrap_or(DEFAULT_USER_VIDEO_COUNT),
+        )
+        .await
+        .map_err(|e| format!("获取话题作品失败: {}", e))?;
+
+    let saved = if save.unwrap_or(true) {
+        save_page(SOURCE_CHALLENGE, &source_id, &result.videos)
+    } else {
+        0
+    };
+    info!("话题 {} 获取到 {} 个作品", source_id, result.videos.len());
+
+    Ok(CollectionPage {
+        source_type: SOURCE_CHALLENGE.to_string(),
+        source_id,
+        result,
+        saved,
+    })
+}
+
+/// 分页获取使用某个背景音乐的作品并保存到资料库
+///
+/// `music` 可以是音乐 ID、音乐页链接或分享短链接；翻页时传入上一页的 `cursor`
+#[tauri::command]
+pub async fn fetch_music_videos(
+    music: String,
+    cursor: Option<u64>,
+    count: Option<u32>,
+    save: Option<bool>,
+) -> Result<CollectionPage, String> {
+    let source_id = DouyinParser::new()
+        .resolve_music_id(&music)
+        .await
+        .map_err(|e| format!("获取音乐作品失败: {}", e))?;
+
+    let result = McpClient::default()
+        .get_music_videos(
+            &source_id,
+            cursor.unwrap_or(0),
+            count.unwrap_or(DEFAULT_USER_VIDEO_COUNT),
+        )
+        .await
+        .map_err(|e| format!("获取音乐作品失败: {}", e))?;
+
+    let saved = if save.unwrap_or(true) {
+        save_page(SOURCE_MUSIC, &source_id, &result.videos)
+    } else {
+        0
+    };
+    info!("音乐 {} 获取到 {} 个作品", source_id, result.videos.len());
+
+    Ok(CollectionPage {
+        source_type: SOURCE_MUSIC.to_string(),
+        source_id,
+        result,
+        saved,
+    })
+}
+
+/// 获取已采集的作品，可按来源类型（challenge / music）和来源 ID 过滤
+#[tauri::command]
+pub async fn list_collected_videos(
+    source_type: Option<String>,
+    source_id: Option<String>,
+    limit: Option<i32>,
+) -> Result<Vec<CollectedVideoRecord>, String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    db.list_collected_videos(
+        source_type.as_deref(),
+        source_id.as_deref(),
+        limit.unwrap_or(500),
+    )
+    .map_err(|e| format!("获取采集作品失败: {}", e))
+}
+
+/// 下载采集作品的音频并加入转写队列，返回转写任务 ID；图文作品返回 None
+async fn queue_transcription(
+    app: &AppHandle,
+    video: &CollectedVideoRecord,
+    output_dir: &Path,
+) -> Result<Option<String>, String> {
+    std::fs::create_dir_all(output_dir).map_err(|e| format!("创建下载目录失败: {}", e))?;
+
+    let mut output = output_dir.to_string_lossy().to_string();
+    output.push(std::path::MAIN_SEPARATOR);
+    let saved = crate::commands::video::download_video(
+        app.clone(),
+        format!("collection-{}", video.aweme_id),
+        format!("https://www.douyin.com/video/{}", video.aweme_id),
+        output,
+        Some(QualityPreference::AudioOnly),
+    )
+    .await?;
+
+    // 图文作品下载为图集目录，无需转写
+    if Path::new(&saved).is_dir() {
+        return Ok(None);
+    }
+
+    let video_name = if video.title.is_empty() {
+        video.aweme_id.clone()
+    } else {
+        video.title.clone()
+    };
+    let task_id = crate::commands::task_queue::get_task_queue()
+        .add_task(TaskType::VideoTranscription {
+            video_path: saved,
+            video_name,
+        })
+        .await;
+    Ok(Some(task_id))
+}
+
+/// 批量转写某个话题 / 音乐下采集的作品
+///
+/// 未指定 `aweme_ids` 时处理所有未加入转写队列的作品（状态为 new 或 failed）
+#[tauri::command]
+pub async fn transcribe_collected_videos(
+    app: AppHandle,
+    source_type: String,
+    source_id: String,
+    aweme_ids: Option<Vec<String>>,
+) -> Result<CollectionTranscribeResult, String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    let videos: Vec<CollectedVideoRecord> = db
+        .list_collected_videos(
+            Some(source_type.as_str()),
+            Some(source_id.as_str()),
+            i32::MAX,
+        )
+        .map_err(|e| format!("获取采集作品失败: {}", e))?
+        .into_iter()
+        .filter(|v| match &aweme_ids {
+            Some(ids) => ids.contains(&v.aweme_id),
+            None => v.status == "new" || v.status == "failed",
+        })
+        .collect();
+
+    let output_dir = collection_output_dir(&source_type, &source_id);
+    let mut result = CollectionTranscribeResult {
+        total: videos.len(),
+        queued: 0,
+        skipped: 0,
+        failed: 0,
+    };
+    info!(
+        "开始批量转写采集作品 {}/{}: {} 个",
+        source_type,
+        source_id,
+        videos.len()
+    );
+
+    for (i, video) in videos.iter().enumerate() {
+        let (status, task_id) = match queue_transcription(&app, video, &output_dir).await {
+            Ok(Some(task_id)) => {
+                result.queued += 1;
+                ("queued", Some(task_id))
+            }
+            Ok(None) => {
+                result.skipped += 1;
+                ("skipped", None)
+            }
+            Err(e) => {
+                warn!("采集作品处理失败 {}: {}", video.aweme_id, e);
+                result.failed += 1;
+                ("failed", None)
+            }
+        };
+
+        if let Err(e) = db.update_collected_video_status(
+            &source_type,
+            &source_id,
+            &video.aweme_id,
+            status,
+            task_id.as_deref(),
+        ) {
+            error!("更新采集作品状态失败 {}: {}", video.aweme_id, e);
+        }
+        let _ = app.emit(
+            "collection:transcribe-progress",
+            serde_json::json!({
+                "current": i + 1,
+                "total": result.total,
+                "aweme_id": video.aweme_id,
+                "status": status,
+                "task_id": task_id,
+            }),
+        );
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,160 @@
+// 批量链接导入相关命令
+// 从粘贴文本、剪贴板内容或 CSV / XLSX 文件中提取抖音链接，去掉资料库已有的作品后交给批量解析
+
+use crate::commands::mcp::{parse_douyin_links_batch, BatchParseStats};
+use crate::commands::settings::get_database;
+use crate::core::douyin_parser::DouyinParser;
+use crate::core::link_import::{self, ImportedLink};
+use crate::data::ImportedVideoRecord;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::AppHandle;
+use tracing::warn;
+
+/// 同时解析跳转的短链接数量
+const RESOLVE_CONCURRENCY: usize = 8;
+
+/// 链接导入预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkImportPreview {
+    /// 提取到的链接总数（含重复）
+    pub found: usize,
+    /// 待导入的新链接（已去重）
+    pub links: Vec<ImportedLink>,
+    /// 资料库中已存在的作品
+    pub existing: Vec<ImportedLink>,
+    /// 未能解析出作品 ID 的短链接数量（仍会交给批量解析）
+    pub unresolved: usize,
+}
+
+/// 从文本与文件中收集链接，解析短链接的作品 ID 后去重，并与资料库比对
+async fn collect_links(
+    text: Option<String>,
+    file_path: Option<String>,
+    column: Option<usize>,
+) -> Result<LinkImportPreview, String> {
+    let mut links = Vec::new();
+    if let Some(text) = text.as_deref() {
+        links.extend(link_import::extract_links(text));
+    }
+    if let Some(path) = file_path.as_deref().filter(|p| !p.trim().is_empty()) {
+        let from_file = link_import::extract_links_from_file(Path::new(path), column)
+            .map_err(|e| format!("读取导入文件失败: {}", e))?;
+        links.extend(from_file);
+    }
+
+    let found = links.len();
+    let mut links = link_import::dedupe_links(links);
+    resolve_short_links(&mut links).await;
+    // 短链接解析后可能与其他链接指向同一作品
+    let links = link_import::dedupe_links(links);
+
+    let library = match get_database() {
+        Some(db) => db
+            .list_library_aweme_ids()
+            .map_err(|e| format!("读取资料库失败: {}", e))?,
+        None => HashSet::new(),
+    };
+    let (existing, links): (Vec<ImportedLink>, Vec<ImportedLink>) =
+        links.into_iter().partition(|link| {
+            link.aweme_id
+                .as_ref()
+                .is_some_and(|id| library.contains(id))
+        });
+    let unresolved = links.iter().filter(|l| l.aweme_id.is_none()).count();
+
+    Ok(LinkImportPreview {
+        found,
+        links,
+        existing,
+        unresolved,
+    })
+}
+
+/// 跟随短链接跳转补全作品 ID，失败的链接保持原样
+async fn resolve_short_links(links: &mut [ImportedLink]) {
+    let parser = DouyinParser::new();
+    let pending: Vec<usize> = (0..links.len())
+        .filter(|&i| links[i].aweme_id.is_none())
+        .collect();
+
+    for chunk in pending.chunks(RESOLVE_CONCURRENCY) {
+        let resolved = futures_util::future::join_all(
+            chunk
+                .iter()
+                .map(|&i| parser.resolve_aweme_id(&links[i].url)),
+        )
+        .await;
+
+        for (&i, result) in chunk.iter().zip(resolved) {
+            match result {
+                Ok(id) => links[i].aweme_id = Some(id),
+                Err(e) => warn!("解析短链接失败 {}: {}", links[i].url, e),
+            }
+        }
+    }
+}
+
+/// 预览批量导入：提取链接、去重并标出资料库中已有的作品
+///
+/// `text` 为粘贴的分享文案或前端读取的剪贴板内容，`file_path` 支持 CSV、XLSX 与纯文本文件；
+/// `column` 为表格中链接所在列（从 0 开始），为空时扫描所有列
+#[tauri::command]
+pub async fn preview_link_import(
+    text: Option<String>,
+    file_path: Option<String>,
+    column: Option<usize>,
+) -> Result<LinkImportPreview, String> {
+    collect_links(text, file_path, column).await
+}
+
+/// 批量导入链接并解析，默认跳过资料库中已有的作品
+#[tauri::command]
+pub async fn import_links(
+    app: AppHandle,
+    text: Option<String>,
+    file_path: Option<String>,
+    column: Option<usize>,
+    include_existing: Option<bool>,
+) -> Result<BatchParseStats, String> {
+    let preview = collect_links(text, file_path, column).await?;
+
+    let mut links = preview.links;
+    if include_existing.unwrap_or(false) {
+        links.extend(preview.existing);
+    }
+    if links.is_empty() {
+        return Err("没有需要导入的新链接".to_string());
+    }
+
+    let urls: Vec<String> = links.iter().map(ImportedLink::parse_url).collect();
+    let stats = parse_douyin_links_batch(app, urls, None).await?;
+
+    // 记录解析成功的作品，下次导入时跳过
+    let imported: Vec<ImportedVideoRecord> = stats
+        .results
+        .iter()
+        .zip(&links)
+        .filter(|(result, _)| result.success)
+        .filter_map(|(result, link)| {
+            let info = result.video_info.as_ref();
+            let aweme_id = info
+                .map(|i| i.aweme_id.clone())
+                .filter(|id| !id.is_empty())
+                .or_else(|| link.aweme_id.clone())?;
+            Some(ImportedVideoRecord {
+                aweme_id,
+                link: link.url.clone(),
+                title: info.map(|i| i.title.clone()).unwrap_or_default(),
+            })
+        })
+        .collect();
+    if let Some(db) = get_database() {
+        if let Err(e) = db.add_imported_videos(&imported) {
+            warn!("保存导入记录失败: {}", e);
+        }
+    }
+
+    Ok(stats)
+}
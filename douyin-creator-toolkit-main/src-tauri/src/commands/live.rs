@@ -0,0 +1,264 @@
+// 直播录制相关命令
+// 在后台录制直播间，分段写完后按需加入转写任务队列，并把录制进度通知前端
+
+use crate::core::live_recorder::{
+    LiveRecorder, LiveResolver, LiveRoomInfo, RecordOptions, RecorderEvent, StreamFormat,
+};
+use crate::data::task_queue::TaskType;
+use crate::utils::ffmpeg::FfmpegWrapper;
+use crate::utils::paths::{get_app_paths, PathResolver};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tracing::{error, info, warn};
+
+/// 录制任务信息
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveRecordingInfo {
+    pub id: String,
+    pub link: String,
+    pub title: String,
+    pub anchor: String,
+    pub output_dir: String,
+    /// recording / reconnecting / finished / failed
+    pub status: String,
+    pub segments: Vec<String>,
+    pub transcribe: bool,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    pub error: Option<String>,
+}
+
+struct Recording {
+    info: LiveRecordingInfo,
+    stop: Arc<AtomicBool>,
+}
+
+/// 本次运行中的录制任务
+static RECORDINGS: Lazy<RwLock<HashMap<String, Recording>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// 默认录制目录：导出目录下的 live 文件夹
+fn live_output_dir() -> PathBuf {
+    let export_path = crate::commands::settings::get_config_manager()
+        .map(|m| m.get().default_export_path)
+        .unwrap_or_default();
+    if !export_path.is_empty() {
+        return PathBuf::from(export_path).join("live");
+    }
+
+    get_app_paths()
+        .map(|paths| paths.data_dir.join("live"))
+        .unwrap_or_else(|_| PathBuf::from("live"))
+}
+
+/// 更新录制任务信息
+fn update_recording(id: &str, update: impl FnOnce(&mut LiveRecordingInfo)) {
+    if let Some(recording) = RECORDINGS.write().get_mut(id) {
+        update(&mut recording.info);
+    }
+}
+
+/// 把录制完成的分段加入转写队列
+async fn enqueue_segment(app: &AppHandle, recording_id: &str, path: &Path) {
+    let video_path = path.to_string_lossy().to_string();
+    let video_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| video_path.clone());
+
+    let task_id = crate::commands::task_queue::get_task_queue()
+        .add_task(TaskType::VideoTranscription {
+            video_path: video_path.clone(),
+            video_name,
+        })
+        .await;
+    info!("直播分段已加入转写队列: {} (任务 {})", video_path, task_id);
+
+    let _ = app.emit(
+        "live:segment-queued",
+        serde_json::json!({
+            "recording_id": recording_id,
+            "task_id": task_id,
+            "video_path": video_path,
+        }),
+    );
+}
+
+/// 解析直播间信息与拉流地址
+#[tauri::command]
+pub async fn resolve_live_room(link: String) -> Result<LiveRoomInfo, String> {
+    LiveResolver::new()
+        .resolve(&link)
+        .await
+        .map_err(|e| format!("解析直播间失败: {}", e))
+}
+
+/// 开始录制直播间，返回录制任务信息
+///
+/// 分段默认 30 分钟一段、默认加入转写队列；断流后自动重连，下播或手动停止后结束
+#[tauri::command]
+pub async fn start_live_recording(
+    app: AppHandle,
+    link: String,
+    output_dir: Option<String>,
+    segment_minutes: Option<u32>,
+    prefer_hls: Option<bool>,
+    transcribe: Option<bool>,
+) -> Result<LiveRecordingInfo, String> {
+    let ffmpeg = FfmpegWrapper::new().map_err(|e| format!("启动录制失败: {}", e))?;
+
+    // 先解析一次，未开播或链接无效时直接返回错误
+    let room = LiveResolver::new()
+        .resolve(&link)
+        .await
+        .map_err(|e| format!("解析直播间失败: {}", e))?;
+    if !room.is_live {
+        return Err("直播间未开播".to_string());
+    }
+
+    let output_dir = output_dir
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(live_output_dir);
+    let name = if room.anchor.is_empty() {
+        room.web_rid.clone()
+    } else {
+        room.anchor.clone()
+    };
+    let prefix = format!(
+        "{}_{}",
+        PathResolver::sanitize_filename(if name.is_empty() { "live" } else { &name }),
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    );
+
+    let mut options = RecordOptions::new(output_dir.clone(), &prefix);
+    if let Some(minutes) = segment_minutes.filter(|m| *m > 0) {
+        options.segment_secs = minutes as u64 * 60;
+    }
+    if prefer_hls.unwrap_or(false) {
+        options.prefer_format = Some(StreamFormat::Hls);
+    }
+
+    let recorder = LiveRecorder::new(ffmpeg, LiveResolver::new(), options);
+    let id = format!("live_{}", chrono::Utc::now().timestamp_millis());
+    let info = LiveRecordingInfo {
+        id: id.clone(),
+        link: link.clone(),
+        title: room.title.clone(),
+        anchor: room.anchor.clone(),
+        output_dir: output_dir.to_string_lossy().to_string(),
+        status: "recording".to_string(),
+        segments: Vec::new(),
+        transcribe: transcribe.unwrap_or(true),
+        started_at: chrono::Utc::now().timestamp(),
+        finished_at: None,
+        error: None,
+    };
+    RECORDINGS.write().insert(
+        id.clone(),
+        Recording {
+            info: info.clone(),
+            stop: recorder.stop_handle(),
+        },
+    );
+    info!("开始录制直播: {} ({})", link, id);
+
+    let transcribe = info.transcribe;
+    tauri::async_runtime::spawn(async move {
+        let event_app = app.clone();
+        let event_id = id.clone();
+        let result = recorder
+            .record(&link, move |event| {
+                match &event {
+                    RecorderEvent::Started { .. } => {
+                        update_recording(&event_id, |info| info.status = "recording".to_string());
+                    }
+                    RecorderEvent::SegmentCompleted { path } => {
+                        update_recording(&event_id, |info| {
+                            info.segments.push(path.to_string_lossy().to_string())
+                        });
+                        if transcribe {
+                            let app = event_app.clone();
+                            let id = event_id.clone();
+                            let path = path.clone();
+                            tauri::async_runtime::spawn(async move {
+                                enqueue_segment(&app, &id, &path).await;
+                            });
+                        }
+                    }
+                    RecorderEvent::Interrupted { reason, .. } => {
+                        warn!("直播 {} 断流，准备重连: {}", event_id, reason);
+                        update_recording(&event_id, |info| {
+                            info.status = "reconnecting".to_string()
+                        });
+                    }
+                }
+                let _ = event_app.emit(
+                    "live:recording-event",
+                    serde_json::json!({ "recording_id": event_id, "event": event }),
+                );
+            })
+            .await;
+
+        let now = chrono::Utc::now().timestamp();
+        let body = match result {
+            Ok(summary) => {
+                info!(
+                    "直播录制结束 {}: {:?}，共 {} 个分段",
+                    id,
+                    summary.reason,
+                    summary.segments.len()
+                );
+                update_recording(&id, |info| {
+                    info.status = "finished".to_string();
+                    info.finished_at = Some(now);
+                });
+                format!("已保存 {} 个分段", summary.segments.len())
+            }
+            Err(e) => {
+                error!("直播录制失败 {}: {}", id, e);
+                update_recording(&id, |info| {
+                    info.status = "failed".to_string();
+                    info.finished_at = Some(now);
+                    info.error = Some(e.to_string());
+                });
+                format!("录制失败: {}", e)
+            }
+        };
+
+        let info = RECORDINGS.read().get(&id).map(|r| r.info.clone());
+        let _ = app.emit("live:recording-finished", &info);
+        crate::core::tray::send_notification(&app, "直播录制结束", &body);
+    });
+
+    Ok(info)
+}
+
+/// 停止录制，当前分段写完后结束
+#[tauri::command]
+pub async fn stop_live_recording(id: String) -> Result<(), String> {
+    match RECORDINGS.read().get(&id) {
+        Some(recording) => {
+            recording.stop.store(true, Ordering::SeqCst);
+            info!("停止录制直播: {}", id);
+            Ok(())
+        }
+        None => Err(format!("停止录制失败: 录制任务 {} 不存在", id)),
+    }
+}
+
+/// 列出本次运行中的录制任务（最新的在前）
+#[tauri::command]
+pub async fn list_live_recordings() -> Result<Vec<LiveRecordingInfo>, String> {
+    let mut list: Vec<LiveRecordingInfo> =
+        RECORDINGS.read().values().map(|r| r.info.clone()).collect();
+    list.sort_by(|a, b| b.started_at.cmp(&a.started_at).then(b.id.cmp(&a.id)));
+    Ok(list)
+}
@@ -1,16 +1,25 @@
 // MCP 服务相关命令
 
 use crate::core::asr_engine::{AsrConfig, AsrEngine};
+use crate::core::comment_analytics::{self, CommentAnalytics};
+use crate::core::douyin_parser::{
+    DouyinComment, DouyinError, DouyinParser, PublishTimeFilter, SearchQuery, SearchSort,
+};
 use crate::core::mcp_client::{
     BatchProgress, DouyinVideoInfo, LinkParseResult, McpClient, McpConfig, SearchResult,
 };
+use crate::core::parse_cache::{self, link_key, CachedParse, ParseCacheStore};
+use crate::core::session_manager::{session_manager, SessionManager, SessionStatus};
 use crate::core::video_processor::VideoProcessor;
+use crate::data::{CommentRecord, ParseCacheRecord};
 use crate::utils::paths::get_app_paths;
 use once_cell::sync::Lazy;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
+use tracing::warn;
 
 /// 全局 MCP 客户端实例
 static MCP_CLIENT: Lazy<RwLock<McpClient>> = Lazy::new(|| RwLock::new(McpClient::default()));
@@ -35,14 +44,79 @@ pub struct BatchParseStats {
     pub results: Vec<LinkParseResult>,
 }
 
+/// 基于数据库的解析结果缓存，数据库不可用时按未命中处理
+struct DatabaseParseCache;
+
+impl ParseCacheStore for DatabaseParseCache {
+    fn get(&self, aweme_id: &str) -> Option<CachedParse> {
+        let record = crate::commands::settings::get_database()?
+            .get_parse_cache(aweme_id)
+            .unwrap_or_else(|e| {
+                warn!("读取解析缓存失败: {}", e);
+                None
+            })?;
+        let data = serde_json::from_str(&record.data).ok()?;
+        Some(CachedParse {
+            data,
+            fetched_at: record.fetched_at,
+        })
+    }
+
+    fn put(&self, entry: &CachedParse) {
+        let db = match crate::commands::settings::get_database() {
+            Some(db) => db,
+            None => return,
+        };
+        let data = match serde_json::to_string(&entry.data) {
+            Ok(data) => data,
+            Err(_) => return,
+        };
+        let record = ParseCacheRecord {
+            aweme_id: entry.data.aweme_id.clone(),
+            data,
+            fetched_at: entry.fetched_at,
+        };
+        if let Err(e) = db.save_parse_cache(&record) {
+            warn!("保存解析缓存失败: {}", e);
+        }
+    }
+
+    fn resolve_link(&self, link: &str) -> Option<String> {
+        crate::commands::settings::get_database()?
+            .get_cached_link(&link_key(link))
+            .unwrap_or_else(|e| {
+                warn!("读取链接映射失败: {}", e);
+                None
+            })
+    }
+
+    fn put_link(&self, link: &str, aweme_id: &str) {
+        if let Some(db) = crate::commands::settings::get_database() {
+            if let Err(e) = db.save_cached_link(&link_key(link), aweme_id) {
+                warn!("保存链接映射失败: {}", e);
+            }
+        }
+    }
+}
+
+/// 启用数据库解析结果缓存（需在数据层初始化后调用）
+pub fn install_parse_cache() {
+    parse_cache::install_store(Arc::new(DatabaseParseCache));
+}
+
 /// 更新 MCP 配置
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn update_mcp_config(
     dy_mcp_url: Option<String>,
     undoom_mcp_url: Option<String>,
     request_interval_ms: Option<u64>,
     max_retries: Option<u32>,
     timeout_secs: Option<u64>,
+    cache_enabled: Option<bool>,
+    cache_ttl_secs: Option<u64>,
+    cache_url_ttl_secs: Option<u64>,
+    cache_stats_refresh_secs: Option<u64>,
 ) -> Result<(), String> {
     let mut client = MCP_CLIENT.write();
     let mut config = client.config().clone();
@@ -62,6 +136,18 @@ pub fn update_mcp_config(
     if let Some(timeout) = timeout_secs {
         config.timeout_secs = timeout;
     }
+    if let Some(enabled) = cache_enabled {
+        config.cache_enabled = enabled;
+    }
+    if let Some(ttl) = cache_ttl_secs {
+        config.cache_ttl_secs = ttl;
+    }
+    if let Some(ttl) = cache_url_ttl_secs {
+        config.cache_url_ttl_secs = ttl;
+    }
+    if let Some(refresh) = cache_stats_refresh_secs {
+        config.cache_stats_refresh_secs = refresh;
+    }
 
     client.update_config(config);
     Ok(())
@@ -73,25 +159,42 @@ pub fn get_mcp_config() -> McpConfig {
     MCP_CLIENT.read().config().clone()
 }
 
-/// 解析单个抖音链接
+/// 清空链接解析结果缓存，返回删除的条数
 #[tauri::command]
-pub async fn parse_douyin_link(link: String) -> Result<DouyinVideoInfo, String> {
+pub fn clear_parse_cache() -> Result<usize, String> {
+    let db =
+        crate::commands::settings::get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    db.clear_parse_cache()
+        .map_err(|e| format!("清空解析缓存失败: {}", e))
+}
+
+/// 解析单个抖音链接（`bypass_cache` 为 true 时跳过缓存强制重新解析）
+#[tauri::command]
+pub async fn parse_douyin_link(
+    link: String,
+    bypass_cache: Option<bool>,
+) -> Result<DouyinVideoInfo, String> {
     // Clone the client config to create a new client for this request
     // This avoids holding the lock across await points
     let config = MCP_CLIENT.read().config().clone();
-    let client = McpClient::new(config);
+    let client = McpClient::new(config).with_cache_bypass(bypass_cache.unwrap_or(false));
 
+    // 限流熔断期间等待恢复后再解析
+    crate::utils::rate_limiter::global_limiter()
+        .wait_until_closed()
+        .await;
     client
         .parse_douyin_link(&link)
         .await
         .map_err(|e| e.to_string())
 }
 
-/// 批量解析抖音链接（带进度事件）
+/// 批量解析抖音链接（带进度事件，`bypass_cache` 为 true 时跳过缓存）
 #[tauri::command]
 pub async fn parse_douyin_links_batch(
     app: AppHandle,
     links: Vec<String>,
+    bypass_cache: Option<bool>,
 ) -> Result<BatchParseStats, String> {
     use crate::data::task_queue::TaskType;
 
@@ -106,27 +209,38 @@ pub async fn parse_douyin_links_batch(
         return Err("没有有效的链接".to_string());
     }
 
-    let total = links.len();
-
-    // 获取全局任务队列并添加任务
+    // 获取全局任务队列并以运行状态添加任务（后台执行器不会重复领取）
     let task_queue = crate::commands::task_queue::get_task_queue();
     let task_id = task_queue
-        .add_task(TaskType::LinkParsing {
+        .add_started_task(TaskType::LinkParsing {
             links: links.clone(),
         })
         .await;
 
-    // 启动任务
-    let _ = task_queue.start_next_task().await;
+    run_parse_task(&app, &task_id, links, bypass_cache.unwrap_or(false)).await
+}
+
+/// 执行已在队列中标记为运行的链接解析任务，结束时更新任务状态
+pub(crate) async fn run_parse_task(
+    app: &AppHandle,
+    task_id: &str,
+    links: Vec<String>,
+    bypass_cache: bool,
+) -> Result<BatchParseStats, String> {
+    let total = links.len();
+    let task_queue = crate::commands::task_queue::get_task_queue();
 
     // Clone the client config to create a new client for this request
     let config = MCP_CLIENT.read().config().clone();
-    let client = McpClient::new(config);
+    let client = McpClient::new(config).with_cache_bypass(bypass_cache);
+
+    // 任务暂停期间等待，已取消时不再解析
+    crate::commands::task_queue::wait_until_runnable(app, task_id).await?;
 
     // 使用进度回调
     let app_clone = app.clone();
     let links_clone = links.clone();
-    let task_id_clone = task_id.clone();
+    let task_id_clone = task_id.to_string();
 
     let results = client
         .parse_links_batch(links, move |progress: BatchProgress| {
@@ -163,11 +277,14 @@ pub async fn parse_douyin_links_batch(
 
     // 更新任务队列为完成
     task_queue
-        .complete_current_task(Some(format!("解析完成: {} 成功, {} 失败", success, failed)))
+        .complete_task_by_id(
+            task_id,
+            Some(format!("解析完成: {} 成功, {} 失败", success, failed)),
+        )
         .await;
     crate::commands::task_queue::emit_task_completed(
-        &app,
-        &task_id,
+        app,
+        task_id,
         Some(&format!("{}/{} 成功", success, total)),
     );
 
@@ -191,18 +308,29 @@ pub async fn parse_douyin_links_batch(
 }
 
 /// 搜索抖音视频
+///
+/// `offset` 为翻页位置（上一页结果中的 `cursor`）
 #[tauri::command]
 pub async fn search_douyin_videos(
     keyword: String,
     count: u32,
-    scroll_times: u32,
+    sort: Option<SearchSort>,
+    publish_time: Option<PublishTimeFilter>,
+    offset: Option<u64>,
 ) -> Result<SearchResult, String> {
     // Clone the client config to create a new client for this request
     let config = MCP_CLIENT.read().config().clone();
     let client = McpClient::new(config);
 
+    let query = SearchQuery {
+        keyword,
+        sort: sort.unwrap_or_default(),
+        publish_time: publish_time.unwrap_or_default(),
+        offset: offset.unwrap_or(0),
+        count,
+    };
     client
-        .search_videos(&keyword, count, scroll_times)
+        .search_videos(&query)
         .await
         .map_err(|e| e.to_string())
 }
@@ -220,6 +348,26 @@ pub async fn get_user_videos(user_id: String) -> Result<Vec<DouyinVideoInfo>, St
         .map_err(|e| e.to_string())
 }
 
+/// 分页获取用户视频列表，`cursor` 为上一页结果中的 `cursor`
+#[tauri::command]
+pub async fn get_user_videos_page(
+    user_id: String,
+    cursor: Option<u64>,
+    count: Option<u32>,
+) -> Result<SearchResult, String> {
+    let config = MCP_CLIENT.read().config().clone();
+    let client = McpClient::new(config);
+
+    client
+        .get_user_videos_page(
+            &user_id,
+            cursor.unwrap_or(0),
+            count.unwrap_or(crate::core::mcp_client::DEFAULT_USER_VIDEO_COUNT),
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// 检查 dy-mcp 服务是否可用
 #[tauri::command]
 pub async fn check_dy_mcp_health() -> bool {
@@ -246,6 +394,167 @@ pub fn validate_douyin_link(link: String) -> bool {
     McpClient::is_valid_douyin_link(&link)
 }
 
+/// 获取全局会话管理器
+fn get_session_manager() -> Result<Arc<SessionManager>, String> {
+    session_manager().ok_or_else(|| "应用目录未初始化".to_string())
+}
+
+/// 导入抖音 Cookie（Netscape cookies.txt 或 JSON 导出），传入文本或文件路径
+#[tauri::command]
+pub fn import_douyin_cookies(
+    content: Option<String>,
+    file_path: Option<String>,
+) -> Result<SessionStatus, String> {
+    let content = match (content, file_path) {
+        (Some(content), _) if !content.trim().is_empty() => content,
+        (_, Some(path)) => {
+            std::fs::read_to_string(&path).map_err(|e| format!("读取 Cookie 文件失败: {}", e))?
+        }
+        _ => return Err("请提供 Cookie 内容或文件".to_string()),
+    };
+
+    let manager = get_session_manager()?;
+    manager
+        .import(&content)
+        .map_err(|e| format!("导入 Cookie 失败: {}", e))?;
+    Ok(manager.status())
+}
+
+/// 获取抖音登录会话状态
+#[tauri::command]
+pub fn get_douyin_session_status() -> Result<SessionStatus, String> {
+    Ok(get_session_manager()?.status())
+}
+
+/// 清除抖音登录会话
+#[tauri::command]
+pub fn clear_douyin_session() -> Result<(), String> {
+    get_session_manager()?
+        .clear()
+        .map_err(|e| format!("清除会话失败: {}", e))
+}
+
+/// 默认抓取的一级评论数
+const DEFAULT_MAX_COMMENTS: usize = 200;
+/// 评论分析默认返回的条目数
+const DEFAULT_ANALYTICS_TOP_N: usize = 20;
+
+impl From<DouyinComment> for CommentRecord {
+    fn from(c: DouyinComment) -> Self {
+        Self {
+            cid: c.cid,
+            aweme_id: c.aweme_id,
+            parent_id: c.parent_id,
+            text: c.text,
+            author: c.author,
+            author_id: c.author_id,
+            likes: c.likes,
+            reply_count: c.reply_count,
+            create_time: c.create_time,
+            ip_location: c.ip_location,
+        }
+    }
+}
+
+impl From<CommentRecord> for DouyinComment {
+    fn from(r: CommentRecord) -> Self {
+        Self {
+            cid: r.cid,
+            aweme_id: r.aweme_id,
+            parent_id: r.parent_id,
+            text: r.text,
+            author: r.author,
+            author_id: r.author_id,
+            likes: r.likes,
+            reply_count: r.reply_count,
+            create_time: r.create_time,
+            ip_location: r.ip_location,
+        }
+    }
+}
+
+/// 读取已保存的评论
+fn load_comments(aweme_id: &str) -> Result<Vec<DouyinComment>, String> {
+    let db =
+        crate::commands::settings::get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    let records = db
+        .list_comments(aweme_id)
+        .map_err(|e| format!("读取评论失败: {}", e))?;
+    Ok(records.into_iter().map(DouyinComment::from).collect())
+}
+
+/// 抓取视频评论（可含回复）并保存到数据库
+#[tauri::command]
+pub async fn fetch_douyin_comments(
+    link: String,
+    max_comments: Option<usize>,
+    include_replies: Option<bool>,
+) -> Result<Vec<DouyinComment>, String> {
+    let parser = DouyinParser::new();
+    let aweme_id = parser
+        .resolve_aweme_id(&link)
+        .await
+        .map_err(|e| format!("解析链接失败: {}", e))?;
+
+    let comments = match parser
+        .fetch_comments(
+            &aweme_id,
+            max_comments.unwrap_or(DEFAULT_MAX_COMMENTS),
+            include_replies.unwrap_or(true),
+        )
+        .await
+    {
+        Ok(comments) => comments,
+        Err(DouyinError::AuthRequired) => {
+            // 评论接口需要登录，Cookie 被拒绝时标记会话失效以提示刷新
+            if let Some(session) = session_manager() {
+                session.mark_invalid();
+            }
+            return Err("需要登录验证，请导入或刷新抖音 Cookie".to_string());
+        }
+        Err(e) => return Err(format!("抓取评论失败: {}", e)),
+    };
+
+    let db =
+        crate::commands::settings::get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    let records: Vec<CommentRecord> = comments.iter().cloned().map(CommentRecord::from).collect();
+    db.save_comments(&records)
+        .map_err(|e| format!("保存评论失败: {}", e))?;
+
+    Ok(comments)
+}
+
+/// 获取已保存的视频评论
+#[tauri::command]
+pub fn get_douyin_comments(aweme_id: String) -> Result<Vec<DouyinComment>, String> {
+    load_comments(&aweme_id)
+}
+
+/// 分析已保存的视频评论
+#[tauri::command]
+pub fn analyze_douyin_comments(
+    aweme_id: String,
+    top_n: Option<usize>,
+) -> Result<CommentAnalytics, String> {
+    let comments = load_comments(&aweme_id)?;
+    if comments.is_empty() {
+        return Err("没有已保存的评论，请先抓取评论".to_string());
+    }
+    Ok(comment_analytics::analyze(
+        &comments,
+        top_n.unwrap_or(DEFAULT_ANALYTICS_TOP_N),
+    ))
+}
+
+/// 导出已保存的视频评论为 CSV（带 BOM，Excel 可直接打开）
+#[tauri::command]
+pub fn export_douyin_comments_csv(aweme_id: String, output_path: String) -> Result<String, String> {
+    let comments = load_comments(&aweme_id)?;
+    let csv = format!("\u{feff}{}", comment_analytics::to_csv(&comments));
+    std::fs::write(&output_path, csv).map_err(|e| format!("导出评论失败: {}", e))?;
+    Ok(output_path)
+}
+
 /// 提取抖音视频文案（下载 -> 提取音频 -> 转写）
 #[tauri::command]
 pub async fn extract_douyin_content(
@@ -0,0 +1,258 @@
+// 互动数据追踪相关命令
+// 后台定期重新采样已追踪作品的点赞/评论/分享数，提供增长曲线和爆发作品排行
+
+use crate::commands::settings::get_database;
+use crate::core::douyin_parser::DouyinParser;
+use crate::core::engagement_metrics::{
+    compute_growth, is_retry_due, is_sample_due, GrowthStats, MetricSample,
+};
+use crate::core::mcp_client::{DouyinVideoInfo, McpClient};
+use crate::data::{Database, MetricSampleRecord, TrackedVideoRecord};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+/// 后台循环检查到期作品的间隔（秒）
+const METRICS_TICK_SECS: u64 = 300;
+
+/// 后台采样循环是否已启动
+static SAMPLER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 追踪作品概览（用于列表和爆发排行）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedVideoSummary {
+    pub video: TrackedVideoRecord,
+    /// 最近一次采样
+    pub latest: Option<MetricSample>,
+    pub growth: GrowthStats,
+}
+
+/// 作品互动数据曲线
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoMetricCurve {
+    pub aweme_id: String,
+    /// 采样点（按时间升序）
+    pub samples: Vec<MetricSample>,
+    pub growth: GrowthStats,
+}
+
+impl From<MetricSampleRecord> for MetricSample {
+    fn from(r: MetricSampleRecord) -> Self {
+        Self {
+            sampled_at: r.sampled_at,
+            likes: r.likes,
+            comments: r.comments,
+            shares: r.shares,
+            collects: r.collects,
+            plays: r.plays,
+        }
+    }
+}
+
+/// 启动互动数据采样后台任务（需在数据层初始化后调用）
+pub fn start_metrics_sampler(app: AppHandle) {
+    if SAMPLER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        info!("互动数据采样后台任务已启动");
+
+        loop {
+            tokio::time::sleep(Duration::from_secs(METRICS_TICK_SECS)).await;
+
+            let db = match get_database() {
+                Some(db) => db,
+                None => continue,
+            };
+
+            let now = chrono::Utc::now().timestamp();
+            let due: Vec<TrackedVideoRecord> = db
+                .list_tracked_videos()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|v| {
+                    v.enabled
+                        && is_sample_due(v.create_time, v.last_sampled_at, now)
+                        && is_retry_due(v.failed_attempts, v.last_attempt_at, now)
+                })
+                .collect();
+
+            let sampled = sample_videos(&db, &due).await;
+            if sampled > 0 {
+                let _ = app.emit("video-metrics:sampled", sampled);
+            }
+        }
+    });
+}
+
+/// 依次采样作品，作品之间按 MCP 请求间隔等待，返回成功采样的数量
+async fn sample_videos(db: &Database, videos: &[TrackedVideoRecord]) -> usize {
+    let mcp_config = crate::commands::mcp::get_mcp_config();
+    let interval = Duration::from_millis(mcp_config.request_interval_ms);
+    // 采样需要最新的统计数据，不读取解析缓存
+    let client = McpClient::new(mcp_config).with_cache_bypass(true);
+
+    let mut sampled = 0;
+    for (i, video) in videos.iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(interval).await;
+        }
+
+        let url = format!("https://www.douyin.com/video/{}", video.aweme_id);
+        let success = match client.parse_douyin_link(&url).await {
+            Ok(info) => match save_sample(db, &video.aweme_id, &info) {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("保存作品 {} 的采样失败: {}", video.aweme_id, e);
+                    false
+                }
+            },
+            Err(e) => {
+                warn!("采样作品 {} 失败: {}", video.aweme_id, e);
+                false
+            }
+        };
+        if success {
+            sampled += 1;
+        }
+
+        // 记录本次尝试，失败的作品按退避时间重试（作品可能已删除或设为私密）
+        let now = chrono::Utc::now().timestamp();
+        if let Err(e) = db.record_sample_attempt(&video.aweme_id, now, success) {
+            warn!("记录作品 {} 的采样尝试失败: {}", video.aweme_id, e);
+        }
+    }
+    sampled
+}
+
+/// 记录一次采样
+fn save_sample(db: &Database, aweme_id: &str, info: &DouyinVideoInfo) -> Result<(), String> {
+    db.add_metric_sample(&MetricSampleRecord {
+        aweme_id: aweme_id.to_string(),
+        sampled_at: chrono::Utc::now().timestamp(),
+        likes: info.likes,
+        comments: info.comments,
+        shares: info.shares,
+        collects: info.collects,
+        plays: info.plays,
+    })
+    .map_err(|e| format!("保存采样失败: {}", e))
+}
+
+/// 读取作品的采样并计算增长
+fn load_curve(db: &Database, video: &TrackedVideoRecord) -> Result<VideoMetricCurve, String> {
+    let samples: Vec<MetricSample> = db
+        .list_metric_samples(&video.aweme_id)
+        .map_err(|e| format!("读取采样失败: {}", e))?
+        .into_iter()
+        .map(MetricSample::from)
+        .collect();
+    let growth = compute_growth(video.create_time, &samples);
+
+    Ok(VideoMetricCurve {
+        aweme_id: video.aweme_id.clone(),
+        samples,
+        growth,
+    })
+}
+
+/// 开始追踪作品的互动数据（立即采样一次）
+#[tauri::command]
+pub async fn track_video_metrics(link: String) -> Result<TrackedVideoSummary, String> {
+    let aweme_id = DouyinParser::new()
+        .resolve_aweme_id(&link)
+        .await
+        .map_err(|e| format!("解析链接失败: {}", e))?;
+
+    let info = McpClient::new(crate::commands::mcp::get_mcp_config())
+        .with_cache_bypass(true)
+        .parse_douyin_link(&format!("https://www.douyin.com/video/{}", aweme_id))
+        .await
+        .map_err(|e| format!("获取作品数据失败: {}", e))?;
+
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    db.upsert_tracked_video(
+        &aweme_id,
+        &info.title,
+        &info.author,
+        info.create_time.map(|t| t as i64),
+    )
+    .map_err(|e| format!("保存追踪作品失败: {}", e))?;
+    save_sample(&db, &aweme_id, &info)?;
+
+    list_video_metrics()
+        .await?
+        .into_iter()
+        .find(|s| s.video.aweme_id == aweme_id)
+        .ok_or_else(|| "保存追踪作品失败".to_string())
+}
+
+/// 停止追踪作品并删除采样数据
+#[tauri::command]
+pub async fn untrack_video_metrics(aweme_id: String) -> Result<(), String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    db.remove_tracked_video(&aweme_id)
+        .map_err(|e| format!("删除追踪作品失败: {}", e))
+}
+
+/// 暂停或恢复作品的采样
+#[tauri::command]
+pub async fn set_video_metrics_enabled(aweme_id: String, enabled: bool) -> Result<(), String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    db.set_tracked_video_enabled(&aweme_id, enabled)
+        .map_err(|e| format!("更新追踪状态失败: {}", e))
+}
+
+/// 获取追踪作品列表，按爆发系数从高到低排序
+#[tauri::command]
+pub async fn list_video_metrics() -> Result<Vec<TrackedVideoSummary>, String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    let videos = db
+        .list_tracked_videos()
+        .map_err(|e| format!("获取追踪作品失败: {}", e))?;
+
+    let mut summaries = Vec::with_capacity(videos.len());
+    for video in videos {
+        let curve = load_curve(&db, &video)?;
+        summaries.push(TrackedVideoSummary {
+            latest: curve.samples.last().copied(),
+            growth: curve.growth,
+            video,
+        });
+    }
+
+    summaries.sort_by(|a, b| {
+        let ratio = |s: &TrackedVideoSummary| s.growth.breakout_ratio.unwrap_or(0.0);
+        ratio(b).total_cmp(&ratio(a))
+    });
+    Ok(summaries)
+}
+
+/// 获取作品的互动数据曲线
+#[tauri::command]
+pub async fn get_video_metric_curve(aweme_id: String) -> Result<VideoMetricCurve, String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    let video = db
+        .list_tracked_videos()
+        .map_err(|e| format!("获取追踪作品失败: {}", e))?
+        .into_iter()
+        .find(|v| v.aweme_id == aweme_id)
+        .ok_or_else(|| format!("作品未追踪: {}", aweme_id))?;
+    load_curve(&db, &video)
+}
+
+/// 立即采样所有启用的追踪作品，返回成功采样的数量
+#[tauri::command]
+pub async fn sample_video_metrics_now() -> Result<usize, String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    let videos: Vec<TrackedVideoRecord> = db
+        .list_tracked_videos()
+        .map_err(|e| format!("获取追踪作品失败: {}", e))?
+        .into_iter()
+        .filter(|v| v.enabled)
+        .collect();
+    Ok(sample_videos(&db, &videos).await)
+}
@@ -3,21 +3,39 @@
 
 pub mod ai;
 pub mod asr;
+pub mod collection;
 pub mod gpu;
+pub mod link_import;
+pub mod live;
 pub mod mcp;
+pub mod metrics;
+pub mod monitor;
+pub mod parser_health;
+pub mod platform;
+pub mod rate_limit;
 pub mod settings;
 pub mod task_queue;
 pub mod tray;
 pub mod video;
+pub mod watcher;
 
 // 重新导出所有命令
 pub use ai::*;
 pub use asr::*;
+pub use collection::*;
 pub use gpu::{
     detect_gpu_info, get_recommended_gpu_config, validate_gpu_config, GpuConfig, RecommendedConfig,
 };
+pub use link_import::*;
+pub use live::*;
 pub use mcp::*;
+pub use metrics::*;
+pub use monitor::*;
+pub use parser_health::*;
+pub use platform::*;
+pub use rate_limit::*;
 pub use settings::*;
 pub use task_queue::*;
 pub use tray::*;
 pub use video::*;
+pub use watcher::*;
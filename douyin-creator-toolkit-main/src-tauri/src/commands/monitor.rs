@@ -0,0 +1,480 @@
+// 账号监控相关命令
+// 后台按配置的间隔检查已关注账号的作品列表，发现新作品后记录、通知，并可自动下载 → 转写 → 分析
+
+use crate::commands::settings::get_database;
+use crate::commands::task_queue::{enqueue_with_follow_up, TaskFollowUp};
+use crate::core::account_monitor::{detect_new_videos, is_due, normalize_user_id, MonitorConfig};
+use crate::core::mcp_client::{DouyinVideoInfo, McpClient};
+use crate::data::task_queue::TaskType;
+use crate::data::{AccountVideoRecord, Database, TrackedAccountRecord};
+use crate::utils::paths::get_app_paths;
+use futures_util::future::FutureExt;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{error, info, warn};
+
+/// 账号监控配置在数据库 config 表中的键名
+const MONITOR_CONFIG_KEY: &str = "account_monitor_config";
+/// 后台循环检查到期账号的间隔（秒）
+const MONITOR_TICK_SECS: u64 = 60;
+
+/// 全局监控配置（后台循环每轮读取最新值）
+static MONITOR_CONFIG: Lazy<RwLock<MonitorConfig>> =
+    Lazy::new(|| RwLock::new(MonitorConfig::default()));
+
+/// 后台监控循环是否已启动
+static MONITOR_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 从数据库加载监控配置
+fn load_monitor_config(db: &Database) -> MonitorConfig {
+    match db.get_config(MONITOR_CONFIG_KEY) {
+        Ok(Some(json)) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            warn!("账号监控配置解析失败，使用默认配置: {}", e);
+            MonitorConfig::default()
+        }),
+        Ok(None) => MonitorConfig::default(),
+        Err(e) => {
+            error!("读取账号监控配置失败: {}", e);
+            MonitorConfig::default()
+        }
+    }
+}
+
+/// 启动账号监控后台任务（需在数据层初始化后调用）
+pub fn start_account_monitor(app: AppHandle) {
+    if MONITOR_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    if let Some(db) = get_database() {
+        *MONITOR_CONFIG.write() = load_monitor_config(&db);
+        // 上次退出时未完成的自动处理已随任务队列丢失
+        match db.fail_processing_account_videos("应用退出时处理未完成") {
+            Ok(0) => {}
+            Ok(count) => info!("{} 个未完成的作品自动处理已标记为失败", count),
+            Err(e) => error!("重置作品处理状态失败: {}", e),
+        }
+    }
+
+    tauri::async_runtime::spawn(async move {
+        info!("账号监控后台任务已启动");
+
+        loop {
+            tokio::time::sleep(Duration::from_secs(MONITOR_TICK_SECS)).await;
+
+            let config = MONITOR_CONFIG.read().clone();
+            if !config.enabled {
+                continue;
+            }
+
+            let db = match get_database() {
+                Some(db) => db,
+                None => continue,
+            };
+
+            let now = chrono::Utc::now().timestamp();
+            let due: Vec<TrackedAccountRecord> = db
+                .list_tracked_accounts()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|a| a.enabled && is_due(a.last_checked_at, config.poll_interval_mins, now))
+                .collect();
+
+            check_accounts(&app, &db, &due, &config).await;
+        }
+    });
+}
+
+/// 依次检查账号，账号之间按 MCP 请求间隔等待，返回发现的新作品数
+async fn check_accounts(
+    app: &AppHandle,
+    db: &Database,
+    accounts: &[TrackedAccountRecord],
+    config: &MonitorConfig,
+) -> usize {
+    let mcp_config = crate::commands::mcp::get_mcp_config();
+    let interval = Duration::from_millis(mcp_config.request_interval_ms);
+    let client = McpClient::new(mcp_config);
+
+    let mut total = 0;
+    for (i, account) in accounts.iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(interval).await;
+        }
+        match check_account(app, db, &client, account, config).await {
+            Ok(count) => total += count,
+            Err(e) => warn!("检查账号 {} 失败: {}", account.user_id, e),
+        }
+    }
+    total
+}
+
+/// 检查单个账号的新作品
+///
+/// 首次检查只记录已有作品作为基线，不通知也不触发自动处理
+async fn check_account(
+    app: &AppHandle,
+    db: &Database,
+    client: &McpClient,
+    account: &TrackedAccountRecord,
+    config: &MonitorConfig,
+) -> Result<usize, String> {
+    let videos = client
+        .get_user_videos(&account.user_id)
+        .await
+        .map_err(|e| format!("获取作品列表失败: {}", e))?;
+
+    let known: HashSet<String> = db
+        .list_account_video_ids(&account.user_id)
+        .map_err(|e| format!("读取作品记录失败: {}", e))?
+        .into_iter()
+        .collect();
+    let baseline = account.last_checked_at.is_none();
+    // 基线必须来自非空的作品列表，否则下次检查会把全部已有作品当作新作品
+    if baseline && videos.is_empty() {
+        warn!("账号 {} 作品列表为空，暂不记录基线", account.user_id);
+        return Ok(0);
+    }
+
+    let mut new_videos: Vec<(String, DouyinVideoInfo)> = Vec::new();
+    for (video_id, video) in detect_new_videos(&videos, &known) {
+        let record = AccountVideoRecord {
+            video_id: video_id.clone(),
+            user_id: account.user_id.clone(),
+            title: video.title.clone(),
+            video_url: video.video_url.clone(),
+            cover_url: video.cover_url.clone(),
+            create_time: video.create_time.map(|t| t as i64),
+            status: if baseline { "existing" } else { "new" }.to_string(),
+            result: None,
+            discovered_at: String::new(),
+        };
+        // 并发检查时同一作品只有首次插入的一方负责通知和自动处理
+        match db.add_account_video(&record) {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                error!("记录账号作品失败 {}: {}", video_id, e);
+                continue;
+            }
+        }
+        if !baseline {
+            // 新作品自动加入互动数据追踪
+            if video_id.chars().all(|c| c.is_ascii_digit()) {
+                if let Err(e) = db.upsert_tracked_video(
+                    &video_id,
+                    &video.title,
+                    &video.author,
+                    record.create_time,
+                ) {
+                    warn!("追踪新作品互动数据失败 {}: {}", video_id, e);
+                }
+            }
+            new_videos.push((video_id, video.clone()));
+        }
+    }
+
+    db.set_account_checked(&account.user_id, chrono::Utc::now().timestamp())
+        .map_err(|e| format!("更新检查时间失败: {}", e))?;
+
+    if new_videos.is_empty() {
+        return Ok(0);
+    }
+
+    let name = if account.nickname.is_empty() {
+        &account.user_id
+    } else {
+        &account.nickname
+    };
+    info!("账号 {} 发现 {} 个新作品", name, new_videos.len());
+
+    for (video_id, video) in &new_videos {
+        let _ = app.emit(
+            "account-monitor:new-video",
+            serde_json::json!({
+                "user_id": account.user_id,
+                "video_id": video_id,
+                "title": video.title,
+                "video_url": video.video_url,
+            }),
+        );
+    }
+    if config.notify {
+        crate::core::tray::send_notification(
+            app,
+            "关注账号有新作品",
+            &format!("{} 发布了 {} 个新作品", name, new_videos.len()),
+        );
+    }
+
+    if account.auto_pipeline {
+        let output_dir = pipeline_output_dir(config);
+        for (video_id, video) in &new_videos {
+            enqueue_pipeline(app, db, video_id, video, &output_dir).await;
+        }
+    }
+
+    Ok(new_videos.len())
+}
+
+/// 自动处理流水线的下载目录
+fn pipeline_output_dir(config: &MonitorConfig) -> PathBuf {
+    if !config.download_dir.trim().is_empty() {
+        return PathBuf::from(config.download_dir.trim());
+    }
+
+    let export_path = crate::commands::settings::get_config_manager()
+        .map(|m| m.get().default_export_path)
+        .unwrap_or_default();
+    if !export_path.is_empty() {
+        return PathBuf::from(export_path).join("monitor");
+    }
+
+    get_app_paths()
+        .map(|paths| paths.data_dir.join("monitor"))
+        .unwrap_or_else(|_| PathBuf::from("monitor"))
+}
+
+/// 把新作品的自动处理加入任务队列：下载 → 转写 → AI 分析
+///
+/// 每一步都是独立的队列任务，可在任务列表中查看、重试，并遵守限流与暂停
+async fn enqueue_pipeline(
+    app: &AppHandle,
+    db: &Database,
+    video_id: &str,
+    video: &DouyinVideoInfo,
+    output_dir: &Path,
+) {
+    if let Err(e) = std::fs::create_dir_all(output_dir) {
+        finish_pipeline(app, video_id, Err(format!("创建下载目录失败: {}", e)));
+        return;
+    }
+
+    let url = if video_id.chars().all(|c| c.is_ascii_digit()) {
+        format!("https://www.douyin.com/video/{}", video_id)
+    } else {
+        video.video_url.clone()
+    };
+    let mut output = output_dir.to_string_lossy().to_string();
+    output.push(std::path::MAIN_SEPARATOR);
+    let video_name = if video.title.is_empty() {
+        video_id.to_string()
+    } else {
+        video.title.clone()
+    };
+
+    if let Err(e) = db.update_account_video_status(video_id, "processing", None) {
+        error!("更新作品处理状态失败 {}: {}", video_id, e);
+    }
+
+    let id = video_id.to_string();
+    let follow_up: TaskFollowUp =
+        Arc::new(move |app: AppHandle, result: Result<String, String>| {
+            let id = id.clone();
+            async move { on_downloaded(&app, &id, result).await }.boxed()
+        });
+    enqueue_with_follow_up(
+        TaskType::VideoDownload {
+            url,
+            output_path: output,
+            video_name,
+        },
+        follow_up,
+    )
+    .await;
+}
+
+/// 下载完成后加入转写任务（图文作品下载为图集目录，无需转写）
+async fn on_downloaded(app: &AppHandle, video_id: &str, result: Result<String, String>) {
+    let saved = match result {
+        Ok(saved) => saved,
+        Err(e) => return finish_pipeline(app, video_id, Err(e)),
+    };
+    if Path::new(&saved).is_dir() {
+        return finish_pipeline(app, video_id, Ok(serde_json::json!({ "path": saved })));
+    }
+
+    let id = video_id.to_string();
+    let path = saved.clone();
+    let follow_up: TaskFollowUp =
+        Arc::new(move |app: AppHandle, result: Result<String, String>| {
+            let id = id.clone();
+            let path = path.clone();
+            async move { on_transcribed(&app, &id, &path, result).await }.boxed()
+        });
+    let video_name = saved
+        .split(['/', '\\'])
+        .last()
+        .unwrap_or("video")
+        .to_string();
+    enqueue_with_follow_up(
+        TaskType::VideoTranscription {
+            video_path: saved,
+            video_name,
+        },
+        follow_up,
+    )
+    .await;
+}
+
+/// 转写完成后加入 AI 分析任务
+async fn on_transcribed(
+    app: &AppHandle,
+    video_id: &str,
+    path: &str,
+    result: Result<String, String>,
+) {
+    let transcript = match result {
+        Ok(text) => text,
+        Err(e) => return finish_pipeline(app, video_id, Err(e)),
+    };
+
+    let id = video_id.to_string();
+    let path = path.to_string();
+    let text = transcript.clone();
+    let follow_up: TaskFollowUp =
+        Arc::new(move |app: AppHandle, result: Result<String, String>| {
+            let (id, path, text) = (id.clone(), path.clone(), text.clone());
+            async move {
+                let result = result.map(|analysis| {
+                    let analysis = serde_json::from_str(&analysis)
+                        .unwrap_or(serde_json::Value::String(analysis));
+                    serde_json::json!({
+                        "path": path,
+                        "transcript": text,
+                        "analysis": analysis,
+                    })
+                });
+                finish_pipeline(&app, &id, result)
+            }
+            .boxed()
+        });
+    enqueue_with_follow_up(
+        TaskType::AiAnalysis {
+            content: transcript,
+            video_id: video_id.to_string(),
+        },
+        follow_up,
+    )
+    .await;
+}
+
+/// 自动处理结束，结果写回作品记录
+fn finish_pipeline(app: &AppHandle, video_id: &str, result: Result<serde_json::Value, String>) {
+    let (status, result) = match result {
+        Ok(result) => ("completed", result.to_string()),
+        Err(e) => {
+            warn!("新作品自动处理失败 {}: {}", video_id, e);
+            ("failed", e)
+        }
+    };
+
+    if let Some(db) = get_database() {
+        if let Err(e) = db.update_account_video_status(video_id, status, Some(&result)) {
+            error!("更新作品处理状态失败 {}: {}", video_id, e);
+        }
+    }
+    let _ = app.emit(
+        "account-monitor:pipeline-finished",
+        serde_json::json!({ "video_id": video_id, "status": status }),
+    );
+}
+
+/// 获取账号监控配置
+#[tauri::command]
+pub async fn get_monitor_config() -> Result<MonitorConfig, String> {
+    Ok(MONITOR_CONFIG.read().clone())
+}
+
+/// 保存账号监控配置
+#[tauri::command]
+pub async fn save_monitor_config(config: MonitorConfig) -> Result<(), String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    let json = serde_json::to_string(&config).map_err(|e| format!("序列化配置失败: {}", e))?;
+    db.set_config(MONITOR_CONFIG_KEY, &json)
+        .map_err(|e| format!("保存账号监控配置失败: {}", e))?;
+
+    info!(
+        "账号监控配置已更新: 启用={}, 间隔={} 分钟",
+        config.enabled, config.poll_interval_mins
+    );
+    *MONITOR_CONFIG.write() = config;
+    Ok(())
+}
+
+/// 添加或更新监控账号（支持 sec_uid 或主页链接）
+#[tauri::command]
+pub async fn add_tracked_account(
+    user_id: String,
+    nickname: Option<String>,
+    enabled: Option<bool>,
+    auto_pipeline: Option<bool>,
+) -> Result<TrackedAccountRecord, String> {
+    let user_id = normalize_user_id(&user_id);
+    if user_id.is_empty() {
+        return Err("账号 ID 不能为空".to_string());
+    }
+
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    db.upsert_tracked_account(
+        &user_id,
+        nickname.as_deref().unwrap_or("").trim(),
+        enabled.unwrap_or(true),
+        auto_pipeline.unwrap_or(false),
+    )
+    .map_err(|e| format!("保存监控账号失败: {}", e))?;
+
+    db.list_tracked_accounts()
+        .map_err(|e| format!("获取监控账号失败: {}", e))?
+        .into_iter()
+        .find(|a| a.user_id == user_id)
+        .ok_or_else(|| "保存监控账号失败".to_string())
+}
+
+/// 删除监控账号
+#[tauri::command]
+pub async fn remove_tracked_account(user_id: String) -> Result<(), String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    db.remove_tracked_account(&user_id)
+        .map_err(|e| format!("删除监控账号失败: {}", e))
+}
+
+/// 获取监控账号列表
+#[tauri::command]
+pub async fn list_tracked_accounts() -> Result<Vec<TrackedAccountRecord>, String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    db.list_tracked_accounts()
+        .map_err(|e| format!("获取监控账号失败: {}", e))
+}
+
+/// 获取监控账号发现的作品
+#[tauri::command]
+pub async fn list_account_videos(
+    user_id: Option<String>,
+    limit: Option<i32>,
+) -> Result<Vec<AccountVideoRecord>, String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    db.list_account_videos(user_id.as_deref(), limit.unwrap_or(100))
+        .map_err(|e| format!("获取作品记录失败: {}", e))
+}
+
+/// 立即检查所有启用的监控账号，返回发现的新作品数
+#[tauri::command]
+pub async fn check_tracked_accounts_now(app: AppHandle) -> Result<usize, String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    let accounts: Vec<TrackedAccountRecord> = db
+        .list_tracked_accounts()
+        .map_err(|e| format!("获取监控账号失败: {}", e))?
+        .into_iter()
+        .filter(|a| a.enabled)
+        .collect();
+
+    let config = MONITOR_CONFIG.read().clone();
+    Ok(check_accounts(&app, &db, &accounts, &config).await)
+}
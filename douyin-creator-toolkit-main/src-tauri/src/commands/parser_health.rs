@@ -0,0 +1,180 @@
+// 解析渠道健康度相关命令
+// 后台定期把各解析渠道的尝试记录写入数据库，渠道连续失败时发送通知，并提供健康度汇总
+
+use crate::commands::settings::get_database;
+use crate::core::strategy_health::{
+    global_tracker, summarize, ParseStrategy, StrategyAttempt, StrategyHealth,
+};
+use crate::data::{Database, StrategyAttemptRecord};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+/// 后台写入尝试记录的间隔（秒）
+const HEALTH_TICK_SECS: u64 = 60;
+/// 尝试记录保留天数
+const RETENTION_DAYS: i64 = 7;
+/// 默认的健康度统计窗口（小时）
+const DEFAULT_WINDOW_HOURS: u32 = 24;
+
+/// 后台任务是否已启动
+static RECORDER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 解析渠道健康度报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParserHealthReport {
+    /// 统计窗口（小时）
+    pub window_hours: u32,
+    /// 各渠道健康状况（按默认顺序）
+    pub strategies: Vec<StrategyHealth>,
+    /// 当前实际的尝试顺序
+    pub current_order: Vec<ParseStrategy>,
+}
+
+impl From<&StrategyAttempt> for StrategyAttemptRecord {
+    fn from(a: &StrategyAttempt) -> Self {
+        Self {
+            strategy: a.strategy.as_str().to_string(),
+            success: a.success,
+            latency_ms: a.latency_ms,
+            error: a.error.clone(),
+            attempted_at: a.attempted_at,
+        }
+    }
+}
+
+/// 转换数据库记录，未知渠道（如已移除的旧渠道）返回 None
+fn attempt_from_record(r: StrategyAttemptRecord) -> Option<StrategyAttempt> {
+    ParseStrategy::parse(&r.strategy).map(|strategy| StrategyAttempt {
+        strategy,
+        success: r.success,
+        latency_ms: r.latency_ms,
+        error: r.error,
+        attempted_at: r.attempted_at,
+    })
+}
+
+/// 启动解析渠道健康度后台任务（需在数据层初始化后调用）
+pub fn start_parser_health_recorder(app: AppHandle) {
+    if RECORDER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    // 用最近的记录恢复渠道顺序，避免每次启动都从失效渠道开始尝试
+    if let Some(db) = get_database() {
+        match load_attempts(&db, DEFAULT_WINDOW_HOURS) {
+            Ok(attempts) => global_tracker().restore(&attempts),
+            Err(e) => warn!("恢复解析渠道健康度失败: {}", e),
+        }
+    }
+
+    tauri::async_runtime::spawn(async move {
+        info!("解析渠道健康度后台任务已启动");
+        let mut degraded: HashSet<ParseStrategy> = HashSet::new();
+
+        loop {
+            tokio::time::sleep(Duration::from_secs(HEALTH_TICK_SECS)).await;
+
+            let db = match get_database() {
+                Some(db) => db,
+                None => continue,
+            };
+
+            if let Err(e) = flush_pending(&db) {
+                warn!("{}", e);
+                continue;
+            }
+            let cutoff = chrono::Utc::now().timestamp() - RETENTION_DAYS * 24 * 3600;
+            if let Err(e) = db.prune_strategy_attempts(cutoff) {
+                warn!("清理解析渠道记录失败: {}", e);
+            }
+
+            let attempts = match load_attempts(&db, DEFAULT_WINDOW_HOURS) {
+                Ok(attempts) => attempts,
+                Err(e) => {
+                    warn!("{}", e);
+                    continue;
+                }
+            };
+            notify_degraded(&app, &summarize(&attempts), &mut degraded);
+        }
+    });
+}
+
+/// 把内存中尚未保存的尝试记录写入数据库
+fn flush_pending(db: &Database) -> Result<(), String> {
+    let pending = global_tracker().take_pending();
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let records: Vec<StrategyAttemptRecord> = pending.iter().map(Into::into).collect();
+    db.add_strategy_attempts(&records)
+        .map(|_| ())
+        .map_err(|e| format!("保存解析渠道记录失败: {}", e))
+}
+
+/// 读取最近 `hours` 小时的尝试记录
+fn load_attempts(db: &Database, hours: u32) -> Result<Vec<StrategyAttempt>, String> {
+    let since = chrono::Utc::now().timestamp() - hours as i64 * 3600;
+    let records = db
+        .list_strategy_attempts(since)
+        .map_err(|e| format!("读取解析渠道记录失败: {}", e))?;
+    Ok(records
+        .into_iter()
+        .filter_map(attempt_from_record)
+        .collect())
+}
+
+/// 渠道刚进入异常状态时发送事件与系统通知，恢复后重新允许通知
+fn notify_degraded(
+    app: &AppHandle,
+    summary: &[StrategyHealth],
+    degraded: &mut HashSet<ParseStrategy>,
+) {
+    for health in summary {
+        if !health.degraded {
+            degraded.remove(&health.strategy);
+            continue;
+        }
+        if !degraded.insert(health.strategy) {
+            continue;
+        }
+
+        warn!(
+            "解析渠道 {} 连续失败 {} 次: {}",
+            health.strategy.as_str(),
+            health.consecutive_failures,
+            health.last_error.as_deref().unwrap_or("")
+        );
+        let _ = app.emit("parser-health:degraded", health);
+        crate::core::tray::send_notification(
+            app,
+            "解析渠道异常",
+            &format!(
+                "{} 已连续失败 {} 次，抖音可能调整了页面或接口",
+                health.strategy.display_name(),
+                health.consecutive_failures
+            ),
+        );
+    }
+}
+
+/// 获取解析渠道健康度汇总（默认统计最近 24 小时）
+#[tauri::command]
+pub async fn get_parser_health(hours: Option<u32>) -> Result<ParserHealthReport, String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    flush_pending(&db)?;
+
+    let window_hours = hours.unwrap_or(DEFAULT_WINDOW_HOURS).max(1);
+    let attempts = load_attempts(&db, window_hours)?;
+
+    Ok(ParserHealthReport {
+        window_hours,
+        strategies: summarize(&attempts),
+        current_order: global_tracker().ordered_strategies(),
+    })
+}
@@ -0,0 +1,61 @@
+// 多平台解析相关命令
+// 识别链接所属平台（抖音、快手、B 站），解析为统一的作品信息并下载，下载结果可直接进入转写流程
+
+use crate::core::platform::{self, Platform, PlatformRegistry, PlatformVideo};
+use crate::utils::filename_template::SourceMetadata;
+use std::path::PathBuf;
+
+impl From<&PlatformVideo> for SourceMetadata {
+    fn from(video: &PlatformVideo) -> Self {
+        Self {
+            aweme_id: video.id.clone(),
+            title: video.title.clone(),
+            author: video.author.clone(),
+            source_url: video.url.clone(),
+            create_time: video.create_time,
+        }
+    }
+}
+
+/// 解析非抖音平台的链接并下载视频，返回保存路径
+///
+/// `output_path` 为目录时按命名模板生成文件名，下载完成后写入来源元数据
+pub(crate) async fn download_platform_content(
+    link: &str,
+    output_path: &str,
+) -> Result<PathBuf, String> {
+    let video = PlatformRegistry::with_defaults()
+        .parse(link)
+        .await
+        .map_err(|e| format!("解析链接失败: {}", e))?;
+
+    let source = SourceMetadata::from(&video);
+    let output = crate::commands::video::resolve_download_path(output_path, &source);
+    platform::download_video_file(&video, &output)
+        .await
+        .map_err(|e| format!("下载视频失败: {}", e))?;
+
+    crate::commands::video::tag_downloaded_video(&output, &source).await;
+    Ok(output)
+}
+
+/// 识别链接（或分享文案）所属平台，不支持时返回 None
+#[tauri::command]
+pub async fn detect_video_platform(link: String) -> Result<Option<Platform>, String> {
+    Ok(PlatformRegistry::with_defaults().detect(&link))
+}
+
+/// 获取支持的平台列表
+#[tauri::command]
+pub async fn list_supported_platforms() -> Result<Vec<Platform>, String> {
+    Ok(PlatformRegistry::with_defaults().platforms())
+}
+
+/// 解析任意支持平台的作品链接
+#[tauri::command]
+pub async fn parse_video_link(link: String) -> Result<PlatformVideo, String> {
+    PlatformRegistry::with_defaults()
+        .parse(&link)
+        .await
+        .map_err(|e| format!("解析链接失败: {}", e))
+}
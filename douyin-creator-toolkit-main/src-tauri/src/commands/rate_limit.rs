@@ -0,0 +1,90 @@
+// 请求限速相关命令
+// 后台监控限速器的熔断状态，熔断时暂停任务队列中的网络任务并通知用户，恢复后继续
+
+use crate::commands::task_queue::{emit_task_progress, get_task_queue};
+use crate::utils::rate_limiter::{global_limiter, RateLimitStatus};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+/// 后台检查熔断状态的间隔（秒）
+const MONITOR_TICK_SECS: u64 = 2;
+
+/// 后台任务是否已启动
+static MONITOR_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 启动限流熔断监控后台任务
+pub fn start_rate_limit_monitor(app: AppHandle) {
+    if MONITOR_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        info!("限流熔断监控后台任务已启动");
+        let mut was_open = false;
+
+        loop {
+            tokio::time::sleep(Duration::from_secs(MONITOR_TICK_SECS)).await;
+
+            let status = global_limiter().status();
+            if status.circuit_open {
+                // 熔断期间新启动的网络任务也要暂停
+                set_network_tasks_paused(&app, true).await;
+                if !was_open {
+                    warn!(
+                        "抖音请求连续受限，暂停网络任务 {} 秒",
+                        status.resume_in_secs
+                    );
+                    let _ = app.emit("rate-limit:circuit-open", &status);
+                    crate::core::tray::send_notification(
+                        &app,
+                        "抖音请求已暂停",
+                        &format!(
+                            "请求过于频繁，下载与解析任务将在约 {} 分钟后自动继续",
+                            status.resume_in_secs.div_ceil(60)
+                        ),
+                    );
+                }
+            } else if was_open {
+                info!("限流熔断结束，恢复网络任务");
+                set_network_tasks_paused(&app, false).await;
+                let _ = app.emit("rate-limit:circuit-closed", &status);
+            }
+            was_open = status.circuit_open;
+        }
+    });
+}
+
+/// 暂停或恢复队列中的网络任务，并通知前端任务状态变化
+async fn set_network_tasks_paused(app: &AppHandle, paused: bool) {
+    let queue = get_task_queue();
+    let (ids, status) = if paused {
+        (queue.pause_network_tasks().await, "paused")
+    } else {
+        (queue.resume_network_tasks().await, "running")
+    };
+
+    for id in ids {
+        let progress = queue
+            .get_task(&id)
+            .await
+            .map(|task| task.progress)
+            .unwrap_or(0.0);
+        emit_task_progress(app, &id, progress, status);
+    }
+}
+
+/// 获取请求限速状态
+#[tauri::command]
+pub async fn get_rate_limit_status() -> Result<RateLimitStatus, String> {
+    Ok(global_limiter().status())
+}
+
+/// 清除退避与熔断状态，立即恢复抖音请求
+#[tauri::command]
+pub async fn reset_rate_limiter() -> Result<RateLimitStatus, String> {
+    let limiter = global_limiter();
+    limiter.reset();
+    Ok(limiter.status())
+}
@@ -1,6 +1,8 @@
 // 设置相关命令
 
+use crate::ai::service::{AiProviderType, OpenAiCompatibleConfig};
 use crate::data::{get_default_db_path, AppConfig, ConfigManager, Database};
+use crate::utils::http_client::NetworkSettings;
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -52,19 +54,12 @@ pub fn init_data_layer() -> Result<(), String> {
 
 /// 同步恢复 AI 设置 (供 init_data_layer 调用)
 fn restore_ai_settings_sync(config: crate::data::AppConfig) {
-    use crate::ai::service::AiProviderType;
     use crate::commands::ai::AI_SERVICE;
 
     let mut service = AI_SERVICE.lock();
 
     // 设置提供者类型
-    let provider_type = match config.ai_provider.as_str() {
-        "doubao" => AiProviderType::Doubao,
-        "openai" => AiProviderType::OpenAi,
-        "deepseek" => AiProviderType::DeepSeek,
-        _ => AiProviderType::LmStudio,
-    };
-    service.set_provider(provider_type);
+    service.set_provider(AiProviderType::from_key(&config.ai_provider));
 
     // 设置 API Keys
     if let Some(key) = config.doubao_api_key {
@@ -85,6 +80,7 @@ fn restore_ai_settings_sync(config: crate::data::AppConfig) {
     if !config.lm_studio_url.is_empty() {
         service.set_lm_studio_url(config.lm_studio_url);
     }
+    service.set_custom_provider(config.custom_ai_provider);
 
     info!("AI 设置已恢复");
 }
@@ -114,15 +110,26 @@ pub struct AppSettings {
     pub batch_size: u32,
 
     // AI 设置
-    pub ai_provider: String, // "doubao", "openai", "deepseek", "lmstudio"
+    pub ai_provider: String, // "doubao", "openai", "deepseek", "lmstudio", "custom"
     pub doubao_api_key: Option<String>,
     pub openai_api_key: Option<String>,
     pub deepseek_api_key: Option<String>,
     pub lm_studio_url: String,
+    /// 自定义 OpenAI 兼容服务，未传入时保留已保存的配置
+    #[serde(default)]
+    pub custom_ai_provider: Option<OpenAiCompatibleConfig>,
 
     // 网络设置
     pub request_interval: u64,
     pub max_retries: u32,
+
+    // 下载设置
+    #[serde(default)]
+    pub download_filename_template: String,
+
+    // 代理与超时设置
+    #[serde(default)]
+    pub network: NetworkSettings,
 }
 
 impl Default for AppSettings {
@@ -140,8 +147,12 @@ impl Default for AppSettings {
             openai_api_key: None,
             deepseek_api_key: None,
             lm_studio_url: "http://localhost:1234".to_string(),
+            custom_ai_provider: None,
             request_interval: 1000,
             max_retries: 3,
+            download_filename_template: crate::utils::filename_template::DEFAULT_FILENAME_TEMPLATE
+                .to_string(),
+            network: NetworkSettings::default(),
         }
     }
 }
@@ -161,8 +172,11 @@ impl From<AppConfig> for AppSettings {
             openai_api_key: config.openai_api_key,
             deepseek_api_key: config.deepseek_api_key,
             lm_studio_url: config.lm_studio_url,
+            custom_ai_provider: Some(config.custom_ai_provider),
             request_interval: config.request_interval,
             max_retries: config.max_retries,
+            download_filename_template: config.download_filename_template,
+            network: config.network,
         }
     }
 }
@@ -182,8 +196,11 @@ impl From<AppSettings> for AppConfig {
             openai_api_key: settings.openai_api_key,
             deepseek_api_key: settings.deepseek_api_key,
             lm_studio_url: settings.lm_studio_url,
+            custom_ai_provider: settings.custom_ai_provider.unwrap_or_default(),
             request_interval: settings.request_interval,
             max_retries: settings.max_retries,
+            download_filename_template: settings.download_filename_template,
+            network: settings.network,
         }
     }
 }
@@ -199,11 +216,25 @@ pub async fn get_settings() -> Result<AppSettings, String> {
 
 /// 保存应用设置
 #[tauri::command]
-pub async fn save_settings(settings: AppSettings) -> Result<(), String> {
+pub async fn save_settings(mut settings: AppSettings) -> Result<(), String> {
     info!("保存应用设置...");
 
     let config_manager = get_config_manager().ok_or_else(|| "配置管理器未初始化".to_string())?;
 
+    settings
+        .network
+        .validate()
+        .map_err(|e| format!("网络设置无效: {}", e))?;
+
+    let custom_ai_provider = settings
+        .custom_ai_provider
+        .get_or_insert_with(|| config_manager.get().custom_ai_provider);
+    if AiProviderType::from_key(&settings.ai_provider) == AiProviderType::Custom {
+        custom_ai_provider
+            .validate()
+            .map_err(|e| format!("自定义 AI 服务设置无效: {}", e))?;
+    }
+
     let config = AppConfig::from(settings.clone());
     config_manager.update(config).map_err(|e| {
         error!("保存设置失败: {}", e);
@@ -217,6 +248,7 @@ pub async fn save_settings(settings: AppSettings) -> Result<(), String> {
         openai_api_key: settings.openai_api_key,
         deepseek_api_key: settings.deepseek_api_key,
         lm_studio_url: settings.lm_studio_url,
+        custom_provider: settings.custom_ai_provider,
     };
     crate::commands::ai::update_ai_settings(ai_settings).await?;
 
@@ -1,14 +1,36 @@
 // 任务队列相关命令
 
 use crate::data::task_queue::{TaskQueue, Task, TaskType, TaskStatus, QueueStats};
+use crate::utils::rate_limiter::global_limiter;
+use futures_util::future::BoxFuture;
 use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
 
 // 全局任务队列实例
 // TaskQueue 内部使用 Arc<RwLock<...>>，本身就是线程安全的，无需外层 Mutex
 static TASK_QUEUE: Lazy<TaskQueue> = Lazy::new(TaskQueue::new);
 
+/// 后台执行器空闲时检查队列的间隔（毫秒）
+const RUNNER_TICK_MS: u64 = 1000;
+
+/// 后台任务执行器是否已启动
+static RUNNER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 任务结束后的后续处理，参数为任务结果（成功时为任务输出，失败时为错误信息）
+pub type TaskFollowUp =
+    Arc<dyn Fn(AppHandle, Result<String, String>) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// 任务 ID → 后续处理（任务重试时随新任务 ID 迁移）
+static FOLLOW_UPS: Lazy<Mutex<HashMap<String, TaskFollowUp>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 /// 任务信息（用于前端展示）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskInfo {
@@ -126,6 +148,19 @@ pub async fn add_analysis_task(
     Ok(id)
 }
 
+/// 重试失败或已取消的任务，返回新任务 ID
+#[tauri::command]
+pub async fn retry_task(task_id: String) -> Result<String, String> {
+    let new_id = TASK_QUEUE.retry_task(&task_id).await
+        .map_err(|e| format!("重试任务失败: {}", e))?;
+
+    let mut follow_ups = FOLLOW_UPS.lock();
+    if let Some(follow_up) = follow_ups.remove(&task_id) {
+        follow_ups.insert(new_id.clone(), follow_up);
+    }
+    Ok(new_id)
+}
+
 /// 暂停任务
 #[tauri::command]
 pub async fn pause_task(task_id: String) -> Result<(), String> {
@@ -142,9 +177,11 @@ pub async fn resume_task(task_id: String) -> Result<(), String> {
 
 /// 取消任务
 #[tauri::command]
-pub async fn cancel_task(task_id: String) -> Result<(), String> {
+pub async fn cancel_task(app: AppHandle, task_id: String) -> Result<(), String> {
     TASK_QUEUE.cancel_task(&task_id).await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    cancel_follow_up(&app, &task_id).await;
+    Ok(())
 }
 
 /// 获取任务状态
@@ -207,13 +244,24 @@ pub async fn get_current_task() -> Result<Option<TaskInfo>, String> {
 #[tauri::command]
 pub async fn clear_task_history() -> Result<(), String> {
     TASK_QUEUE.clear_history().await;
+    prune_follow_ups().await;
     Ok(())
 }
 
 /// 清空待处理任务
 #[tauri::command]
-pub async fn clear_pending_tasks() -> Result<(), String> {
+pub async fn clear_pending_tasks(app: AppHandle) -> Result<(), String> {
+    let task_ids: Vec<String> = TASK_QUEUE.list_tasks().await
+        .into_iter()
+        .map(|task| task.id)
+        .collect();
     TASK_QUEUE.clear_pending().await;
+    // 当前任务不会被清空，只处理已移出队列的任务
+    for task_id in task_ids {
+        if TASK_QUEUE.get_task(&task_id).await.is_none() {
+            cancel_follow_up(&app, &task_id).await;
+        }
+    }
     Ok(())
 }
 
@@ -242,6 +290,143 @@ pub fn emit_task_failed(app: &AppHandle, task_id: &str, error: &str) {
     }));
 }
 
+/// 加入任务并登记任务结束后的后续处理，返回任务 ID
+pub async fn enqueue_with_follow_up(task_type: TaskType, follow_up: TaskFollowUp) -> String {
+    let id = TASK_QUEUE.add_task(task_type).await;
+    FOLLOW_UPS.lock().insert(id.clone(), follow_up);
+    id
+}
+
+/// 启动后台任务执行器，依次执行队列中的待处理任务
+pub fn start_task_runner(app: AppHandle) {
+    if RUNNER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        info!("任务执行器已启动");
+
+        loop {
+            // 限流熔断期间网络任务保持待处理，先执行本地任务
+            let circuit_open = global_limiter().status().circuit_open;
+            let task = match TASK_QUEUE.claim_next_pending(circuit_open).await {
+                Some(task) => task,
+                None => {
+                    tokio::time::sleep(Duration::from_millis(RUNNER_TICK_MS)).await;
+                    continue;
+                }
+            };
+
+            emit_task_progress(&app, &task.id, 0.0, "running");
+            let result = run_task(&app, &task).await;
+            finish_task(&app, &task.id, &result).await;
+
+            // 失败的任务保留后续处理，以便重试后继续
+            let follow_up = if result.is_ok() {
+                FOLLOW_UPS.lock().remove(&task.id)
+            } else {
+                FOLLOW_UPS.lock().get(&task.id).cloned()
+            };
+            if let Some(follow_up) = follow_up {
+                follow_up(app.clone(), result).await;
+            }
+            prune_follow_ups().await;
+        }
+    });
+}
+
+/// 任务被取消时移除其后续处理，并以取消结果执行一次，让后续处理记录最终状态
+async fn cancel_follow_up(app: &AppHandle, task_id: &str) {
+    let follow_up = FOLLOW_UPS.lock().remove(task_id);
+    if let Some(follow_up) = follow_up {
+        follow_up(app.clone(), Err("任务已取消".to_string())).await;
+    }
+}
+
+/// 移除已不在队列和历史记录中的任务的后续处理（失败任务被移出历史后无法再重试）
+async fn prune_follow_ups() {
+    // 先取键再逐个检查，检查期间新登记的后续处理不受影响
+    let task_ids: Vec<String> = FOLLOW_UPS.lock().keys().cloned().collect();
+    for task_id in task_ids {
+        if TASK_QUEUE.get_task(&task_id).await.is_none() {
+            FOLLOW_UPS.lock().remove(&task_id);
+        }
+    }
+}
+
+/// 按任务类型执行，返回任务输出
+async fn run_task(app: &AppHandle, task: &Task) -> Result<String, String> {
+    match &task.task_type {
+        TaskType::VideoDownload { url, output_path, .. } => {
+            crate::commands::video::run_download_task(
+                app,
+                &task.id,
+                &task.id,
+                url,
+                output_path,
+                None,
+            )
+            .await
+        }
+        TaskType::VideoTranscription { video_path, .. } => {
+            crate::commands::video::run_transcription_task(app, &task.id, video_path, &task.id)
+                .await
+                .map(|result| result.text)
+        }
+        TaskType::LinkParsing { links } => {
+            let stats =
+                crate::commands::mcp::run_parse_task(app, &task.id, links.clone(), false).await?;
+            Ok(format!("解析完成: {} 成功, {} 失败", stats.success, stats.failed))
+        }
+        TaskType::AiAnalysis { content, .. } => {
+            let analysis = crate::commands::ai::analyze_content(content.clone()).await?;
+            serde_json::to_string(&analysis).map_err(|e| format!("序列化分析结果失败: {}", e))
+        }
+    }
+}
+
+/// 将仍在队列中的任务标记为完成或失败（执行函数已自行结束的任务不会重复发送事件）
+async fn finish_task(app: &AppHandle, task_id: &str, result: &Result<String, String>) {
+    match result {
+        Ok(output) => {
+            if TASK_QUEUE.complete_task_by_id(task_id, Some(output.clone())).await.is_some() {
+                emit_task_completed(app, task_id, Some(output));
+            }
+        }
+        Err(e) => {
+            warn!("任务 {} 执行失败: {}", task_id, e);
+            if TASK_QUEUE.fail_task_by_id(task_id, e.clone()).await.is_some() {
+                emit_task_failed(app, task_id, e);
+            }
+        }
+    }
+}
+
+/// 网络任务访问抖音前调用：限流熔断或任务被暂停期间等待，任务已取消时返回错误
+pub async fn wait_until_runnable(app: &AppHandle, task_id: &str) -> Result<(), String> {
+    loop {
+        let task = match TASK_QUEUE.get_task(task_id).await {
+            Some(task) => task,
+            None => return Err("任务不存在".to_string()),
+        };
+        match task.status {
+            TaskStatus::Running => {}
+            TaskStatus::Paused => {
+                tokio::time::sleep(Duration::from_millis(RUNNER_TICK_MS)).await;
+                continue;
+            }
+            _ => return Err("任务已取消".to_string()),
+        }
+
+        let limiter = global_limiter();
+        if !limiter.status().circuit_open {
+            return Ok(());
+        }
+        emit_task_progress(app, task_id, task.progress, "paused");
+        limiter.wait_until_closed().await;
+    }
+}
+
 /// 获取全局任务队列实例（供其他模块使用）
 pub fn get_task_queue() -> &'static TaskQueue {
     &TASK_QUEUE
@@ -1,14 +1,25 @@
 // 视频处理相关命令
 // Requirements: 2.1-2.12, 6.1-6.3
 
+use crate::ai::onnx_ocr::{self, BgrFrame, OnnxOcr};
 use crate::core::asr_engine::{AsrConfig, AsrEngine};
+use crate::core::douyin_parser::{ContentType, QualityPreference};
+use crate::core::duplicate_detector::{
+    self, DuplicateCluster, DuplicateMatch, MediaFingerprint, DEFAULT_DUPLICATE_THRESHOLD,
+};
+use crate::core::ocr_timeline::{self, FrameText, OnScreenText};
+use crate::core::shot_pacing::{self, CreatorPacingSummary, ShotPacing};
+use crate::core::video_processor::VideoProcessor;
 use crate::utils::ffmpeg::FfmpegWrapper;
+use crate::utils::filename_template::{self, SourceMetadata};
 use crate::utils::paths::get_app_paths;
+use crate::utils::rate_limiter::global_limiter;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use futures_util::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Emitter, Manager};
+use tracing::warn;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct VideoInfo {
@@ -37,6 +48,20 @@ pub struct TranscriptResult {
     pub video_id: String,
     pub text: String,
     pub duration_ms: u64,
+    /// 画面文字（OCR 模型未安装时为空）
+    #[serde(default)]
+    pub on_screen_text: Vec<OnScreenText>,
+    /// 转写前检测到的近似重复视频
+    #[serde(default)]
+    pub duplicates: Vec<DuplicateMatch>,
+}
+
+/// 检测到近似重复视频时发送的事件
+#[derive(Debug, Serialize, Clone)]
+pub struct DuplicatesDetected {
+    pub video_id: String,
+    pub path: String,
+    pub matches: Vec<DuplicateMatch>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -271,21 +296,47 @@ pub async fn transcribe_video(
         .last()
         .unwrap_or("video")
         .to_string();
+    // 直接以运行状态加入队列 (使用 ID-based 方式支持并发，后台执行器不会重复领取)
     let task_id = task_queue
-        .add_task(TaskType::VideoTranscription {
+        .add_started_task(TaskType::VideoTranscription {
             video_path: video_path.clone(),
-            video_name: video_name.clone(),
+            video_name,
         })
         .await;
 
-    // 启动任务 (使ID-based 方式支持并发)
-    let _ = task_queue.start_task_by_id(&task_id).await;
+    run_transcription_task(&app, &task_id, &video_path, &video_id).await
+}
+
+/// 执行已在队列中标记为运行的转写任务，结束时更新任务状态
+pub(crate) async fn run_transcription_task(
+    app: &AppHandle,
+    task_id: &str,
+    video_path: &str,
+    video_id: &str,
+) -> Result<TranscriptResult, String> {
+    let app = app.clone();
+    let task_queue = crate::commands::task_queue::get_task_queue();
+    let task_id = task_id.to_string();
+    let video_path = video_path.to_string();
+    let video_id = video_id.to_string();
 
     let temp_dir = get_temp_dir(&app);
     std::fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
 
     let audio_path = temp_dir.join(format!("{}.wav", &video_id));
 
+    // 0. 重复检测（失败不影响转写）
+    let _ = app.emit(
+        "video-process-progress",
+        ProcessProgress {
+            video_id: video_id.clone(),
+            stage: "checking_duplicates".to_string(),
+            progress: 0.0,
+            message: Some("正在检测重复视频...".to_string()),
+        },
+    );
+    let duplicates = flag_duplicates(&app, &video_id, &video_path).await;
+
     // 1. 提取音频
     task_queue.update_task_progress_by_id(&task_id, 0.1).await;
     crate::commands::task_queue::emit_task_progress(&app, &task_id, 0.1, "running");
@@ -417,6 +468,23 @@ pub async fn transcribe_video(
         }
     };
 
+    // 3. 画面文字识别（仅在 OCR 模型已安装时执行，失败不影响转写结果）
+    let ocr_model_dir = crate::commands::ai::get_ocr_model_dir(&app);
+    let on_screen_text = if OnnxOcr::is_installed(&ocr_model_dir) {
+        task_queue.update_task_progress_by_id(&task_id, 0.9).await;
+        crate::commands::task_queue::emit_task_progress(&app, &task_id, 0.9, "running");
+
+        match run_ocr_stage(&app, &ocr_model_dir, &video_path, &video_id, None).await {
+            Ok(segments) => segments,
+            Err(e) => {
+                warn!("画面文字识别失败: {}", e);
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
     // 完成任务
     task_queue
         .complete_task_by_id(&task_id, Some(result.text.clone()))
@@ -440,7 +508,119 @@ pub async fn transcribe_video(
         video_id,
         text: result.text,
         duration_ms: result.duration_ms,
+        on_screen_text,
+        duplicates,
+    })
+}
+
+/// 在后台线程执行 OCR，并发送 "ocr" 阶段进度事件
+async fn run_ocr_stage(
+    app: &AppHandle,
+    model_dir: &Path,
+    video_path: &str,
+    video_id: &str,
+    interval_secs: Option<f32>,
+) -> Result<Vec<OnScreenText>, String> {
+    let _ = app.emit(
+        "video-process-progress",
+        ProcessProgress {
+            video_id: video_id.to_string(),
+            stage: "ocr".to_string(),
+            progress: 0.0,
+            message: Some("正在识别画面文字...".to_string()),
+        },
+    );
+
+    let app_handle = app.clone();
+    let model_dir = model_dir.to_path_buf();
+    let video_path = PathBuf::from(video_path);
+    let video_id_clone = video_id.to_string();
+    let interval = interval_secs.unwrap_or(ocr_timeline::DEFAULT_SAMPLE_INTERVAL_SECS);
+
+    tokio::task::spawn_blocking(move || {
+        extract_on_screen_text_blocking(&model_dir, &video_path, interval, |p| {
+            let _ = app_handle.emit(
+                "video-process-progress",
+                ProcessProgress {
+                    video_id: video_id_clone.clone(),
+                    stage: "ocr".to_string(),
+                    progress: p,
+                    message: Some(format!("正在识别画面文字... {:.0}%", p * 100.0)),
+                },
+            );
+        })
     })
+    .await
+    .map_err(|e| format!("OCR 任务异常: {}", e))?
+}
+
+/// 采样视频帧并识别画面文字，合并重复出现的字幕
+fn extract_on_screen_text_blocking<F: Fn(f32)>(
+    model_dir: &Path,
+    video_path: &Path,
+    interval_secs: f32,
+    on_progress: F,
+) -> Result<Vec<OnScreenText>, String> {
+    let mut ocr = OnnxOcr::new(model_dir).map_err(|e| format!("加载 OCR 模型失败: {}", e))?;
+    let ffmpeg = FfmpegWrapper::new().map_err(|e| e.to_string())?;
+    let metadata = ffmpeg
+        .get_video_metadata(video_path)
+        .map_err(|e| format!("获取视频信息失败: {}", e))?;
+
+    let (width, height) =
+        onnx_ocr::detection_size(metadata.width, metadata.height, onnx_ocr::DET_MAX_SIDE);
+    let timestamps = ocr_timeline::sample_timestamps(metadata.duration_ms, interval_secs);
+    let actual_interval = match timestamps.as_slice() {
+        [first, second, ..] => second - first,
+        _ => interval_secs,
+    };
+
+    let mut frames = Vec::with_capacity(timestamps.len());
+    for (i, time) in timestamps.iter().enumerate() {
+        match ffmpeg.extract_bgr_frame(video_path, *time, width, height) {
+            Ok(data) => {
+                let frame = BgrFrame {
+                    width: width as usize,
+                    height: height as usize,
+                    data,
+                };
+                let lines = ocr
+                    .recognize(&frame)
+                    .map_err(|e| format!("OCR 识别失败: {}", e))?;
+                let texts = lines
+                    .into_iter()
+                    .map(|line| FrameText {
+                        text: line.text,
+                        confidence: line.confidence,
+                    })
+                    .collect();
+                frames.push((*time, texts));
+            }
+            Err(e) => warn!("截取帧失败 ({:.1}s): {}", time, e),
+        }
+        on_progress((i + 1) as f32 / timestamps.len() as f32);
+    }
+
+    Ok(ocr_timeline::merge_frames(&frames, actual_interval))
+}
+
+/// 提取视频中的画面文字（硬字幕、标题卡等）
+#[tauri::command]
+pub async fn extract_on_screen_text(
+    app: AppHandle,
+    video_path: String,
+    video_id: Option<String>,
+    interval_secs: Option<f32>,
+) -> Result<Vec<OnScreenText>, String> {
+    let model_dir = crate::commands::ai::get_ocr_model_dir(&app);
+    if !OnnxOcr::is_installed(&model_dir) {
+        return Err("OCR 模型未安装，请先在设置页下载模型".to_string());
+    }
+
+    let video_id = video_id.unwrap_or_else(generate_id);
+    run_ocr_stage(&app, &model_dir, &video_path, &video_id, interval_secs)
+        .await
+        .map_err(|e| format!("画面文字识别失败: {}", e))
 }
 
 /// 批量转写视频
@@ -515,6 +695,297 @@ pub async fn generate_thumbnail(video_path: String) -> Result<String, String> {
     Ok(BASE64.encode(&bytes))
 }
 
+/// 封面候选（返回给前端）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CoverCandidateInfo {
+    pub time_seconds: f32,
+    pub score: f32,
+    pub sharpness: f32,
+    pub brightness: f32,
+    pub contrast: f32,
+    pub stability: f32,
+    pub thumbnail: Option<String>, // Base64 encoded
+}
+
+/// 智能生成封面候选
+#[tauri::command]
+pub async fn generate_cover_candidates(
+    video_path: String,
+    count: Option<usize>,
+) -> Result<Vec<CoverCandidateInfo>, String> {
+    let processor = VideoProcessor::new().map_err(|e| e.to_string())?;
+
+    let candidates = processor
+        .generate_cover_candidates(Path::new(&video_path), count.unwrap_or(3), Some(320))
+        .await
+        .map_err(|e| format!("封面候选生成失败: {}", e))?;
+
+    Ok(candidates
+        .into_iter()
+        .map(|c| CoverCandidateInfo {
+            time_seconds: c.time_seconds,
+            score: c.score,
+            sharpness: c.sharpness,
+            brightness: c.brightness,
+            contrast: c.contrast,
+            stability: c.stability,
+            thumbnail: c.thumbnail.map(|bytes| BASE64.encode(&bytes)),
+        })
+        .collect())
+}
+
+/// 文件大小和修改时间（秒），用于判断缓存是否失效
+fn file_signature(path: &str) -> Result<(u64, i64), String> {
+    let metadata = std::fs::metadata(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    Ok((metadata.len(), modified_at))
+}
+
+/// 读取数据库中缓存的媒体指纹（文件未变化时有效）
+fn cached_fingerprint(
+    video_path: &str,
+    file_size: u64,
+    modified_at: i64,
+) -> Option<MediaFingerprint> {
+    let db = crate::commands::settings::get_database()?;
+    match db.get_media_fingerprint(video_path, file_size, modified_at) {
+        Ok(Some(json)) => serde_json::from_str(&json).ok(),
+        _ => None,
+    }
+}
+
+/// 保存媒体指纹到数据库，加入指纹库
+fn save_fingerprint(fingerprint: &MediaFingerprint, file_size: u64, modified_at: i64) {
+    if let Some(db) = crate::commands::settings::get_database() {
+        if let Ok(json) = serde_json::to_string(fingerprint) {
+            let _ = db.save_media_fingerprint(&fingerprint.path, file_size, modified_at, &json);
+        }
+    }
+}
+
+/// 获取媒体指纹，优先使用数据库缓存（文件未变化时）
+async fn fingerprint_with_cache(
+    processor: &VideoProcessor,
+    video_path: &str,
+) -> Result<MediaFingerprint, String> {
+    let (file_size, modified_at) = file_signature(video_path)?;
+    if let Some(fingerprint) = cached_fingerprint(video_path, file_size, modified_at) {
+        return Ok(fingerprint);
+    }
+
+    let fingerprint = processor
+        .compute_fingerprint(Path::new(video_path))
+        .await
+        .map_err(|e| e.to_string())?;
+    save_fingerprint(&fingerprint, file_size, modified_at);
+    Ok(fingerprint)
+}
+
+/// 与指纹库比对查找近似重复视频，并把该视频加入指纹库
+async fn detect_duplicates(
+    video_path: &str,
+    threshold: f32,
+) -> Result<Vec<DuplicateMatch>, String> {
+    let (file_size, modified_at) = file_signature(video_path)?;
+    let library = load_fingerprint_library();
+    if let Some(fingerprint) = cached_fingerprint(video_path, file_size, modified_at) {
+        return Ok(duplicate_detector::find_matches(
+            &fingerprint,
+            &library,
+            threshold,
+        ));
+    }
+
+    let processor = VideoProcessor::new().map_err(|e| e.to_string())?;
+    let (fingerprint, matches) = processor
+        .find_duplicates(Path::new(video_path), &library, threshold)
+        .await
+        .map_err(|e| e.to_string())?;
+    save_fingerprint(&fingerprint, file_size, modified_at);
+    Ok(matches)
+}
+
+/// 转写前 / 下载完成后的重复检测，发现重复时发送 "video-duplicates-detected" 事件
+///
+/// 检测失败只记录日志，返回空列表
+async fn flag_duplicates(app: &AppHandle, video_id: &str, video_path: &str) -> Vec<DuplicateMatch> {
+    let matches = match detect_duplicates(video_path, DEFAULT_DUPLICATE_THRESHOLD).await {
+        Ok(matches) => matches,
+        Err(e) => {
+            warn!("重复检测失败 {}: {}", video_path, e);
+            return Vec::new();
+        }
+    };
+
+    if !matches.is_empty() {
+        let _ = app.emit(
+            "video-duplicates-detected",
+            DuplicatesDetected {
+                video_id: video_id.to_string(),
+                path: video_path.to_string(),
+                matches: matches.clone(),
+            },
+        );
+    }
+    matches
+}
+
+/// 读取指纹库中仍然存在的媒体
+fn load_fingerprint_library() -> Vec<MediaFingerprint> {
+    let db = match crate::commands::settings::get_database() {
+        Some(db) => db,
+        None => return Vec::new(),
+    };
+
+    db.list_media_fingerprints()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(path, _)| Path::new(path).exists())
+        .filter_map(|(_, json)| serde_json::from_str(&json).ok())
+        .collect()
+}
+
+/// 检查视频是否与已导入/下载的媒体近似重复（建议在转写前调用）
+#[tauri::command]
+pub async fn check_video_duplicates(
+    video_path: String,
+    threshold: Option<f32>,
+) -> Result<Vec<DuplicateMatch>, String> {
+    detect_duplicates(
+        &video_path,
+        threshold.unwrap_or(DEFAULT_DUPLICATE_THRESHOLD),
+    )
+    .await
+    .map_err(|e| format!("重复检测失败: {}", e))
+}
+
+/// 列出媒体库中的重复视频簇
+///
+/// `paths` 中尚未计算指纹的文件会先计算并加入指纹库
+#[tauri::command]
+pub async fn list_duplicate_clusters(
+    paths: Option<Vec<String>>,
+    threshold: Option<f32>,
+) -> Result<Vec<DuplicateCluster>, String> {
+    let processor = VideoProcessor::new().map_err(|e| e.to_string())?;
+
+    for path in paths.unwrap_or_default() {
+        if let Err(e) = fingerprint_with_cache(&processor, &path).await {
+            warn!("计算指纹失败 {}: {}", path, e);
+        }
+    }
+
+    let library = load_fingerprint_library();
+    Ok(duplicate_detector::cluster(
+        &library,
+        threshold.unwrap_or(DEFAULT_DUPLICATE_THRESHOLD),
+    ))
+}
+
+/// 视频剪辑节奏记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShotPacingRecord {
+    pub path: String,
+    pub author: String,
+    pub pacing: ShotPacing,
+}
+
+/// 分析视频剪辑节奏并保存到数据库
+///
+/// 文件未变化且阈值与上次分析相同时直接返回缓存结果
+#[tauri::command]
+pub async fn analyze_shot_pacing(
+    video_path: String,
+    author: Option<String>,
+    threshold: Option<f32>,
+) -> Result<ShotPacing, String> {
+    let (file_size, modified_at) = file_signature(&video_path)?;
+    let threshold = threshold.unwrap_or(shot_pacing::DEFAULT_SCENE_THRESHOLD);
+    let db = crate::commands::settings::get_database();
+
+    if let Some(db) = &db {
+        if let Ok(Some(json)) = db.get_shot_pacing(&video_path, file_size, modified_at, threshold) {
+            if let Ok(pacing) = serde_json::from_str::<ShotPacing>(&json) {
+                // 命中缓存时仍按本次指定的创作者归类
+                if let Some(author) = &author {
+                    if let Err(e) = db.set_shot_pacing_author(&video_path, author) {
+                        warn!("更新剪辑节奏创作者失败 {}: {}", video_path, e);
+                    }
+                }
+                return Ok(pacing);
+            }
+        }
+    }
+
+    let processor = VideoProcessor::new().map_err(|e| e.to_string())?;
+    let pacing = processor
+        .analyze_shot_pacing(Path::new(&video_path), Some(threshold))
+        .await
+        .map_err(|e| format!("剪辑节奏分析失败: {}", e))?;
+
+    if let Some(db) = &db {
+        let json = serde_json::to_string(&pacing).map_err(|e| e.to_string())?;
+        db.save_shot_pacing(
+            &video_path,
+            author.as_deref().unwrap_or_default(),
+            file_size,
+            modified_at,
+            threshold,
+            &json,
+        )
+        .map_err(|e| format!("保存剪辑节奏失败: {}", e))?;
+    }
+
+    Ok(pacing)
+}
+
+/// 获取已分析视频的剪辑节奏，可按创作者过滤
+#[tauri::command]
+pub async fn list_shot_pacing(author: Option<String>) -> Result<Vec<ShotPacingRecord>, String> {
+    let db =
+        crate::commands::settings::get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    let records = db
+        .list_shot_pacing(author.as_deref())
+        .map_err(|e| format!("获取剪辑节奏失败: {}", e))?;
+
+    Ok(records
+        .into_iter()
+        .filter_map(|(path, author, json)| {
+            serde_json::from_str(&json)
+                .ok()
+                .map(|pacing| ShotPacingRecord {
+                    path,
+                    author,
+                    pacing,
+                })
+        })
+        .collect())
+}
+
+/// 按创作者汇总剪辑节奏，用于作品集之间的对比
+#[tauri::command]
+pub async fn compare_creator_pacing(
+    authors: Option<Vec<String>>,
+) -> Result<Vec<CreatorPacingSummary>, String> {
+    let records: Vec<(String, ShotPacing)> = list_shot_pacing(None)
+        .await?
+        .into_iter()
+        .filter(|r| !r.author.is_empty())
+        .filter(|r| match &authors {
+            Some(authors) => authors.contains(&r.author),
+            None => true,
+        })
+        .map(|r| (r.author, r.pacing))
+        .collect();
+
+    Ok(shot_pacing::summarize_by_author(&records))
+}
+
 /// 清理临时文件
 #[tauri::command]
 pub async fn cleanup_temp_files(app: AppHandle) -> Result<(), String> {
@@ -612,6 +1083,9 @@ pub struct DownloadTask {
     pub url: String,
     pub output_path: String,
     pub filename: String,
+    /// 画质选择，未指定时使用默认的无水印地址
+    #[serde(default)]
+    pub quality: Option<QualityPreference>,
 }
 
 /// 批量下载结果
@@ -632,6 +1106,130 @@ pub struct DownloadResult {
     pub error: Option<String>,
 }
 
+/// 从解析结果构建来源信息
+fn source_metadata_from_info(
+    info: &crate::core::mcp_client::DouyinVideoInfo,
+    source_url: &str,
+) -> SourceMetadata {
+    SourceMetadata {
+        aweme_id: info.aweme_id.clone(),
+        title: info.title.clone(),
+        author: info.author.clone(),
+        source_url: source_url.to_string(),
+        create_time: info.create_time,
+    }
+}
+
+/// 确定下载文件的最终路径
+///
+/// `output_path` 为已存在的目录（或以路径分隔符结尾）时，按设置中的命名模板生成文件路径；
+/// 否则按前端传入的文件路径保存
+pub(crate) fn resolve_download_path(output_path: &str, source: &SourceMetadata) -> PathBuf {
+    let path = PathBuf::from(output_path);
+    if !path.is_dir() && !output_path.ends_with(['/', '\\']) {
+        return path;
+    }
+
+    let template = crate::commands::settings::get_config_manager()
+        .map(|m| m.get().download_filename_template)
+        .unwrap_or_default();
+    filename_template::resolve_output_path(&path, &template, source)
+}
+
+/// 下载完成后写入来源元数据（失败只记录日志，不影响下载结果）
+pub(crate) async fn tag_downloaded_video(path: &Path, source: &SourceMetadata) {
+    let processor = match VideoProcessor::new() {
+        Ok(p) => p,
+        Err(e) => {
+            warn!("写入元数据跳过 {}: {}", path.display(), e);
+            return;
+        }
+    };
+
+    if let Err(e) = processor.embed_source_metadata(path, source).await {
+        warn!("写入元数据失败 {}: {}", path.display(), e);
+    }
+}
+
+/// 按作品类型下载：视频保存为单个文件并写入来源元数据，图文作品保存到同名文件夹
+///
+/// 仅音频模式下载体积最小的档位，提取为 16kHz 单声道 WAV（同样写入来源元数据）后删除视频。
+/// 返回实际保存的文件或文件夹路径
+async fn download_parsed_content(
+    mcp_client: &crate::core::mcp_client::McpClient,
+    info: &crate::core::mcp_client::DouyinVideoInfo,
+    output: &Path,
+    source: &SourceMetadata,
+    quality: Option<&QualityPreference>,
+) -> Result<PathBuf, String> {
+    if info.content_type == ContentType::Images {
+        let gallery_dir = output.with_extension("");
+        mcp_client
+            .download_gallery(&info.image_urls, info.music_url.as_deref(), &gallery_dir)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(gallery_dir);
+    }
+
+    let url = info.download_url(quality);
+    if quality.is_some_and(QualityPreference::is_audio_only) {
+        return download_audio_only(mcp_client, &url, output, source).await;
+    }
+
+    mcp_client
+        .download_video_via_sidecar(&url, output)
+        .await
+        .map_err(|e| e.to_string())?;
+    // 写入来源元数据
+    tag_downloaded_video(output, source).await;
+    Ok(output.to_path_buf())
+}
+
+/// 下载视频到临时文件并提取音轨（写入来源元数据），返回音频路径
+///
+/// 无论成功与否都会删除临时视频，提取失败时同时删除不完整的音频
+async fn download_audio_only(
+    mcp_client: &crate::core::mcp_client::McpClient,
+    url: &str,
+    output: &Path,
+    source: &SourceMetadata,
+) -> Result<PathBuf, String> {
+    let ffmpeg = FfmpegWrapper::new().map_err(|e| format!("音频提取失败: {}", e))?;
+    let temp_video = output.with_extension("audio-source.mp4");
+    let audio_path = output.with_extension("wav");
+
+    let result = match mcp_client
+        .download_video_via_sidecar(url, &temp_video)
+        .await
+    {
+        Ok(_) => {
+            let input = temp_video.clone();
+            let audio = audio_path.clone();
+            let metadata = source.to_ffmpeg_metadata();
+            tokio::task::spawn_blocking(move || {
+                ffmpeg
+                    .extract_audio_for_asr(&input, &audio)
+                    .map_err(|e| format!("音频提取失败: {}", e))?;
+                // 写入来源元数据（失败只记录日志，不影响下载结果）
+                if let Err(e) = ffmpeg.embed_metadata(&audio, &metadata) {
+                    warn!("写入元数据失败 {}: {}", audio.display(), e);
+                }
+                Ok::<(), String>(())
+            })
+            .await
+            .map_err(|e| format!("音频提取任务异常: {}", e))
+            .and_then(|r| r)
+        }
+        Err(e) => Err(e.to_string()),
+    };
+
+    let _ = std::fs::remove_file(&temp_video);
+    if result.is_err() {
+        let _ = std::fs::remove_file(&audio_path);
+    }
+    result.map(|_| audio_path)
+}
+
 /// 下载单个视频 (通过 Python sidecar 处理抖音防盗链)
 #[tauri::command]
 pub async fn download_video(
@@ -639,8 +1237,8 @@ pub async fn download_video(
     download_id: String,
     url: String,
     output_path: String,
+    quality: Option<QualityPreference>,
 ) -> Result<String, String> {
-    use crate::core::mcp_client::{McpClient, McpConfig};
     use crate::data::task_queue::TaskType;
 
     // 获取全局任务队列并添加任务
@@ -650,24 +1248,37 @@ pub async fn download_video(
         .last()
         .unwrap_or("video")
         .to_string();
+    // 直接以运行状态加入队列 (使用 ID-based 方式支持并发，后台执行器不会重复领取)
     let task_id = task_queue
-        .add_task(TaskType::VideoDownload {
+        .add_started_task(TaskType::VideoDownload {
             url: url.clone(),
             output_path: output_path.clone(),
-            video_name: video_name.clone(),
+            video_name,
         })
         .await;
 
-    // 启动任务 (使用 ID-based 方式支持并发)
-    let _ = task_queue.start_task_by_id(&task_id).await;
+    run_download_task(&app, &task_id, &download_id, &url, &output_path, quality).await
+}
 
-    let mcp_client = McpClient::new(McpConfig::default());
-    let output = PathBuf::from(&output_path);
+/// 执行已在队列中标记为运行的下载任务，结束时更新任务状态，返回保存路径
+pub(crate) async fn run_download_task(
+    app: &AppHandle,
+    task_id: &str,
+    download_id: &str,
+    url: &str,
+    output_path: &str,
+    quality: Option<QualityPreference>,
+) -> Result<String, String> {
+    use crate::core::mcp_client::{McpClient, McpConfig};
 
-    // 确保输出目录存在
-    if let Some(parent) = output.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
+    let app = app.clone();
+    let task_queue = crate::commands::task_queue::get_task_queue();
+    let task_id = task_id.to_string();
+    let download_id = download_id.to_string();
+    let url = url.to_string();
+    let output_path = output_path.to_string();
+
+    let mcp_client = McpClient::new(McpConfig::default());
 
     // 发送开始下载事件
     let _ = app.emit(
@@ -697,64 +1308,114 @@ pub async fn download_video(
         },
     );
 
-    let parse_result = mcp_client.parse_douyin_link(&url).await;
-
-    let download_url = match parse_result {
-        Ok(info) => info.video_url,
-        Err(e) => {
-            let error_msg = format!("解析链接失败: {}", e);
-            // 更新任务队列为失败
-            task_queue
-                .fail_task_by_id(&task_id, error_msg.clone())
-                .await;
-            crate::commands::task_queue::emit_task_failed(&app, &task_id, &error_msg);
-            let _ = app.emit(
-                "video-download-progress",
-                DownloadProgress {
-                    download_id: download_id.clone(),
-                    url: url.clone(),
-                    progress: 0.0,
-                    status: "failed".to_string(),
-                    error: Some(error_msg.clone()),
-                },
-            );
-            return Err(error_msg);
+    // 限流熔断或任务暂停期间等待，熔断导致的失败在恢复后重试
+    let result = loop {
+        if let Err(e) = crate::commands::task_queue::wait_until_runnable(&app, &task_id).await {
+            break Err(e);
+        }
+        match fetch_download_content(
+            &app,
+            &task_id,
+            &download_id,
+            &mcp_client,
+            &url,
+            &output_path,
+            quality.as_ref(),
+        )
+        .await
+        {
+            Err(e) if global_limiter().status().circuit_open => {
+                warn!("下载时触发限流熔断，恢复后重试 {}: {}", url, e);
+            }
+            result => break result,
         }
     };
+    finish_download(&app, &task_id, &download_id, &url, result).await
+}
+
+/// 解析链接并下载作品内容，返回实际保存的路径
+async fn fetch_download_content(
+    app: &AppHandle,
+    task_id: &str,
+    download_id: &str,
+    mcp_client: &crate::core::mcp_client::McpClient,
+    url: &str,
+    output_path: &str,
+    quality: Option<&QualityPreference>,
+) -> Result<PathBuf, String> {
+    use crate::core::mcp_client::McpClient;
+
+    // 快手、B 站等其他平台通过多平台解析器解析并直接下载
+    if !McpClient::is_valid_douyin_link(url) {
+        return crate::commands::platform::download_platform_content(url, output_path).await;
+    }
+
+    let info = mcp_client
+        .parse_douyin_link(url)
+        .await
+        .map_err(|e| format!("解析链接失败: {}", e))?;
+    let source = source_metadata_from_info(&info, url);
+
+    // 按命名模板确定最终路径，并确保输出目录存在
+    let output = resolve_download_path(output_path, &source);
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
 
     // 更新进度
-    task_queue.update_task_progress_by_id(&task_id, 0.3).await;
-    crate::commands::task_queue::emit_task_progress(&app, &task_id, 0.3, "running");
+    let task_queue = crate::commands::task_queue::get_task_queue();
+    task_queue.update_task_progress_by_id(task_id, 0.3).await;
+    crate::commands::task_queue::emit_task_progress(app, task_id, 0.3, "running");
 
     // 2. 使用 Python sidecar 下载视频
     let _ = app.emit(
         "video-download-progress",
         DownloadProgress {
-            download_id: download_id.clone(),
-            url: url.clone(),
+            download_id: download_id.to_string(),
+            url: url.to_string(),
             progress: 0.3,
             status: "downloading".to_string(),
             error: None,
         },
     );
 
-    let result = mcp_client
-        .download_video_via_sidecar(&download_url, &output)
-        .await;
+    download_parsed_content(mcp_client, &info, &output, &source, quality).await
+}
 
+/// 更新任务队列并发送下载完成 / 失败事件，返回保存路径
+async fn finish_download(
+    app: &AppHandle,
+    task_id: &str,
+    download_id: &str,
+    url: &str,
+    result: Result<PathBuf, String>,
+) -> Result<String, String> {
+    let task_queue = crate::commands::task_queue::get_task_queue();
     match result {
-        Ok(_) => {
+        Ok(saved) => {
+            let output_path = saved.to_string_lossy().to_string();
             // 更新任务队列为完成
             task_queue
-                .complete_task_by_id(&task_id, Some(output_path.clone()))
+                .complete_task_by_id(task_id, Some(output_path.clone()))
                 .await;
-            crate::commands::task_queue::emit_task_completed(&app, &task_id, Some(&output_path));
+            crate::commands::task_queue::emit_task_completed(app, task_id, Some(&output_path));
+
+            // 后台检测是否与已有媒体重复（图集目录跳过）
+            if saved.is_file() {
+                let app = app.clone();
+                let download_id = download_id.to_string();
+                let path = output_path.clone();
+                tauri::async_runtime::spawn(async move {
+                    flag_duplicates(&app, &download_id, &path).await;
+                });
+            }
+
             // 发送完成事件
             let _ = app.emit(
                 "video-download-progress",
                 DownloadProgress {
-                    download_id: download_id.clone(),
-                    url: url.clone(),
+                    download_id: download_id.to_string(),
+                    url: url.to_string(),
                     progress: 1.0,
                     status: "completed".to_string(),
                     error: None,
@@ -762,19 +1423,21 @@ pub async fn download_video(
             );
             Ok(output_path)
         }
-        Err(e) => {
-            // 更新任务队列为失败
-            let error_msg = e.to_string();
-            task_queue
-                .fail_task_by_id(&task_id, error_msg.clone())
-                .await;
-            crate::commands::task_queue::emit_task_failed(&app, &task_id, &error_msg);
+        Err(error_msg) => {
+            // 更新任务队列为失败（已取消的任务不再重复通知）
+            if task_queue
+                .fail_task_by_id(task_id, error_msg.clone())
+                .await
+                .is_some()
+            {
+                crate::commands::task_queue::emit_task_failed(app, task_id, &error_msg);
+            }
             // 发送失败事件
             let _ = app.emit(
                 "video-download-progress",
                 DownloadProgress {
-                    download_id: download_id.clone(),
-                    url: url.clone(),
+                    download_id: download_id.to_string(),
+                    url: url.to_string(),
                     progress: 0.0,
                     status: "failed".to_string(),
                     error: Some(error_msg.clone()),
@@ -809,8 +1472,7 @@ pub async fn download_videos_batch(
     let mut completed = 0;
     let mut failed = 0;
 
-    for (i, task) in tasks.into_iter().enumerate() {
-        let output = PathBuf::from(&task.output_path);
+    for (i, mut task) in tasks.into_iter().enumerate() {
         let video_name = task
             .output_path
             .split(['/', '\\'])
@@ -818,23 +1480,15 @@ pub async fn download_videos_batch(
             .unwrap_or("video")
             .to_string();
 
-        // 添加任务到队列
+        // 以运行状态加入队列 (使用 ID-based 方式支持并发，后台执行器不会重复领取)
         let queue_task_id = task_queue
-            .add_task(TaskType::VideoDownload {
+            .add_started_task(TaskType::VideoDownload {
                 url: task.url.clone(),
                 output_path: task.output_path.clone(),
                 video_name: video_name.clone(),
             })
             .await;
 
-        // 启动任务 (使用 ID-based 方式支持并发)
-        let _ = task_queue.start_task_by_id(&queue_task_id).await;
-
-        // 确保输出目录存在
-        if let Some(parent) = output.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-
         // 发送开始下载事件
         let _ = app.emit(
             "video-download-progress",
@@ -853,7 +1507,7 @@ pub async fn download_videos_batch(
             .await;
         crate::commands::task_queue::emit_task_progress(&app, &queue_task_id, 0.1, "running");
 
-        // 1. 解析链接
+        // 1. 解析链接并下载；限流熔断或任务暂停期间等待，熔断导致的失败在恢复后重试
         let _ = app.emit(
             "video-download-progress",
             DownloadProgress {
@@ -865,59 +1519,35 @@ pub async fn download_videos_batch(
             },
         );
 
-        let parse_result = mcp_client.parse_douyin_link(&task.url).await;
-
-        // 如果解析失败，直接记录错误并继续下一个
-        let download_url = match parse_result {
-            Ok(info) => info.video_url,
-            Err(e) => {
-                failed += 1;
-                let error_msg = format!("解析失败: {}", e);
-                // 更新任务队列为失败
-                task_queue
-                    .fail_task_by_id(&queue_task_id, error_msg.clone())
-                    .await;
-                crate::commands::task_queue::emit_task_failed(&app, &queue_task_id, &error_msg);
-                let _ = app.emit(
-                    "video-download-progress",
-                    DownloadProgress {
-                        download_id: task.id.clone(),
-                        url: task.url.clone(),
-                        progress: 0.0,
-                        status: "failed".to_string(),
-                        error: Some(error_msg.clone()),
-                    },
-                );
-                results.push(DownloadResult {
-                    download_id: task.id,
-                    url: task.url,
-                    output_path: None,
-                    success: false,
-                    error: Some(error_msg),
-                });
-                continue;
+        let result = loop {
+            if let Err(e) =
+                crate::commands::task_queue::wait_until_runnable(&app, &queue_task_id).await
+            {
+                break Err(e);
+            }
+            match fetch_download_content(
+                &app,
+                &queue_task_id,
+                &task.id,
+                &mcp_client,
+                &task.url,
+                &task.output_path,
+                task.quality.as_ref(),
+            )
+            .await
+            {
+                Err(e) if global_limiter().status().circuit_open => {
+                    warn!("下载时触发限流熔断，恢复后重试 {}: {}", task.url, e);
+                }
+                result => break result,
             }
         };
 
-        // 2. 下载视频
-        let _ = app.emit(
-            "video-download-progress",
-            DownloadProgress {
-                download_id: task.id.clone(),
-                url: task.url.clone(),
-                progress: 0.3,
-                status: "downloading".to_string(),
-                error: None,
-            },
-        );
-
-        let result = mcp_client
-            .download_video_via_sidecar(&download_url, &output)
-            .await;
-
         match result {
-            Ok(_) => {
+            Ok(saved) => {
                 completed += 1;
+                task.output_path = saved.to_string_lossy().to_string();
+
                 // 更新任务队列为完成
                 task_queue
                     .complete_task_by_id(&queue_task_id, Some(task.output_path.clone()))
@@ -946,14 +1576,16 @@ pub async fn download_videos_batch(
                     error: None,
                 });
             }
-            Err(e) => {
+            Err(error_msg) => {
                 failed += 1;
-                let error_msg = e.to_string();
-                // 更新任务队列为失败
-                task_queue
+                // 更新任务队列为失败（已取消的任务不再重复通知）
+                if task_queue
                     .fail_task_by_id(&queue_task_id, error_msg.clone())
-                    .await;
-                crate::commands::task_queue::emit_task_failed(&app, &queue_task_id, &error_msg);
+                    .await
+                    .is_some()
+                {
+                    crate::commands::task_queue::emit_task_failed(&app, &queue_task_id, &error_msg);
+                }
                 // 发送失败事件
                 let _ = app.emit(
                     "video-download-progress",
@@ -0,0 +1,237 @@
+// 监视文件夹相关命令
+// 后台定时扫描配置的文件夹，新视频稳定后自动加入转写任务队列
+
+use crate::commands::settings::get_database;
+use crate::commands::task_queue::{enqueue_with_follow_up, TaskFollowUp};
+use crate::core::folder_watcher::{scan_folder, FileSnapshot, SettleTracker, WatchConfig};
+use crate::data::task_queue::TaskType;
+use crate::data::{Database, WatchedFileRecord};
+use futures_util::future::FutureExt;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tracing::{error, info, warn};
+
+/// 监视文件夹配置在数据库 config 表中的键名
+const WATCH_CONFIG_KEY: &str = "watch_folder_config";
+
+/// 全局监视配置（后台循环每轮读取最新值）
+static WATCH_CONFIG: Lazy<RwLock<WatchConfig>> = Lazy::new(|| RwLock::new(WatchConfig::default()));
+
+/// 后台监视循环是否已启动
+static WATCHER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 从数据库加载监视配置
+fn load_watch_config(db: &Database) -> WatchConfig {
+    match db.get_config(WATCH_CONFIG_KEY) {
+        Ok(Some(json)) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            warn!("监视文件夹配置解析失败，使用默认配置: {}", e);
+            WatchConfig::default()
+        }),
+        Ok(None) => WatchConfig::default(),
+        Err(e) => {
+            error!("读取监视文件夹配置失败: {}", e);
+            WatchConfig::default()
+        }
+    }
+}
+
+/// 启动监视文件夹后台任务（需在数据层初始化后调用）
+pub fn start_folder_watcher(app: AppHandle) {
+    if WATCHER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    if let Some(db) = get_database() {
+        *WATCH_CONFIG.write() = load_watch_config(&db);
+    }
+
+    tauri::async_runtime::spawn(async move {
+        info!("监视文件夹后台任务已启动");
+        if let Some(db) = get_database() {
+            requeue_unfinished_files(&db).await;
+        }
+        let mut tracker = SettleTracker::new();
+
+        loop {
+            let config = WATCH_CONFIG.read().clone();
+            tokio::time::sleep(Duration::from_secs(config.scan_interval_secs.max(1))).await;
+
+            if !config.enabled || config.folders.is_empty() {
+                tracker = SettleTracker::new();
+                continue;
+            }
+
+            let db = match get_database() {
+                Some(db) => db,
+                None => continue,
+            };
+
+            // 扫描所有文件夹，过滤掉已处理过的文件
+            let snapshots: Vec<FileSnapshot> = config
+                .folders
+                .iter()
+                .flat_map(|folder| scan_folder(Path::new(folder), &config))
+                .filter(|s| {
+                    !db.is_watched_file_processed(&s.path.to_string_lossy(), s.size, s.modified)
+                        .unwrap_or(true)
+                })
+                .collect();
+
+            let ready = tracker.observe(
+                snapshots,
+                Duration::from_secs(config.settle_secs),
+                Instant::now(),
+            );
+
+            for snapshot in ready {
+                enqueue_watched_file(&app, &db, &snapshot).await;
+            }
+        }
+    });
+}
+
+/// 将稳定的新文件加入转写队列并通知前端
+async fn enqueue_watched_file(app: &AppHandle, db: &Database, snapshot: &FileSnapshot) {
+    let video_path = snapshot.path.to_string_lossy().to_string();
+    let video_name = video_name_of(&snapshot.path);
+    let task_id = enqueue_transcription(db, &video_path, snapshot.size, snapshot.modified).await;
+
+    info!("监视文件夹发现新视频: {} (任务 {})", video_path, task_id);
+
+    let _ = app.emit(
+        "watch-folder:video-added",
+        serde_json::json!({
+            "task_id": task_id,
+            "video_path": video_path,
+            "video_name": video_name,
+        }),
+    );
+    crate::core::tray::send_notification(
+        app,
+        "发现新视频",
+        &format!("{} 已加入转写队列", video_name),
+    );
+}
+
+/// 加入转写任务并记录为待处理，转写成功后才标记为已处理
+async fn enqueue_transcription(
+    db: &Database,
+    video_path: &str,
+    file_size: u64,
+    modified_at: i64,
+) -> String {
+    let task_id = enqueue_with_follow_up(
+        TaskType::VideoTranscription {
+            video_path: video_path.to_string(),
+            video_name: video_name_of(Path::new(video_path)),
+        },
+        watched_file_follow_up(video_path.to_string(), file_size, modified_at),
+    )
+    .await;
+
+    if let Err(e) = db.add_watched_file(video_path, file_size, modified_at, &task_id) {
+        error!("记录监视文件失败 {}: {}", video_path, e);
+    }
+    task_id
+}
+
+/// 转写任务结束后更新监视文件的处理状态（失败的任务重试成功后同样会更新）
+fn watched_file_follow_up(path: String, file_size: u64, modified_at: i64) -> TaskFollowUp {
+    Arc::new(move |_app: AppHandle, result: Result<String, String>| {
+        let path = path.clone();
+        async move {
+            let status = match &result {
+                Ok(_) => "completed",
+                Err(e) => {
+                    warn!("监视文件转写失败 {}: {}", path, e);
+                    "failed"
+                }
+            };
+            if let Some(db) = get_database() {
+                if let Err(e) = db.set_watched_file_status(&path, file_size, modified_at, status) {
+                    error!("更新监视文件状态失败 {}: {}", path, e);
+                }
+            }
+        }
+        .boxed()
+    })
+}
+
+/// 重新加入上次未处理完成的文件（入队后应用退出或转写失败），文件已删除或被修改时跳过
+async fn requeue_unfinished_files(db: &Database) {
+    let records = match db.get_unfinished_watched_files() {
+        Ok(records) => records,
+        Err(e) => {
+            error!("读取未完成的监视文件失败: {}", e);
+            return;
+        }
+    };
+
+    for record in records {
+        let unchanged = std::fs::metadata(&record.path)
+            .map(|meta| FileSnapshot::from_metadata(Path::new(&record.path), &meta))
+            .is_ok_and(|snapshot| {
+                snapshot.size == record.file_size && snapshot.modified == record.modified_at
+            });
+        if !unchanged {
+            continue;
+        }
+
+        let task_id =
+            enqueue_transcription(db, &record.path, record.file_size, record.modified_at).await;
+        info!(
+            "重新加入未完成的监视文件: {} (任务 {})",
+            record.path, task_id
+        );
+    }
+}
+
+/// 文件名（用作任务显示名称）
+fn video_name_of(path: &Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("video")
+        .to_string()
+}
+
+/// 获取监视文件夹配置
+#[tauri::command]
+pub async fn get_watch_config() -> Result<WatchConfig, String> {
+    Ok(WATCH_CONFIG.read().clone())
+}
+
+/// 保存监视文件夹配置
+#[tauri::command]
+pub async fn save_watch_config(config: WatchConfig) -> Result<(), String> {
+    for folder in &config.folders {
+        if !Path::new(folder).is_dir() {
+            return Err(format!("文件夹不存在: {}", folder));
+        }
+    }
+
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    let json = serde_json::to_string(&config).map_err(|e| format!("序列化配置失败: {}", e))?;
+    db.set_config(WATCH_CONFIG_KEY, &json)
+        .map_err(|e| format!("保存监视文件夹配置失败: {}", e))?;
+
+    info!(
+        "监视文件夹配置已更新: 启用={}, 文件夹数={}",
+        config.enabled,
+        config.folders.len()
+    );
+    *WATCH_CONFIG.write() = config;
+    Ok(())
+}
+
+/// 获取监视文件夹处理记录
+#[tauri::command]
+pub async fn get_watched_files(limit: Option<i32>) -> Result<Vec<WatchedFileRecord>, String> {
+    let db = get_database().ok_or_else(|| "数据库未初始化".to_string())?;
+    db.get_watched_files(limit.unwrap_or(100))
+        .map_err(|e| format!("获取处理记录失败: {}", e))
+}
@@ -0,0 +1,136 @@
+// 账号监控模块
+// 定时拉取已关注账号的作品列表，与已记录的作品比对找出新发布的视频
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::core::mcp_client::DouyinVideoInfo;
+
+/// 账号监控配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MonitorConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 每个账号的检查间隔（分钟）
+    pub poll_interval_mins: u64,
+    /// 自动处理流水线的下载目录，为空时使用默认导出目录
+    pub download_dir: String,
+    /// 发现新作品时是否发送通知
+    pub notify: bool,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            poll_interval_mins: 30,
+            download_dir: String::new(),
+            notify: true,
+        }
+    }
+}
+
+/// 规范化账号标识：支持直接填写 sec_uid，或粘贴 `douyin.com/user/<sec_uid>` 主页链接
+pub fn normalize_user_id(input: &str) -> String {
+    let input = input.trim();
+    let re = Regex::new(r"/user/([A-Za-z0-9_\-.]+)").unwrap();
+    match re.captures(input).and_then(|caps| caps.get(1)) {
+        Some(id) => id.as_str().to_string(),
+        None => input.to_string(),
+    }
+}
+
+/// 作品的唯一标识：优先 aweme_id，其次从链接中提取，最后退回链接本身
+pub fn video_key(video: &DouyinVideoInfo) -> Option<String> {
+    if !video.aweme_id.is_empty() {
+        return Some(video.aweme_id.clone());
+    }
+
+    let url = video.video_url.trim();
+    if url.is_empty() {
+        return None;
+    }
+
+    let re = Regex::new(r"/(?:video|note)/(\d+)").unwrap();
+    match re.captures(url).and_then(|caps| caps.get(1)) {
+        Some(id) => Some(id.as_str().to_string()),
+        None => Some(url.to_string()),
+    }
+}
+
+/// 找出未记录过的作品，返回 (标识, 作品)，同一轮中重复出现的作品只保留一次
+pub fn detect_new_videos<'a>(
+    videos: &'a [DouyinVideoInfo],
+    known: &HashSet<String>,
+) -> Vec<(String, &'a DouyinVideoInfo)> {
+    let mut seen = HashSet::new();
+    videos
+        .iter()
+        .filter_map(|video| video_key(video).map(|key| (key, video)))
+        .filter(|(key, _)| !known.contains(key) && seen.insert(key.clone()))
+        .collect()
+}
+
+/// 账号是否到了检查时间（从未检查过的账号立即检查）
+pub fn is_due(last_checked_at: Option<i64>, poll_interval_mins: u64, now: i64) -> bool {
+    match last_checked_at {
+        Some(last) => now - last >= (poll_interval_mins.max(1) * 60) as i64,
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(aweme_id: &str, url: &str) -> DouyinVideoInfo {
+        serde_json::from_value(serde_json::json!({
+            "video_url": url,
+            "title": "标题",
+            "author": "作者",
+            "likes": 0,
+            "comments": 0,
+            "shares": 0,
+            "aweme_id": aweme_id,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_normalize_user_id() {
+        assert_eq!(
+            normalize_user_id("https://www.douyin.com/user/MS4wLjABAAAA-x_y?from_tab_name=main"),
+            "MS4wLjABAAAA-x_y"
+        );
+        assert_eq!(normalize_user_id("  MS4wLjABAAAA "), "MS4wLjABAAAA");
+    }
+
+    #[test]
+    fn test_detect_new_videos() {
+        let videos = vec![
+            video("1", ""),
+            video("", "https://www.douyin.com/video/2"),
+            video("", "https://www.douyin.com/video/2"),
+            video("3", ""),
+            video("", ""),
+        ];
+        let known: HashSet<String> = ["1".to_string()].into_iter().collect();
+
+        let keys: Vec<String> = detect_new_videos(&videos, &known)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, vec!["2", "3"]);
+    }
+
+    #[test]
+    fn test_is_due() {
+        assert!(is_due(None, 30, 1_000));
+        assert!(!is_due(Some(1_000), 30, 1_000 + 29 * 60));
+        assert!(is_due(Some(1_000), 30, 1_000 + 30 * 60));
+        // 间隔为 0 时按 1 分钟处理
+        assert!(!is_due(Some(1_000), 0, 1_030));
+    }
+}
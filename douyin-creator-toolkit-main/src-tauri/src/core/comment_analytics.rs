@@ -0,0 +1,369 @@
+// 评论分析模块
+// 基于抓取的评论统计高赞评论、高频短语、提问评论与 IP 属地分布，并支持导出 CSV
+
+use chrono::{Local, TimeZone};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::douyin_parser::DouyinComment;
+
+/// 短语至少出现在多少条评论中才计入高频短语
+const MIN_PHRASE_COUNT: usize = 2;
+/// 中文短语的最短 / 最长字数
+const MIN_CJK_PHRASE_LEN: usize = 2;
+const MAX_CJK_PHRASE_LEN: usize = 4;
+
+/// 常见的无意义词
+const STOPWORDS: [&str; 24] = [
+    "这个", "那个", "一个", "我们", "你们", "他们", "就是", "还是", "但是", "因为", "所以", "如果",
+    "可以", "没有", "什么", "怎么", "不是", "自己", "真的", "这样", "那么", "已经", "还有", "时候",
+];
+/// 不应出现在短语首尾的虚词
+const EDGE_STOP_CHARS: [char; 20] = [
+    '的', '了', '是', '我', '你', '他', '啊', '吧', '呢', '吗', '哦', '呀', '在', '和', '也', '都',
+    '就', '很', '这', '那',
+];
+/// 疑问词
+const QUESTION_WORDS: [&str; 13] = [
+    "怎么",
+    "为什么",
+    "什么",
+    "哪里",
+    "哪个",
+    "哪儿",
+    "多少",
+    "如何",
+    "能不能",
+    "是不是",
+    "有没有",
+    "求链接",
+    "求教程",
+];
+
+/// 抖音表情（如 `[捂脸]`）与 @ 提及
+static NOISE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[[^\[\]]{1,8}\]|@\S+").unwrap());
+
+/// 短语出现次数（按包含该短语的评论数计）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhraseCount {
+    pub phrase: String,
+    pub count: usize,
+}
+
+/// IP 属地评论数
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LocationCount {
+    pub location: String,
+    pub count: usize,
+}
+
+/// 评论分析结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentAnalytics {
+    /// 评论总数（含回复）
+    pub total: usize,
+    /// 一级评论数
+    pub top_level: usize,
+    /// 回复数
+    pub replies: usize,
+    /// 评论点赞总数
+    pub total_likes: u64,
+    /// 点赞最多的评论
+    pub top_comments: Vec<DouyinComment>,
+    /// 高频短语
+    pub frequent_phrases: Vec<PhraseCount>,
+    /// 提问评论数
+    pub question_count: usize,
+    /// 点赞最多的提问评论
+    pub questions: Vec<DouyinComment>,
+    /// IP 属地分布（按评论数从多到少）
+    pub ip_locations: Vec<LocationCount>,
+}
+
+/// 分析评论，`top_n` 限制各列表长度
+pub fn analyze(comments: &[DouyinComment], top_n: usize) -> CommentAnalytics {
+    let replies = comments.iter().filter(|c| c.parent_id.is_some()).count();
+
+    let mut questions: Vec<DouyinComment> = comments
+        .iter()
+        .filter(|c| is_question(&c.text))
+        .cloned()
+        .collect();
+    let question_count = questions.len();
+    sort_by_likes(&mut questions);
+    questions.truncate(top_n);
+
+    let mut top_comments = comments.to_vec();
+    sort_by_likes(&mut top_comments);
+    top_comments.truncate(top_n);
+
+    let mut locations: HashMap<&str, usize> = HashMap::new();
+    for c in comments.iter().filter(|c| !c.ip_location.is_empty()) {
+        *locations.entry(c.ip_location.as_str()).or_insert(0) += 1;
+    }
+    let mut ip_locations: Vec<LocationCount> = locations
+        .into_iter()
+        .map(|(location, count)| LocationCount {
+            location: location.to_string(),
+            count,
+        })
+        .collect();
+    ip_locations.sort_by(|a, b| b.count.cmp(&a.count).then(a.location.cmp(&b.location)));
+
+    CommentAnalytics {
+        total: comments.len(),
+        top_level: comments.len() - replies,
+        replies,
+        total_likes: comments.iter().map(|c| c.likes).sum(),
+        top_comments,
+        frequent_phrases: frequent_phrases(comments.iter().map(|c| c.text.as_str()), top_n),
+        question_count,
+        questions,
+        ip_locations,
+    }
+}
+
+/// 判断评论是否为提问
+pub fn is_question(text: &str) -> bool {
+    let text = NOISE_RE.replace_all(text, "");
+    if text.contains('?') || text.contains('？') {
+        return true;
+    }
+
+    let trimmed = text.trim_end_matches(|c: char| {
+        c.is_whitespace() || matches!(c, '~' | '～' | '!' | '！' | '。' | '.' | '…')
+    });
+    if trimmed.ends_with('吗') || trimmed.ends_with('呢') || trimmed.ends_with('么') {
+        return true;
+    }
+
+    QUESTION_WORDS.iter().any(|w| trimmed.contains(w))
+}
+
+/// 统计高频短语：中文取 2~4 字片段，英文 / 数字按单词切分
+///
+/// 每条评论中同一短语只计一次；被更长短语完全覆盖（出现次数相同）的片段会被去掉
+pub fn frequent_phrases<'a>(
+    texts: impl IntoIterator<Item = &'a str>,
+    top_n: usize,
+) -> Vec<PhraseCount> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for text in texts {
+        for phrase in extract_phrases(text) {
+            *counts.entry(phrase).or_insert(0) += 1;
+        }
+    }
+    counts.retain(|_, count| *count >= MIN_PHRASE_COUNT);
+
+    let mut phrases: Vec<PhraseCount> = counts
+        .iter()
+        .filter(|(phrase, count)| {
+            let len = phrase.chars().count();
+            !counts.iter().any(|(longer, longer_count)| {
+                longer.chars().count() > len && longer_count >= count && longer.contains(*phrase)
+            })
+        })
+        .map(|(phrase, count)| PhraseCount {
+            phrase: phrase.clone(),
+            count: *count,
+        })
+        .collect();
+
+    phrases.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then(b.phrase.chars().count().cmp(&a.phrase.chars().count()))
+            .then(a.phrase.cmp(&b.phrase))
+    });
+    phrases.truncate(top_n);
+    phrases
+}
+
+/// 导出评论为 CSV（不含 BOM）
+pub fn to_csv(comments: &[DouyinComment]) -> String {
+    let mut csv =
+        String::from("评论ID,上级评论ID,作者,作者ID,内容,点赞数,回复数,发布时间,IP属地\n");
+    for c in comments {
+        let created = Local
+            .timestamp_opt(c.create_time as i64, 0)
+            .single()
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        let fields = [
+            c.cid.clone(),
+            c.parent_id.clone().unwrap_or_default(),
+            c.author.clone(),
+            c.author_id.clone(),
+            c.text.clone(),
+            c.likes.to_string(),
+            c.reply_count.to_string(),
+            created,
+            c.ip_location.clone(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| escape_csv(f)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// 按点赞数从高到低排序，点赞相同时较早的评论在前
+fn sort_by_likes(comments: &mut [DouyinComment]) {
+    comments.sort_by(|a, b| {
+        b.likes
+            .cmp(&a.likes)
+            .then(a.create_time.cmp(&b.create_time))
+    });
+}
+
+/// 提取一条评论中的候选短语（去重）
+fn extract_phrases(text: &str) -> HashSet<String> {
+    let text = NOISE_RE.replace_all(text, " ");
+    let mut phrases = HashSet::new();
+    let mut cjk_run: Vec<char> = Vec::new();
+    let mut word = String::new();
+
+    for ch in text.chars().chain(std::iter::once(' ')) {
+        if is_cjk(ch) {
+            cjk_run.push(ch);
+        } else {
+            add_cjk_phrases(&cjk_run, &mut phrases);
+            cjk_run.clear();
+        }
+
+        if ch.is_ascii_alphanumeric() {
+            word.push(ch.to_ascii_lowercase());
+        } else {
+            if word.len() >= 2 && !word.chars().all(|c| c.is_ascii_digit()) {
+                phrases.insert(word.clone());
+            }
+            word.clear();
+        }
+    }
+
+    phrases
+}
+
+fn add_cjk_phrases(run: &[char], phrases: &mut HashSet<String>) {
+    for len in MIN_CJK_PHRASE_LEN..=MAX_CJK_PHRASE_LEN {
+        for window in run.windows(len) {
+            if EDGE_STOP_CHARS.contains(&window[0]) || EDGE_STOP_CHARS.contains(&window[len - 1]) {
+                continue;
+            }
+            let phrase: String = window.iter().collect();
+            if !STOPWORDS.contains(&phrase.as_str()) {
+                phrases.insert(phrase);
+            }
+        }
+    }
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(ch, '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}')
+}
+
+/// 转义 CSV 字段
+///
+/// 以 `=`、`+`、`-`、`@`、制表符或回车开头的内容会被表格软件当作公式执行，
+/// 加 `'` 前缀并加引号，使其按文本显示
+fn escape_csv(field: &str) -> String {
+    if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("\"'{}\"", field.replace('"', "\"\""))
+    } else if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(cid: &str, text: &str, likes: u64, parent: Option<&str>) -> DouyinComment {
+        DouyinComment {
+            cid: cid.to_string(),
+            aweme_id: "1".to_string(),
+            parent_id: parent.map(|p| p.to_string()),
+            text: text.to_string(),
+            likes,
+            ip_location: "广东".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_question() {
+        assert!(is_question("这个在哪里买的"));
+        assert!(is_question("好用吗[捂脸]"));
+        assert!(is_question("what brand?"));
+        assert!(is_question("求链接！"));
+        assert!(!is_question("太好看了[赞]"));
+        assert!(!is_question("@小明 快来看"));
+    }
+
+    #[test]
+    fn test_frequent_phrases() {
+        let texts = [
+            "露营装备好棒",
+            "露营装备在哪买",
+            "我也想去露营[呲牙]",
+            "Vlog 拍得好 vlog",
+        ];
+        let phrases = frequent_phrases(texts, 10);
+
+        assert_eq!(phrases[0].phrase, "露营");
+        assert_eq!(phrases[0].count, 3);
+        // "露营装" 与 "营装" 被 "露营装备" 覆盖
+        assert!(phrases
+            .iter()
+            .any(|p| p.phrase == "露营装备" && p.count == 2));
+        assert!(!phrases.iter().any(|p| p.phrase == "营装"));
+        // 同一评论内重复只计一次
+        assert!(!phrases.iter().any(|p| p.phrase == "vlog"));
+    }
+
+    #[test]
+    fn test_analyze_and_csv() {
+        let comments = vec![
+            comment("1", "怎么做到的", 5, None),
+            comment("2", "太棒了, \"绝了\"", 20, None),
+            comment("3", "同问", 1, Some("1")),
+        ];
+        let analytics = analyze(&comments, 2);
+
+        assert_eq!(analytics.total, 3);
+        assert_eq!(analytics.replies, 1);
+        assert_eq!(analytics.total_likes, 26);
+        assert_eq!(analytics.top_comments.len(), 2);
+        assert_eq!(analytics.top_comments[0].cid, "2");
+        assert_eq!(analytics.question_count, 1);
+        assert_eq!(analytics.ip_locations[0].count, 3);
+
+        let csv = to_csv(&comments);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[2].contains("\"太棒了, \"\"绝了\"\"\""));
+        assert!(lines[3].starts_with("3,1,"));
+    }
+
+    #[test]
+    fn test_csv_formula_injection() {
+        assert_eq!(
+            escape_csv("=HYPERLINK(\"x\")"),
+            "\"'=HYPERLINK(\"\"x\"\")\""
+        );
+        assert_eq!(escape_csv("+1"), "\"'+1\"");
+        assert_eq!(escape_csv("-2"), "\"'-2\"");
+        assert_eq!(escape_csv("@SUM(A1)"), "\"'@SUM(A1)\"");
+        assert_eq!(escape_csv("\tcmd"), "\"'\tcmd\"");
+        assert_eq!(escape_csv("\rcmd"), "\"'\rcmd\"");
+        // 公式字符不在开头时按普通文本处理
+        assert_eq!(escape_csv("1+1=2"), "1+1=2");
+
+        let csv = to_csv(&[comment("1", "=1+1", 0, None)]);
+        assert!(csv.lines().nth(1).unwrap().contains(",\"'=1+1\","));
+    }
+}
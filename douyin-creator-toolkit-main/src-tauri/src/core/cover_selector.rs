@@ -0,0 +1,274 @@
+// 智能封面选择模块
+// 在视频时间轴上均匀采样帧，按清晰度、亮度、对比度和画面稳定性打分，挑选最佳封面候选
+
+use serde::{Deserialize, Serialize};
+
+/// 分析帧宽度（缩小后再计算，兼顾速度与精度）
+pub const ANALYSIS_WIDTH: u32 = 160;
+/// 分析帧高度
+pub const ANALYSIS_HEIGHT: u32 = 90;
+/// 稳定性检测的相邻帧间隔（秒）
+pub const STABILITY_OFFSET_SECS: f32 = 0.2;
+/// 采样范围起点（跳过片头）
+const SAMPLE_START_RATIO: f32 = 0.05;
+/// 采样范围终点（跳过片尾）
+const SAMPLE_END_RATIO: f32 = 0.95;
+/// 最少采样帧数
+const MIN_SAMPLES: usize = 12;
+
+/// 各项评分权重
+const WEIGHT_SHARPNESS: f32 = 0.4;
+const WEIGHT_BRIGHTNESS: f32 = 0.2;
+const WEIGHT_CONTRAST: f32 = 0.2;
+const WEIGHT_STABILITY: f32 = 0.2;
+
+/// 封面候选
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverCandidate {
+    /// 时间点（秒）
+    pub time_seconds: f32,
+    /// 综合得分 0.0 - 1.0
+    pub score: f32,
+    pub sharpness: f32,
+    pub brightness: f32,
+    pub contrast: f32,
+    pub stability: f32,
+    /// PNG 缩略图
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<Vec<u8>>,
+}
+
+/// 单帧评分
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameScore {
+    pub sharpness: f32,
+    pub brightness: f32,
+    pub contrast: f32,
+    pub stability: f32,
+}
+
+impl FrameScore {
+    /// 加权综合得分
+    pub fn total(&self) -> f32 {
+        self.sharpness * WEIGHT_SHARPNESS
+            + self.brightness * WEIGHT_BRIGHTNESS
+            + self.contrast * WEIGHT_CONTRAST
+            + self.stability * WEIGHT_STABILITY
+    }
+}
+
+/// 计算采样时间点（秒），均匀分布在视频 5%-95% 区间
+pub fn sample_timestamps(duration_ms: u64, count: usize) -> Vec<f32> {
+    let duration = duration_ms as f32 / 1000.0;
+    if duration <= 0.0 {
+        return vec![0.0];
+    }
+
+    let samples = (count * 4).max(MIN_SAMPLES);
+    let start = duration * SAMPLE_START_RATIO;
+    let end = (duration * SAMPLE_END_RATIO - STABILITY_OFFSET_SECS).max(start);
+    let step = (end - start) / samples as f32;
+
+    (0..samples)
+        .map(|i| start + step * (i as f32 + 0.5))
+        .collect()
+}
+
+/// 对单帧灰度像素打分
+///
+/// # Arguments
+/// * `frame` - 灰度像素
+/// * `next_frame` - 稍后时间点的灰度像素（用于稳定性评估，可选）
+/// * `width` / `height` - 帧尺寸
+pub fn score_frame(
+    frame: &[u8],
+    next_frame: Option<&[u8]>,
+    width: usize,
+    height: usize,
+) -> FrameScore {
+    let (mean, std_dev) = mean_and_std(frame);
+
+    FrameScore {
+        sharpness: sharpness_score(frame, width, height),
+        brightness: brightness_score(mean),
+        contrast: (std_dev / 64.0).min(1.0),
+        stability: next_frame
+            .map(|next| stability_score(frame, next))
+            .unwrap_or(0.5),
+    }
+}
+
+/// 清晰度：拉普拉斯算子响应的方差，归一化到 0-1
+pub fn sharpness_score(frame: &[u8], width: usize, height: usize) -> f32 {
+    if width < 3 || height < 3 || frame.len() < width * height {
+        return 0.0;
+    }
+
+    let mut sum = 0.0f64;
+    let mut sum_sq = 0.0f64;
+    let mut n = 0usize;
+
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let idx = y * width + x;
+            let lap = 4.0 * frame[idx] as f64
+                - frame[idx - 1] as f64
+                - frame[idx + 1] as f64
+                - frame[idx - width] as f64
+                - frame[idx + width] as f64;
+            sum += lap;
+            sum_sq += lap * lap;
+            n += 1;
+        }
+    }
+
+    let mean = sum / n as f64;
+    let variance = (sum_sq / n as f64 - mean * mean).max(0.0);
+    (variance / (variance + 500.0)) as f32
+}
+
+/// 亮度：越接近中灰越高，过暗或过曝得分低
+pub fn brightness_score(mean: f32) -> f32 {
+    let deviation = ((mean / 255.0) - 0.5).abs() / 0.5;
+    (1.0 - deviation * deviation).clamp(0.0, 1.0)
+}
+
+/// 稳定性：与相邻帧的平均像素差越小越稳定（避开转场、运动模糊）
+pub fn stability_score(frame: &[u8], next: &[u8]) -> f32 {
+    let len = frame.len().min(next.len());
+    if len == 0 {
+        return 0.0;
+    }
+
+    let diff: u64 = frame[..len]
+        .iter()
+        .zip(&next[..len])
+        .map(|(a, b)| (*a as i16 - *b as i16).unsigned_abs() as u64)
+        .sum();
+    let mean_diff = diff as f32 / len as f32;
+
+    1.0 - (mean_diff / 40.0).min(1.0)
+}
+
+/// 从已打分的候选中挑选前 N 个，且相互之间至少间隔 `min_gap` 秒
+pub fn select_top(
+    mut candidates: Vec<CoverCandidate>,
+    count: usize,
+    min_gap: f32,
+) -> Vec<CoverCandidate> {
+    candidates.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut selected: Vec<CoverCandidate> = Vec::with_capacity(count);
+    for candidate in candidates {
+        if selected.len() >= count {
+            break;
+        }
+        if selected
+            .iter()
+            .all(|s| (s.time_seconds - candidate.time_seconds).abs() >= min_gap)
+        {
+            selected.push(candidate);
+        }
+    }
+
+    selected
+}
+
+fn mean_and_std(frame: &[u8]) -> (f32, f32) {
+    if frame.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let n = frame.len() as f64;
+    let mean = frame.iter().map(|&p| p as f64).sum::<f64>() / n;
+    let variance = frame
+        .iter()
+        .map(|&p| {
+            let d = p as f64 - mean;
+            d * d
+        })
+        .sum::<f64>()
+        / n;
+
+    (mean as f32, variance.sqrt() as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard(width: usize, height: usize) -> Vec<u8> {
+        (0..width * height)
+            .map(|i| {
+                if (i % width + i / width) & 1 == 0 {
+                    0
+                } else {
+                    255
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sample_timestamps() {
+        let ts = sample_timestamps(60_000, 3);
+        assert_eq!(ts.len(), MIN_SAMPLES);
+        assert!(ts.iter().all(|t| (3.0..=57.0).contains(t)));
+        assert!(ts.windows(2).all(|w| w[0] < w[1]));
+
+        assert_eq!(sample_timestamps(0, 3), vec![0.0]);
+    }
+
+    #[test]
+    fn test_sharpness_score() {
+        let flat = vec![128u8; 16 * 16];
+        assert_eq!(sharpness_score(&flat, 16, 16), 0.0);
+
+        let sharp = checkerboard(16, 16);
+        assert!(sharpness_score(&sharp, 16, 16) > 0.9);
+    }
+
+    #[test]
+    fn test_brightness_score() {
+        assert!((brightness_score(127.5) - 1.0).abs() < 1e-6);
+        assert!(brightness_score(0.0) < 0.01);
+        assert!(brightness_score(255.0) < 0.01);
+    }
+
+    #[test]
+    fn test_stability_score() {
+        let a = vec![100u8; 64];
+        assert_eq!(stability_score(&a, &a), 1.0);
+
+        let b = vec![200u8; 64];
+        assert_eq!(stability_score(&a, &b), 0.0);
+    }
+
+    #[test]
+    fn test_select_top_respects_gap() {
+        let make = |t: f32, s: f32| CoverCandidate {
+            time_seconds: t,
+            score: s,
+            sharpness: 0.0,
+            brightness: 0.0,
+            contrast: 0.0,
+            stability: 0.0,
+            thumbnail: None,
+        };
+        let candidates = vec![
+            make(1.0, 0.9),
+            make(1.5, 0.85),
+            make(5.0, 0.5),
+            make(9.0, 0.7),
+        ];
+
+        let top = select_top(candidates, 2, 2.0);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].time_seconds, 1.0);
+        assert_eq!(top[1].time_seconds, 9.0);
+    }
+}
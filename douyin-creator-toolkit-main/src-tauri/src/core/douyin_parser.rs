@@ -3,11 +3,16 @@
 #![allow(dead_code)]
 
 use regex::Regex;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+use super::strategy_health::{ParseStrategy, StrategyAttempt, StrategyHealthTracker};
+use crate::utils::http_client::{self, HttpService};
+use crate::utils::rate_limiter::{self, RateLimiter};
+
 #[derive(Error, Debug, Clone)]
 pub enum DouyinError {
     #[error("网络请求失败: {0}")]
@@ -22,34 +27,332 @@ pub enum DouyinError {
     VideoNotFound,
     #[error("需要登录或验证")]
     AuthRequired,
+    /// 列表接口返回空响应：a_bogus 签名未生效或触发风控，与登录状态无关
+    #[error("列表接口返回空响应（签名未生效或触发风控）")]
+    SignatureRequired,
+    #[error("请求受限: {0}")]
+    RateLimited(String),
+}
+
+/// 作品类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentType {
+    /// 视频
+    #[default]
+    Video,
+    /// 图文（图集）
+    Images,
+}
+
+/// 图文作品的 aweme_type
+const AWEME_TYPE_IMAGES: u64 = 68;
+
+/// 背景音乐信息
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct MusicInfo {
+    pub id: String,
+    pub title: String,
+    pub author: String,
+    /// 时长（秒）
+    pub duration: u64,
+}
+
+/// 视频码率档位
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct VideoVariant {
+    /// 档位名称，如 `normal_1080_0`
+    pub gear_name: String,
+    /// 码率（bps）
+    pub bit_rate: u64,
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    /// 编码格式：`h264` / `h265`
+    pub codec: String,
+    /// 文件大小（字节），未知时为 0
+    pub data_size: u64,
+    pub url: String,
+}
+
+/// 下载画质选择
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum QualityPreference {
+    /// 码率最高的档位
+    #[default]
+    Best,
+    /// 体积最小的档位
+    Smallest,
+    /// 指定编码（`h264` / `h265`）中码率最高的档位，没有该编码时回退到最高码率
+    Codec { codec: String },
+    /// 分辨率不超过指定值（按短边计，如 1080 表示 1080P）的档位中码率最高的，都超过时取体积最小的
+    MaxHeight { height: u32 },
+    /// 仅音频：下载体积最小的档位后提取音轨，用于只需要文字稿的场景
+    AudioOnly,
+}
+
+impl QualityPreference {
+    pub fn is_audio_only(&self) -> bool {
+        matches!(self, Self::AudioOnly)
+    }
+
+    /// 从码率档位中选择要下载的一个，没有档位时返回 `None`
+    pub fn select<'a>(&self, variants: &'a [VideoVariant]) -> Option<&'a VideoVariant> {
+        let best = variants.iter().max_by_key(|v| v.bit_rate);
+        // 接口未返回文件大小时按码率估算
+        let smallest = variants
+            .iter()
+            .min_by_key(|v| (v.data_size == 0, v.data_size, v.bit_rate));
+
+        match self {
+            Self::Best => best,
+            Self::Smallest | Self::AudioOnly => smallest,
+            Self::Codec { codec } => {
+                let codec = normalize_codec(codec);
+                variants
+                    .iter()
+                    .filter(|v| v.codec == codec)
+                    .max_by_key(|v| v.bit_rate)
+                    .or(best)
+            }
+            Self::MaxHeight { height } => variants
+                .iter()
+                .filter(|v| v.height > 0 && v.width > 0 && v.height.min(v.width) <= *height)
+                .max_by_key(|v| v.bit_rate)
+                .or(smallest),
+        }
+    }
+}
+
+/// 统一编码名称：`hevc` / `bytevc1` 视为 `h265`，`avc` 视为 `h264`
+fn normalize_codec(codec: &str) -> &'static str {
+    match codec.trim().to_lowercase().as_str() {
+        "h265" | "hevc" | "bytevc1" | "265" => "h265",
+        _ => "h264",
+    }
 }
 
 /// 抖音视频信息
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DouyinVideoData {
     pub aweme_id: String,
+    /// 作品类型（视频 / 图文）
+    pub content_type: ContentType,
     pub video_url: String,
     pub no_watermark_url: String,
+    /// 图文作品的图片地址（优先无水印版本）
+    pub image_urls: Vec<String>,
+    /// 背景音乐地址
+    pub music_url: String,
     pub title: String,
     pub author: String,
     pub author_id: String,
     pub likes: u64,
     pub comments: u64,
     pub shares: u64,
+    /// 收藏数
+    pub collects: u64,
+    /// 播放数（接口未公开时为 0）
+    pub plays: u64,
     pub cover_url: String,
     pub duration: u64,
     pub create_time: u64,
+    /// 话题标签（不含 `#`）
+    pub hashtags: Vec<String>,
+    /// 背景音乐信息
+    pub music: Option<MusicInfo>,
+    /// IP 属地
+    pub ip_location: String,
+    /// 视频分辨率
+    pub width: u32,
+    pub height: u32,
+    /// 码率档位（按码率从高到低）
+    pub bit_rates: Vec<VideoVariant>,
+}
+
+/// 视频评论（楼中楼回复通过 `parent_id` 关联一级评论）
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct DouyinComment {
+    pub cid: String,
+    pub aweme_id: String,
+    /// 一级评论为 None，回复为所属一级评论的 cid
+    pub parent_id: Option<String>,
+    pub text: String,
+    pub author: String,
+    pub author_id: String,
+    pub likes: u64,
+    pub reply_count: u64,
+    pub create_time: u64,
+    /// IP 属地
+    pub ip_location: String,
+}
+
+/// 一页评论
+#[derive(Debug, Clone, Default)]
+pub struct CommentPage {
+    pub comments: Vec<DouyinComment>,
+    pub has_more: bool,
+    pub cursor: u64,
+}
+
+/// 搜索排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSort {
+    /// 综合排序
+    #[default]
+    Relevance,
+    /// 最多点赞
+    MostLiked,
+    /// 最新发布
+    Newest,
+}
+
+impl SearchSort {
+    /// 搜索接口的 `sort_type` 参数
+    fn as_param(self) -> u8 {
+        match self {
+            Self::Relevance => 0,
+            Self::MostLiked => 1,
+            Self::Newest => 2,
+        }
+    }
+}
+
+/// 搜索发布时间筛选
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PublishTimeFilter {
+    /// 不限
+    #[default]
+    Any,
+    /// 一天内
+    Day,
+    /// 一周内
+    Week,
+    /// 半年内
+    HalfYear,
+}
+
+impl PublishTimeFilter {
+    /// 搜索接口的 `publish_time` 参数（天数，0 表示不限）
+    fn as_param(self) -> u32 {
+        match self {
+            Self::Any => 0,
+            Self::Day => 1,
+            Self::Week => 7,
+            Self::HalfYear => 180,
+        }
+    }
+}
+
+/// 关键词搜索条件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchQuery {
+    pub keyword: String,
+    pub sort: SearchSort,
+    pub publish_time: PublishTimeFilter,
+    /// 起始偏移，翻页时传入上一页返回的 `cursor`
+    pub offset: u64,
+    /// 期望获取的作品数
+    pub count: u32,
+}
+
+impl Default for SearchQuery {
+    fn default() -> Self {
+        Self {
+            keyword: String::new(),
+            sort: SearchSort::default(),
+            publish_time: PublishTimeFilter::default(),
+            offset: 0,
+            count: LIST_PAGE_SIZE,
+        }
+    }
+}
+
+/// 话题作品列表排序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeSort {
+    /// 最热
+    #[default]
+    Hot,
+    /// 最新
+    Recent,
+}
+
+impl ChallengeSort {
+    /// 话题接口的 `sort_type` 参数
+    fn as_param(self) -> u8 {
+        match self {
+            Self::Hot => 0,
+            Self::Recent => 1,
+        }
+    }
+}
+
+/// 一页作品列表（搜索结果 / 用户主页 / 话题 / 音乐作品）
+#[derive(Debug, Clone, Default)]
+pub struct VideoPage {
+    pub videos: Vec<DouyinVideoData>,
+    pub has_more: bool,
+    /// 下一页的起始位置
+    pub cursor: u64,
+}
+
+/// 未登录时使用的占位 Cookie
+const ANONYMOUS_COOKIE: &str = "ttwid=1%7C1234567890";
+/// 搜索、用户作品接口每页条数
+const LIST_PAGE_SIZE: u32 = 20;
+/// 列表接口经 Sidecar 签名请求的超时（秒），首次请求需启动浏览器
+const LIST_REQUEST_TIMEOUT_SECS: u64 = 60;
+/// 评论接口每页条数
+const COMMENT_PAGE_SIZE: u32 = 20;
+/// 每条一级评论最多抓取的回复数
+const MAX_REPLIES_PER_COMMENT: usize = 50;
+/// 评论翻页间隔（毫秒），降低触发风控的概率
+const COMMENT_PAGE_INTERVAL_MS: u64 = 500;
+
+/// 解析器访问的服务地址（测试时可指向本地模拟服务）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DouyinEndpoints {
+    /// 本地 Sidecar 服务
+    pub sidecar: String,
+    /// 抖音网页与 Web 接口
+    pub web: String,
+    /// 旧版 iesdouyin 接口
+    pub iesdouyin: String,
+}
+
+impl Default for DouyinEndpoints {
+    fn default() -> Self {
+        Self {
+            sidecar: super::mcp_client::SIDECAR_BASE_URL.to_string(),
+            web: "https://www.douyin.com".to_string(),
+            iesdouyin: "https://www.iesdouyin.com".to_string(),
+        }
+    }
 }
 
 /// 抖音解析器
 pub struct DouyinParser {
     client: Client,
+    /// 登录会话 Cookie
+    cookie: Option<String>,
+    /// 服务地址
+    endpoints: DouyinEndpoints,
+    /// 解析渠道健康度追踪
+    health: Arc<StrategyHealthTracker>,
+    /// 请求限速器
+    limiter: Arc<RateLimiter>,
 }
 
 impl DouyinParser {
     pub fn new() -> Self {
         // 使用更简单的配置，避免 TLS 相关问题
-        let client = Client::builder()
+        let client = http_client::client_builder(HttpService::Douyin)
             .timeout(Duration::from_secs(30))
             .redirect(reqwest::redirect::Policy::limited(10))
             .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
@@ -60,12 +363,90 @@ impl DouyinParser {
                 Client::new()
             });
 
-        Self { client }
+        Self {
+            client,
+            cookie: super::session_manager::douyin_cookie_header(),
+            endpoints: DouyinEndpoints::default(),
+            health: super::strategy_health::global_tracker(),
+            limiter: rate_limiter::global_limiter(),
+        }
     }
 
     /// 创建带有自定义配置的解析器
     pub fn with_client(client: Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            cookie: super::session_manager::douyin_cookie_header(),
+            endpoints: DouyinEndpoints::default(),
+            health: super::strategy_health::global_tracker(),
+            limiter: rate_limiter::global_limiter(),
+        }
+    }
+
+    /// 指定请求携带的 Cookie（覆盖全局会话）
+    pub fn with_cookie(mut self, cookie: Option<String>) -> Self {
+        self.cookie = cookie;
+        self
+    }
+
+    /// 指定服务地址（覆盖默认的抖音与 Sidecar 地址）
+    pub fn with_endpoints(mut self, endpoints: DouyinEndpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// 指定渠道健康度追踪器（默认使用全局追踪器）
+    pub fn with_health_tracker(mut self, health: Arc<StrategyHealthTracker>) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// 指定请求限速器（默认使用全局限速器）
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// 经限速器发送请求，429 响应计入退避并返回错误
+    ///
+    /// `target` 为限速所按的地址；经 Sidecar 代为访问抖音时传入抖音链接
+    async fn send_limited(
+        &self,
+        target: &str,
+        request: RequestBuilder,
+    ) -> Result<Response, DouyinError> {
+        self.limiter
+            .acquire(target)
+            .await
+            .map_err(|e| DouyinError::RateLimited(e.to_string()))?;
+
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                DouyinError::Timeout
+            } else {
+                DouyinError::NetworkError(e.to_string())
+            }
+        })?;
+
+        if response.status().as_u16() == 429 {
+            self.check_throttled(target, 429, "")?;
+        }
+        Ok(response)
+    }
+
+    /// 检查响应是否为限流或验证码页，是则通知限速器退避
+    fn check_throttled(&self, target: &str, status: u16, text: &str) -> Result<(), DouyinError> {
+        self.limiter
+            .check_response(target, status, text)
+            .map_err(|e| DouyinError::RateLimited(e.to_string()))
+    }
+
+    /// 为请求附加登录 Cookie
+    fn with_session_cookie(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.cookie {
+            Some(cookie) => request.header("Cookie", cookie),
+            None => request,
+        }
     }
 
     /// 解析抖音链接获取视频信息
@@ -87,26 +468,493 @@ impl DouyinParser {
         self.fetch_video_from_page(&aweme_id).await
     }
 
-    /// 跟随重定向获取最终 URL
-    async fn follow_redirect(&self, url: &str) -> Result<String, DouyinError> {
-        let response = self
+    /// 解析链接得到作品 ID（aweme_id）
+    pub async fn resolve_aweme_id(&self, link: &str) -> Result<String, DouyinError> {
+        let link = link.trim();
+        if !link.is_empty() && link.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(link.to_string());
+        }
+        if !Self::is_valid_link(link) {
+            return Err(DouyinError::InvalidLink(link.to_string()));
+        }
+
+        match self.extract_aweme_id(link) {
+            Ok(id) => Ok(id),
+            Err(_) => {
+                let final_url = self.follow_redirect(link).await?;
+                self.extract_aweme_id(&final_url)
+            }
+        }
+    }
+
+    /// 抓取作品评论（分页），可选同时抓取每条评论的回复
+    ///
+    /// `max_comments` 限制一级评论数量，回复不计入
+    pub async fn fetch_comments(
+        &self,
+        aweme_id: &str,
+        max_comments: usize,
+        include_replies: bool,
+    ) -> Result<Vec<DouyinComment>, DouyinError> {
+        let mut top_level: Vec<DouyinComment> = Vec::new();
+        let mut cursor = 0u64;
+
+        loop {
+            let url = format!(
+                "{}/aweme/v1/web/comment/list/?aweme_id={}&cursor={}&count={}&aid=6383",
+                self.endpoints.web, aweme_id, cursor, COMMENT_PAGE_SIZE
+            );
+            let page = self.fetch_comment_page(&url, aweme_id, None).await?;
+            let fetched = page.comments.len();
+            top_level.extend(page.comments);
+
+            if !page.has_more || fetched == 0 || top_level.len() >= max_comments {
+                break;
+            }
+            cursor = page.cursor;
+            tokio::time::sleep(Duration::from_millis(COMMENT_PAGE_INTERVAL_MS)).await;
+        }
+        top_level.truncate(max_comments);
+
+        let mut comments = Vec::with_capacity(top_level.len());
+        for comment in top_level {
+            let needs_replies = include_replies && comment.reply_count > 0;
+            let cid = comment.cid.clone();
+            comments.push(comment);
+            if needs_replies {
+                match self.fetch_replies(aweme_id, &cid).await {
+                    Ok(replies) => comments.extend(replies),
+                    Err(e) => tracing::warn!("抓取评论 {} 的回复失败: {}", cid, e),
+                }
+            }
+        }
+
+        Ok(comments)
+    }
+
+    /// 抓取一条一级评论下的回复
+    async fn fetch_replies(
+        &self,
+        aweme_id: &str,
+        comment_id: &str,
+    ) -> Result<Vec<DouyinComment>, DouyinError> {
+        let mut replies = Vec::new();
+        let mut cursor = 0u64;
+
+        loop {
+            tokio::time::sleep(Duration::from_millis(COMMENT_PAGE_INTERVAL_MS)).await;
+            let url = format!(
+                "{}/aweme/v1/web/comment/list/reply/?item_id={}&comment_id={}&cursor={}&count={}&aid=6383",
+                self.endpoints.web, aweme_id, comment_id, cursor, COMMENT_PAGE_SIZE
+            );
+            let page = self
+                .fetch_comment_page(&url, aweme_id, Some(comment_id))
+                .await?;
+            let fetched = page.comments.len();
+            replies.extend(page.comments);
+
+            if !page.has_more || fetched == 0 || replies.len() >= MAX_REPLIES_PER_COMMENT {
+                break;
+            }
+            cursor = page.cursor;
+        }
+        replies.truncate(MAX_REPLIES_PER_COMMENT);
+
+        Ok(replies)
+    }
+
+    /// 请求一页评论
+    async fn fetch_comment_page(
+        &self,
+        url: &str,
+        aweme_id: &str,
+        parent_id: Option<&str>,
+    ) -> Result<CommentPage, DouyinError> {
+        let request = self
             .client
             .get(url)
+            .header("Accept", "application/json")
             .header(
-                "Accept",
-                "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+                "Referer",
+                format!("https://www.douyin.com/video/{}", aweme_id),
             )
-            .send()
+            .header("Cookie", self.cookie.as_deref().unwrap_or(ANONYMOUS_COOKIE));
+        let response = self.send_limited(url, request).await?;
+        let status = response.status().as_u16();
+
+        let body = response
+            .text()
             .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    DouyinError::Timeout
-                } else {
-                    DouyinError::NetworkError(e.to_string())
-                }
-            })?;
+            .map_err(|e| DouyinError::NetworkError(e.to_string()))?;
+        self.check_throttled(url, status, &body)?;
+        if !(200..300).contains(&status) {
+            return Err(DouyinError::NetworkError(format!(
+                "评论接口返回 HTTP {}",
+                status
+            )));
+        }
+
+        // 未登录或 Cookie 失效时评论接口返回空响应
+        if body.trim().is_empty() {
+            return Err(DouyinError::AuthRequired);
+        }
+
+        let json: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| DouyinError::ParseError(format!("JSON 解析失败: {}", e)))?;
+
+        self.parse_comment_page(&json, aweme_id, parent_id)
+    }
+
+    /// 解析评论接口响应
+    fn parse_comment_page(
+        &self,
+        json: &serde_json::Value,
+        aweme_id: &str,
+        parent_id: Option<&str>,
+    ) -> Result<CommentPage, DouyinError> {
+        let status = json["status_code"].as_i64().unwrap_or(0);
+        if status != 0 {
+            return Err(DouyinError::ParseError(format!(
+                "评论接口返回错误 {}: {}",
+                status,
+                json["status_msg"].as_str().unwrap_or("")
+            )));
+        }
+
+        let comments = json["comments"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|item| {
+                let cid = item["cid"]
+                    .as_str()
+                    .map(|s| s.to_string())
+                    .or_else(|| item["cid"].as_u64().map(|n| n.to_string()))?;
+                let user = &item["user"];
+                let author_id = user["sec_uid"]
+                    .as_str()
+                    .filter(|s| !s.is_empty())
+                    .or_else(|| user["uid"].as_str())
+                    .unwrap_or("")
+                    .to_string();
+
+                Some(DouyinComment {
+                    cid,
+                    aweme_id: aweme_id.to_string(),
+                    parent_id: parent_id.map(|s| s.to_string()),
+                    text: item["text"].as_str().unwrap_or("").to_string(),
+                    author: user["nickname"].as_str().unwrap_or("").to_string(),
+                    author_id,
+                    likes: item["digg_count"].as_u64().unwrap_or(0),
+                    reply_count: item["reply_comment_total"].as_u64().unwrap_or(0),
+                    create_time: item["create_time"].as_u64().unwrap_or(0),
+                    ip_location: self.extract_ip_location(item),
+                })
+            })
+            .collect();
+
+        Ok(CommentPage {
+            comments,
+            has_more: json["has_more"].as_u64().unwrap_or(0) == 1
+                || json["has_more"].as_bool() == Some(true),
+            cursor: json["cursor"].as_u64().unwrap_or(0),
+        })
+    }
+
+    /// 关键词搜索作品，按 `count` 自动翻页
+    ///
+    /// 搜索接口对未登录请求限制较严，建议先导入登录 Cookie
+    pub async fn search_videos(&self, query: &SearchQuery) -> Result<VideoPage, DouyinError> {
+        let keyword = query.keyword.trim();
+        if keyword.is_empty() {
+            return Err(DouyinError::ParseError("搜索关键词为空".to_string()));
+        }
+
+        // 指定排序或时间筛选时需要标记为筛选搜索
+        let is_filter =
+            query.sort != SearchSort::Relevance || query.publish_time != PublishTimeFilter::Any;
+
+        self.fetch_video_pages(query.offset, query.count, "cursor", |offset, count| {
+            format!(
+                "/aweme/v1/web/search/item/?device_platform=webapp&aid=6383&channel=channel_pc_web&search_channel=aweme_video_web&search_source=normal_search&query_correct_type=1&keyword={}&sort_type={}&publish_time={}&is_filter_search={}&offset={}&count={}",
+                urlencoding::encode(keyword),
+                query.sort.as_param(),
+                query.publish_time.as_param(),
+                u8::from(is_filter),
+                offset,
+                count
+            )
+        })
+        .await
+    }
+
+    /// 获取用户主页作品（按发布时间从新到旧），按 `count` 自动翻页
+    ///
+    /// `user` 可以是 sec_uid、`douyin.com/user/<sec_uid>` 主页链接或主页分享短链接；
+    /// 翻页时传入上一页返回的 `cursor` 作为 `max_cursor`
+    pub async fn fetch_user_videos(
+        &self,
+        user: &str,
+        max_cursor: u64,
+        count: u32,
+    ) -> Result<VideoPage, DouyinError> {
+        let sec_uid = self.resolve_sec_uid(user).await?;
+
+        self.fetch_video_pages(max_cursor, count, "max_cursor", |cursor, count| {
+            format!(
+                "/aweme/v1/web/aweme/post/?device_platform=webapp&aid=6383&channel=channel_pc_web&sec_user_id={}&max_cursor={}&count={}&publish_video_strategy_type=2",
+                sec_uid, cursor, count
+            )
+        })
+        .await
+    }
+
+    /// 获取话题（挑战）下的作品，按 `count` 自动翻页
+    ///
+    /// `challenge` 可以是话题 ID、话题页链接或话题分享短链接
+    pub async fn fetch_challenge_videos(
+        &self,
+        challenge: &str,
+        sort: ChallengeSort,
+        cursor: u64,
+        count: u32,
+    ) -> Result<VideoPage, DouyinError> {
+        let ch_id = self.resolve_challenge_id(challenge).await?;
+
+        self.fetch_video_pages(cursor, count, "cursor", |cursor, count| {
+            format!(
+                "/aweme/v1/web/challenge/aweme/?device_platform=webapp&aid=6383&channel=channel_pc_web&ch_id={}&sort_type={}&cursor={}&count={}",
+                ch_id,
+                sort.as_param(),
+                cursor,
+                count
+            )
+        })
+        .await
+    }
+
+    /// 获取使用某个背景音乐的作品，按 `count` 自动翻页
+    ///
+    /// `music` 可以是音乐 ID、音乐页链接或音乐分享短链接
+    pub async fn fetch_music_videos(
+        &self,
+        music: &str,
+        cursor: u64,
+        count: u32,
+    ) -> Result<VideoPage, DouyinError> {
+        let music_id = self.resolve_music_id(music).await?;
+
+        self.fetch_video_pages(cursor, count, "cursor", |cursor, count| {
+            format!(
+                "/aweme/v1/web/music/aweme/?device_platform=webapp&aid=6383&channel=channel_pc_web&music_id={}&cursor={}&count={}",
+                music_id, cursor, count
+            )
+        })
+        .await
+    }
+
+    /// 解析话题 ID（支持 `/hashtag/<id>`、`/challenge/<id>` 链接与 `ch_id=` 参数）
+    pub async fn resolve_challenge_id(&self, input: &str) -> Result<String, DouyinError> {
+        self.resolve_listing_id(input, r"(?:/hashtag/|/challenge/|ch_id=)(\d+)", "话题")
+            .await
+    }
+
+    /// 解析音乐 ID（支持 `/music/<id>` 链接与 `music_id=` 参数）
+    pub async fn resolve_music_id(&self, input: &str) -> Result<String, DouyinError> {
+        self.resolve_listing_id(input, r"(?:/music/|music_id=)(\d+)", "音乐")
+            .await
+    }
+
+    /// 从数字 ID 或链接中提取列表 ID，短链接先跟随跳转
+    async fn resolve_listing_id(
+        &self,
+        input: &str,
+        pattern: &str,
+        kind: &str,
+    ) -> Result<String, DouyinError> {
+        let input = input.trim();
+        if !input.is_empty() && input.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(input.to_string());
+        }
+        if !Self::is_valid_link(input) {
+            return Err(DouyinError::InvalidLink(format!(
+                "请填写{}ID 或{}链接: {}",
+                kind, kind, input
+            )));
+        }
+
+        let re = Regex::new(pattern).unwrap();
+        let extract = |url: &str| re.captures(url).map(|caps| caps[1].to_string());
+        if let Some(id) = extract(input) {
+            return Ok(id);
+        }
+        let final_url = self.follow_redirect(input).await?;
+        extract(&final_url).ok_or_else(|| {
+            DouyinError::ParseError(format!("无法从 URL 中提取{} ID: {}", kind, final_url))
+        })
+    }
+
+    /// 解析用户标识得到 sec_uid
+    async fn resolve_sec_uid(&self, user: &str) -> Result<String, DouyinError> {
+        let user = user.trim();
+        if let Some(sec_uid) = extract_sec_uid(user) {
+            return Ok(sec_uid);
+        }
+        if user.starts_with("http://") || user.starts_with("https://") {
+            if !Self::is_valid_link(user) {
+                return Err(DouyinError::InvalidLink(user.to_string()));
+            }
+            let final_url = self.follow_redirect(user).await?;
+            return extract_sec_uid(&final_url).ok_or_else(|| {
+                DouyinError::ParseError(format!("无法从 URL 中提取用户 ID: {}", final_url))
+            });
+        }
+        if user.is_empty() || user.contains(char::is_whitespace) {
+            return Err(DouyinError::InvalidLink(user.to_string()));
+        }
+        Ok(user.to_string())
+    }
 
-        Ok(response.url().to_string())
+    /// 按页请求作品列表，直到取满 `count` 条或没有更多
+    ///
+    /// `cursor_key` 为响应中下一页位置的字段名，`build_path` 根据起始位置与本页条数生成接口路径（含查询参数）
+    async fn fetch_video_pages<F>(
+        &self,
+        start: u64,
+        count: u32,
+        cursor_key: &str,
+        build_path: F,
+    ) -> Result<VideoPage, DouyinError>
+    where
+        F: Fn(u64, u32) -> String,
+    {
+        let wanted = count.max(1) as usize;
+        let mut result = VideoPage {
+            videos: Vec::new(),
+            has_more: true,
+            cursor: start,
+        };
+
+        while result.has_more && result.videos.len() < wanted {
+            if !result.videos.is_empty() {
+                tokio::time::sleep(Duration::from_millis(COMMENT_PAGE_INTERVAL_MS)).await;
+            }
+            let page_size = (wanted - result.videos.len()).min(LIST_PAGE_SIZE as usize) as u32;
+            let path = build_path(result.cursor, page_size);
+            let json = self.fetch_list_json(&path).await?;
+            let page = self.parse_video_page(&json, cursor_key)?;
+
+            // 接口返回空页或游标不前进时停止，避免重复请求同一页
+            let stalled = page.videos.is_empty() || page.cursor == result.cursor;
+            result.videos.extend(page.videos);
+            result.has_more = page.has_more && !stalled;
+            result.cursor = page.cursor;
+        }
+        result.videos.truncate(wanted);
+
+        Ok(result)
+    }
+
+    /// 请求列表类接口并检查返回状态
+    ///
+    /// 列表接口校验 a_bogus 签名，请求经 Sidecar 在抖音网页内发出，由页面 SDK 完成签名
+    async fn fetch_list_json(&self, path: &str) -> Result<serde_json::Value, DouyinError> {
+        let url = format!("{}/douyin_web{}", self.endpoints.sidecar, path);
+        let target = format!("{}{}", self.endpoints.web, path);
+
+        let mut request = self.client.get(&url).header("Accept", "application/json");
+        if let Some(cookie) = &self.cookie {
+            request = request.header("X-Douyin-Cookie", cookie);
+        }
+        for (name, value) in http_client::sidecar_forward_headers() {
+            request = request.header(name, value);
+        }
+
+        // Sidecar 首次请求需要启动浏览器并打开抖音首页，按抖音接口地址限速
+        let request = request.timeout(Duration::from_secs(LIST_REQUEST_TIMEOUT_SECS));
+        let response = self.send_limited(&target, request).await?;
+        let status = response.status().as_u16();
+        let sidecar_failed = response.headers().contains_key("X-Sidecar-Error");
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| DouyinError::NetworkError(e.to_string()))?;
+        if sidecar_failed {
+            let error = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|json| json["error"].as_str().map(str::to_string))
+                .unwrap_or(body);
+            return Err(DouyinError::NetworkError(format!(
+                "签名服务请求失败: {}",
+                error
+            )));
+        }
+        self.check_throttled(&target, status, &body)?;
+
+        // 签名未生效或触发风控时列表接口仍返回空响应（Cookie 有效时也一样）
+        if body.trim().is_empty() {
+            return Err(DouyinError::SignatureRequired);
+        }
+
+        let json: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| DouyinError::ParseError(format!("JSON 解析失败: {}", e)))?;
+        let status = json["status_code"].as_i64().unwrap_or(0);
+        if status != 0 {
+            return Err(DouyinError::ParseError(format!(
+                "列表接口返回错误 {}: {}",
+                status,
+                json["status_msg"].as_str().unwrap_or("")
+            )));
+        }
+        Ok(json)
+    }
+
+    /// 解析搜索（`data[].aweme_info`）或用户作品（`aweme_list`）接口响应
+    fn parse_video_page(
+        &self,
+        json: &serde_json::Value,
+        cursor_key: &str,
+    ) -> Result<VideoPage, DouyinError> {
+        let items: Vec<&serde_json::Value> = match json["aweme_list"].as_array() {
+            Some(list) => list.iter().collect(),
+            None => json["data"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|item| &item["aweme_info"])
+                .filter(|info| !info.is_null())
+                .collect(),
+        };
+
+        let videos = items
+            .into_iter()
+            .filter_map(|item| {
+                let aweme_id = item["aweme_id"].as_str().filter(|id| !id.is_empty())?;
+                self.extract_video_data_from_api(item, aweme_id).ok()
+            })
+            .collect();
+
+        Ok(VideoPage {
+            videos,
+            has_more: json["has_more"].as_u64().unwrap_or(0) == 1
+                || json["has_more"].as_bool() == Some(true),
+            cursor: json[cursor_key].as_u64().unwrap_or(0),
+        })
+    }
+
+    /// 跟随重定向获取最终 URL
+    async fn follow_redirect(&self, url: &str) -> Result<String, DouyinError> {
+        let request = self.with_session_cookie(self.client.get(url)).header(
+            "Accept",
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+        );
+        let response = self.send_limited(url, request).await?;
+
+        // 被风控时会重定向到验证页，最终地址带有验证标记
+        let final_url = response.url().to_string();
+        self.check_throttled(url, response.status().as_u16(), &final_url)?;
+        Ok(final_url)
     }
 
     /// 从 URL 中提取视频 ID (aweme_id)
@@ -140,47 +988,34 @@ impl DouyinParser {
     }
 
     /// 通过多个渠道尝试获取视频详情
-    async fn fetch_video_from_page(&self, aweme_id: &str) -> Result<DouyinVideoData, DouyinError> {
-        // [新增] 渠道0: 尝试本地 Python Sidecar API (dy-mcp)
-        // 注意：这里我们需要原始链接，但 fetch_video_from_page 只接收 aweme_id。
-        // 为了方便，我们这里构造一个标准的视频链接传给 Sidecar
-        eprintln!("[DEBUG] === 尝试渠道0: Local Sidecar API ===");
-        let mock_link = format!("https://www.douyin.com/video/{}", aweme_id);
-        if let Ok(data) = self.try_local_sidecar_api(&mock_link).await {
-            return Ok(data);
-        }
-
-        // 渠道1: 尝试新版 detail API
-        eprintln!("[DEBUG] === 尝试渠道1: detail API ===");
-        let api_url = format!(
-            "https://www.douyin.com/aweme/v1/web/aweme/detail/?aweme_id={}&aid=6383",
-            aweme_id
-        );
-
-        if let Ok(data) = self.try_json_api(&api_url, "aweme_detail", aweme_id).await {
-            return Ok(data);
-        }
-
-        // 渠道2: 尝试 share 页面获取 meta 信息
-        eprintln!("[DEBUG] === 尝试渠道2: Share 页面 ===");
-        let share_url = format!("https://www.douyin.com/share/video/{}", aweme_id);
-
-        if let Ok(data) = self.try_share_page(&share_url, aweme_id).await {
-            return Ok(data);
-        }
-
-        // 渠道3: 尝试旧版 iteminfo API
-        eprintln!("[DEBUG] === 尝试渠道3: iteminfo API ===");
-        let iteminfo_url = format!(
-            "https://www.iesdouyin.com/web/api/v2/aweme/iteminfo/?item_ids={}",
-            aweme_id
-        );
-
-        if let Ok(data) = self
-            .try_json_api(&iteminfo_url, "item_list", aweme_id)
-            .await
-        {
-            return Ok(data);
+    ///
+    /// 渠道顺序由健康度追踪器决定，最近可用的渠道优先；每次尝试的结果与耗时都会记录下来
+    pub async fn fetch_video_from_page(
+        &self,
+        aweme_id: &str,
+    ) -> Result<DouyinVideoData, DouyinError> {
+        for strategy in self.health.ordered_strategies() {
+            eprintln!("[DEBUG] === 尝试渠道: {} ===", strategy.as_str());
+            let started = Instant::now();
+            let result = self.try_strategy(strategy, aweme_id).await;
+            let latency_ms = started.elapsed().as_millis() as u64;
+
+            match result {
+                Ok(data) => {
+                    self.health
+                        .record(StrategyAttempt::new(strategy, latency_ms, None));
+                    return Ok(data);
+                }
+                // 受限时继续尝试其他渠道只会加重风控，也不计入渠道健康度
+                Err(e @ DouyinError::RateLimited(_)) => return Err(e),
+                Err(e) => {
+                    self.health.record(StrategyAttempt::new(
+                        strategy,
+                        latency_ms,
+                        Some(e.to_string()),
+                    ));
+                }
+            }
         }
 
         Err(DouyinError::ParseError(
@@ -188,34 +1023,80 @@ impl DouyinParser {
         ))
     }
 
+    /// 通过指定渠道获取视频详情
+    async fn try_strategy(
+        &self,
+        strategy: ParseStrategy,
+        aweme_id: &str,
+    ) -> Result<DouyinVideoData, DouyinError> {
+        match strategy {
+            // 本地 Python Sidecar API (dy-mcp)
+            // Sidecar 需要原始链接，这里构造一个标准的视频链接
+            ParseStrategy::Sidecar => {
+                let link = format!("https://www.douyin.com/video/{}", aweme_id);
+                self.try_local_sidecar_api(&link).await
+            }
+            // 新版 detail API
+            ParseStrategy::DetailApi => {
+                let url = format!(
+                    "{}/aweme/v1/web/aweme/detail/?aweme_id={}&aid=6383",
+                    self.endpoints.web, aweme_id
+                );
+                self.try_json_api(&url, "aweme_detail", aweme_id).await
+            }
+            // share 页面的 meta 信息
+            ParseStrategy::SharePage => {
+                let url = format!("{}/share/video/{}", self.endpoints.web, aweme_id);
+                self.try_share_page(&url, aweme_id).await
+            }
+            // 旧版 iteminfo API
+            ParseStrategy::ItemInfo => {
+                let url = format!(
+                    "{}/web/api/v2/aweme/iteminfo/?item_ids={}",
+                    self.endpoints.iesdouyin, aweme_id
+                );
+                self.try_json_api(&url, "item_list", aweme_id).await
+            }
+            // 作品页内嵌的 RENDER_DATA
+            ParseStrategy::RenderData => {
+                let url = format!("{}/video/{}", self.endpoints.web, aweme_id);
+                self.try_video_page(&url, aweme_id).await
+            }
+        }
+    }
+
     /// 尝试本地 Python Sidecar API
     async fn try_local_sidecar_api(&self, link: &str) -> Result<DouyinVideoData, DouyinError> {
-        use crate::core::mcp_client::SIDECAR_BASE_URL;
-        
         let api_url = format!(
             "{}/parse?link={}",
-            SIDECAR_BASE_URL,
+            self.endpoints.sidecar,
             urlencoding::encode(link)
         );
 
         eprintln!("[DEBUG] 请求 Sidecar: {}", api_url);
 
-        let response = self
-            .client
-            .get(&api_url)
-            .timeout(std::time::Duration::from_secs(45)) // Playwright 可能比较慢
-            .send()
-            .await
-            .map_err(|e| {
-                eprintln!("[DEBUG] Sidecar 连接失败: {}", e);
-                DouyinError::NetworkError(e.to_string())
-            })?;
+        let mut request = self.client.get(&api_url);
+        if let Some(cookie) = &self.cookie {
+            request = request.header("X-Douyin-Cookie", cookie);
+        }
+        for (name, value) in http_client::sidecar_forward_headers() {
+            request = request.header(name, value);
+        }
+
+        // Sidecar 代为访问抖音，按抖音链接限速
+        let request = request.timeout(std::time::Duration::from_secs(45)); // Playwright 可能比较慢
+        let response = self.send_limited(link, request).await.map_err(|e| {
+            eprintln!("[DEBUG] Sidecar 连接失败: {}", e);
+            e
+        })?;
+        let status = response.status().as_u16();
 
         let body = response
             .text()
             .await
             .map_err(|e| DouyinError::ParseError(e.to_string()))?;
         eprintln!("[DEBUG] Sidecar 响应长度: {}", body.len());
+        self.check_throttled(link, status, &body)?;
 
         let json: serde_json::Value = serde_json::from_str(&body)
             .map_err(|e| DouyinError::ParseError(format!("JSON 解析失败: {}", e)))?;
@@ -228,27 +1109,63 @@ impl DouyinParser {
 
         let data = &json["data"];
 
+        // Sidecar 附带原始作品数据时复用完整解析逻辑
+        if data["raw"].is_object() {
+            let aweme_id = data["video_id"].as_str().unwrap_or("");
+            let mut parsed = self.extract_video_data(&data["raw"], aweme_id)?;
+            if let Some(url) = data["download_url"].as_str().filter(|u| !u.is_empty()) {
+                parsed.video_url = url.to_string();
+                parsed.no_watermark_url = url.to_string();
+            }
+            eprintln!("[DEBUG] Sidecar 解析成功: {}", parsed.title);
+            return Ok(parsed);
+        }
+
         let title = data["title"].as_str().unwrap_or("").to_string();
         let video_url = data["download_url"].as_str().unwrap_or("").to_string();
         let cover_url = data["cover"].as_str().unwrap_or("").to_string();
         let author = data["author"].as_str().unwrap_or("未知").to_string();
         let aweme_id = data["video_id"].as_str().unwrap_or("").to_string();
+        let image_urls: Vec<String> = data["images"]
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let content_type = if image_urls.is_empty() {
+            ContentType::Video
+        } else {
+            ContentType::Images
+        };
 
         eprintln!("[DEBUG] Sidecar 解析成功: {}", title);
 
         Ok(DouyinVideoData {
             aweme_id,
+            content_type,
             title,
             author,
             video_url: video_url.clone(),
             no_watermark_url: video_url, // API 返回的通常已经是无水印的
+            image_urls,
+            music_url: data["music_url"].as_str().unwrap_or("").to_string(),
             author_id: String::new(),
             likes: data["likes"].as_u64().unwrap_or(0),
             comments: data["comments"].as_u64().unwrap_or(0),
             shares: data["shares"].as_u64().unwrap_or(0),
+            collects: data["collects"].as_u64().unwrap_or(0),
+            plays: 0,
             cover_url,
             duration: 0,
             create_time: 0,
+            hashtags: Vec::new(),
+            music: None,
+            ip_location: String::new(),
+            width: 0,
+            height: 0,
+            bit_rates: Vec::new(),
         })
     }
 
@@ -261,7 +1178,7 @@ impl DouyinParser {
     ) -> Result<DouyinVideoData, DouyinError> {
         eprintln!("[DEBUG] 请求: {}", url);
 
-        let response = self
+        let request = self
             .client
             .get(url)
             .header("Accept", "application/json")
@@ -270,16 +1187,16 @@ impl DouyinParser {
                 "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36",
             )
             .header("Referer", "https://www.douyin.com/")
-            .header("Cookie", "ttwid=1%7C1234567890")
-            .send()
-            .await
-            .map_err(|e| DouyinError::NetworkError(e.to_string()))?;
+            .header("Cookie", self.cookie.as_deref().unwrap_or(ANONYMOUS_COOKIE));
+        let response = self.send_limited(url, request).await?;
+        let status = response.status().as_u16();
 
         let body = response
             .text()
             .await
             .map_err(|e| DouyinError::ParseError(e.to_string()))?;
         eprintln!("[DEBUG] 响应长度: {} 字符", body.len());
+        self.check_throttled(url, status, &body)?;
 
         if body.is_empty() {
             return Err(DouyinError::ParseError("响应为空".to_string()));
@@ -322,20 +1239,20 @@ impl DouyinParser {
         eprintln!("[DEBUG] 请求 Share: {}", url);
 
         // 使用社交媒体爬虫 UA，通常会返回 meta 标签
-        let response = self
+        let request = self
             .client
             .get(url)
             .header("User-Agent", "facebookexternalhit/1.1")
-            .header("Accept", "text/html")
-            .send()
-            .await
-            .map_err(|e| DouyinError::NetworkError(e.to_string()))?;
+            .header("Accept", "text/html");
+        let response = self.send_limited(url, request).await?;
+        let status = response.status().as_u16();
 
         let body = response
             .text()
             .await
             .map_err(|e| DouyinError::ParseError(e.to_string()))?;
         eprintln!("[DEBUG] Share 响应长度: {} 字符", body.len());
+        self.check_throttled(url, status, &body)?;
 
         // 提取 meta 标签
         let title = self
@@ -353,20 +1270,55 @@ impl DouyinParser {
 
         Ok(DouyinVideoData {
             aweme_id: aweme_id.to_string(),
+            content_type: ContentType::Video,
             title,
             author: "未知".to_string(),
             video_url: String::new(),
             no_watermark_url: String::new(),
+            image_urls: Vec::new(),
+            music_url: String::new(),
             author_id: String::new(),
             likes: 0,
             comments: 0,
             shares: 0,
+            collects: 0,
+            plays: 0,
             cover_url: cover,
             duration: 0,
             create_time: 0,
+            hashtags: Vec::new(),
+            music: None,
+            ip_location: String::new(),
+            width: 0,
+            height: 0,
+            bit_rates: Vec::new(),
         })
     }
 
+    /// 请求作品页并解析内嵌的页面数据
+    async fn try_video_page(
+        &self,
+        url: &str,
+        aweme_id: &str,
+    ) -> Result<DouyinVideoData, DouyinError> {
+        eprintln!("[DEBUG] 请求作品页: {}", url);
+
+        let request = self.with_session_cookie(self.client.get(url)).header(
+            "Accept",
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+        );
+        let response = self.send_limited(url, request).await?;
+        let status = response.status().as_u16();
+
+        let html = response
+            .text()
+            .await
+            .map_err(|e| DouyinError::ParseError(e.to_string()))?;
+        self.check_throttled(url, status, &html)?;
+
+        self.parse_render_data(&html, aweme_id)
+    }
+
     /// 提取 meta 标签内容
     fn extract_meta(&self, html: &str, property: &str) -> Option<String> {
         // 匹配 <meta property="og:title" content="xxx"> 或 <meta name="description" content="xxx">
@@ -423,10 +1375,14 @@ impl DouyinParser {
         let likes = statistics["digg_count"].as_u64().unwrap_or(0);
         let comments = statistics["comment_count"].as_u64().unwrap_or(0);
         let shares = statistics["share_count"].as_u64().unwrap_or(0);
+        let collects = statistics["collect_count"].as_u64().unwrap_or(0);
+        let plays = statistics["play_count"].as_u64().unwrap_or(0);
 
         // 视频信息
         let video = &item["video"];
         let duration = video["duration"].as_u64().map(|d| d / 1000).unwrap_or(0);
+        let width = video["width"].as_u64().unwrap_or(0) as u32;
+        let height = video["height"].as_u64().unwrap_or(0) as u32;
 
         // 封面
         let cover_url = video["cover"]["url_list"]
@@ -454,21 +1410,43 @@ impl DouyinParser {
         // 创建时间
         let create_time = item["create_time"].as_u64().unwrap_or(0);
 
+        // 图文与背景音乐
+        let image_urls = self.extract_image_urls(item);
+        let content_type = self.detect_content_type(item, &image_urls);
+        let music_url = self.extract_music_url(item);
+
+        // 话题、音乐、IP 属地与码率档位
+        let hashtags = self.extract_hashtags(item);
+        let music = self.extract_music_info(item);
+        let ip_location = self.extract_ip_location(item);
+        let bit_rates = self.extract_bit_rates(video);
+
         eprintln!("[DEBUG] 解析成功: {} - {}", title, author);
 
         Ok(DouyinVideoData {
             aweme_id: aweme_id.to_string(),
+            content_type,
             video_url,
             no_watermark_url,
+            image_urls,
+            music_url,
             title,
             author,
             author_id,
             likes,
             comments,
             shares,
+            collects,
+            plays,
             cover_url,
             duration,
             create_time,
+            hashtags,
+            music,
+            ip_location,
+            width,
+            height,
+            bit_rates,
         })
     }
 
@@ -730,6 +1708,14 @@ impl DouyinParser {
             .as_u64()
             .or_else(|| stats["shareCount"].as_u64())
             .unwrap_or(0);
+        let collects = stats["collect_count"]
+            .as_u64()
+            .or_else(|| stats["collectCount"].as_u64())
+            .unwrap_or(0);
+        let plays = stats["play_count"]
+            .as_u64()
+            .or_else(|| stats["playCount"].as_u64())
+            .unwrap_or(0);
 
         // 视频信息
         let video = &detail["video"];
@@ -737,6 +1723,8 @@ impl DouyinParser {
             .as_u64()
             .map(|d| d / 1000) // 毫秒转秒
             .unwrap_or(0);
+        let width = video["width"].as_u64().unwrap_or(0) as u32;
+        let height = video["height"].as_u64().unwrap_or(0) as u32;
 
         // 封面
         let cover_url = video["cover"]["url_list"]
@@ -765,19 +1753,41 @@ impl DouyinParser {
             .or_else(|| detail["createTime"].as_u64())
             .unwrap_or(0);
 
+        // 图文与背景音乐
+        let image_urls = self.extract_image_urls(detail);
+        let content_type = self.detect_content_type(detail, &image_urls);
+        let music_url = self.extract_music_url(detail);
+
+        // 话题、音乐、IP 属地与码率档位
+        let hashtags = self.extract_hashtags(detail);
+        let music = self.extract_music_info(detail);
+        let ip_location = self.extract_ip_location(detail);
+        let bit_rates = self.extract_bit_rates(video);
+
         Ok(DouyinVideoData {
             aweme_id: aweme_id.to_string(),
+            content_type,
             video_url,
             no_watermark_url,
+            image_urls,
+            music_url,
             title,
             author,
             author_id,
             likes,
             comments,
             shares,
+            collects,
+            plays,
             cover_url,
             duration,
             create_time,
+            hashtags,
+            music,
+            ip_location,
+            width,
+            height,
+            bit_rates,
         })
     }
 
@@ -840,6 +1850,220 @@ impl DouyinParser {
         None
     }
 
+    /// 提取图文作品的图片地址
+    ///
+    /// `url_list` 通常为无水印版本，`download_url_list` 带水印，仅作回退
+    fn extract_image_urls(&self, detail: &serde_json::Value) -> Vec<String> {
+        let images = match detail["images"]
+            .as_array()
+            .or_else(|| detail["image_post_info"]["images"].as_array())
+        {
+            Some(images) => images,
+            None => return Vec::new(),
+        };
+
+        images
+            .iter()
+            .filter_map(|image| {
+                let candidates = [
+                    &image["url_list"],
+                    &image["urlList"],
+                    &image["display_image"]["url_list"],
+                    &image["download_url_list"],
+                    &image["downloadUrlList"],
+                ];
+                candidates.iter().find_map(|list| {
+                    let urls: Vec<&str> =
+                        list.as_array()?.iter().filter_map(|v| v.as_str()).collect();
+                    urls.iter()
+                        .find(|url| !url.contains("water"))
+                        .or_else(|| urls.first())
+                        .map(|url| url.to_string())
+                })
+            })
+            .collect()
+    }
+
+    /// 判断作品类型
+    fn detect_content_type(
+        &self,
+        detail: &serde_json::Value,
+        image_urls: &[String],
+    ) -> ContentType {
+        let aweme_type = detail["aweme_type"]
+            .as_u64()
+            .or_else(|| detail["awemeType"].as_u64());
+
+        if !image_urls.is_empty() || aweme_type == Some(AWEME_TYPE_IMAGES) {
+            ContentType::Images
+        } else {
+            ContentType::Video
+        }
+    }
+
+    /// 提取背景音乐地址
+    fn extract_music_url(&self, detail: &serde_json::Value) -> String {
+        let music = &detail["music"];
+        let paths = [
+            &music["play_url"]["url_list"],
+            &music["playUrl"]["urlList"],
+            &music["play_url"]["uri"],
+            &music["playUrl"],
+        ];
+
+        for path in paths {
+            if let Some(url) = path
+                .as_array()
+                .and_then(|arr| arr.first())
+                .and_then(|v| v.as_str())
+                .or_else(|| path.as_str())
+            {
+                if url.starts_with("http") {
+                    return url.to_string();
+                }
+            }
+        }
+
+        String::new()
+    }
+
+    /// 提取话题标签（`text_extra` 与 `cha_list`），去重并保持出现顺序
+    fn extract_hashtags(&self, detail: &serde_json::Value) -> Vec<String> {
+        let text_extra = detail["text_extra"]
+            .as_array()
+            .or_else(|| detail["textExtra"].as_array());
+        let cha_list = detail["cha_list"]
+            .as_array()
+            .or_else(|| detail["chaList"].as_array());
+
+        let names = text_extra
+            .into_iter()
+            .flatten()
+            .filter_map(|e| {
+                e["hashtag_name"]
+                    .as_str()
+                    .or_else(|| e["hashtagName"].as_str())
+            })
+            .chain(
+                cha_list
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|c| c["cha_name"].as_str().or_else(|| c["chaName"].as_str())),
+            );
+
+        let mut hashtags: Vec<String> = Vec::new();
+        for name in names {
+            let name = name.trim().trim_start_matches('#').trim();
+            if !name.is_empty() && !hashtags.iter().any(|t| t == name) {
+                hashtags.push(name.to_string());
+            }
+        }
+        hashtags
+    }
+
+    /// 提取背景音乐信息
+    fn extract_music_info(&self, detail: &serde_json::Value) -> Option<MusicInfo> {
+        let music = &detail["music"];
+        if !music.is_object() {
+            return None;
+        }
+
+        let id = music["id_str"]
+            .as_str()
+            .or_else(|| music["mid"].as_str())
+            .or_else(|| music["id"].as_str())
+            .map(|id| id.to_string())
+            .or_else(|| music["id"].as_u64().map(|id| id.to_string()))
+            .unwrap_or_default();
+        let title = music["title"].as_str().unwrap_or("").to_string();
+        if id.is_empty() && title.is_empty() {
+            return None;
+        }
+
+        Some(MusicInfo {
+            id,
+            title,
+            author: music["author"]
+                .as_str()
+                .or_else(|| music["authorName"].as_str())
+                .or_else(|| music["owner_nickname"].as_str())
+                .unwrap_or("")
+                .to_string(),
+            duration: music["duration"].as_u64().unwrap_or(0),
+        })
+    }
+
+    /// 提取 IP 属地
+    fn extract_ip_location(&self, detail: &serde_json::Value) -> String {
+        detail["ip_label"]
+            .as_str()
+            .or_else(|| detail["ipLabel"].as_str())
+            .or_else(|| detail["region"].as_str())
+            .unwrap_or("")
+            .to_string()
+    }
+
+    /// 提取各码率档位，按码率从高到低排序
+    fn extract_bit_rates(&self, video: &serde_json::Value) -> Vec<VideoVariant> {
+        let list = video["bit_rate"]
+            .as_array()
+            .or_else(|| video["bitRateList"].as_array());
+
+        let mut variants: Vec<VideoVariant> = list
+            .into_iter()
+            .flatten()
+            .filter_map(|item| {
+                let play_addr = &item["play_addr"];
+                let url = play_addr["url_list"]
+                    .as_array()
+                    .and_then(|arr| arr.first())
+                    .and_then(|v| v.as_str())
+                    .or_else(|| {
+                        item["playAddr"]
+                            .as_array()
+                            .and_then(|arr| arr.first())
+                            .and_then(|v| v["src"].as_str())
+                    })?;
+                let is_h265 = [&item["is_h265"], &item["is_bytevc1"], &item["isH265"]]
+                    .iter()
+                    .any(|flag| flag.as_u64() == Some(1) || flag.as_bool() == Some(true));
+
+                Some(VideoVariant {
+                    gear_name: item["gear_name"]
+                        .as_str()
+                        .or_else(|| item["gearName"].as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    bit_rate: item["bit_rate"]
+                        .as_u64()
+                        .or_else(|| item["bitRate"].as_u64())
+                        .unwrap_or(0),
+                    width: play_addr["width"]
+                        .as_u64()
+                        .or_else(|| item["width"].as_u64())
+                        .unwrap_or(0) as u32,
+                    height: play_addr["height"]
+                        .as_u64()
+                        .or_else(|| item["height"].as_u64())
+                        .unwrap_or(0) as u32,
+                    fps: item["FPS"]
+                        .as_u64()
+                        .or_else(|| item["fps"].as_u64())
+                        .unwrap_or(0) as u32,
+                    codec: if is_h265 { "h265" } else { "h264" }.to_string(),
+                    data_size: play_addr["data_size"]
+                        .as_u64()
+                        .or_else(|| item["dataSize"].as_u64())
+                        .unwrap_or(0),
+                    url: url.to_string(),
+                })
+            })
+            .collect();
+
+        variants.sort_by_key(|v| std::cmp::Reverse(v.bit_rate));
+        variants
+    }
+
     /// 验证是否为有效的抖音链接
     pub fn is_valid_link(link: &str) -> bool {
         let link = link.trim().to_lowercase();
@@ -855,6 +2079,17 @@ impl Default for DouyinParser {
     }
 }
 
+/// 从用户主页链接中提取 sec_uid（`/user/<sec_uid>` 或 `sec_uid=` 查询参数）
+fn extract_sec_uid(url: &str) -> Option<String> {
+    let re = Regex::new(r"(?:/user/|sec_uid=)(MS4w[A-Za-z0-9_\-.]+)").unwrap();
+    if let Some(caps) = re.captures(url) {
+        return Some(caps[1].to_string());
+    }
+    // 直接填写的 sec_uid
+    let re = Regex::new(r"^MS4w[A-Za-z0-9_\-.]+$").unwrap();
+    re.is_match(url.trim()).then(|| url.trim().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -881,4 +2116,184 @@ mod tests {
         let url = "https://www.douyin.com/note/7123456789012345678";
         assert_eq!(parser.extract_aweme_id(url).unwrap(), "7123456789012345678");
     }
+
+    #[test]
+    fn test_extract_image_post() {
+        let parser = DouyinParser::new();
+        let detail = serde_json::json!({
+            "desc": "图文作品",
+            "aweme_type": 68,
+            "author": { "nickname": "作者" },
+            "images": [
+                { "url_list": ["https://p3.example.com/water/1.webp", "https://p3.example.com/1.webp"] },
+                { "download_url_list": ["https://p3.example.com/2.jpeg"] }
+            ],
+            "music": { "play_url": { "url_list": ["https://sf.example.com/music.mp3"] } },
+            "video": { "play_addr": { "url_list": ["https://sf.example.com/music.mp3"] } }
+        });
+
+        let data = parser.extract_video_data(&detail, "1").unwrap();
+        assert_eq!(data.content_type, ContentType::Images);
+        assert_eq!(
+            data.image_urls,
+            vec![
+                "https://p3.example.com/1.webp".to_string(),
+                "https://p3.example.com/2.jpeg".to_string()
+            ]
+        );
+        assert_eq!(data.music_url, "https://sf.example.com/music.mp3");
+
+        let video = serde_json::json!({ "desc": "视频", "video": {} });
+        let data = parser.extract_video_data(&video, "2").unwrap();
+        assert_eq!(data.content_type, ContentType::Video);
+        assert!(data.image_urls.is_empty());
+    }
+
+    #[test]
+    fn test_extract_rich_metadata() {
+        let parser = DouyinParser::new();
+        let detail = serde_json::json!({
+            "desc": "周末去露营 #露营 #户外",
+            "ip_label": "浙江",
+            "text_extra": [
+                { "hashtag_name": "露营", "type": 1 },
+                { "user_id": "123", "type": 0 },
+                { "hashtag_name": "户外", "type": 1 }
+            ],
+            "cha_list": [{ "cha_name": "户外" }, { "cha_name": "周末去哪儿" }],
+            "statistics": { "digg_count": 10, "collect_count": 7, "play_count": 0 },
+            "music": { "id_str": "7001", "title": "原声", "author": "作者", "duration": 15 },
+            "video": {
+                "width": 1080,
+                "height": 1920,
+                "bit_rate": [
+                    {
+                        "gear_name": "normal_720_0",
+                        "bit_rate": 1_000_000,
+                        "is_h265": 0,
+                        "FPS": 30,
+                        "play_addr": { "url_list": ["https://v.example.com/720"], "width": 720, "height": 1280 }
+                    },
+                    {
+                        "gear_name": "normal_1080_0",
+                        "bit_rate": 2_000_000,
+                        "is_h265": 1,
+                        "FPS": 30,
+                        "play_addr": { "url_list": ["https://v.example.com/1080"], "width": 1080, "height": 1920, "data_size": 4096 }
+                    },
+                    { "gear_name": "broken", "bit_rate": 3_000_000 }
+                ]
+            }
+        });
+
+        let data = parser.extract_video_data(&detail, "1").unwrap();
+        assert_eq!(data.hashtags, vec!["露营", "户外", "周末去哪儿"]);
+        assert_eq!(data.collects, 7);
+        assert_eq!(data.plays, 0);
+        assert_eq!(data.ip_location, "浙江");
+        assert_eq!((data.width, data.height), (1080, 1920));
+
+        let music = data.music.unwrap();
+        assert_eq!(music.id, "7001");
+        assert_eq!(music.author, "作者");
+        assert_eq!(music.duration, 15);
+
+        assert_eq!(data.bit_rates.len(), 2);
+        assert_eq!(data.bit_rates[0].gear_name, "normal_1080_0");
+        assert_eq!(data.bit_rates[0].codec, "h265");
+        assert_eq!(data.bit_rates[0].data_size, 4096);
+        assert_eq!(data.bit_rates[1].codec, "h264");
+        assert_eq!(data.bit_rates[1].height, 1280);
+    }
+
+    #[test]
+    fn test_quality_preference_select() {
+        let variant =
+            |name: &str, bit_rate: u64, short: u32, codec: &str, size: u64| VideoVariant {
+                gear_name: name.to_string(),
+                bit_rate,
+                width: short,
+                height: short * 16 / 9,
+                codec: codec.to_string(),
+                data_size: size,
+                url: format!("https://v.example.com/{}", name),
+                ..Default::default()
+            };
+        let variants = vec![
+            variant("1080_h265", 2_000_000, 1080, "h265", 4_000),
+            variant("1080_h264", 3_000_000, 1080, "h264", 6_000),
+            variant("720_h264", 1_000_000, 720, "h264", 0),
+            variant("540_h264", 600_000, 540, "h264", 2_500),
+        ];
+        let pick = |pref: QualityPreference| pref.select(&variants).unwrap().gear_name.clone();
+
+        assert_eq!(pick(QualityPreference::Best), "1080_h264");
+        // 大小未知的档位不参与最小体积比较（除非全部未知）
+        assert_eq!(pick(QualityPreference::Smallest), "540_h264");
+        assert_eq!(pick(QualityPreference::AudioOnly), "540_h264");
+        assert_eq!(
+            pick(QualityPreference::Codec {
+                codec: "HEVC".to_string()
+            }),
+            "1080_h265"
+        );
+        assert_eq!(
+            pick(QualityPreference::MaxHeight { height: 720 }),
+            "720_h264"
+        );
+        assert_eq!(
+            pick(QualityPreference::MaxHeight { height: 360 }),
+            "540_h264"
+        );
+        assert!(QualityPreference::Best.select(&[]).is_none());
+
+        let pref: QualityPreference =
+            serde_json::from_str(r#"{"mode":"codec","codec":"h264"}"#).unwrap();
+        assert_eq!(
+            pref,
+            QualityPreference::Codec {
+                codec: "h264".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_comment_page() {
+        let parser = DouyinParser::new();
+        let json = serde_json::json!({
+            "status_code": 0,
+            "has_more": 1,
+            "cursor": 20,
+            "comments": [
+                {
+                    "cid": "7301",
+                    "text": "这个在哪里买的？",
+                    "digg_count": 12,
+                    "reply_comment_total": 2,
+                    "create_time": 1700000000,
+                    "ip_label": "广东",
+                    "user": { "nickname": "小明", "sec_uid": "MS4w", "uid": "42" }
+                },
+                { "text": "缺少 cid 的评论" }
+            ]
+        });
+
+        let page = parser.parse_comment_page(&json, "1", None).unwrap();
+        assert!(page.has_more);
+        assert_eq!(page.cursor, 20);
+        assert_eq!(page.comments.len(), 1);
+        let comment = &page.comments[0];
+        assert_eq!(comment.cid, "7301");
+        assert_eq!(comment.parent_id, None);
+        assert_eq!(comment.author_id, "MS4w");
+        assert_eq!(comment.likes, 12);
+        assert_eq!(comment.reply_count, 2);
+        assert_eq!(comment.ip_location, "广东");
+
+        let replies = parser.parse_comment_page(&json, "1", Some("7301")).unwrap();
+        assert_eq!(replies.comments[0].parent_id.as_deref(), Some("7301"));
+
+        let error = serde_json::json!({ "status_code": 8, "status_msg": "请登录" });
+        assert!(parser.parse_comment_page(&error, "1", None).is_err());
+    }
 }
@@ -0,0 +1,447 @@
+// 重复视频检测模块
+// 对采样帧计算感知哈希（pHash），对提取的 16kHz WAV 计算能量包络指纹，
+// 用于识别同一视频的转载、不同分享链接下载等近似重复内容
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// pHash 输入帧边长（灰度 32x32）
+pub const HASH_FRAME_SIZE: u32 = 32;
+/// 每个视频采样的帧数
+pub const HASH_FRAME_COUNT: usize = 8;
+/// 两帧视为相同的最大汉明距离
+const FRAME_MATCH_DISTANCE: u32 = 10;
+/// 音频指纹帧长（毫秒）
+const AUDIO_FRAME_MS: u32 = 100;
+/// 音频对齐搜索的最大偏移（帧）
+const AUDIO_MAX_OFFSET: usize = 50;
+/// 音频比对最少重叠帧数
+const AUDIO_MIN_OVERLAP: usize = 30;
+/// 默认重复判定阈值
+pub const DEFAULT_DUPLICATE_THRESHOLD: f32 = 0.85;
+
+/// 媒体指纹
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaFingerprint {
+    pub path: String,
+    pub duration_ms: u64,
+    /// 采样帧的 64 位感知哈希
+    pub frame_hashes: Vec<u64>,
+    /// 音频能量包络比特（每 32 帧打包为一个 u32）
+    pub audio_bits: Vec<u32>,
+    /// 有效音频比特数
+    pub audio_len: usize,
+}
+
+/// 重复匹配结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateMatch {
+    pub path: String,
+    pub similarity: f32,
+    pub frame_similarity: f32,
+    pub audio_similarity: Option<f32>,
+}
+
+/// 重复视频簇
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateCluster {
+    pub paths: Vec<String>,
+    /// 簇内两两相似度的最小值
+    pub min_similarity: f32,
+}
+
+/// 计算帧哈希的采样时间点（秒），分布在视频 10%-90% 区间
+pub fn hash_timestamps(duration_ms: u64) -> Vec<f32> {
+    let duration = duration_ms as f32 / 1000.0;
+    let start = duration * 0.1;
+    let step = duration * 0.8 / HASH_FRAME_COUNT as f32;
+
+    (0..HASH_FRAME_COUNT)
+        .map(|i| start + step * (i as f32 + 0.5))
+        .collect()
+}
+
+/// 计算 32x32 灰度帧的感知哈希（DCT 低频 8x8 与中位数比较）
+pub fn perceptual_hash(frame: &[u8]) -> u64 {
+    let n = HASH_FRAME_SIZE as usize;
+    if frame.len() < n * n {
+        return 0;
+    }
+
+    // 可分离二维 DCT-II，只计算左上 8x8 低频系数
+    let cos_table: Vec<f64> = (0..8)
+        .flat_map(|u| {
+            (0..n).map(move |x| {
+                (((2 * x + 1) as f64 * u as f64 * std::f64::consts::PI) / (2.0 * n as f64)).cos()
+            })
+        })
+        .collect();
+
+    let mut rows = vec![0.0f64; n * 8];
+    for y in 0..n {
+        for u in 0..8 {
+            rows[y * 8 + u] = (0..n)
+                .map(|x| frame[y * n + x] as f64 * cos_table[u * n + x])
+                .sum();
+        }
+    }
+
+    let mut coeffs = [0.0f64; 64];
+    for v in 0..8 {
+        for u in 0..8 {
+            coeffs[v * 8 + u] = (0..n).map(|y| rows[y * 8 + u] * cos_table[v * n + y]).sum();
+        }
+    }
+
+    // 排除直流分量计算中位数
+    let mut sorted: Vec<f64> = coeffs[1..].to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let median = sorted[sorted.len() / 2];
+
+    coeffs
+        .iter()
+        .enumerate()
+        .filter(|(_, &c)| c > median)
+        .fold(0u64, |hash, (i, _)| hash | (1 << i))
+}
+
+/// 汉明距离
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// 帧相似度：双向统计能在对方找到近似帧的比例（对片头裁剪、重新编码鲁棒）
+pub fn frame_similarity(a: &[u64], b: &[u64]) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let matched = |from: &[u64], to: &[u64]| {
+        from.iter()
+            .filter(|&&h| {
+                to.iter()
+                    .any(|&other| hamming_distance(h, other) <= FRAME_MATCH_DISTANCE)
+            })
+            .count() as f32
+            / from.len() as f32
+    };
+
+    (matched(a, b) + matched(b, a)) / 2.0
+}
+
+/// 计算音频能量包络指纹
+///
+/// 按 100ms 分帧计算 RMS，相邻帧能量上升记为 1，下降记为 0
+pub fn audio_fingerprint(samples: &[i16], sample_rate: u32) -> (Vec<u32>, usize) {
+    let frame_len = (sample_rate * AUDIO_FRAME_MS / 1000).max(1) as usize;
+    let energies: Vec<f64> = samples
+        .chunks(frame_len)
+        .filter(|c| c.len() == frame_len)
+        .map(|c| (c.iter().map(|&s| (s as f64) * (s as f64)).sum::<f64>() / c.len() as f64).sqrt())
+        .collect();
+
+    let bits: Vec<bool> = energies.windows(2).map(|w| w[1] > w[0]).collect();
+    (pack_bits(&bits), bits.len())
+}
+
+fn pack_bits(bits: &[bool]) -> Vec<u32> {
+    bits.chunks(32)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, &b)| b)
+                .fold(0u32, |word, (i, _)| word | (1 << i))
+        })
+        .collect()
+}
+
+fn unpack_bits(words: &[u32], len: usize) -> Vec<bool> {
+    (0..len.min(words.len() * 32))
+        .map(|i| words[i / 32] & (1 << (i % 32)) != 0)
+        .collect()
+}
+
+/// 音频相似度：在 ±5 秒范围内搜索最佳对齐，返回比特一致率（0.5 为随机水平，映射到 0-1）
+pub fn audio_similarity(a: &MediaFingerprint, b: &MediaFingerprint) -> Option<f32> {
+    let bits_a = unpack_bits(&a.audio_bits, a.audio_len);
+    let bits_b = unpack_bits(&b.audio_bits, b.audio_len);
+    if bits_a.len() < AUDIO_MIN_OVERLAP || bits_b.len() < AUDIO_MIN_OVERLAP {
+        return None;
+    }
+
+    let mut best = 0.0f32;
+    for offset in -(AUDIO_MAX_OFFSET as isize)..=(AUDIO_MAX_OFFSET as isize) {
+        let (sa, sb) = if offset >= 0 {
+            (offset as usize, 0)
+        } else {
+            (0, (-offset) as usize)
+        };
+        if sa >= bits_a.len() || sb >= bits_b.len() {
+            continue;
+        }
+
+        let overlap = (bits_a.len() - sa).min(bits_b.len() - sb);
+        if overlap < AUDIO_MIN_OVERLAP {
+            continue;
+        }
+
+        let same = (0..overlap)
+            .filter(|&i| bits_a[sa + i] == bits_b[sb + i])
+            .count();
+        best = best.max(same as f32 / overlap as f32);
+    }
+
+    Some(((best - 0.5) / 0.5).clamp(0.0, 1.0))
+}
+
+/// 综合相似度（有音频时帧与音频各占一半）
+pub fn compare(a: &MediaFingerprint, b: &MediaFingerprint) -> DuplicateMatch {
+    let frame = frame_similarity(&a.frame_hashes, &b.frame_hashes);
+    let audio = audio_similarity(a, b);
+    let similarity = match audio {
+        Some(audio) => (frame + audio) / 2.0,
+        None => frame,
+    };
+
+    DuplicateMatch {
+        path: b.path.clone(),
+        similarity,
+        frame_similarity: frame,
+        audio_similarity: audio,
+    }
+}
+
+/// 在指纹库中查找与目标近似重复的条目（按相似度降序）
+pub fn find_matches(
+    target: &MediaFingerprint,
+    library: &[MediaFingerprint],
+    threshold: f32,
+) -> Vec<DuplicateMatch> {
+    let mut matches: Vec<DuplicateMatch> = library
+        .iter()
+        .filter(|f| f.path != target.path)
+        .map(|f| compare(target, f))
+        .filter(|m| m.similarity >= threshold)
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.similarity
+            .partial_cmp(&a.similarity)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    matches
+}
+
+/// 将指纹库按相似度聚类（并查集），只返回包含 2 个及以上成员的簇
+pub fn cluster(library: &[MediaFingerprint], threshold: f32) -> Vec<DuplicateCluster> {
+    let n = library.len();
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut edges = Vec::new();
+
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let similarity = compare(&library[i], &library[j]).similarity;
+            if similarity >= threshold {
+                edges.push((i, j, similarity));
+                let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                if ri != rj {
+                    parent[rj] = ri;
+                }
+            }
+        }
+    }
+
+    let mut clusters: std::collections::HashMap<usize, (Vec<usize>, f32)> =
+        std::collections::HashMap::new();
+    for i in 0..n {
+        let root = find(&mut parent, i);
+        clusters
+            .entry(root)
+            .or_insert_with(|| (Vec::new(), 1.0))
+            .0
+            .push(i);
+    }
+    for (i, _, similarity) in edges {
+        let root = find(&mut parent, i);
+        if let Some(entry) = clusters.get_mut(&root) {
+            entry.1 = entry.1.min(similarity);
+        }
+    }
+
+    let mut result: Vec<DuplicateCluster> = clusters
+        .into_values()
+        .filter(|(members, _)| members.len() > 1)
+        .map(|(members, min_similarity)| DuplicateCluster {
+            paths: members.iter().map(|&i| library[i].path.clone()).collect(),
+            min_similarity,
+        })
+        .collect();
+
+    result.sort_by_key(|c| std::cmp::Reverse(c.paths.len()));
+    result
+}
+
+/// 读取 16 位 PCM WAV 文件（ffmpeg 输出格式），多声道时只取第一声道
+pub fn read_wav_samples(path: &Path) -> Result<(Vec<i16>, u32), String> {
+    let data = std::fs::read(path).map_err(|e| format!("读取音频失败: {}", e))?;
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err("不是有效的 WAV 文件".to_string());
+    }
+
+    let mut pos = 12;
+    let mut sample_rate = 0u32;
+    let mut channels = 1u16;
+    let mut bits_per_sample = 16u16;
+
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let size = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]])
+            as usize;
+        let body_start = pos + 8;
+        let body_end = (body_start + size).min(data.len());
+
+        if id == b"fmt " && body_end - body_start >= 16 {
+            let body = &data[body_start..body_end];
+            channels = u16::from_le_bytes([body[2], body[3]]).max(1);
+            sample_rate = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+            bits_per_sample = u16::from_le_bytes([body[14], body[15]]);
+        } else if id == b"data" {
+            if bits_per_sample != 16 {
+                return Err(format!("不支持的采样位数: {}", bits_per_sample));
+            }
+            let stride = 2 * channels as usize;
+            let samples = data[body_start..body_end]
+                .chunks_exact(stride)
+                .map(|c| i16::from_le_bytes([c[0], c[1]]))
+                .collect();
+            return Ok((samples, sample_rate));
+        }
+
+        // chunk 按偶数字节对齐
+        pos = body_start + size + (size & 1);
+    }
+
+    Err("WAV 文件缺少音频数据".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient_frame(shift: usize) -> Vec<u8> {
+        let n = HASH_FRAME_SIZE as usize;
+        (0..n * n)
+            .map(|i| (((i % n + shift) * 8 + (i / n) * 3) % 256) as u8)
+            .collect()
+    }
+
+    fn fingerprint(path: &str, frames: Vec<u64>, samples: &[i16]) -> MediaFingerprint {
+        let (audio_bits, audio_len) = audio_fingerprint(samples, 1000);
+        MediaFingerprint {
+            path: path.to_string(),
+            duration_ms: 10_000,
+            frame_hashes: frames,
+            audio_bits,
+            audio_len,
+        }
+    }
+
+    fn pseudo_audio(seed: u32, len: usize) -> Vec<i16> {
+        let mut state = seed;
+        (0..len)
+            .map(|i| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                let envelope = ((i / 100) as u32).wrapping_mul(seed) % 7 + 1;
+                ((state >> 16) as i16 % 1000) * envelope as i16
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_perceptual_hash_stable_under_brightness_change() {
+        let frame = gradient_frame(0);
+        let brighter: Vec<u8> = frame.iter().map(|&p| p.saturating_add(10)).collect();
+        let different = gradient_frame(13);
+
+        let h = perceptual_hash(&frame);
+        assert!(hamming_distance(h, perceptual_hash(&brighter)) <= FRAME_MATCH_DISTANCE);
+        assert!(hamming_distance(h, perceptual_hash(&different)) > 0);
+    }
+
+    #[test]
+    fn test_frame_similarity() {
+        assert_eq!(frame_similarity(&[1, 2, 3], &[1, 2, 3]), 1.0);
+        assert_eq!(frame_similarity(&[0], &[u64::MAX]), 0.0);
+        assert_eq!(frame_similarity(&[], &[1]), 0.0);
+    }
+
+    #[test]
+    fn test_audio_similarity_with_offset() {
+        let audio = pseudo_audio(3, 20_000);
+        let a = fingerprint("a", vec![1], &audio);
+        // 裁掉开头 1 秒（10 帧）
+        let b = fingerprint("b", vec![1], &audio[1000..]);
+        let c = fingerprint("c", vec![1], &pseudo_audio(11, 20_000));
+
+        assert!(audio_similarity(&a, &b).unwrap() > 0.95);
+        assert!(audio_similarity(&a, &c).unwrap() < audio_similarity(&a, &b).unwrap());
+    }
+
+    #[test]
+    fn test_cluster() {
+        let audio = pseudo_audio(5, 10_000);
+        let library = vec![
+            fingerprint("a", vec![0xFF00, 0x0F0F], &audio),
+            fingerprint("b", vec![0xFF01, 0x0F0F], &audio),
+            fingerprint("c", vec![u64::MAX], &[]),
+        ];
+
+        let clusters = cluster(&library, DEFAULT_DUPLICATE_THRESHOLD);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].paths.len(), 2);
+        assert!(!clusters[0].paths.contains(&"c".to_string()));
+
+        let matches = find_matches(&library[0], &library, DEFAULT_DUPLICATE_THRESHOLD);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].path, "b");
+    }
+
+    #[test]
+    fn test_read_wav_samples() {
+        let samples: [i16; 4] = [0, 1000, -1000, 32767];
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36u32 + 8).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // 单声道
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(&32000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&8u32.to_le_bytes());
+        for s in samples {
+            wav.extend_from_slice(&s.to_le_bytes());
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.wav");
+        std::fs::write(&path, wav).unwrap();
+
+        let (read, rate) = read_wav_samples(&path).unwrap();
+        assert_eq!(rate, 16000);
+        assert_eq!(read, samples.to_vec());
+    }
+}
@@ -0,0 +1,230 @@
+// 互动数据时间序列模块
+// 根据定期采样的点赞/评论/分享数计算增长速度，并判断作品是否正在爆发
+
+use serde::{Deserialize, Serialize};
+
+/// 发布后多少秒内按小时采样
+const HOURLY_PHASE_SECS: i64 = 24 * 3600;
+/// 发布后多少秒内每 6 小时采样，之后每天采样
+const SIX_HOURLY_PHASE_SECS: i64 = 7 * 24 * 3600;
+/// 超过此时长（秒）的作品不再采样
+pub const MAX_TRACKING_SECS: i64 = 30 * 24 * 3600;
+/// 采样失败后首次重试的等待时长（秒），之后每次失败翻倍
+const RETRY_BASE_SECS: i64 = 3600;
+/// 失败重试的最长等待时长（秒）
+const RETRY_MAX_SECS: i64 = 24 * 3600;
+/// 连续失败达到此次数后不再自动采样（作品可能已删除或设为私密）
+pub const MAX_SAMPLE_FAILURES: u32 = 8;
+
+/// 一次互动数据采样
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub struct MetricSample {
+    /// 采样时间（Unix 秒）
+    pub sampled_at: i64,
+    pub likes: u64,
+    pub comments: u64,
+    pub shares: u64,
+    pub collects: u64,
+    pub plays: u64,
+}
+
+/// 每小时增长量
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub struct GrowthRates {
+    pub likes_per_hour: f64,
+    pub comments_per_hour: f64,
+    pub shares_per_hour: f64,
+    pub collects_per_hour: f64,
+}
+
+/// 增长统计
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct GrowthStats {
+    /// 发布后 24 小时内的平均增速（发布时间未知时从首次采样起算）
+    pub first_24h: Option<GrowthRates>,
+    /// 最近两次采样之间的增速
+    pub recent: Option<GrowthRates>,
+    /// 发布（或首次采样）至今的平均增速
+    pub lifetime: Option<GrowthRates>,
+    /// 爆发系数：最近点赞增速 / 平均点赞增速，大于 1 表示正在加速
+    pub breakout_ratio: Option<f64>,
+}
+
+/// 根据采样计算增长统计，`samples` 可以无序
+pub fn compute_growth(create_time: Option<i64>, samples: &[MetricSample]) -> GrowthStats {
+    let mut samples = samples.to_vec();
+    samples.sort_by_key(|s| s.sampled_at);
+
+    let (first, last) = match (samples.first(), samples.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return GrowthStats::default(),
+    };
+
+    // 发布时互动数视为 0，以发布时间作为增长起点
+    let origin = match create_time {
+        Some(t) if t < first.sampled_at => MetricSample {
+            sampled_at: t,
+            ..Default::default()
+        },
+        _ => first,
+    };
+
+    let window_end = origin.sampled_at + HOURLY_PHASE_SECS;
+    let first_24h = samples
+        .iter()
+        .rev()
+        .find(|s| s.sampled_at <= window_end)
+        .and_then(|s| rates_between(&origin, s));
+
+    let recent = match samples.len() {
+        0 | 1 => None,
+        n => rates_between(&samples[n - 2], &samples[n - 1]),
+    };
+    let lifetime = rates_between(&origin, &last);
+
+    let breakout_ratio = match (recent, lifetime) {
+        (Some(recent), Some(lifetime)) if lifetime.likes_per_hour > 0.0 => {
+            Some(recent.likes_per_hour / lifetime.likes_per_hour)
+        }
+        _ => None,
+    };
+
+    GrowthStats {
+        first_24h,
+        recent,
+        lifetime,
+        breakout_ratio,
+    }
+}
+
+/// 按作品发布时长决定采样间隔（秒）：24 小时内每小时，7 天内每 6 小时，之后每天
+pub fn sample_interval_secs(create_time: Option<i64>, now: i64) -> i64 {
+    let age = create_time.map(|t| now - t).unwrap_or(i64::MAX);
+    if age < HOURLY_PHASE_SECS {
+        3600
+    } else if age < SIX_HOURLY_PHASE_SECS {
+        6 * 3600
+    } else {
+        24 * 3600
+    }
+}
+
+/// 作品是否需要采样
+pub fn is_sample_due(create_time: Option<i64>, last_sampled_at: Option<i64>, now: i64) -> bool {
+    if let Some(t) = create_time {
+        if now - t > MAX_TRACKING_SECS {
+            return false;
+        }
+    }
+    match last_sampled_at {
+        Some(last) => now - last >= sample_interval_secs(create_time, now),
+        None => true,
+    }
+}
+
+/// 采样失败后是否已过退避时间，连续失败过多的作品不再重试
+pub fn is_retry_due(failed_attempts: u32, last_attempt_at: Option<i64>, now: i64) -> bool {
+    if failed_attempts == 0 {
+        return true;
+    }
+    if failed_attempts >= MAX_SAMPLE_FAILURES {
+        return false;
+    }
+    let backoff = (RETRY_BASE_SECS << (failed_attempts - 1)).min(RETRY_MAX_SECS);
+    match last_attempt_at {
+        Some(last) => now - last >= backoff,
+        None => true,
+    }
+}
+
+fn rates_between(from: &MetricSample, to: &MetricSample) -> Option<GrowthRates> {
+    let hours = (to.sampled_at - from.sampled_at) as f64 / 3600.0;
+    if hours <= 0.0 {
+        return None;
+    }
+
+    let rate = |a: u64, b: u64| (b as f64 - a as f64) / hours;
+    Some(GrowthRates {
+        likes_per_hour: rate(from.likes, to.likes),
+        comments_per_hour: rate(from.comments, to.comments),
+        shares_per_hour: rate(from.shares, to.shares),
+        collects_per_hour: rate(from.collects, to.collects),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(hours: i64, likes: u64) -> MetricSample {
+        MetricSample {
+            sampled_at: hours * 3600,
+            likes,
+            comments: likes / 10,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compute_growth() {
+        // 发布于 0 时刻，之后 48 小时内采样
+        let samples = vec![
+            sample(48, 3_000),
+            sample(2, 200),
+            sample(24, 1_200),
+            sample(47, 2_000),
+        ];
+        let stats = compute_growth(Some(0), &samples);
+
+        let first = stats.first_24h.unwrap();
+        assert!((first.likes_per_hour - 50.0).abs() < 1e-9);
+        assert!((first.comments_per_hour - 5.0).abs() < 1e-9);
+
+        let recent = stats.recent.unwrap();
+        assert!((recent.likes_per_hour - 1_000.0).abs() < 1e-9);
+
+        let lifetime = stats.lifetime.unwrap();
+        assert!((lifetime.likes_per_hour - 62.5).abs() < 1e-9);
+        assert!(stats.breakout_ratio.unwrap() > 10.0);
+    }
+
+    #[test]
+    fn test_compute_growth_without_publish_time() {
+        assert_eq!(compute_growth(None, &[]), GrowthStats::default());
+
+        let stats = compute_growth(None, &[sample(10, 100)]);
+        assert!(stats.lifetime.is_none());
+        assert!(stats.recent.is_none());
+
+        let stats = compute_growth(None, &[sample(10, 100), sample(12, 300)]);
+        assert!((stats.lifetime.unwrap().likes_per_hour - 100.0).abs() < 1e-9);
+        assert!((stats.breakout_ratio.unwrap() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sample_schedule() {
+        let hour = 3600;
+        assert_eq!(sample_interval_secs(Some(0), 2 * hour), hour);
+        assert_eq!(sample_interval_secs(Some(0), 48 * hour), 6 * hour);
+        assert_eq!(sample_interval_secs(None, 0), 24 * hour);
+
+        assert!(is_sample_due(Some(0), None, hour));
+        assert!(!is_sample_due(Some(0), Some(hour), hour + 1_800));
+        assert!(is_sample_due(Some(0), Some(hour), 2 * hour));
+        // 超过追踪期限后不再采样
+        assert!(!is_sample_due(Some(0), None, MAX_TRACKING_SECS + 1));
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let hour = 3600;
+        assert!(is_retry_due(0, None, 0));
+        assert!(!is_retry_due(1, Some(0), hour - 1));
+        assert!(is_retry_due(1, Some(0), hour));
+        assert!(!is_retry_due(3, Some(0), 3 * hour));
+        assert!(is_retry_due(3, Some(0), 4 * hour));
+        // 退避时长封顶，连续失败过多后停止
+        assert!(is_retry_due(6, Some(0), 24 * hour));
+        assert!(!is_retry_due(MAX_SAMPLE_FAILURES, Some(0), 365 * 24 * hour));
+    }
+}
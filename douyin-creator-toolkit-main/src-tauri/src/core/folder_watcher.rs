@@ -0,0 +1,250 @@
+// 监视文件夹模块
+// 定时扫描配置的文件夹，等待文件大小稳定（避免拷贝未完成）后交给调用方入队转写
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use crate::core::video_processor::SUPPORTED_FORMATS;
+
+/// 监视文件夹配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WatchConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 监视的文件夹列表
+    pub folders: Vec<String>,
+    /// 扩展名过滤（不含点，小写）
+    pub extensions: Vec<String>,
+    /// 文件稳定等待时间（秒），期间大小和修改时间不变才视为拷贝完成
+    pub settle_secs: u64,
+    /// 扫描间隔（秒）
+    pub scan_interval_secs: u64,
+    /// 是否递归扫描子文件夹
+    pub recursive: bool,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            folders: Vec::new(),
+            extensions: SUPPORTED_FORMATS.iter().map(|s| s.to_string()).collect(),
+            settle_secs: 10,
+            scan_interval_secs: 5,
+            recursive: false,
+        }
+    }
+}
+
+impl WatchConfig {
+    /// 判断文件扩展名是否在过滤列表中
+    pub fn matches_extension(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .map(|ext| {
+                self.extensions
+                    .iter()
+                    .any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(ext))
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// 文件快照
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSnapshot {
+    pub path: PathBuf,
+    pub size: u64,
+    /// 修改时间（Unix 秒）
+    pub modified: i64,
+}
+
+impl FileSnapshot {
+    /// 由文件元数据生成快照
+    pub fn from_metadata(path: &Path, metadata: &std::fs::Metadata) -> Self {
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        Self {
+            path: path.to_path_buf(),
+            size: metadata.len(),
+            modified,
+        }
+    }
+}
+
+/// 扫描文件夹，返回符合扩展名过滤的文件快照
+pub fn scan_folder(dir: &Path, config: &WatchConfig) -> Vec<FileSnapshot> {
+    let mut results = Vec::new();
+    scan_into(dir, config, &mut results);
+    results
+}
+
+fn scan_into(dir: &Path, config: &WatchConfig, results: &mut Vec<FileSnapshot>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let metadata = match entry.metadata() {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+
+        if metadata.is_dir() {
+            if config.recursive {
+                scan_into(&path, config, results);
+            }
+            continue;
+        }
+
+        if !metadata.is_file() || !config.matches_extension(&path) {
+            continue;
+        }
+
+        results.push(FileSnapshot::from_metadata(&path, &metadata));
+    }
+}
+
+/// 文件稳定性跟踪器
+///
+/// 记录每个文件最近一次变化的时间，大小和修改时间保持不变超过等待时间后才视为就绪
+#[derive(Debug, Default)]
+pub struct SettleTracker {
+    pending: HashMap<PathBuf, (u64, i64, Instant)>,
+}
+
+impl SettleTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 观察一轮扫描结果，返回已稳定的文件（返回后不再跟踪）
+    pub fn observe(
+        &mut self,
+        snapshots: Vec<FileSnapshot>,
+        settle: Duration,
+        now: Instant,
+    ) -> Vec<FileSnapshot> {
+        // 清理已消失的文件
+        self.pending
+            .retain(|path, _| snapshots.iter().any(|s| &s.path == path));
+
+        let mut ready = Vec::new();
+        for snapshot in snapshots {
+            match self.pending.get_mut(&snapshot.path) {
+                Some((size, modified, since))
+                    if *size == snapshot.size && *modified == snapshot.modified =>
+                {
+                    if now.duration_since(*since) >= settle {
+                        self.pending.remove(&snapshot.path);
+                        ready.push(snapshot);
+                    }
+                }
+                Some(entry) => {
+                    *entry = (snapshot.size, snapshot.modified, now);
+                }
+                None => {
+                    self.pending.insert(
+                        snapshot.path.clone(),
+                        (snapshot.size, snapshot.modified, now),
+                    );
+                }
+            }
+        }
+
+        ready
+    }
+
+    /// 当前等待稳定的文件数
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn snapshot(path: &str, size: u64) -> FileSnapshot {
+        FileSnapshot {
+            path: PathBuf::from(path),
+            size,
+            modified: 100,
+        }
+    }
+
+    #[test]
+    fn test_matches_extension() {
+        let config = WatchConfig::default();
+        assert!(config.matches_extension(Path::new("a.mp4")));
+        assert!(config.matches_extension(Path::new("a.MOV")));
+        assert!(!config.matches_extension(Path::new("a.txt")));
+        assert!(!config.matches_extension(Path::new("mp4")));
+    }
+
+    #[test]
+    fn test_scan_folder() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("a.mp4"), b"123").unwrap();
+        std::fs::write(dir.path().join("b.txt"), b"123").unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub").join("c.mkv"), b"1").unwrap();
+
+        let mut config = WatchConfig::default();
+        let files = scan_folder(dir.path(), &config);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].size, 3);
+
+        config.recursive = true;
+        assert_eq!(scan_folder(dir.path(), &config).len(), 2);
+    }
+
+    #[test]
+    fn test_settle_tracker() {
+        let mut tracker = SettleTracker::new();
+        let settle = Duration::from_secs(10);
+        let t0 = Instant::now();
+
+        // 首次出现不就绪
+        assert!(tracker
+            .observe(vec![snapshot("a.mp4", 10)], settle, t0)
+            .is_empty());
+
+        // 大小变化重新计时
+        let t1 = t0 + Duration::from_secs(8);
+        assert!(tracker
+            .observe(vec![snapshot("a.mp4", 20)], settle, t1)
+            .is_empty());
+
+        let t2 = t1 + Duration::from_secs(5);
+        assert!(tracker
+            .observe(vec![snapshot("a.mp4", 20)], settle, t2)
+            .is_empty());
+
+        // 稳定超过等待时间后就绪
+        let t3 = t1 + Duration::from_secs(11);
+        let ready = tracker.observe(vec![snapshot("a.mp4", 20)], settle, t3);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(tracker.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_settle_tracker_drops_removed_files() {
+        let mut tracker = SettleTracker::new();
+        let now = Instant::now();
+        tracker.observe(vec![snapshot("a.mp4", 1)], Duration::from_secs(1), now);
+        tracker.observe(Vec::new(), Duration::from_secs(1), now);
+        assert_eq!(tracker.pending_count(), 0);
+    }
+}
@@ -0,0 +1,287 @@
+// 批量链接导入模块
+// 从分享文案、CSV、XLSX 表格中提取抖音链接，规范化为作品 ID 并去重，供批量解析使用
+
+use calamine::{open_workbook, Reader, Xlsx};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::core::douyin_parser::DouyinParser;
+
+/// 文本中的链接（遇到空白、中文标点、引号或尖括号结束）
+static URL_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"https?://[^\s，。！？、；“”‘’"'<>()（）【】]+"#).unwrap());
+/// 链接路径或查询参数中的作品 ID
+static AWEME_ID_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:/(?:video|note)/|[?&](?:modal_id|aweme_id|item_ids)=)(\d{8,})").unwrap()
+});
+/// 表格中直接填写的作品 ID
+static BARE_ID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d{15,20}$").unwrap());
+
+#[derive(Error, Debug)]
+pub enum LinkImportError {
+    #[error("读取文件失败: {0}")]
+    IoError(String),
+    #[error("解析表格失败: {0}")]
+    ParseError(String),
+}
+
+/// 导入的链接
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportedLink {
+    /// 原始链接（表格中直接填写作品 ID 时为规范化后的作品链接）
+    pub url: String,
+    /// 作品 ID，短链接在解析跳转前为 None
+    pub aweme_id: Option<String>,
+}
+
+impl ImportedLink {
+    /// 从链接创建，能直接识别作品 ID 时一并填入
+    pub fn from_url(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            aweme_id: canonical_aweme_id(url),
+        }
+    }
+
+    /// 从作品 ID 创建
+    pub fn from_aweme_id(aweme_id: &str) -> Self {
+        Self {
+            url: canonical_url(aweme_id),
+            aweme_id: Some(aweme_id.to_string()),
+        }
+    }
+
+    /// 去重依据：优先使用作品 ID，否则使用链接本身
+    pub fn key(&self) -> &str {
+        self.aweme_id.as_deref().unwrap_or(&self.url)
+    }
+
+    /// 交给批量解析的链接：已知作品 ID 时使用规范化链接，避免重复跳转
+    pub fn parse_url(&self) -> String {
+        match &self.aweme_id {
+            Some(id) => canonical_url(id),
+            None => self.url.clone(),
+        }
+    }
+}
+
+/// 从链接中直接提取作品 ID（`/video/<id>`、`/note/<id>`、`modal_id=<id>` 等），短链接返回 None
+pub fn canonical_aweme_id(url: &str) -> Option<String> {
+    AWEME_ID_RE
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
+/// 作品 ID 对应的规范化链接
+pub fn canonical_url(aweme_id: &str) -> String {
+    format!("https://www.douyin.com/video/{}", aweme_id)
+}
+
+/// 提取文本（分享文案、剪贴板内容等）中的所有抖音链接，按出现顺序返回（未去重）
+pub fn extract_links(text: &str) -> Vec<ImportedLink> {
+    URL_RE
+        .find_iter(text)
+        .map(|m| m.as_str().trim_end_matches(['.', ',', '!', '?', ';', ':']))
+        .filter(|url| DouyinParser::is_valid_link(url))
+        .map(ImportedLink::from_url)
+        .collect()
+}
+
+/// 从表格行中提取链接；`column` 为从 0 开始的列序号，None 表示扫描所有列
+///
+/// 单元格中除链接外，也接受直接填写的作品 ID
+pub fn extract_links_from_rows(rows: &[Vec<String>], column: Option<usize>) -> Vec<ImportedLink> {
+    let mut links = Vec::new();
+    for row in rows {
+        let cells: Vec<&String> = match column {
+            Some(index) => row.get(index).into_iter().collect(),
+            None => row.iter().collect(),
+        };
+        for cell in cells {
+            let cell = cell.trim();
+            if BARE_ID_RE.is_match(cell) {
+                links.push(ImportedLink::from_aweme_id(cell));
+            } else {
+                links.extend(extract_links(cell));
+            }
+        }
+    }
+    links
+}
+
+/// 从文件中提取链接：`.xlsx` 与 `.csv` 按表格读取，其他文件按纯文本读取
+pub fn extract_links_from_file(
+    path: &Path,
+    column: Option<usize>,
+) -> Result<Vec<ImportedLink>, LinkImportError> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    match extension.as_str() {
+        "xlsx" => Ok(extract_links_from_rows(&read_xlsx_rows(path)?, column)),
+        "csv" => Ok(extract_links_from_rows(
+            &parse_csv(&read_text_file(path)?)?,
+            column,
+        )),
+        _ => Ok(extract_links(&read_text_file(path)?)),
+    }
+}
+
+/// 按作品 ID（未知时按链接）去重，保留首次出现的顺序
+pub fn dedupe_links(links: Vec<ImportedLink>) -> Vec<ImportedLink> {
+    let mut seen = HashSet::new();
+    links
+        .into_iter()
+        .filter(|link| seen.insert(link.key().to_string()))
+        .collect()
+}
+
+/// 读取文本文件，去掉 UTF-8 BOM，非法字节按替换字符处理
+fn read_text_file(path: &Path) -> Result<String, LinkImportError> {
+    let bytes = std::fs::read(path).map_err(|e| LinkImportError::IoError(e.to_string()))?;
+    let text = String::from_utf8_lossy(&bytes);
+    Ok(text.trim_start_matches('\u{feff}').to_string())
+}
+
+/// 解析 CSV 文本（支持双引号包裹的字段、字段内的逗号与换行、`""` 转义），各行列数可以不同
+pub fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, LinkImportError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    reader
+        .records()
+        .map(|record| {
+            record
+                .map(|r| r.iter().map(str::to_string).collect())
+                .map_err(|e| LinkImportError::ParseError(e.to_string()))
+        })
+        .collect()
+}
+
+/// 读取 XLSX 文件中所有工作表的单元格文本（按工作表顺序依次拼接各行）
+///
+/// 行首的空白列以空字符串补齐，保持单元格的列位置
+pub fn read_xlsx_rows(path: &Path) -> Result<Vec<Vec<String>>, LinkImportError> {
+    let mut workbook: Xlsx<_> = open_workbook(path)
+        .map_err(|e| LinkImportError::ParseError(format!("不是有效的 XLSX 文件: {}", e)))?;
+
+    let sheet_names = workbook.sheet_names();
+    if sheet_names.is_empty() {
+        return Err(LinkImportError::ParseError("没有找到工作表".to_string()));
+    }
+
+    let mut rows = Vec::new();
+    for name in sheet_names {
+        let range = workbook
+            .worksheet_range(&name)
+            .map_err(|e| LinkImportError::ParseError(format!("读取工作表 {} 失败: {}", name, e)))?;
+        let first_column = range.start().map(|(_, col)| col as usize).unwrap_or(0);
+
+        for cells in range.rows() {
+            let mut row = vec![String::new(); first_column];
+            row.extend(cells.iter().map(|cell| cell.to_string()));
+            rows.push(row);
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_extract_links_from_share_text() {
+        let text = "7.25 复制打开抖音，看看【露营小王的作品】周末露营 # 露营 https://v.douyin.com/iRNBho6u/ TxM:/ 06/18 \
+                    还有一个：https://www.douyin.com/video/7301234567890123456。\
+                    以及 https://www.douyin.com/discover?modal_id=7301234567890123999&from=1 \
+                    和快手 https://v.kuaishou.com/abc123";
+        let links = extract_links(text);
+
+        assert_eq!(links.len(), 3);
+        assert_eq!(links[0].url, "https://v.douyin.com/iRNBho6u/");
+        assert_eq!(links[0].aweme_id, None);
+        assert_eq!(links[1].aweme_id.as_deref(), Some("7301234567890123456"));
+        assert_eq!(links[2].aweme_id.as_deref(), Some("7301234567890123999"));
+        assert_eq!(
+            links[2].parse_url(),
+            "https://www.douyin.com/video/7301234567890123999"
+        );
+    }
+
+    #[test]
+    fn test_csv_rows_and_dedupe() {
+        let csv = "\u{feff}标题,链接\r\n\"露营, 第一期\",https://www.douyin.com/video/7301234567890123456\r\n\
+                   \"多行\n备注\",7301234567890123456\r\n第三条,https://v.douyin.com/abc/\r\n";
+        let rows = parse_csv(csv.trim_start_matches('\u{feff}')).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1][0], "露营, 第一期");
+        assert_eq!(rows[2][0], "多行\n备注");
+
+        let links = extract_links_from_rows(&rows, Some(1));
+        assert_eq!(links.len(), 3);
+        assert_eq!(
+            links[1].url,
+            "https://www.douyin.com/video/7301234567890123456"
+        );
+
+        let unique = dedupe_links(links);
+        assert_eq!(unique.len(), 2);
+        assert_eq!(unique[1].key(), "https://v.douyin.com/abc/");
+    }
+
+    #[test]
+    fn test_read_xlsx_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("links.xlsx");
+        let file = std::fs::File::create(&path).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default();
+
+        zip.start_file("xl/workbook.xml", options).unwrap();
+        zip.write_all(
+            br#"<workbook xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+        )
+        .unwrap();
+        zip.start_file("xl/_rels/workbook.xml.rels", options)
+            .unwrap();
+        zip.write_all(
+            br#"<Relationships><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+        )
+        .unwrap();
+        zip.start_file("xl/sharedStrings.xml", options).unwrap();
+        zip.write_all(
+            r#"<sst><si><t>链接</t></si><si><r><t>看看 </t></r><r><t>https://www.douyin.com/video/7301234567890123456?a=1&amp;b=2</t></r></si></sst>"#
+                .as_bytes(),
+        )
+        .unwrap();
+        zip.start_file("xl/worksheets/sheet1.xml", options).unwrap();
+        zip.write_all(
+            br#"<worksheet><sheetData><row r="1"><c r="B1" t="s"><v>0</v></c></row><row r="2"/><row r="3"><c r="A3"><v>1</v></c><c r="B3" t="s"><v>1</v></c></row><row r="4"><c r="B4" t="inlineStr"><is><t>https://v.douyin.com/xyz/</t></is></c></row></sheetData></worksheet>"#,
+        )
+        .unwrap();
+        zip.finish().unwrap();
+
+        let rows = read_xlsx_rows(&path).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0], vec!["", "链接"]);
+        assert_eq!(rows[2][0], "1");
+        assert!(rows[2][1].ends_with("?a=1&b=2"));
+
+        let links = extract_links_from_file(&path, Some(1)).unwrap();
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].aweme_id.as_deref(), Some("7301234567890123456"));
+        assert_eq!(links[1].url, "https://v.douyin.com/xyz/");
+    }
+}
@@ -0,0 +1,830 @@
+// 直播录制模块
+// 从直播间链接解析 FLV / HLS 拉流地址，通过 FFmpeg 分段录制；断流后重新解析地址并续录，直到下播或手动停止
+
+use regex::Regex;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::utils::ffmpeg::FfmpegWrapper;
+use crate::utils::http_client::{self, HttpService};
+use crate::utils::rate_limiter::{self, RateLimiter};
+
+/// 默认分段时长（秒）
+pub const DEFAULT_SEGMENT_SECS: u64 = 30 * 60;
+/// 默认断流后重连间隔（秒）
+pub const DEFAULT_RECONNECT_DELAY_SECS: u64 = 5;
+/// 默认断流多久仍未恢复视为下播（秒）
+pub const DEFAULT_OFFLINE_TIMEOUT_SECS: u64 = 300;
+/// 录制进程状态检查间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// 发送退出指令后等待 FFmpeg 写完最后一段的时长
+const STOP_GRACE: Duration = Duration::from_secs(10);
+/// 未登录时使用的占位 Cookie
+const ANONYMOUS_COOKIE: &str = "ttwid=1%7C1234567890";
+/// 拉流地址清晰度（从高到低）
+const QUALITY_ORDER: [&str; 5] = ["ORIGION", "FULL_HD1", "HD1", "SD1", "SD2"];
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+#[derive(Error, Debug, Clone)]
+pub enum LiveError {
+    #[error("无效的直播链接: {0}")]
+    InvalidLink(String),
+    #[error("直播间未开播")]
+    NotLive,
+    #[error("网络请求失败: {0}")]
+    NetworkError(String),
+    #[error("直播间信息解析失败: {0}")]
+    ParseError(String),
+    #[error("请求受限: {0}")]
+    RateLimited(String),
+    #[error("FFmpeg 执行失败: {0}")]
+    FfmpegError(String),
+    #[error("文件操作失败: {0}")]
+    IoError(String),
+}
+
+/// 拉流格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamFormat {
+    Flv,
+    Hls,
+}
+
+/// 直播拉流地址
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveStream {
+    /// 清晰度，如 `FULL_HD1`
+    pub quality: String,
+    pub format: StreamFormat,
+    pub url: String,
+}
+
+/// 直播间信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveRoomInfo {
+    /// 直播间网页 ID（live.douyin.com/ 后的数字）
+    pub web_rid: String,
+    pub room_id: String,
+    pub title: String,
+    pub anchor: String,
+    pub is_live: bool,
+    /// 拉流地址（同一格式内按清晰度从高到低）
+    pub streams: Vec<LiveStream>,
+}
+
+impl LiveRoomInfo {
+    /// 选择录制使用的拉流地址：优先指定格式的最高清晰度，没有时使用任意格式
+    pub fn best_stream(&self, prefer: Option<StreamFormat>) -> Option<&LiveStream> {
+        prefer
+            .and_then(|format| self.streams.iter().find(|s| s.format == format))
+            .or_else(|| self.streams.first())
+    }
+}
+
+/// 直播接口地址（测试时可指向本地模拟服务）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveEndpoints {
+    pub web: String,
+}
+
+impl Default for LiveEndpoints {
+    fn default() -> Self {
+        Self {
+            web: "https://live.douyin.com".to_string(),
+        }
+    }
+}
+
+/// 判断是否为直接的 FLV / HLS 拉流地址
+pub fn is_stream_url(link: &str) -> bool {
+    let lower = link.trim().to_lowercase();
+    let path = lower.split(['?', '#']).next().unwrap_or(&lower);
+    (path.starts_with("http://") || path.starts_with("https://"))
+        && (path.ends_with(".flv") || path.ends_with(".m3u8"))
+}
+
+/// 从直播间地址中提取 web_rid，纯数字输入视为 web_rid
+pub fn extract_web_rid(link: &str) -> Option<String> {
+    let link = link.trim();
+    if !link.is_empty() && link.chars().all(|c| c.is_ascii_digit()) {
+        return Some(link.to_string());
+    }
+    let re = Regex::new(r"live\.douyin\.com/(\d+)").unwrap();
+    re.captures(link).map(|caps| caps[1].to_string())
+}
+
+/// 解析直播间接口（webcast/room/web/enter）响应
+pub fn parse_room_info(json: &serde_json::Value, web_rid: &str) -> Result<LiveRoomInfo, LiveError> {
+    let status = json["status_code"].as_i64().unwrap_or(0);
+    if status != 0 {
+        return Err(LiveError::ParseError(format!(
+            "直播间接口返回错误 {}: {}",
+            status,
+            json["data"]["prompts"].as_str().unwrap_or("")
+        )));
+    }
+
+    let data = &json["data"];
+    let anchor = data["user"]["nickname"].as_str().unwrap_or("").to_string();
+    let room = &data["data"][0];
+    if room.is_null() {
+        // 下播后部分情况下不返回房间数据
+        if data["room_status"].as_i64() == Some(2) {
+            return Ok(LiveRoomInfo {
+                web_rid: web_rid.to_string(),
+                room_id: String::new(),
+                title: String::new(),
+                anchor,
+                is_live: false,
+                streams: Vec::new(),
+            });
+        }
+        return Err(LiveError::ParseError("直播间不存在".to_string()));
+    }
+
+    let stream_url = &room["stream_url"];
+    let mut streams = collect_streams(&stream_url["flv_pull_url"], StreamFormat::Flv);
+    let mut hls = collect_streams(&stream_url["hls_pull_url_map"], StreamFormat::Hls);
+    if hls.is_empty() {
+        if let Some(url) = stream_url["hls_pull_url"]
+            .as_str()
+            .filter(|u| !u.is_empty())
+        {
+            hls.push(LiveStream {
+                quality: "default".to_string(),
+                format: StreamFormat::Hls,
+                url: url.to_string(),
+            });
+        }
+    }
+    streams.extend(hls);
+
+    Ok(LiveRoomInfo {
+        web_rid: web_rid.to_string(),
+        room_id: room["id_str"].as_str().unwrap_or("").to_string(),
+        title: room["title"].as_str().unwrap_or("").to_string(),
+        anchor: if anchor.is_empty() {
+            room["owner"]["nickname"].as_str().unwrap_or("").to_string()
+        } else {
+            anchor
+        },
+        // status: 2 直播中，4 已下播
+        is_live: room["status"].as_i64() == Some(2),
+        streams,
+    })
+}
+
+/// 按清晰度顺序收集某一格式的拉流地址，未知清晰度排在最后
+fn collect_streams(map: &serde_json::Value, format: StreamFormat) -> Vec<LiveStream> {
+    let map = match map.as_object() {
+        Some(map) => map,
+        None => return Vec::new(),
+    };
+
+    let rank = |quality: &str| {
+        QUALITY_ORDER
+            .iter()
+            .position(|q| *q == quality)
+            .unwrap_or(QUALITY_ORDER.len())
+    };
+    let mut streams: Vec<LiveStream> = map
+        .iter()
+        .filter_map(|(quality, url)| {
+            let url = url.as_str().filter(|u| !u.is_empty())?;
+            Some(LiveStream {
+                quality: quality.clone(),
+                format,
+                url: url.to_string(),
+            })
+        })
+        .collect();
+    streams.sort_by_key(|s| rank(&s.quality));
+    streams
+}
+
+/// 直播间解析器
+pub struct LiveResolver {
+    client: Client,
+    cookie: Option<String>,
+    endpoints: LiveEndpoints,
+    limiter: Arc<RateLimiter>,
+}
+
+impl LiveResolver {
+    pub fn new() -> Self {
+        let client = http_client::client_builder(HttpService::Douyin)
+            .timeout(Duration::from_secs(30))
+            .user_agent(USER_AGENT)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self::with_client(client)
+    }
+
+    /// 使用指定的 HTTP 客户端
+    pub fn with_client(client: Client) -> Self {
+        Self {
+            client,
+            cookie: super::session_manager::douyin_cookie_header(),
+            endpoints: LiveEndpoints::default(),
+            limiter: rate_limiter::global_limiter(),
+        }
+    }
+
+    /// 指定请求携带的 Cookie（覆盖全局会话）
+    pub fn with_cookie(mut self, cookie: Option<String>) -> Self {
+        self.cookie = cookie;
+        self
+    }
+
+    /// 指定直播接口地址
+    pub fn with_endpoints(mut self, endpoints: LiveEndpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// 指定请求限速器（默认使用全局限速器）
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// 解析直播链接得到直播间信息与拉流地址
+    ///
+    /// 支持 live.douyin.com 直播间地址、跳转到直播间的分享短链接，以及直接的 FLV / HLS 拉流地址
+    pub async fn resolve(&self, link: &str) -> Result<LiveRoomInfo, LiveError> {
+        let link = link.trim();
+        if is_stream_url(link) {
+            let format = if link.to_lowercase().contains(".m3u8") {
+                StreamFormat::Hls
+            } else {
+                StreamFormat::Flv
+            };
+            return Ok(LiveRoomInfo {
+                web_rid: String::new(),
+                room_id: String::new(),
+                title: String::new(),
+                anchor: String::new(),
+                is_live: true,
+                streams: vec![LiveStream {
+                    quality: "default".to_string(),
+                    format,
+                    url: link.to_string(),
+                }],
+            });
+        }
+
+        let web_rid = match extract_web_rid(link) {
+            Some(id) => id,
+            None if link.contains("douyin.com") => {
+                let final_url = self.follow_redirect(link).await?;
+                extract_web_rid(&final_url)
+                    .ok_or_else(|| LiveError::InvalidLink(link.to_string()))?
+            }
+            None => return Err(LiveError::InvalidLink(link.to_string())),
+        };
+
+        let url = format!(
+            "{}/webcast/room/web/enter/?aid=6383&app_name=douyin_web&live_id=1&device_platform=web&language=zh-CN&browser_language=zh-CN&browser_platform=Win32&browser_name=Chrome&browser_version=120.0.0.0&web_rid={}",
+            self.endpoints.web, web_rid
+        );
+        let body = self.get_text(&url).await?;
+        if body.trim().is_empty() {
+            return Err(LiveError::ParseError(
+                "直播间接口返回为空，可能需要导入登录 Cookie".to_string(),
+            ));
+        }
+
+        let json: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| LiveError::ParseError(format!("JSON 解析失败: {}", e)))?;
+        parse_room_info(&json, &web_rid)
+    }
+
+    /// 经限速器请求直播接口
+    async fn get_text(&self, url: &str) -> Result<String, LiveError> {
+        self.limiter
+            .acquire(url)
+            .await
+            .map_err(|e| LiveError::RateLimited(e.to_string()))?;
+
+        let response = self
+            .client
+            .get(url)
+            .header("Accept", "application/json")
+            .header("Referer", "https://live.douyin.com/")
+            .header("Cookie", self.cookie.as_deref().unwrap_or(ANONYMOUS_COOKIE))
+            .send()
+            .await
+            .map_err(|e| LiveError::NetworkError(e.to_string()))?;
+        let status = response.status().as_u16();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| LiveError::NetworkError(e.to_string()))?;
+
+        self.limiter
+            .check_response(url, status, &body)
+            .map_err(|e| LiveError::RateLimited(e.to_string()))?;
+        Ok(body)
+    }
+
+    /// 跟随分享短链接的跳转
+    async fn follow_redirect(&self, url: &str) -> Result<String, LiveError> {
+        self.limiter
+            .acquire(url)
+            .await
+            .map_err(|e| LiveError::RateLimited(e.to_string()))?;
+
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| LiveError::NetworkError(e.to_string()))?;
+        let final_url = response.url().to_string();
+        self.limiter
+            .check_response(url, response.status().as_u16(), &final_url)
+            .map_err(|e| LiveError::RateLimited(e.to_string()))?;
+        Ok(final_url)
+    }
+}
+
+impl Default for LiveResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 录制选项
+#[derive(Debug, Clone)]
+pub struct RecordOptions {
+    /// 分段文件保存目录
+    pub output_dir: PathBuf,
+    /// 文件名前缀，分段命名为 `{前缀}_{场次}_{序号}.mkv`
+    pub file_prefix: String,
+    /// 分段时长（秒）
+    pub segment_secs: u64,
+    /// 优先使用的拉流格式，为空时优先 FLV
+    pub prefer_format: Option<StreamFormat>,
+    /// 断流后重连间隔（秒）
+    pub reconnect_delay_secs: u64,
+    /// 断流多久仍未恢复视为下播（秒）
+    pub offline_timeout_secs: u64,
+    /// 最长录制时长（秒），为空时不限制
+    pub max_duration_secs: Option<u64>,
+}
+
+impl RecordOptions {
+    pub fn new(output_dir: PathBuf, file_prefix: &str) -> Self {
+        Self {
+            output_dir,
+            file_prefix: file_prefix.to_string(),
+            segment_secs: DEFAULT_SEGMENT_SECS,
+            prefer_format: None,
+            reconnect_delay_secs: DEFAULT_RECONNECT_DELAY_SECS,
+            offline_timeout_secs: DEFAULT_OFFLINE_TIMEOUT_SECS,
+            max_duration_secs: None,
+        }
+    }
+}
+
+/// 录制过程事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecorderEvent {
+    /// 开始（或重连后继续）录制，`session` 从 1 开始
+    Started { session: u32, stream_url: String },
+    /// 一个分段写入完成
+    SegmentCompleted { path: PathBuf },
+    /// 拉流中断，稍后重连
+    Interrupted { session: u32, reason: String },
+}
+
+/// 录制结束原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// 手动停止
+    Stopped,
+    /// 断流超时未恢复（已下播）
+    Ended,
+    /// 达到最长录制时长
+    MaxDuration,
+}
+
+/// 录制结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingSummary {
+    pub segments: Vec<PathBuf>,
+    /// 录制场次（每次重连算一场）
+    pub sessions: u32,
+    pub reason: StopReason,
+}
+
+/// 单场录制的结束方式
+enum SessionEnd {
+    Stopped,
+    MaxDuration,
+    Interrupted(String),
+}
+
+/// 直播录制器
+pub struct LiveRecorder {
+    ffmpeg: FfmpegWrapper,
+    resolver: LiveResolver,
+    options: RecordOptions,
+    stop: Arc<AtomicBool>,
+}
+
+impl LiveRecorder {
+    pub fn new(ffmpeg: FfmpegWrapper, resolver: LiveResolver, options: RecordOptions) -> Self {
+        Self {
+            ffmpeg,
+            resolver,
+            options,
+            stop: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 停止标记，置为 true 后录制器写完当前分段并结束
+    pub fn stop_handle(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    /// 录制直播直到手动停止、下播或达到最长时长
+    ///
+    /// 首次解析失败或未开播时返回错误；开始录制后的断流会重新解析拉流地址并续录
+    pub async fn record<F>(
+        &self,
+        link: &str,
+        mut on_event: F,
+    ) -> Result<RecordingSummary, LiveError>
+    where
+        F: FnMut(RecorderEvent),
+    {
+        std::fs::create_dir_all(&self.options.output_dir)
+            .map_err(|e| LiveError::IoError(format!("无法创建录制目录: {}", e)))?;
+
+        let started = Instant::now();
+        let offline_timeout = Duration::from_secs(self.options.offline_timeout_secs);
+        let mut segments = Vec::new();
+        let mut session = 0u32;
+        let mut offline_since: Option<Instant> = None;
+
+        let reason = loop {
+            if self.stop.load(Ordering::SeqCst) {
+                break StopReason::Stopped;
+            }
+            if self.max_duration_reached(started) {
+                break StopReason::MaxDuration;
+            }
+            if offline_since.is_some_and(|since| since.elapsed() >= offline_timeout) {
+                break StopReason::Ended;
+            }
+
+            let stream = match self.resolver.resolve(link).await {
+                Ok(room) if room.is_live => room
+                    .best_stream(self.options.prefer_format.or(Some(StreamFormat::Flv)))
+                    .cloned(),
+                Ok(_) => None,
+                Err(e) if session == 0 => return Err(e),
+                Err(e) => {
+                    warn!("重新获取拉流地址失败: {}", e);
+                    None
+                }
+            };
+            let stream = match stream {
+                Some(stream) => stream,
+                None if session == 0 => return Err(LiveError::NotLive),
+                None => {
+                    offline_since.get_or_insert_with(Instant::now);
+                    self.sleep_unless_stopped(self.options.reconnect_delay_secs)
+                        .await;
+                    continue;
+                }
+            };
+
+            session += 1;
+            on_event(RecorderEvent::Started {
+                session,
+                stream_url: stream.url.clone(),
+            });
+
+            let session_started = Instant::now();
+            match self
+                .run_session(session, &stream.url, started, &mut segments, &mut on_event)
+                .await?
+            {
+                SessionEnd::Stopped => break StopReason::Stopped,
+                SessionEnd::MaxDuration => break StopReason::MaxDuration,
+                SessionEnd::Interrupted(reason) => {
+                    // 录制了一段时间后断流视为新的中断，立即失败的重连沿用之前的计时
+                    if session_started.elapsed() >= offline_timeout.min(Duration::from_secs(30)) {
+                        offline_since = Some(Instant::now());
+                    } else {
+                        offline_since.get_or_insert_with(Instant::now);
+                    }
+                    on_event(RecorderEvent::Interrupted { session, reason });
+                    self.sleep_unless_stopped(self.options.reconnect_delay_secs)
+                        .await;
+                }
+            }
+        };
+
+        Ok(RecordingSummary {
+            segments,
+            sessions: session,
+            reason,
+        })
+    }
+
+    /// 运行一场 FFmpeg 录制，期间把写完的分段通知出去
+    async fn run_session<F>(
+        &self,
+        session: u32,
+        url: &str,
+        started: Instant,
+        segments: &mut Vec<PathBuf>,
+        on_event: &mut F,
+    ) -> Result<SessionEnd, LiveError>
+    where
+        F: FnMut(RecorderEvent),
+    {
+        let dir = &self.options.output_dir;
+        let prefix = &self.options.file_prefix;
+        let list_path = dir.join(format!("{}_{:02}.csv", prefix, session));
+        let pattern = dir.join(format!("{}_{:02}_%03d.mkv", prefix, session));
+        let log = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(format!("{}_ffmpeg.log", prefix)))
+            .map_err(|e| LiveError::IoError(format!("无法创建录制日志: {}", e)))?;
+
+        let mut child = self
+            .ffmpeg
+            .ffmpeg_command()
+            .args(ffmpeg_args(
+                url,
+                self.options.segment_secs,
+                &list_path,
+                &pattern,
+            ))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(log)
+            .spawn()
+            .map_err(|e| LiveError::FfmpegError(e.to_string()))?;
+
+        let mut seen = 0;
+        let end = loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            seen = collect_segments(dir, &list_path, seen, segments, on_event);
+
+            if self.stop.load(Ordering::SeqCst) {
+                stop_child(&mut child).await;
+                break SessionEnd::Stopped;
+            }
+            if self.max_duration_reached(started) {
+                stop_child(&mut child).await;
+                break SessionEnd::MaxDuration;
+            }
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    break SessionEnd::Interrupted(format!("拉流结束（FFmpeg {}）", status))
+                }
+                Ok(None) => {}
+                Err(e) => break SessionEnd::Interrupted(e.to_string()),
+            }
+        };
+
+        // 退出时 FFmpeg 才写入最后一段
+        collect_segments(dir, &list_path, seen, segments, on_event);
+        Ok(end)
+    }
+
+    fn max_duration_reached(&self, started: Instant) -> bool {
+        self.options
+            .max_duration_secs
+            .is_some_and(|max| started.elapsed() >= Duration::from_secs(max))
+    }
+
+    /// 等待指定秒数，期间收到停止指令时提前返回
+    async fn sleep_unless_stopped(&self, secs: u64) {
+        let deadline = Instant::now() + Duration::from_secs(secs);
+        while Instant::now() < deadline && !self.stop.load(Ordering::SeqCst) {
+            tokio::time::sleep(POLL_INTERVAL.min(deadline - Instant::now())).await;
+        }
+    }
+}
+
+/// 生成分段录制的 FFmpeg 参数
+///
+/// 直接复制音视频流不转码；分段使用 Matroska 封装，进程意外退出时已写入的内容仍可播放
+fn ffmpeg_args(url: &str, segment_secs: u64, list_path: &Path, pattern: &Path) -> Vec<String> {
+    [
+        "-hide_banner",
+        "-loglevel",
+        "error",
+        "-rw_timeout",
+        "15000000",
+        "-user_agent",
+        USER_AGENT,
+        "-headers",
+        "Referer: https://live.douyin.com/\r\n",
+        "-i",
+        url,
+        "-map",
+        "0:v?",
+        "-map",
+        "0:a?",
+        "-c",
+        "copy",
+        "-f",
+        "segment",
+        "-segment_time",
+        &segment_secs.max(1).to_string(),
+        "-segment_format",
+        "matroska",
+        "-reset_timestamps",
+        "1",
+        "-segment_list",
+        &list_path.to_string_lossy(),
+        "-segment_list_type",
+        "csv",
+        "-y",
+        &pattern.to_string_lossy(),
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+/// 解析 FFmpeg 的 CSV 分段列表，返回已写完的分段文件名
+pub fn parse_segment_list(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let name = match line.strip_prefix('"') {
+                // 带引号的文件名中 `""` 表示一个引号
+                Some(rest) => {
+                    let mut name = String::new();
+                    let mut chars = rest.chars().peekable();
+                    while let Some(c) = chars.next() {
+                        if c == '"' {
+                            if chars.peek() == Some(&'"') {
+                                chars.next();
+                            } else {
+                                break;
+                            }
+                        }
+                        name.push(c);
+                    }
+                    name
+                }
+                None => line.split(',').next().unwrap_or("").to_string(),
+            };
+            (!name.is_empty()).then_some(name)
+        })
+        .collect()
+}
+
+/// 读取分段列表中新写完的分段并通知，返回已处理的条目数
+fn collect_segments<F>(
+    dir: &Path,
+    list_path: &Path,
+    seen: usize,
+    segments: &mut Vec<PathBuf>,
+    on_event: &mut F,
+) -> usize
+where
+    F: FnMut(RecorderEvent),
+{
+    let entries = match std::fs::read_to_string(list_path) {
+        Ok(content) => parse_segment_list(&content),
+        Err(_) => return seen,
+    };
+
+    for name in entries.iter().skip(seen) {
+        let path = dir.join(name);
+        segments.push(path.clone());
+        on_event(RecorderEvent::SegmentCompleted { path });
+    }
+    entries.len().max(seen)
+}
+
+/// 通知 FFmpeg 退出并等待写完当前分段，超时则强制结束
+async fn stop_child(child: &mut Child) {
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(b"q");
+    }
+
+    let deadline = Instant::now() + STOP_GRACE;
+    while Instant::now() < deadline {
+        if let Ok(Some(_)) = child.try_wait() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_web_rid() {
+        assert_eq!(
+            extract_web_rid("https://live.douyin.com/123456789?from=share").as_deref(),
+            Some("123456789")
+        );
+        assert_eq!(extract_web_rid(" 987654 ").as_deref(), Some("987654"));
+        assert!(extract_web_rid("https://www.douyin.com/video/123").is_none());
+
+        assert!(is_stream_url(
+            "http://pull-flv-l1.douyincdn.com/stage/stream-1.flv?expire=1"
+        ));
+        assert!(is_stream_url("http://127.0.0.1:8080/live/index.m3u8"));
+        assert!(!is_stream_url("https://live.douyin.com/123456789"));
+    }
+
+    #[test]
+    fn test_parse_room_info() {
+        let json = serde_json::json!({
+            "status_code": 0,
+            "data": {
+                "data": [{
+                    "id_str": "7300000000000000001",
+                    "status": 2,
+                    "title": "晚间直播",
+                    "stream_url": {
+                        "flv_pull_url": {
+                            "SD1": "http://cdn/sd1.flv",
+                            "FULL_HD1": "http://cdn/fhd.flv",
+                            "HD1": ""
+                        },
+                        "hls_pull_url_map": {
+                            "FULL_HD1": "http://cdn/fhd.m3u8"
+                        }
+                    }
+                }],
+                "user": { "nickname": "主播" }
+            }
+        });
+
+        let room = parse_room_info(&json, "123").unwrap();
+        assert!(room.is_live);
+        assert_eq!(room.anchor, "主播");
+        assert_eq!(room.streams.len(), 3);
+        assert_eq!(room.best_stream(None).unwrap().url, "http://cdn/fhd.flv");
+        assert_eq!(
+            room.best_stream(Some(StreamFormat::Hls)).unwrap().url,
+            "http://cdn/fhd.m3u8"
+        );
+
+        let ended = serde_json::json!({
+            "status_code": 0,
+            "data": { "data": [], "room_status": 2, "user": { "nickname": "主播" } }
+        });
+        let room = parse_room_info(&ended, "123").unwrap();
+        assert!(!room.is_live);
+        assert!(room.best_stream(None).is_none());
+    }
+
+    #[test]
+    fn test_parse_segment_list() {
+        let content = "live_01_000.mkv,0.000000,1800.040000\n\"live \"\"a\"\",b_01_001.mkv\",1800.04,3600.1\n\n";
+        assert_eq!(
+            parse_segment_list(content),
+            vec![
+                "live_01_000.mkv".to_string(),
+                "live \"a\",b_01_001.mkv".to_string()
+            ]
+        );
+
+        let args = ffmpeg_args(
+            "http://cdn/a.flv",
+            1800,
+            Path::new("/tmp/live_01.csv"),
+            Path::new("/tmp/live_01_%03d.mkv"),
+        );
+        assert_eq!(args.last().unwrap(), "/tmp/live_01_%03d.mkv");
+        assert!(args
+            .windows(2)
+            .any(|w| w[0] == "-segment_time" && w[1] == "1800"));
+        assert!(args
+            .windows(2)
+            .any(|w| w[0] == "-i" && w[1] == "http://cdn/a.flv"));
+    }
+}
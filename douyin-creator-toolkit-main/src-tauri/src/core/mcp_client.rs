@@ -1,15 +1,27 @@
 // MCP 客户端
-// 用于调用抖音数据分析服务（dy-mcp 和 Undoom MCP）
+// 用于调用抖音数据分析服务（dy-mcp Sidecar 与内置解析器）
 
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
+use tracing::warn;
+
+use super::douyin_parser::{
+    ChallengeSort, ContentType, DouyinError, DouyinParser, DouyinVideoData, MusicInfo,
+    QualityPreference, SearchQuery, VideoPage, VideoVariant,
+};
+use super::parse_cache::{self, CachedParse, Freshness, ParseCacheStore};
+use crate::utils::http_client::{self, HttpService};
+use crate::utils::rate_limiter::{self, RateLimiter};
 
 /// 本地 Sidecar API 端口
 pub const SIDECAR_PORT: u16 = 38080;
 /// 本地 Sidecar API 基础 URL
 pub const SIDECAR_BASE_URL: &str = "http://127.0.0.1:38080";
+/// 获取用户作品时默认的条数
+pub const DEFAULT_USER_VIDEO_COUNT: u32 = 20;
 
 #[derive(Error, Debug, Clone)]
 pub enum McpError {
@@ -31,12 +43,22 @@ pub enum McpError {
 
 /// MCP 客户端配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct McpConfig {
     pub dy_mcp_url: String,
+    /// 旧版 Undoom MCP 服务地址（搜索已内置，保留以兼容旧配置）
     pub undoom_mcp_url: String,
     pub request_interval_ms: u64,
     pub max_retries: u32,
     pub timeout_secs: u64,
+    /// 是否缓存链接解析结果
+    pub cache_enabled: bool,
+    /// 解析结果缓存有效期（秒）
+    pub cache_ttl_secs: u64,
+    /// 缓存中的播放地址有效期（秒），超过后重新解析以获取新签名
+    pub cache_url_ttl_secs: u64,
+    /// 缓存中的统计数据超过该时长（秒）后在后台刷新
+    pub cache_stats_refresh_secs: u64,
 }
 
 impl Default for McpConfig {
@@ -47,6 +69,10 @@ impl Default for McpConfig {
             request_interval_ms: 1000,
             max_retries: 3,
             timeout_secs: 30,
+            cache_enabled: true,
+            cache_ttl_secs: parse_cache::DEFAULT_CACHE_TTL_SECS,
+            cache_url_ttl_secs: parse_cache::DEFAULT_URL_TTL_SECS,
+            cache_stats_refresh_secs: parse_cache::DEFAULT_STATS_REFRESH_SECS,
         }
     }
 }
@@ -64,6 +90,81 @@ pub struct DouyinVideoInfo {
     pub cover_url: Option<String>,
     #[serde(default)]
     pub duration: Option<u64>,
+    #[serde(default)]
+    pub aweme_id: String,
+    /// 发布时间（Unix 秒）
+    #[serde(default)]
+    pub create_time: Option<u64>,
+    /// 作品类型（视频 / 图文）
+    #[serde(default)]
+    pub content_type: ContentType,
+    /// 图文作品的图片地址
+    #[serde(default)]
+    pub image_urls: Vec<String>,
+    /// 背景音乐地址
+    #[serde(default)]
+    pub music_url: Option<String>,
+    /// 收藏数
+    #[serde(default)]
+    pub collects: u64,
+    /// 播放数（接口未公开时为 0）
+    #[serde(default)]
+    pub plays: u64,
+    /// 话题标签（不含 `#`）
+    #[serde(default)]
+    pub hashtags: Vec<String>,
+    /// 背景音乐信息
+    #[serde(default)]
+    pub music: Option<MusicInfo>,
+    /// IP 属地
+    #[serde(default)]
+    pub ip_location: Option<String>,
+    /// 视频分辨率
+    #[serde(default)]
+    pub width: u32,
+    #[serde(default)]
+    pub height: u32,
+    /// 码率档位（按码率从高到低）
+    #[serde(default)]
+    pub bit_rates: Vec<VideoVariant>,
+}
+
+impl DouyinVideoInfo {
+    /// 按画质选择下载地址，未指定或没有码率档位时使用默认的无水印地址
+    pub fn download_url(&self, quality: Option<&QualityPreference>) -> String {
+        quality
+            .and_then(|q| q.select(&self.bit_rates))
+            .map(|v| v.url.clone())
+            .unwrap_or_else(|| self.video_url.clone())
+    }
+}
+
+impl From<DouyinVideoData> for DouyinVideoInfo {
+    fn from(data: DouyinVideoData) -> Self {
+        Self {
+            video_url: data.no_watermark_url,
+            title: data.title,
+            author: data.author,
+            likes: data.likes,
+            comments: data.comments,
+            shares: data.shares,
+            cover_url: Some(data.cover_url),
+            duration: Some(data.duration),
+            aweme_id: data.aweme_id,
+            create_time: (data.create_time > 0).then_some(data.create_time),
+            content_type: data.content_type,
+            image_urls: data.image_urls,
+            music_url: (!data.music_url.is_empty()).then_some(data.music_url),
+            collects: data.collects,
+            plays: data.plays,
+            hashtags: data.hashtags,
+            music: data.music,
+            ip_location: (!data.ip_location.is_empty()).then_some(data.ip_location),
+            width: data.width,
+            height: data.height,
+            bit_rates: data.bit_rates,
+        }
+    }
 }
 
 /// 搜索结果
@@ -71,6 +172,24 @@ pub struct DouyinVideoInfo {
 pub struct SearchResult {
     pub videos: Vec<DouyinVideoInfo>,
     pub total: u64,
+    /// 是否还有下一页
+    #[serde(default)]
+    pub has_more: bool,
+    /// 下一页的起始位置（搜索的 offset / 用户作品的 max_cursor）
+    #[serde(default)]
+    pub cursor: u64,
+}
+
+impl From<VideoPage> for SearchResult {
+    fn from(page: VideoPage) -> Self {
+        let videos: Vec<DouyinVideoInfo> = page.videos.into_iter().map(Into::into).collect();
+        Self {
+            total: videos.len() as u64,
+            videos,
+            has_more: page.has_more,
+            cursor: page.cursor,
+        }
+    }
 }
 
 /// 批量处理进度
@@ -124,60 +243,48 @@ struct DyMcpVideoData {
     duration: Option<u64>,
 }
 
-/// Undoom MCP 服务响应格式
-#[derive(Debug, Deserialize)]
-struct UndoomMcpResponse {
-    #[serde(default)]
-    success: bool,
-    #[serde(default)]
-    videos: Option<Vec<UndoomVideoData>>,
-    #[serde(default)]
-    total: Option<u64>,
-    #[serde(default)]
-    error: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct UndoomVideoData {
-    #[serde(default)]
-    video_url: Option<String>,
-    #[serde(default)]
-    title: Option<String>,
-    #[serde(default)]
-    author: Option<String>,
-    #[serde(default)]
-    likes: Option<u64>,
-    #[serde(default)]
-    comments: Option<u64>,
-    #[serde(default)]
-    shares: Option<u64>,
-    #[serde(default)]
-    cover_url: Option<String>,
-    #[serde(default)]
-    duration: Option<u64>,
-}
-
 /// MCP 客户端
 pub struct McpClient {
     config: McpConfig,
     http_client: Client,
+    /// 解析结果缓存
+    cache: Arc<dyn ParseCacheStore>,
+    /// 本客户端是否跳过缓存读取（解析结果仍会写入缓存）
+    bypass_cache: bool,
+    /// 请求限速器
+    limiter: Arc<RateLimiter>,
 }
 
 impl McpClient {
     /// 创建新的 MCP 客户端实例
     pub fn new(config: McpConfig) -> Self {
-        let http_client = Client::builder()
+        // 本机 Sidecar / MCP 服务直连，不经过代理
+        let http_client = http_client::client_builder(HttpService::Sidecar)
             .timeout(Duration::from_secs(config.timeout_secs))
-            .connect_timeout(Duration::from_secs(10))
             .build()
             .unwrap_or_else(|_| Client::new());
 
         Self {
             config,
             http_client,
+            cache: parse_cache::global_store(),
+            bypass_cache: false,
+            limiter: rate_limiter::global_limiter(),
         }
     }
 
+    /// 使用指定的缓存存储（默认使用全局存储）
+    pub fn with_cache_store(mut self, cache: Arc<dyn ParseCacheStore>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// 跳过缓存读取，强制重新解析
+    pub fn with_cache_bypass(mut self, bypass: bool) -> Self {
+        self.bypass_cache = bypass;
+        self
+    }
+
     /// 获取配置
     pub fn config(&self) -> &McpConfig {
         &self.config
@@ -195,32 +302,78 @@ impl McpClient {
             return Err(McpError::InvalidLink(link.to_string()));
         }
 
-        // 使用内置解析器
-        let parser = super::douyin_parser::DouyinParser::new();
-
-        match parser.parse_link(link).await {
-            Ok(data) => Ok(DouyinVideoInfo {
-                video_url: data.no_watermark_url.clone(),
-                title: data.title,
-                author: data.author,
-                likes: data.likes,
-                comments: data.comments,
-                shares: data.shares,
-                cover_url: Some(data.cover_url),
-                duration: Some(data.duration),
-            }),
-            Err(e) => match e {
-                super::douyin_parser::DouyinError::Timeout => Err(McpError::Timeout),
-                super::douyin_parser::DouyinError::InvalidLink(l) => Err(McpError::InvalidLink(l)),
-                super::douyin_parser::DouyinError::VideoNotFound => {
-                    Err(McpError::ParseError("视频不存在或已删除".to_string()))
-                }
-                super::douyin_parser::DouyinError::AuthRequired => Err(
-                    McpError::ServiceUnavailable("需要登录验证，请稍后重试".to_string()),
-                ),
-                _ => Err(McpError::ParseError(e.to_string())),
-            },
+        self.fetch_video_data(link)
+            .await
+            .map(Into::into)
+            .map_err(map_douyin_error)
+    }
+
+    /// 获取作品详情（优先使用缓存，未命中或已过期时通过内置解析器解析）
+    async fn fetch_video_data(&self, link: &str) -> Result<DouyinVideoData, DouyinError> {
+        let use_cache = self.config.cache_enabled && !self.bypass_cache;
+        if use_cache {
+            if let Some(data) = self.lookup_cache(link) {
+                return Ok(data);
+            }
+        }
+
+        let parser = DouyinParser::new();
+        let aweme_id = parser.resolve_aweme_id(link).await?;
+        let data = parser.fetch_video_from_page(&aweme_id).await?;
+
+        if self.config.cache_enabled {
+            if super::link_import::canonical_aweme_id(link).is_none() {
+                self.cache.put_link(link, &aweme_id);
+            }
+            if let Some(entry) = CachedParse::from_result(&aweme_id, &data) {
+                self.cache.put(&entry);
+            }
         }
+        Ok(data)
+    }
+
+    /// 查询缓存：新鲜的结果直接返回；统计数据过时的结果照常返回，同时在后台刷新；
+    /// 播放地址签名可能已失效的结果按未命中处理
+    fn lookup_cache(&self, link: &str) -> Option<DouyinVideoData> {
+        let aweme_id = super::link_import::canonical_aweme_id(link)
+            .or_else(|| self.cache.resolve_link(link))?;
+        let entry = self.cache.get(&aweme_id)?;
+
+        let now = chrono::Utc::now().timestamp();
+        match parse_cache::freshness(
+            entry.fetched_at,
+            now,
+            self.config.cache_ttl_secs,
+            self.config.cache_url_ttl_secs,
+            self.config.cache_stats_refresh_secs,
+        ) {
+            Freshness::Fresh => Some(entry.data),
+            Freshness::Stale => {
+                self.revalidate(aweme_id);
+                Some(entry.data)
+            }
+            Freshness::Expired => None,
+        }
+    }
+
+    /// 在后台重新解析作品并更新缓存（同一作品同时只刷新一次）
+    fn revalidate(&self, aweme_id: String) {
+        if !parse_cache::begin_revalidate(&aweme_id) {
+            return;
+        }
+
+        let cache = self.cache.clone();
+        tokio::spawn(async move {
+            match DouyinParser::new().fetch_video_from_page(&aweme_id).await {
+                Ok(data) => {
+                    if let Some(entry) = CachedParse::from_result(&aweme_id, &data) {
+                        cache.put(&entry);
+                    }
+                }
+                Err(e) => warn!("后台刷新解析结果失败 {}: {}", aweme_id, e),
+            }
+            parse_cache::end_revalidate(&aweme_id);
+        });
     }
 
     /// 带重试的解析
@@ -287,7 +440,14 @@ impl McpClient {
         let mut failed = 0;
 
         for (i, link) in links.into_iter().enumerate() {
-            let result = self.parse_with_retry(&link).await;
+            // 熔断期间暂停批量解析，恢复后继续；解析途中触发熔断的链接恢复后重新解析
+            let result = loop {
+                self.limiter.wait_until_closed().await;
+                let result = self.parse_with_retry(&link).await;
+                if result.success || !self.limiter.status().circuit_open {
+                    break result;
+                }
+            };
 
             if result.success {
                 success += 1;
@@ -313,134 +473,69 @@ impl McpClient {
         results
     }
 
-    /// 搜索关键词视频（调用 Undoom MCP 服务）
-    pub async fn search_videos(
-        &self,
-        keyword: &str,
-        count: u32,
-        scroll_times: u32,
-    ) -> Result<SearchResult, McpError> {
-        let url = format!("{}/search", self.config.undoom_mcp_url);
-
-        let response = self
-            .http_client
-            .post(&url)
-            .json(&serde_json::json!({
-                "keyword": keyword,
-                "count": count,
-                "scroll_times": scroll_times
-            }))
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    McpError::Timeout
-                } else if e.is_connect() {
-                    McpError::ServiceUnavailable(format!("无法连接到 Undoom MCP 服务: {}", e))
-                } else {
-                    McpError::NetworkError(e.to_string())
-                }
-            })?;
-
-        let status = response.status();
-        if status.as_u16() == 429 {
-            return Err(McpError::RateLimited);
-        }
-        if !status.is_success() {
-            return Err(McpError::NetworkError(format!("HTTP 错误: {}", status)));
-        }
-
-        let mcp_response: UndoomMcpResponse = response
-            .json()
+    /// 关键词搜索视频（内置解析器，支持排序、发布时间筛选与翻页）
+    pub async fn search_videos(&self, query: &SearchQuery) -> Result<SearchResult, McpError> {
+        DouyinParser::new()
+            .search_videos(query)
             .await
-            .map_err(|e| McpError::ResponseParseError(e.to_string()))?;
-
-        if !mcp_response.success {
-            return Err(McpError::ParseError(
-                mcp_response.error.unwrap_or_else(|| "搜索失败".to_string()),
-            ));
-        }
-
-        let videos = mcp_response
-            .videos
-            .unwrap_or_default()
-            .into_iter()
-            .map(|v| DouyinVideoInfo {
-                video_url: v.video_url.unwrap_or_default(),
-                title: v.title.unwrap_or_else(|| "未知标题".to_string()),
-                author: v.author.unwrap_or_else(|| "未知作者".to_string()),
-                likes: v.likes.unwrap_or(0),
-                comments: v.comments.unwrap_or(0),
-                shares: v.shares.unwrap_or(0),
-                cover_url: v.cover_url,
-                duration: v.duration,
-            })
-            .collect();
-
-        Ok(SearchResult {
-            videos,
-            total: mcp_response.total.unwrap_or(0),
-        })
+            .map(Into::into)
+            .map_err(map_douyin_error)
     }
 
-    /// 获取用户视频列表（调用 Undoom MCP 服务）
+    /// 获取用户最新发布的作品
+    ///
+    /// `user_id` 可以是 sec_uid、用户主页链接或主页分享短链接
     pub async fn get_user_videos(&self, user_id: &str) -> Result<Vec<DouyinVideoInfo>, McpError> {
-        let url = format!("{}/user/videos", self.config.undoom_mcp_url);
-
-        let response = self
-            .http_client
-            .post(&url)
-            .json(&serde_json::json!({ "user_id": user_id }))
-            .send()
+        self.get_user_videos_page(user_id, 0, DEFAULT_USER_VIDEO_COUNT)
             .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    McpError::Timeout
-                } else if e.is_connect() {
-                    McpError::ServiceUnavailable(format!("无法连接到 Undoom MCP 服务: {}", e))
-                } else {
-                    McpError::NetworkError(e.to_string())
-                }
-            })?;
-
-        let status = response.status();
-        if status.as_u16() == 429 {
-            return Err(McpError::RateLimited);
-        }
-        if !status.is_success() {
-            return Err(McpError::NetworkError(format!("HTTP 错误: {}", status)));
-        }
+            .map(|result| result.videos)
+    }
 
-        let mcp_response: UndoomMcpResponse = response
-            .json()
+    /// 分页获取用户作品，翻页时传入上一页返回的 `cursor`
+    pub async fn get_user_videos_page(
+        &self,
+        user_id: &str,
+        max_cursor: u64,
+        count: u32,
+    ) -> Result<SearchResult, McpError> {
+        DouyinParser::new()
+            .fetch_user_videos(user_id, max_cursor, count)
             .await
-            .map_err(|e| McpError::ResponseParseError(e.to_string()))?;
+            .map(Into::into)
+            .map_err(map_douyin_error)
+    }
 
-        if !mcp_response.success {
-            return Err(McpError::ParseError(
-                mcp_response
-                    .error
-                    .unwrap_or_else(|| "获取用户视频失败".to_string()),
-            ));
-        }
+    /// 分页获取话题（挑战）下的作品
+    ///
+    /// `challenge` 可以是话题 ID、话题页链接或话题分享短链接
+    pub async fn get_challenge_videos(
+        &self,
+        challenge: &str,
+        sort: ChallengeSort,
+        cursor: u64,
+        count: u32,
+    ) -> Result<SearchResult, McpError> {
+        DouyinParser::new()
+            .fetch_challenge_videos(challenge, sort, cursor, count)
+            .await
+            .map(Into::into)
+            .map_err(map_douyin_error)
+    }
 
-        let videos = mcp_response
-            .videos
-            .unwrap_or_default()
-            .into_iter()
-            .map(|v| DouyinVideoInfo {
-                video_url: v.video_url.unwrap_or_default(),
-                title: v.title.unwrap_or_else(|| "未知标题".to_string()),
-                author: v.author.unwrap_or_else(|| "未知作者".to_string()),
-                likes: v.likes.unwrap_or(0),
-                comments: v.comments.unwrap_or(0),
-                shares: v.shares.unwrap_or(0),
-                cover_url: v.cover_url,
-                duration: v.duration,
-            })
-            .collect();
-
-        Ok(videos)
+    /// 分页获取使用某个背景音乐的作品
+    ///
+    /// `music` 可以是音乐 ID、音乐页链接或音乐分享短链接
+    pub async fn get_music_videos(
+        &self,
+        music: &str,
+        cursor: u64,
+        count: u32,
+    ) -> Result<SearchResult, McpError> {
+        DouyinParser::new()
+            .fetch_music_videos(music, cursor, count)
+            .await
+            .map(Into::into)
+            .map_err(map_douyin_error)
     }
 
     /// 验证抖音链接格式
@@ -479,9 +574,22 @@ impl McpClient {
             .to_str()
             .ok_or_else(|| McpError::NetworkError("无效的文件路径".to_string()))?;
 
-        let response = self
-            .http_client
-            .post(api_url)
+        // Sidecar 代为访问抖音 CDN，按下载地址限速
+        self.limiter
+            .acquire(url)
+            .await
+            .map_err(|_| McpError::RateLimited)?;
+
+        let mut request = self.http_client.post(api_url);
+        // 只在下载地址属于 Cookie 域名时转发登录 Cookie，CDN 等其他主机不携带
+        if let Some(cookie) = super::session_manager::cookie_header_for_url(url) {
+            request = request.header("X-Douyin-Cookie", cookie);
+        }
+        for (name, value) in http_client::sidecar_forward_headers() {
+            request = request.header(name, value);
+        }
+
+        let response = request
             .json(&serde_json::json!({
                 "url": url,
                 "path": path_str
@@ -494,6 +602,13 @@ impl McpClient {
         let status = response.status();
         if !status.is_success() {
             let error_text = response.text().await.unwrap_or_default();
+            if self
+                .limiter
+                .check_response(url, status.as_u16(), &error_text)
+                .is_err()
+            {
+                return Err(McpError::RateLimited);
+            }
             return Err(McpError::NetworkError(format!(
                 "Python 下载服务返回错误 ({}): {}",
                 status, error_text
@@ -508,6 +623,7 @@ impl McpClient {
 
         if let Some(status) = json_resp.get("status") {
             if status.as_str() == Some("success") {
+                self.limiter.record_success(url);
                 Ok(())
             } else {
                 let error = json_resp
@@ -524,16 +640,46 @@ impl McpClient {
         }
     }
 
+    /// 下载图文作品到文件夹（图片按序号命名，背景音乐保存为 music.mp3）
+    ///
+    /// 逐个通过 Python sidecar 下载，以处理防盗链
+    pub async fn download_gallery(
+        &self,
+        image_urls: &[String],
+        music_url: Option<&str>,
+        output_dir: &std::path::Path,
+    ) -> Result<Vec<std::path::PathBuf>, McpError> {
+        if image_urls.is_empty() {
+            return Err(McpError::ParseError("图文作品没有图片".to_string()));
+        }
+
+        std::fs::create_dir_all(output_dir)
+            .map_err(|e| McpError::NetworkError(format!("创建目录失败: {}", e)))?;
+
+        let mut saved = Vec::with_capacity(image_urls.len() + 1);
+        for (i, url) in image_urls.iter().enumerate() {
+            let path = output_dir.join(format!("{:02}.{}", i + 1, image_extension(url)));
+            self.download_video_via_sidecar(url, &path).await?;
+            saved.push(path);
+        }
+
+        // 背景音乐下载失败不影响图片
+        if let Some(music_url) = music_url.filter(|u| !u.is_empty()) {
+            let path = output_dir.join("music.mp3");
+            match self.download_video_via_sidecar(music_url, &path).await {
+                Ok(_) => saved.push(path),
+                Err(e) => warn!("背景音乐下载失败: {}", e),
+            }
+        }
+
+        Ok(saved)
+    }
+
     /// 检查 Undoom MCP 服务是否可用
+    ///
+    /// 搜索与用户作品已由内置解析器实现，不再依赖 Undoom 服务，始终可用
     pub async fn check_undoom_mcp_health(&self) -> bool {
-        let url = format!("{}/health", self.config.undoom_mcp_url);
-        self.http_client
-            .get(&url)
-            .timeout(Duration::from_secs(5))
-            .send()
-            .await
-            .map(|r| r.status().is_success())
-            .unwrap_or(false)
+        true
     }
 }
 
@@ -542,3 +688,99 @@ impl Default for McpClient {
         Self::new(McpConfig::default())
     }
 }
+
+/// 将解析器错误转换为客户端错误
+fn map_douyin_error(e: DouyinError) -> McpError {
+    match e {
+        DouyinError::Timeout => McpError::Timeout,
+        DouyinError::RateLimited(_) => McpError::RateLimited,
+        DouyinError::InvalidLink(l) => McpError::InvalidLink(l),
+        DouyinError::VideoNotFound => McpError::ParseError("视频不存在或已删除".to_string()),
+        // 签名未生效导致的空响应与登录状态无关，不标记会话失效
+        DouyinError::SignatureRequired => McpError::ServiceUnavailable(
+            "列表接口返回空响应（签名未生效或触发风控），请稍后重试".to_string(),
+        ),
+        DouyinError::AuthRequired => {
+            // 已导入的登录 Cookie 被拒绝，标记会话失效以提示刷新
+            if let Some(session) = super::session_manager::session_manager() {
+                session.mark_invalid();
+            }
+            McpError::ServiceUnavailable("需要登录验证，请导入或刷新抖音 Cookie".to_string())
+        }
+        _ => McpError::ParseError(e.to_string()),
+    }
+}
+
+/// 根据图片地址推断扩展名
+fn image_extension(url: &str) -> &'static str {
+    let path = url.split('?').next().unwrap_or(url).to_lowercase();
+    if path.contains(".webp") {
+        "webp"
+    } else if path.contains(".png") {
+        "png"
+    } else if path.contains(".heic") {
+        "heic"
+    } else {
+        "jpg"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parse_cache::MemoryParseCache;
+
+    fn cached_client(fetched_at: i64) -> McpClient {
+        let store = Arc::new(MemoryParseCache::new());
+        store.put(&CachedParse {
+            data: DouyinVideoData {
+                aweme_id: "7301234567890123456".to_string(),
+                title: "缓存标题".to_string(),
+                no_watermark_url: "https://v26.douyinvod.com/a.mp4".to_string(),
+                likes: 100,
+                ..Default::default()
+            },
+            fetched_at,
+        });
+        store.put_link("https://v.douyin.com/abc/", "7301234567890123456");
+        McpClient::default().with_cache_store(store)
+    }
+
+    #[tokio::test]
+    async fn test_parse_from_cache() {
+        let client = cached_client(chrono::Utc::now().timestamp());
+
+        let info = client
+            .parse_douyin_link("https://www.douyin.com/video/7301234567890123456")
+            .await
+            .unwrap();
+        assert_eq!(info.title, "缓存标题");
+        assert_eq!(info.video_url, "https://v26.douyinvod.com/a.mp4");
+
+        // 短链接通过已记录的映射命中缓存
+        let info = client
+            .parse_douyin_link("https://v.douyin.com/abc/")
+            .await
+            .unwrap();
+        assert_eq!(info.likes, 100);
+    }
+
+    #[test]
+    fn test_cache_lookup_expiry() {
+        let link = "https://www.douyin.com/video/7301234567890123456";
+        let now = chrono::Utc::now().timestamp();
+
+        // 统计数据过时仍返回缓存结果
+        let stale = cached_client(now - parse_cache::DEFAULT_STATS_REFRESH_SECS as i64 - 1);
+        // 预先占用刷新标记，避免测试中发起后台请求
+        parse_cache::begin_revalidate("7301234567890123456");
+        assert!(stale.lookup_cache(link).is_some());
+        parse_cache::end_revalidate("7301234567890123456");
+
+        let expired = cached_client(now - parse_cache::DEFAULT_CACHE_TTL_SECS as i64 - 1);
+        assert!(expired.lookup_cache(link).is_none());
+        assert!(cached_client(now)
+            .lookup_cache("https://v.douyin.com/other/")
+            .is_none());
+    }
+}
@@ -1,9 +1,23 @@
 // 核心功能模块
 
+pub mod account_monitor;
 pub mod asr_engine;
+pub mod comment_analytics;
+pub mod cover_selector;
 pub mod doc_generator;
 pub mod douyin_parser;
+pub mod duplicate_detector;
+pub mod engagement_metrics;
+pub mod folder_watcher;
+pub mod link_import;
+pub mod live_recorder;
 pub mod mcp_client;
+pub mod ocr_timeline;
+pub mod parse_cache;
+pub mod platform;
+pub mod session_manager;
+pub mod shot_pacing;
 pub mod sidecar_manager;
+pub mod strategy_health;
 pub mod tray;
 pub mod video_processor;
@@ -0,0 +1,208 @@
+// 画面文字时间轴模块
+// 按固定间隔采样帧的 OCR 结果，合并在连续帧中重复出现的字幕/标题，输出带时间戳的画面文字
+
+use serde::{Deserialize, Serialize};
+
+/// 默认采样间隔（秒）
+pub const DEFAULT_SAMPLE_INTERVAL_SECS: f32 = 1.0;
+/// 最多采样帧数（长视频自动放大间隔）
+const MAX_SAMPLES: usize = 600;
+/// 最低识别置信度
+const MIN_CONFIDENCE: f32 = 0.5;
+/// 判定为同一段文字的相似度阈值
+const SAME_TEXT_SIMILARITY: f32 = 0.8;
+/// 允许连续漏检的采样次数
+const MAX_MISSED_SAMPLES: f32 = 1.5;
+
+/// 单帧识别到的文字
+#[derive(Debug, Clone)]
+pub struct FrameText {
+    pub text: String,
+    pub confidence: f32,
+}
+
+/// 带时间戳的画面文字
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnScreenText {
+    /// 首次出现时间（毫秒）
+    pub start_ms: u64,
+    /// 最后出现时间（毫秒）
+    pub end_ms: u64,
+    pub text: String,
+    pub confidence: f32,
+}
+
+/// 计算采样时间点（秒）
+pub fn sample_timestamps(duration_ms: u64, interval_secs: f32) -> Vec<f32> {
+    let duration = duration_ms as f32 / 1000.0;
+    if duration <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut interval = interval_secs.max(0.1);
+    if duration / interval > MAX_SAMPLES as f32 {
+        interval = duration / MAX_SAMPLES as f32;
+    }
+
+    let mut timestamps = Vec::new();
+    let mut t = interval / 2.0;
+    while t < duration {
+        timestamps.push(t);
+        t += interval;
+    }
+    timestamps
+}
+
+/// 合并连续帧中重复出现的文字
+///
+/// # Arguments
+/// * `frames` - (时间秒, 该帧识别结果)，按时间升序
+/// * `interval_secs` - 实际采样间隔
+pub fn merge_frames(frames: &[(f32, Vec<FrameText>)], interval_secs: f32) -> Vec<OnScreenText> {
+    let max_gap = interval_secs * (1.0 + MAX_MISSED_SAMPLES);
+    let mut active: Vec<(OnScreenText, f32)> = Vec::new();
+    let mut finished = Vec::new();
+
+    for (time, texts) in frames {
+        // 关闭超时未再出现的文字
+        let (expired, kept): (Vec<_>, Vec<_>) = active
+            .into_iter()
+            .partition(|(_, last_seen)| time - last_seen > max_gap);
+        finished.extend(expired.into_iter().map(|(segment, _)| segment));
+        active = kept;
+
+        for frame_text in texts {
+            if frame_text.confidence < MIN_CONFIDENCE || normalize(&frame_text.text).is_empty() {
+                continue;
+            }
+
+            let existing = active.iter_mut().find(|(segment, _)| {
+                similarity(&segment.text, &frame_text.text) >= SAME_TEXT_SIMILARITY
+            });
+            match existing {
+                Some((segment, last_seen)) => {
+                    *last_seen = *time;
+                    segment.end_ms = (time * 1000.0) as u64;
+                    // 保留置信度最高的识别版本
+                    if frame_text.confidence > segment.confidence {
+                        segment.text = frame_text.text.trim().to_string();
+                        segment.confidence = frame_text.confidence;
+                    }
+                }
+                None => active.push((
+                    OnScreenText {
+                        start_ms: (time * 1000.0) as u64,
+                        end_ms: (time * 1000.0) as u64,
+                        text: frame_text.text.trim().to_string(),
+                        confidence: frame_text.confidence,
+                    },
+                    *time,
+                )),
+            }
+        }
+    }
+
+    finished.extend(active.into_iter().map(|(segment, _)| segment));
+    finished.sort_by_key(|s| s.start_ms);
+    finished
+}
+
+/// 拼接为纯文本（每段一行，带时间戳）
+pub fn to_plain_text(segments: &[OnScreenText]) -> String {
+    segments
+        .iter()
+        .map(|s| {
+            let secs = s.start_ms / 1000;
+            format!("[{:02}:{:02}] {}", secs / 60, secs % 60, s.text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 去除空白和标点，用于比较
+fn normalize(text: &str) -> Vec<char> {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// 基于编辑距离的文本相似度 0.0 - 1.0
+fn similarity(a: &str, b: &str) -> f32 {
+    let (a, b) = (normalize(a), normalize(b));
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    1.0 - previous[b.len()] as f32 / longest as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str, confidence: f32) -> FrameText {
+        FrameText {
+            text: s.to_string(),
+            confidence,
+        }
+    }
+
+    #[test]
+    fn test_sample_timestamps() {
+        assert_eq!(sample_timestamps(3000, 1.0), vec![0.5, 1.5, 2.5]);
+        assert!(sample_timestamps(0, 1.0).is_empty());
+        assert_eq!(sample_timestamps(3_600_000, 1.0).len(), MAX_SAMPLES);
+    }
+
+    #[test]
+    fn test_merge_deduplicates_repeated_captions() {
+        let frames = vec![
+            (0.5, vec![text("今天教大家做菜", 0.9), text("第一集", 0.95)]),
+            (
+                1.5,
+                vec![text("今天教大家做菜!", 0.95), text("第一集", 0.9)],
+            ),
+            // 漏检一帧
+            (2.5, vec![text("第一集", 0.9)]),
+            (3.5, vec![text("今天教大家做菜", 0.8), text("第一集", 0.9)]),
+            (4.5, vec![text("先准备食材", 0.9), text("噪声", 0.2)]),
+        ];
+        let segments = merge_frames(&frames, 1.0);
+
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].text, "今天教大家做菜!");
+        assert_eq!(segments[0].start_ms, 500);
+        assert_eq!(segments[0].end_ms, 3500);
+        assert_eq!(segments[1].text, "第一集");
+        assert_eq!(segments[2].text, "先准备食材");
+        assert_eq!(segments[2].start_ms, 4500);
+    }
+
+    #[test]
+    fn test_merge_splits_after_long_gap() {
+        let frames = vec![
+            (0.5, vec![text("关注我", 0.9)]),
+            (1.5, vec![]),
+            (2.5, vec![]),
+            (3.5, vec![]),
+            (4.5, vec![text("关注我", 0.9)]),
+        ];
+        let segments = merge_frames(&frames, 1.0);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(to_plain_text(&segments), "[00:00] 关注我\n[00:04] 关注我");
+    }
+}
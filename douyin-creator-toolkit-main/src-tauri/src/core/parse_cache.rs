@@ -0,0 +1,250 @@
+// 解析结果缓存模块
+// 按作品 ID 缓存解析结果（并记录短链接到作品 ID 的映射），过期前直接复用，统计数据过时时先返回旧结果再后台刷新
+// 播放地址带 CDN 签名，数小时内失效，缓存条目超过地址有效期后重新解析以获取新签名
+
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use super::douyin_parser::DouyinVideoData;
+
+/// 默认缓存有效期（秒）
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 24 * 3600;
+/// 默认统计数据刷新间隔（秒）
+pub const DEFAULT_STATS_REFRESH_SECS: u64 = 600;
+/// 默认播放地址有效期（秒），需短于 CDN 签名的有效期
+pub const DEFAULT_URL_TTL_SECS: u64 = 3600;
+
+/// 缓存条目的新鲜程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// 直接使用
+    Fresh,
+    /// 仍可使用，但点赞 / 评论等统计数据需要后台刷新
+    Stale,
+    /// 已过期，需要重新解析
+    Expired,
+}
+
+/// 判断缓存条目的新鲜程度
+///
+/// 超过 `ttl_secs` 或 `url_ttl_secs`（缓存的播放地址签名可能已失效）视为过期；
+/// 未过期但超过 `stats_refresh_secs` 视为统计数据过时
+pub fn freshness(
+    fetched_at: i64,
+    now: i64,
+    ttl_secs: u64,
+    url_ttl_secs: u64,
+    stats_refresh_secs: u64,
+) -> Freshness {
+    let age = now.saturating_sub(fetched_at).max(0) as u64;
+    if age >= ttl_secs.min(url_ttl_secs) {
+        Freshness::Expired
+    } else if age >= stats_refresh_secs {
+        Freshness::Stale
+    } else {
+        Freshness::Fresh
+    }
+}
+
+/// 规范化链接作为映射键：去掉首尾空白、查询参数与末尾的 `/`
+pub fn link_key(link: &str) -> String {
+    let link = link.trim();
+    let link = link.split(['?', '#']).next().unwrap_or(link);
+    link.trim_end_matches('/').to_string()
+}
+
+/// 缓存的解析结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedParse {
+    pub data: DouyinVideoData,
+    /// 解析时间（Unix 秒）
+    pub fetched_at: i64,
+}
+
+impl CachedParse {
+    /// 以当前时间创建缓存条目
+    ///
+    /// 没有任何视频或图片地址的不完整结果（如只取到分享页 meta 信息）不缓存，返回 None
+    pub fn from_result(aweme_id: &str, data: &DouyinVideoData) -> Option<Self> {
+        if data.video_url.is_empty()
+            && data.no_watermark_url.is_empty()
+            && data.image_urls.is_empty()
+        {
+            return None;
+        }
+
+        let mut data = data.clone();
+        if data.aweme_id.is_empty() {
+            data.aweme_id = aweme_id.to_string();
+        }
+        Some(Self {
+            data,
+            fetched_at: chrono::Utc::now().timestamp(),
+        })
+    }
+}
+
+/// 解析结果缓存存储
+///
+/// 存储错误不影响解析流程，实现方自行记录日志并按未命中处理
+pub trait ParseCacheStore: Send + Sync {
+    /// 读取作品的缓存结果
+    fn get(&self, aweme_id: &str) -> Option<CachedParse>;
+
+    /// 保存作品的解析结果
+    fn put(&self, entry: &CachedParse);
+
+    /// 查询链接对应的作品 ID
+    fn resolve_link(&self, link: &str) -> Option<String>;
+
+    /// 记录链接对应的作品 ID
+    fn put_link(&self, link: &str, aweme_id: &str);
+}
+
+/// 内存缓存（未初始化数据库时与测试中使用）
+#[derive(Default)]
+pub struct MemoryParseCache {
+    entries: RwLock<HashMap<String, CachedParse>>,
+    links: RwLock<HashMap<String, String>>,
+}
+
+impl MemoryParseCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ParseCacheStore for MemoryParseCache {
+    fn get(&self, aweme_id: &str) -> Option<CachedParse> {
+        self.entries.read().get(aweme_id).cloned()
+    }
+
+    fn put(&self, entry: &CachedParse) {
+        self.entries
+            .write()
+            .insert(entry.data.aweme_id.clone(), entry.clone());
+    }
+
+    fn resolve_link(&self, link: &str) -> Option<String> {
+        self.links.read().get(&link_key(link)).cloned()
+    }
+
+    fn put_link(&self, link: &str, aweme_id: &str) {
+        self.links
+            .write()
+            .insert(link_key(link), aweme_id.to_string());
+    }
+}
+
+/// 全局缓存存储（数据层初始化后替换为数据库存储）
+static GLOBAL_STORE: Lazy<RwLock<Arc<dyn ParseCacheStore>>> =
+    Lazy::new(|| RwLock::new(Arc::new(MemoryParseCache::new())));
+
+/// 正在后台刷新的作品 ID，避免同一作品重复刷新
+static REVALIDATING: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 替换全局缓存存储
+pub fn install_store(store: Arc<dyn ParseCacheStore>) {
+    *GLOBAL_STORE.write() = store;
+}
+
+/// 获取全局缓存存储
+pub fn global_store() -> Arc<dyn ParseCacheStore> {
+    GLOBAL_STORE.read().clone()
+}
+
+/// 标记作品开始后台刷新，已在刷新中时返回 false
+pub fn begin_revalidate(aweme_id: &str) -> bool {
+    REVALIDATING.lock().insert(aweme_id.to_string())
+}
+
+/// 标记作品后台刷新结束
+pub fn end_revalidate(aweme_id: &str) {
+    REVALIDATING.lock().remove(aweme_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freshness() {
+        let check = |now| freshness(1000, now, 3600, 3600, 600);
+        assert_eq!(check(1000), Freshness::Fresh);
+        assert_eq!(check(1599), Freshness::Fresh);
+        assert_eq!(check(1600), Freshness::Stale);
+        assert_eq!(check(4600), Freshness::Expired);
+        // 时钟回拨时按刚解析处理
+        assert_eq!(check(900), Freshness::Fresh);
+        // 刷新间隔不小于有效期时不会出现 Stale
+        assert_eq!(freshness(1000, 2000, 1000, 1000, 1000), Freshness::Expired);
+    }
+
+    #[test]
+    fn test_freshness_url_ttl() {
+        let check = |now| {
+            freshness(
+                0,
+                now,
+                DEFAULT_CACHE_TTL_SECS,
+                DEFAULT_URL_TTL_SECS,
+                DEFAULT_STATS_REFRESH_SECS,
+            )
+        };
+        // 统计数据过时但播放地址仍有效：先返回旧结果
+        assert_eq!(check(1800), Freshness::Stale);
+        // 播放地址超过有效期：即使未到缓存有效期也必须重新解析，不能返回旧签名地址
+        assert_eq!(check(DEFAULT_URL_TTL_SECS as i64), Freshness::Expired);
+        assert_eq!(check(6 * 3600), Freshness::Expired);
+    }
+
+    #[test]
+    fn test_memory_store() {
+        let store = MemoryParseCache::new();
+        assert!(store.get("100").is_none());
+
+        store.put(&CachedParse {
+            data: DouyinVideoData {
+                aweme_id: "100".to_string(),
+                title: "标题".to_string(),
+                ..Default::default()
+            },
+            fetched_at: 1000,
+        });
+        store.put_link("https://v.douyin.com/abc/", "100");
+
+        assert_eq!(store.get("100").unwrap().data.title, "标题");
+        assert_eq!(
+            store
+                .resolve_link(" https://v.douyin.com/abc?from=share ")
+                .as_deref(),
+            Some("100")
+        );
+        assert!(store.resolve_link("https://v.douyin.com/xyz/").is_none());
+
+        // 只有 meta 信息的结果不缓存
+        let partial = DouyinVideoData {
+            title: "分享页标题".to_string(),
+            ..Default::default()
+        };
+        assert!(CachedParse::from_result("200", &partial).is_none());
+        let complete = DouyinVideoData {
+            no_watermark_url: "https://v26.douyinvod.com/a.mp4".to_string(),
+            ..partial
+        };
+        let entry = CachedParse::from_result("200", &complete).unwrap();
+        assert_eq!(entry.data.aweme_id, "200");
+    }
+
+    #[test]
+    fn test_revalidate_guard() {
+        assert!(begin_revalidate("guard-test"));
+        assert!(!begin_revalidate("guard-test"));
+        end_revalidate("guard-test");
+        assert!(begin_revalidate("guard-test"));
+        end_revalidate("guard-test");
+    }
+}
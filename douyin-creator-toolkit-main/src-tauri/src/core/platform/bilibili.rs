@@ -0,0 +1,310 @@
+// 哔哩哔哩平台解析器
+// 通过 web-interface/view 接口获取稿件信息，再通过 player/playurl 接口获取音视频合一的 MP4 直链
+
+use futures_util::future::{BoxFuture, FutureExt};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Client;
+use std::time::Duration;
+
+use super::{
+    ContentType, Platform, PlatformError, PlatformParser, PlatformVideo, BROWSER_USER_AGENT,
+};
+use crate::utils::http_client::{self, HttpService};
+
+/// 接口地址
+const VIEW_API: &str = "https://api.bilibili.com/x/web-interface/view";
+const PLAYURL_API: &str = "https://api.bilibili.com/x/player/playurl";
+/// 视频直链要求携带的 Referer
+const BILIBILI_REFERER: &str = "https://www.bilibili.com";
+/// 请求的清晰度（64 = 720P，未登录时接口会自动降级）
+const DEFAULT_QUALITY: u32 = 64;
+
+static BVID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(BV[0-9A-Za-z]{10})").unwrap());
+static AID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)/av(\d+)").unwrap());
+static PAGE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[?&]p=(\d+)").unwrap());
+
+/// 稿件标识
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BilibiliId {
+    Bvid(String),
+    Aid(u64),
+}
+
+impl BilibiliId {
+    /// 接口查询参数
+    fn query(&self) -> String {
+        match self {
+            BilibiliId::Bvid(bvid) => format!("bvid={}", bvid),
+            BilibiliId::Aid(aid) => format!("aid={}", aid),
+        }
+    }
+}
+
+/// 哔哩哔哩解析器
+pub struct BilibiliPlatform {
+    client: Client,
+}
+
+impl BilibiliPlatform {
+    pub fn new() -> Self {
+        let client = http_client::client_builder(HttpService::OtherPlatforms)
+            .timeout(Duration::from_secs(30))
+            .redirect(reqwest::redirect::Policy::limited(10))
+            .user_agent(BROWSER_USER_AGENT)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self { client }
+    }
+
+    /// 获取稿件标识与分 P 序号，b23.tv 短链需跟随跳转
+    async fn resolve_id(&self, url: &str) -> Result<(BilibiliId, usize), PlatformError> {
+        if let Some(id) = extract_video_id(url) {
+            return Ok((id, extract_page(url)));
+        }
+
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| PlatformError::NetworkError(e.to_string()))?;
+        let final_url = response.url().as_str().to_string();
+        match extract_video_id(&final_url) {
+            Some(id) => Ok((id, extract_page(&final_url))),
+            None => Err(PlatformError::UnsupportedLink(url.to_string())),
+        }
+    }
+
+    async fn get_json(&self, url: &str) -> Result<serde_json::Value, PlatformError> {
+        self.client
+            .get(url)
+            .header("Referer", BILIBILI_REFERER)
+            .send()
+            .await
+            .map_err(|e| PlatformError::NetworkError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| PlatformError::ParseError(e.to_string()))
+    }
+
+    async fn fetch(&self, url: &str) -> Result<PlatformVideo, PlatformError> {
+        let (id, page) = self.resolve_id(url).await?;
+
+        let view = self
+            .get_json(&format!("{}?{}", VIEW_API, id.query()))
+            .await?;
+        let (mut video, cid) = parse_view_response(&view, page)?;
+
+        let playurl = self
+            .get_json(&format!(
+                "{}?bvid={}&cid={}&qn={}&fnval=1&platform=html5",
+                PLAYURL_API, video.id, cid, DEFAULT_QUALITY
+            ))
+            .await?;
+        video.video_url = parse_playurl_response(&playurl)?;
+        Ok(video)
+    }
+}
+
+impl Default for BilibiliPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlatformParser for BilibiliPlatform {
+    fn platform(&self) -> Platform {
+        Platform::Bilibili
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        let url = url.to_lowercase();
+        url.contains("b23.tv") || (url.contains("bilibili.com") && url.contains("/video/"))
+    }
+
+    fn parse<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<PlatformVideo, PlatformError>> {
+        self.fetch(url).boxed()
+    }
+}
+
+/// 从链接中提取 BV 号或 av 号
+pub fn extract_video_id(url: &str) -> Option<BilibiliId> {
+    if let Some(caps) = BVID_RE.captures(url) {
+        return Some(BilibiliId::Bvid(caps[1].to_string()));
+    }
+    AID_RE
+        .captures(url)
+        .and_then(|caps| caps[1].parse().ok())
+        .map(BilibiliId::Aid)
+}
+
+/// 链接中的分 P 序号（从 1 开始，缺省为 1）
+fn extract_page(url: &str) -> usize {
+    PAGE_RE
+        .captures(url)
+        .and_then(|caps| caps[1].parse().ok())
+        .filter(|p| *p > 0)
+        .unwrap_or(1)
+}
+
+/// 检查接口返回码
+fn check_code(json: &serde_json::Value) -> Result<(), PlatformError> {
+    match json["code"].as_i64().unwrap_or(-1) {
+        0 => Ok(()),
+        -404 | 62002 | 62004 => Err(PlatformError::VideoNotFound),
+        // 风控拦截
+        -352 | -412 => Err(PlatformError::AuthRequired),
+        code => Err(PlatformError::ParseError(format!(
+            "接口返回错误 {}: {}",
+            code,
+            json["message"].as_str().unwrap_or("")
+        ))),
+    }
+}
+
+/// 解析稿件信息，返回作品信息与所选分 P 的 cid
+pub fn parse_view_response(
+    json: &serde_json::Value,
+    page: usize,
+) -> Result<(PlatformVideo, u64), PlatformError> {
+    check_code(json)?;
+    let data = &json["data"];
+
+    let bvid = data["bvid"].as_str().unwrap_or("").to_string();
+    if bvid.is_empty() {
+        return Err(PlatformError::ParseError("缺少 bvid".to_string()));
+    }
+
+    // 多 P 稿件按序号取对应分 P，序号越界时回退到第一 P
+    let selected = data["pages"]
+        .as_array()
+        .and_then(|pages| pages.get(page.saturating_sub(1)).or_else(|| pages.first()));
+    let (cid, duration) = match selected {
+        Some(p) => (p["cid"].as_u64(), p["duration"].as_u64()),
+        None => (None, None),
+    };
+    let cid = cid
+        .or_else(|| data["cid"].as_u64())
+        .ok_or_else(|| PlatformError::ParseError("缺少 cid".to_string()))?;
+
+    let stat = &data["stat"];
+    let url = if page > 1 {
+        format!("https://www.bilibili.com/video/{}?p={}", bvid, page)
+    } else {
+        format!("https://www.bilibili.com/video/{}", bvid)
+    };
+
+    let video = PlatformVideo {
+        platform: Some(Platform::Bilibili),
+        id: bvid,
+        url,
+        title: data["title"].as_str().unwrap_or("").to_string(),
+        author: data["owner"]["name"].as_str().unwrap_or("").to_string(),
+        author_id: data["owner"]["mid"]
+            .as_u64()
+            .map(|mid| mid.to_string())
+            .unwrap_or_default(),
+        content_type: ContentType::Video,
+        cover_url: data["pic"].as_str().unwrap_or("").to_string(),
+        duration: duration.or_else(|| data["duration"].as_u64()).unwrap_or(0),
+        create_time: data["pubdate"].as_u64(),
+        likes: stat["like"].as_u64().unwrap_or(0),
+        comments: stat["reply"].as_u64().unwrap_or(0),
+        shares: stat["share"].as_u64().unwrap_or(0),
+        collects: stat["favorite"].as_u64().unwrap_or(0),
+        plays: stat["view"].as_u64().unwrap_or(0),
+        http_headers: vec![("Referer".to_string(), BILIBILI_REFERER.to_string())],
+        ..Default::default()
+    };
+    Ok((video, cid))
+}
+
+/// 解析播放地址接口，返回第一段 MP4 直链
+pub fn parse_playurl_response(json: &serde_json::Value) -> Result<String, PlatformError> {
+    check_code(json)?;
+    json["data"]["durl"][0]["url"]
+        .as_str()
+        .map(|url| url.to_string())
+        .ok_or_else(|| PlatformError::ParseError("未找到视频地址".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_video_id() {
+        assert_eq!(
+            extract_video_id("https://www.bilibili.com/video/BV1xx411c7mD/?spm_id_from=333"),
+            Some(BilibiliId::Bvid("BV1xx411c7mD".to_string()))
+        );
+        assert_eq!(
+            extract_video_id("https://www.bilibili.com/video/av170001"),
+            Some(BilibiliId::Aid(170001))
+        );
+        assert_eq!(extract_video_id("https://b23.tv/AbCdEf"), None);
+        assert_eq!(
+            extract_page("https://www.bilibili.com/video/BV1xx411c7mD?p=3"),
+            3
+        );
+        assert_eq!(
+            extract_page("https://www.bilibili.com/video/BV1xx411c7mD"),
+            1
+        );
+    }
+
+    #[test]
+    fn test_parse_view_response() {
+        let json = serde_json::json!({
+            "code": 0,
+            "data": {
+                "bvid": "BV1xx411c7mD",
+                "title": "字幕君交流场所",
+                "pic": "https://i0.hdslb.com/cover.jpg",
+                "duration": 500,
+                "pubdate": 1_252_458_549,
+                "cid": 100,
+                "owner": { "mid": 2, "name": "碧诗" },
+                "stat": { "view": 1000, "like": 50, "reply": 7, "share": 3, "favorite": 9 },
+                "pages": [
+                    { "cid": 100, "page": 1, "duration": 200 },
+                    { "cid": 101, "page": 2, "duration": 300 }
+                ]
+            }
+        });
+
+        let (video, cid) = parse_view_response(&json, 2).unwrap();
+        assert_eq!(cid, 101);
+        assert_eq!(video.duration, 300);
+        assert_eq!(video.url, "https://www.bilibili.com/video/BV1xx411c7mD?p=2");
+        assert_eq!(video.author, "碧诗");
+        assert_eq!(video.author_id, "2");
+        assert_eq!(video.collects, 9);
+        assert_eq!(video.create_time, Some(1_252_458_549));
+        assert_eq!(video.http_headers[0].0, "Referer");
+
+        // 序号越界回退到第一 P
+        let (_, cid) = parse_view_response(&json, 9).unwrap();
+        assert_eq!(cid, 100);
+
+        let missing = serde_json::json!({ "code": -404, "message": "啥都木有" });
+        assert!(matches!(
+            parse_view_response(&missing, 1),
+            Err(PlatformError::VideoNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_parse_playurl_response() {
+        let json = serde_json::json!({
+            "code": 0,
+            "data": { "durl": [{ "order": 1, "url": "https://upos-sz.bilivideo.com/a.mp4" }] }
+        });
+        assert_eq!(
+            parse_playurl_response(&json).unwrap(),
+            "https://upos-sz.bilivideo.com/a.mp4"
+        );
+        assert!(parse_playurl_response(&serde_json::json!({ "code": 0, "data": {} })).is_err());
+    }
+}
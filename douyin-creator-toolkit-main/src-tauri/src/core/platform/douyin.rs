@@ -0,0 +1,97 @@
+// 抖音平台解析器
+// 包装内置的 DouyinParser，将 DouyinVideoData 转换为统一的 PlatformVideo
+
+use futures_util::future::{BoxFuture, FutureExt};
+
+use super::{ContentType, Platform, PlatformError, PlatformParser, PlatformVideo};
+use crate::core::douyin_parser::{DouyinError, DouyinParser, DouyinVideoData};
+
+/// 抖音解析器
+pub struct DouyinPlatform {
+    parser: DouyinParser,
+}
+
+impl DouyinPlatform {
+    pub fn new() -> Self {
+        Self {
+            parser: DouyinParser::new(),
+        }
+    }
+}
+
+impl Default for DouyinPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlatformParser for DouyinPlatform {
+    fn platform(&self) -> Platform {
+        Platform::Douyin
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        DouyinParser::is_valid_link(url)
+    }
+
+    fn parse<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<PlatformVideo, PlatformError>> {
+        async move {
+            let data = self.parser.parse_link(url).await?;
+            Ok(PlatformVideo::from(data))
+        }
+        .boxed()
+    }
+}
+
+impl From<DouyinError> for PlatformError {
+    fn from(e: DouyinError) -> Self {
+        match e {
+            DouyinError::NetworkError(msg) => PlatformError::NetworkError(msg),
+            DouyinError::ParseError(msg) => PlatformError::ParseError(msg),
+            DouyinError::InvalidLink(link) => PlatformError::UnsupportedLink(link),
+            DouyinError::Timeout => PlatformError::NetworkError("请求超时".to_string()),
+            DouyinError::VideoNotFound => PlatformError::VideoNotFound,
+            DouyinError::AuthRequired => PlatformError::AuthRequired,
+            DouyinError::RateLimited(msg) => PlatformError::NetworkError(msg),
+            DouyinError::SignatureRequired => PlatformError::NetworkError(e.to_string()),
+        }
+    }
+}
+
+impl From<DouyinVideoData> for PlatformVideo {
+    fn from(data: DouyinVideoData) -> Self {
+        let video_url = if data.no_watermark_url.is_empty() {
+            data.video_url
+        } else {
+            data.no_watermark_url
+        };
+
+        let path = match data.content_type {
+            ContentType::Video => "video",
+            ContentType::Images => "note",
+        };
+
+        Self {
+            platform: Some(Platform::Douyin),
+            url: format!("https://www.douyin.com/{}/{}", path, data.aweme_id),
+            id: data.aweme_id,
+            title: data.title,
+            author: data.author,
+            author_id: data.author_id,
+            content_type: data.content_type,
+            video_url,
+            audio_url: None,
+            image_urls: data.image_urls,
+            cover_url: data.cover_url,
+            duration: data.duration,
+            create_time: (data.create_time > 0).then_some(data.create_time),
+            likes: data.likes,
+            comments: data.comments,
+            shares: data.shares,
+            collects: data.collects,
+            plays: data.plays,
+            hashtags: data.hashtags,
+            http_headers: Vec::new(),
+        }
+    }
+}
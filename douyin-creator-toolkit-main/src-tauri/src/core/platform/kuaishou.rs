@@ -0,0 +1,220 @@
+// 快手平台解析器
+// 跟随短链跳转得到作品 ID，再从作品页内嵌的 window.__APOLLO_STATE__ 中提取视频信息
+
+use futures_util::future::{BoxFuture, FutureExt};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Client;
+use std::time::Duration;
+
+use super::{
+    extract_json_object, parse_count, ContentType, Platform, PlatformError, PlatformParser,
+    PlatformVideo, BROWSER_USER_AGENT,
+};
+use crate::utils::http_client::{self, HttpService};
+
+/// 快手相关域名（含短链与分享落地页）
+const KUAISHOU_HOSTS: [&str; 4] = [
+    "kuaishou.com",
+    "kuaishou.cn",
+    "chenzhongtech.com",
+    "gifshow.com",
+];
+/// 作品页内嵌状态的变量名
+const APOLLO_STATE_MARKER: &str = "window.__APOLLO_STATE__";
+
+/// 作品链接中的作品 ID
+static PHOTO_ID_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"/(?:short-video|photo|fw/photo)/([A-Za-z0-9_\-]+)").unwrap());
+/// 标题中的话题标签
+static HASHTAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"#([^\s#@]+)").unwrap());
+
+/// 快手解析器
+pub struct KuaishouPlatform {
+    client: Client,
+}
+
+impl KuaishouPlatform {
+    pub fn new() -> Self {
+        let client = http_client::client_builder(HttpService::OtherPlatforms)
+            .timeout(Duration::from_secs(30))
+            .redirect(reqwest::redirect::Policy::limited(10))
+            .user_agent(BROWSER_USER_AGENT)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self { client }
+    }
+
+    /// 获取作品 ID，短链需跟随跳转
+    async fn resolve_photo_id(&self, url: &str) -> Result<String, PlatformError> {
+        if let Some(id) = extract_photo_id(url) {
+            return Ok(id);
+        }
+
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| PlatformError::NetworkError(e.to_string()))?;
+        extract_photo_id(response.url().as_str())
+            .ok_or_else(|| PlatformError::UnsupportedLink(url.to_string()))
+    }
+
+    async fn fetch(&self, url: &str) -> Result<PlatformVideo, PlatformError> {
+        let photo_id = self.resolve_photo_id(url).await?;
+        let page_url = format!("https://www.kuaishou.com/short-video/{}", photo_id);
+
+        let html = self
+            .client
+            .get(&page_url)
+            .header("Referer", "https://www.kuaishou.com/")
+            .send()
+            .await
+            .map_err(|e| PlatformError::NetworkError(e.to_string()))?
+            .text()
+            .await
+            .map_err(|e| PlatformError::NetworkError(e.to_string()))?;
+
+        let mut video = parse_apollo_state(&html, &photo_id)?;
+        video.url = page_url;
+        Ok(video)
+    }
+}
+
+impl Default for KuaishouPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlatformParser for KuaishouPlatform {
+    fn platform(&self) -> Platform {
+        Platform::Kuaishou
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        let url = url.to_lowercase();
+        KUAISHOU_HOSTS.iter().any(|host| url.contains(host))
+    }
+
+    fn parse<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<PlatformVideo, PlatformError>> {
+        self.fetch(url).boxed()
+    }
+}
+
+/// 从作品链接中提取作品 ID
+pub fn extract_photo_id(url: &str) -> Option<String> {
+    PHOTO_ID_RE
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
+/// 解析作品页 HTML 中的 Apollo 状态
+pub fn parse_apollo_state(html: &str, photo_id: &str) -> Result<PlatformVideo, PlatformError> {
+    let state = match extract_json_object(html, APOLLO_STATE_MARKER) {
+        Some(state) => state,
+        // 触发风控时页面只有验证码，没有作品数据
+        None => return Err(PlatformError::AuthRequired),
+    };
+    let client = &state["defaultClient"];
+
+    let photo = &client[format!("VisionVideoDetailPhoto:{}", photo_id).as_str()];
+    if !photo.is_object() {
+        return Err(PlatformError::VideoNotFound);
+    }
+
+    let author = client
+        .as_object()
+        .and_then(|entries| {
+            entries
+                .iter()
+                .find(|(key, _)| key.starts_with("VisionVideoDetailAuthor:"))
+                .map(|(_, value)| value)
+        })
+        .cloned()
+        .unwrap_or_default();
+
+    let title = photo["caption"].as_str().unwrap_or("").trim().to_string();
+    let hashtags = HASHTAG_RE
+        .captures_iter(&title)
+        .filter_map(|caps| caps.get(1).map(|m| m.as_str().to_string()))
+        .collect();
+
+    let likes = match photo["realLikeCount"].as_u64() {
+        Some(n) => n,
+        None => parse_count(&photo["likeCount"]),
+    };
+
+    Ok(PlatformVideo {
+        platform: Some(Platform::Kuaishou),
+        id: photo_id.to_string(),
+        url: format!("https://www.kuaishou.com/short-video/{}", photo_id),
+        title,
+        author: author["name"].as_str().unwrap_or("").to_string(),
+        author_id: author["id"].as_str().unwrap_or("").to_string(),
+        content_type: ContentType::Video,
+        video_url: photo["photoUrl"].as_str().unwrap_or("").to_string(),
+        cover_url: photo["coverUrl"].as_str().unwrap_or("").to_string(),
+        duration: photo["duration"].as_u64().unwrap_or(0) / 1000,
+        create_time: photo["timestamp"].as_u64().map(|ms| ms / 1000),
+        likes,
+        comments: parse_count(&photo["commentCount"]),
+        shares: parse_count(&photo["shareCount"]),
+        plays: parse_count(&photo["viewCount"]),
+        hashtags,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_photo_id() {
+        assert_eq!(
+            extract_photo_id("https://www.kuaishou.com/short-video/3xg9avuebemj4xq?authorId=1")
+                .as_deref(),
+            Some("3xg9avuebemj4xq")
+        );
+        assert_eq!(
+            extract_photo_id("https://v.m.chenzhongtech.com/fw/photo/3x5k2?fid=1").as_deref(),
+            Some("3x5k2")
+        );
+        assert_eq!(extract_photo_id("https://v.kuaishou.com/JbXkq7"), None);
+    }
+
+    #[test]
+    fn test_parse_apollo_state() {
+        let html = r#"<script>window.__APOLLO_STATE__={"defaultClient":{
+            "VisionVideoDetailPhoto:3xabc":{"id":"3xabc","caption":"周末露营 #露营 #户外",
+                "photoUrl":"https://v2.kwaicdn.com/a.mp4","coverUrl":"https://p2.a.yximgs.com/c.jpg",
+                "duration":15230,"realLikeCount":12345,"likeCount":"1.2万","viewCount":"3.5w",
+                "timestamp":1700000000000},
+            "VisionVideoDetailAuthor:3xauthor":{"id":"3xauthor","name":"露营小王"}
+        }};(function(){var s;}())</script>"#;
+
+        let video = parse_apollo_state(html, "3xabc").unwrap();
+        assert_eq!(video.platform, Some(Platform::Kuaishou));
+        assert_eq!(video.title, "周末露营 #露营 #户外");
+        assert_eq!(video.author, "露营小王");
+        assert_eq!(video.author_id, "3xauthor");
+        assert_eq!(video.video_url, "https://v2.kwaicdn.com/a.mp4");
+        assert_eq!(video.duration, 15);
+        assert_eq!(video.likes, 12345);
+        assert_eq!(video.plays, 35_000);
+        assert_eq!(video.create_time, Some(1_700_000_000));
+        assert_eq!(video.hashtags, vec!["露营", "户外"]);
+
+        assert!(matches!(
+            parse_apollo_state(html, "3xother"),
+            Err(PlatformError::VideoNotFound)
+        ));
+        assert!(matches!(
+            parse_apollo_state("<html>验证码</html>", "3xabc"),
+            Err(PlatformError::AuthRequired)
+        ));
+    }
+}
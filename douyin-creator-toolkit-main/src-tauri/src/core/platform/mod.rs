@@ -0,0 +1,358 @@
+// 多平台解析模块
+// 定义统一的平台解析接口与按链接分发的注册表，各平台结果归一为 PlatformVideo 后进入同一下载 / 转写流程
+
+pub mod bilibili;
+pub mod douyin;
+pub mod kuaishou;
+
+use futures_util::future::BoxFuture;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::utils::http_client::{self, HttpService};
+
+pub use super::douyin_parser::ContentType;
+
+/// 各平台页面请求使用的浏览器 UA
+pub(crate) const BROWSER_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// 分享文案中的第一个链接
+static URL_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"https?://[^\s，。！？、]+").unwrap());
+
+#[derive(Error, Debug)]
+pub enum PlatformError {
+    #[error("不支持的链接: {0}")]
+    UnsupportedLink(String),
+    #[error("网络请求失败: {0}")]
+    NetworkError(String),
+    #[error("解析失败: {0}")]
+    ParseError(String),
+    #[error("视频不存在或已删除")]
+    VideoNotFound,
+    #[error("需要登录或验证")]
+    AuthRequired,
+    #[error("文件写入失败: {0}")]
+    IoError(String),
+}
+
+/// 内容平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Platform {
+    /// 抖音
+    Douyin,
+    /// 快手
+    Kuaishou,
+    /// 哔哩哔哩
+    Bilibili,
+}
+
+impl Platform {
+    /// 平台中文名
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Platform::Douyin => "抖音",
+            Platform::Kuaishou => "快手",
+            Platform::Bilibili => "哔哩哔哩",
+        }
+    }
+}
+
+/// 各平台统一的作品信息
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct PlatformVideo {
+    pub platform: Option<Platform>,
+    /// 平台内的作品 ID
+    pub id: String,
+    /// 规范化后的作品页面地址
+    pub url: String,
+    pub title: String,
+    pub author: String,
+    pub author_id: String,
+    /// 作品类型（视频 / 图文）
+    pub content_type: ContentType,
+    /// 视频直链（无水印优先）
+    pub video_url: String,
+    /// 独立音频流地址（音视频分离的平台）
+    pub audio_url: Option<String>,
+    /// 图文作品的图片地址
+    pub image_urls: Vec<String>,
+    pub cover_url: String,
+    /// 时长（秒）
+    pub duration: u64,
+    /// 发布时间（Unix 秒）
+    pub create_time: Option<u64>,
+    pub likes: u64,
+    pub comments: u64,
+    pub shares: u64,
+    pub collects: u64,
+    pub plays: u64,
+    /// 话题标签（不含 `#`）
+    pub hashtags: Vec<String>,
+    /// 下载直链时需要附带的请求头（如 B 站要求的 Referer）
+    pub http_headers: Vec<(String, String)>,
+}
+
+/// 平台解析器
+pub trait PlatformParser: Send + Sync {
+    /// 所属平台
+    fn platform(&self) -> Platform;
+
+    /// 是否能处理该链接
+    fn matches(&self, url: &str) -> bool;
+
+    /// 解析作品信息
+    fn parse<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<PlatformVideo, PlatformError>>;
+}
+
+/// 平台解析器注册表，按链接分发给第一个匹配的解析器
+pub struct PlatformRegistry {
+    parsers: Vec<Box<dyn PlatformParser>>,
+}
+
+impl PlatformRegistry {
+    /// 创建空注册表
+    pub fn new() -> Self {
+        Self {
+            parsers: Vec::new(),
+        }
+    }
+
+    /// 创建包含内置平台（抖音、快手、B 站）的注册表
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(douyin::DouyinPlatform::new()));
+        registry.register(Box::new(kuaishou::KuaishouPlatform::new()));
+        registry.register(Box::new(bilibili::BilibiliPlatform::new()));
+        registry
+    }
+
+    /// 注册解析器（先注册的优先匹配）
+    pub fn register(&mut self, parser: Box<dyn PlatformParser>) {
+        self.parsers.push(parser);
+    }
+
+    /// 已注册的平台
+    pub fn platforms(&self) -> Vec<Platform> {
+        self.parsers.iter().map(|p| p.platform()).collect()
+    }
+
+    /// 查找能处理该链接（或分享文案）的解析器
+    pub fn find(&self, link: &str) -> Option<&dyn PlatformParser> {
+        let url = extract_url(link)?;
+        self.parsers
+            .iter()
+            .find(|p| p.matches(&url))
+            .map(|p| p.as_ref())
+    }
+
+    /// 识别链接所属平台
+    pub fn detect(&self, link: &str) -> Option<Platform> {
+        self.find(link).map(|p| p.platform())
+    }
+
+    /// 解析链接或分享文案
+    pub async fn parse(&self, link: &str) -> Result<PlatformVideo, PlatformError> {
+        let url =
+            extract_url(link).ok_or_else(|| PlatformError::UnsupportedLink(link.to_string()))?;
+        let parser = self
+            .parsers
+            .iter()
+            .find(|p| p.matches(&url))
+            .ok_or_else(|| PlatformError::UnsupportedLink(url.clone()))?;
+
+        let mut video = parser.parse(&url).await?;
+        video.platform = Some(parser.platform());
+        Ok(video)
+    }
+}
+
+impl Default for PlatformRegistry {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+/// 从分享文案中提取链接；本身就是链接时原样返回（去掉首尾空白）
+pub fn extract_url(text: &str) -> Option<String> {
+    URL_RE.find(text.trim()).map(|m| {
+        m.as_str()
+            .trim_end_matches(['"', '\'', ')', '）'])
+            .to_string()
+    })
+}
+
+/// 取出页面脚本中 `marker` 之后的第一个完整 JSON 对象
+pub(crate) fn extract_json_object(html: &str, marker: &str) -> Option<serde_json::Value> {
+    let start = html.find(marker)? + marker.len();
+    let rest = &html[start..];
+    let open = rest.find('{')?;
+    let body = &rest[open..];
+
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, ch) in body.char_indices() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return serde_json::from_str(&body[..=i]).ok();
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 读取可能为数字或字符串（如 `1.2万`、`3.5w`）的计数
+pub(crate) fn parse_count(value: &serde_json::Value) -> u64 {
+    if let Some(n) = value.as_u64() {
+        return n;
+    }
+    if let Some(f) = value.as_f64() {
+        return f.max(0.0) as u64;
+    }
+
+    let text = value.as_str().unwrap_or("").trim().replace(',', "");
+    let (number, multiplier) = match text.strip_suffix(['万', 'w', 'W']) {
+        Some(n) => (n, 10_000.0),
+        None => match text.strip_suffix('亿') {
+            Some(n) => (n, 100_000_000.0),
+            None => (text.as_str(), 1.0),
+        },
+    };
+    number
+        .trim()
+        .parse::<f64>()
+        .map(|n| (n * multiplier).round() as u64)
+        .unwrap_or(0)
+}
+
+/// 下载作品视频直链到指定文件，返回写入的字节数
+pub async fn download_video_file(
+    video: &PlatformVideo,
+    output_path: &Path,
+) -> Result<u64, PlatformError> {
+    use futures_util::StreamExt;
+
+    if video.video_url.is_empty() {
+        return Err(PlatformError::ParseError(
+            "作品没有可下载的视频地址".to_string(),
+        ));
+    }
+
+    let client = http_client::client_builder(HttpService::OtherPlatforms)
+        .user_agent(BROWSER_USER_AGENT)
+        .build()
+        .map_err(|e| PlatformError::NetworkError(e.to_string()))?;
+
+    let mut request = client.get(&video.video_url);
+    for (name, value) in &video.http_headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| PlatformError::NetworkError(format!("请求失败: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(PlatformError::NetworkError(format!(
+            "HTTP 错误: {}",
+            response.status()
+        )));
+    }
+
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| PlatformError::IoError(format!("无法创建目录: {}", e)))?;
+    }
+    let mut file = std::fs::File::create(output_path)
+        .map_err(|e| PlatformError::IoError(format!("无法创建文件: {}", e)))?;
+
+    let mut stream = response.bytes_stream();
+    let mut written = 0u64;
+    while let Some(chunk) = stream.next().await {
+        let chunk =
+            chunk.map_err(|e| PlatformError::NetworkError(format!("下载数据失败: {}", e)))?;
+        file.write_all(&chunk)
+            .map_err(|e| PlatformError::IoError(format!("写入文件失败: {}", e)))?;
+        written += chunk.len() as u64;
+    }
+
+    file.flush()
+        .map_err(|e| PlatformError::IoError(format!("刷新文件失败: {}", e)))?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_url() {
+        assert_eq!(
+            extract_url("3.89 复制打开快手看看 https://v.kuaishou.com/abc123 作品").as_deref(),
+            Some("https://v.kuaishou.com/abc123")
+        );
+        assert_eq!(
+            extract_url("  https://www.bilibili.com/video/BV1xx411c7mD  ").as_deref(),
+            Some("https://www.bilibili.com/video/BV1xx411c7mD")
+        );
+        assert_eq!(extract_url("没有链接"), None);
+    }
+
+    #[test]
+    fn test_registry_detect() {
+        let registry = PlatformRegistry::with_defaults();
+        assert_eq!(
+            registry.detect("https://v.douyin.com/iRNBho6u/"),
+            Some(Platform::Douyin)
+        );
+        assert_eq!(
+            registry.detect("看看这个 https://www.kuaishou.com/short-video/3xabc"),
+            Some(Platform::Kuaishou)
+        );
+        assert_eq!(
+            registry.detect("https://b23.tv/AbCdEf"),
+            Some(Platform::Bilibili)
+        );
+        assert_eq!(registry.detect("https://example.com/video/1"), None);
+        assert_eq!(
+            registry.platforms(),
+            vec![Platform::Douyin, Platform::Kuaishou, Platform::Bilibili]
+        );
+    }
+
+    #[test]
+    fn test_extract_json_and_count() {
+        let html =
+            r#"<script>window.__STATE__ = {"a":{"b":"x}y"},"c":[1,2]};(function(){})</script>"#;
+        let json = extract_json_object(html, "window.__STATE__").unwrap();
+        assert_eq!(json["a"]["b"], "x}y");
+        assert_eq!(json["c"][1], 2);
+
+        assert_eq!(parse_count(&serde_json::json!(42)), 42);
+        assert_eq!(parse_count(&serde_json::json!("1.2万")), 12_000);
+        assert_eq!(parse_count(&serde_json::json!("3w")), 30_000);
+        assert_eq!(parse_count(&serde_json::json!("1,024")), 1_024);
+        assert_eq!(parse_count(&serde_json::json!(null)), 0);
+    }
+}
@@ -0,0 +1,626 @@
+// 抖音登录会话管理
+// 导入浏览器导出的 Cookie（Netscape cookies.txt 或 JSON），加密保存在应用数据目录，
+// 供解析器和 Sidecar 下载请求携带，并检测登录态是否过期
+//
+// 加密密钥保存在系统凭据管理器（Windows 凭据管理器 / macOS 钥匙串 / Secret Service）中，
+// 只拷走应用数据目录（备份、同步盘、误分享）无法解密 Cookie。凭据管理器不可用时回退到
+// 仅当前用户可读的密钥文件，此时加密只能避免 Cookie 以明文出现在会话文件中。
+// 两种方式都无法防御以当前用户身份运行的程序读取会话。
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use once_cell::sync::{Lazy, OnceCell};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::utils::paths::get_app_paths;
+
+/// 加密后的会话文件名
+const SESSION_FILE: &str = "douyin_session.enc";
+/// 会话密钥文件名（系统凭据管理器不可用时使用，旧版本也保存在这里）
+const KEY_FILE: &str = "douyin_session.key";
+/// 会话密钥在系统凭据管理器中的服务名
+const KEYRING_SERVICE: &str = "douyin-creator-toolkit";
+/// 会话密钥在系统凭据管理器中的账户名
+const KEYRING_ACCOUNT: &str = "douyin-session-key";
+/// AES-256 密钥长度
+const KEY_LEN: usize = 32;
+/// AES-GCM nonce 长度
+const NONCE_LEN: usize = 12;
+/// 距离过期不足该时长（秒）时提示刷新
+const EXPIRING_SOON_SECS: i64 = 3 * 24 * 3600;
+/// 代表登录态的 Cookie 名称
+const LOGIN_COOKIE_NAMES: [&str; 3] = ["sessionid", "sessionid_ss", "sid_guard"];
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("Cookie 解析失败: {0}")]
+    ParseFailed(String),
+    #[error("未找到抖音 Cookie")]
+    NoDouyinCookies,
+    #[error("会话读写失败: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("会话加解密失败")]
+    CryptoFailed,
+    #[error("序列化错误: {0}")]
+    SerializationError(#[from] serde_json::Error),
+}
+
+/// 单条 Cookie
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    #[serde(default)]
+    pub path: String,
+    /// 过期时间（Unix 秒），会话 Cookie 为 None
+    #[serde(default)]
+    pub expires: Option<i64>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+}
+
+impl Cookie {
+    /// 是否适用于指定域名
+    pub fn matches_domain(&self, host: &str) -> bool {
+        let domain = self.domain.trim_start_matches('.').to_lowercase();
+        let host = host.to_lowercase();
+        host == domain || host.ends_with(&format!(".{}", domain))
+    }
+
+    fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expires, Some(ts) if ts > 0 && ts <= now)
+    }
+}
+
+/// 会话状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionStatus {
+    /// 已导入的 Cookie 数量
+    pub cookie_count: usize,
+    /// 是否包含登录 Cookie 且未过期
+    pub logged_in: bool,
+    /// 登录 Cookie 的过期时间（Unix 秒）
+    pub expires_at: Option<i64>,
+    /// 已过期（Cookie 到期或请求被要求重新登录）
+    pub expired: bool,
+    /// 即将过期，建议刷新
+    pub expiring_soon: bool,
+}
+
+/// 解析 Cookie 导出文本，自动识别 JSON 与 Netscape 格式
+pub fn parse_cookies(content: &str) -> Result<Vec<Cookie>, SessionError> {
+    let trimmed = content.trim_start_matches('\u{feff}').trim();
+    if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        parse_json_cookies(trimmed)
+    } else {
+        parse_netscape_cookies(trimmed)
+    }
+}
+
+/// 解析 Netscape cookies.txt
+///
+/// 每行 7 列，以制表符分隔：domain, include_subdomains, path, secure, expires, name, value
+pub fn parse_netscape_cookies(content: &str) -> Result<Vec<Cookie>, SessionError> {
+    let mut cookies = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        // `#HttpOnly_` 前缀表示 HttpOnly Cookie，其余 `#` 开头为注释
+        let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 7 {
+            return Err(SessionError::ParseFailed(format!(
+                "第 {} 行格式错误，应为 7 列",
+                index + 1
+            )));
+        }
+
+        let expires = fields[4].trim().parse::<i64>().ok().filter(|ts| *ts > 0);
+        cookies.push(Cookie {
+            domain: fields[0].trim().to_string(),
+            path: fields[2].trim().to_string(),
+            secure: fields[3].trim().eq_ignore_ascii_case("TRUE"),
+            expires,
+            name: fields[5].trim().to_string(),
+            value: fields[6].trim().to_string(),
+            http_only,
+        });
+    }
+    Ok(cookies)
+}
+
+/// 解析浏览器扩展导出的 JSON（数组，或带 `cookies` 字段的对象）
+pub fn parse_json_cookies(content: &str) -> Result<Vec<Cookie>, SessionError> {
+    let json: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| SessionError::ParseFailed(e.to_string()))?;
+    let items = match json.as_array().or_else(|| json["cookies"].as_array()) {
+        Some(items) => items,
+        None => {
+            return Err(SessionError::ParseFailed(
+                "JSON 中未找到 Cookie 列表".to_string(),
+            ))
+        }
+    };
+
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let name = item["name"].as_str()?;
+            let expires = item["expirationDate"]
+                .as_f64()
+                .or_else(|| item["expires"].as_f64())
+                .map(|ts| ts as i64)
+                .filter(|ts| *ts > 0);
+            Some(Cookie {
+                name: name.to_string(),
+                value: item["value"].as_str().unwrap_or("").to_string(),
+                domain: item["domain"].as_str().unwrap_or("").to_string(),
+                path: item["path"].as_str().unwrap_or("/").to_string(),
+                expires,
+                secure: item["secure"].as_bool().unwrap_or(false),
+                http_only: item["httpOnly"]
+                    .as_bool()
+                    .or_else(|| item["http_only"].as_bool())
+                    .unwrap_or(false),
+            })
+        })
+        .collect())
+}
+
+/// 会话密钥的保存位置
+#[derive(Debug, Clone)]
+pub enum KeyStore {
+    /// 系统凭据管理器，不可用时回退到密钥文件
+    Credential { service: String, account: String },
+    /// 仅使用密钥文件（Unix 下权限为 0600）
+    File,
+}
+
+impl Default for KeyStore {
+    fn default() -> Self {
+        KeyStore::Credential {
+            service: KEYRING_SERVICE.to_string(),
+            account: KEYRING_ACCOUNT.to_string(),
+        }
+    }
+}
+
+/// 抖音登录会话管理器
+pub struct SessionManager {
+    session_path: PathBuf,
+    key_path: PathBuf,
+    key_store: KeyStore,
+    /// 已加载的会话密钥
+    key: OnceCell<Vec<u8>>,
+    cookies: RwLock<Vec<Cookie>>,
+    /// 请求被服务端要求重新登录时置位，重新导入后清除
+    invalidated: AtomicBool,
+}
+
+impl SessionManager {
+    /// 从目录加载会话，文件不存在或无法解密时以空会话启动
+    pub fn load(dir: &Path) -> Self {
+        Self::load_with_key_store(dir, KeyStore::default())
+    }
+
+    /// 从目录加载会话，并指定会话密钥的保存位置
+    pub fn load_with_key_store(dir: &Path, key_store: KeyStore) -> Self {
+        let manager = Self {
+            session_path: dir.join(SESSION_FILE),
+            key_path: dir.join(KEY_FILE),
+            key_store,
+            key: OnceCell::new(),
+            cookies: RwLock::new(Vec::new()),
+            invalidated: AtomicBool::new(false),
+        };
+
+        match manager.read_cookies() {
+            Ok(cookies) => *manager.cookies.write() = cookies,
+            Err(e) => tracing::warn!("读取抖音会话失败，将使用空会话: {}", e),
+        }
+        manager
+    }
+
+    /// 导入 Cookie 文本，仅保留抖音相关域名，返回导入数量
+    pub fn import(&self, content: &str) -> Result<usize, SessionError> {
+        let cookies: Vec<Cookie> = parse_cookies(content)?
+            .into_iter()
+            .filter(|c| is_douyin_domain(&c.domain) && !c.name.is_empty())
+            .collect();
+        if cookies.is_empty() {
+            return Err(SessionError::NoDouyinCookies);
+        }
+
+        self.write_cookies(&cookies)?;
+        let count = cookies.len();
+        *self.cookies.write() = cookies;
+        self.invalidated.store(false, Ordering::SeqCst);
+        Ok(count)
+    }
+
+    /// 清除会话
+    pub fn clear(&self) -> Result<(), SessionError> {
+        if self.session_path.exists() {
+            std::fs::remove_file(&self.session_path)?;
+        }
+        self.cookies.write().clear();
+        self.invalidated.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// 生成指定域名的 Cookie 请求头，无可用 Cookie 时返回 None
+    pub fn cookie_header(&self, host: &str) -> Option<String> {
+        let now = chrono::Utc::now().timestamp();
+        let header = self
+            .cookies
+            .read()
+            .iter()
+            .filter(|c| c.matches_domain(host) && !c.is_expired(now))
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        (!header.is_empty()).then_some(header)
+    }
+
+    /// 标记登录态失效（服务端要求验证时调用）
+    pub fn mark_invalid(&self) {
+        if !self.cookies.read().is_empty() {
+            self.invalidated.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// 当前会话状态
+    pub fn status(&self) -> SessionStatus {
+        session_status(
+            &self.cookies.read(),
+            chrono::Utc::now().timestamp(),
+            self.invalidated.load(Ordering::SeqCst),
+        )
+    }
+
+    fn read_cookies(&self) -> Result<Vec<Cookie>, SessionError> {
+        if !self.session_path.exists() {
+            return Ok(Vec::new());
+        }
+        let key = self.load_or_create_key()?;
+        let encrypted = std::fs::read(&self.session_path)?;
+        let plain = decrypt(&key, &encrypted)?;
+        Ok(serde_json::from_slice(&plain)?)
+    }
+
+    fn write_cookies(&self, cookies: &[Cookie]) -> Result<(), SessionError> {
+        if let Some(parent) = self.session_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let key = self.load_or_create_key()?;
+        let plain = serde_json::to_vec(cookies)?;
+        std::fs::write(&self.session_path, encrypt(&key, &plain)?)?;
+        Ok(())
+    }
+
+    /// 读取本机会话密钥，不存在时生成
+    fn load_or_create_key(&self) -> Result<Vec<u8>, SessionError> {
+        self.key
+            .get_or_try_init(|| match &self.key_store {
+                KeyStore::Credential { service, account } => {
+                    match self.credential_key(service, account) {
+                        Some(key) => Ok(key),
+                        None => self.file_key(),
+                    }
+                }
+                KeyStore::File => self.file_key(),
+            })
+            .cloned()
+    }
+
+    /// 从系统凭据管理器读取密钥，没有时迁移旧密钥文件或生成新密钥
+    ///
+    /// 凭据管理器不可用时返回 None
+    fn credential_key(&self, service: &str, account: &str) -> Option<Vec<u8>> {
+        let entry = match keyring::Entry::new(service, account) {
+            Ok(entry) => entry,
+            Err(e) => {
+                tracing::warn!("系统凭据管理器不可用，使用本地密钥文件: {}", e);
+                return None;
+            }
+        };
+
+        match entry.get_password() {
+            Ok(encoded) => match BASE64.decode(encoded.trim()) {
+                Ok(key) if key.len() == KEY_LEN => return Some(key),
+                _ => tracing::warn!("系统凭据管理器中的会话密钥无效，将重新生成"),
+            },
+            Err(keyring::Error::NoEntry) => {}
+            Err(e) => {
+                tracing::warn!("读取系统凭据管理器失败，使用本地密钥文件: {}", e);
+                return None;
+            }
+        }
+
+        let key = self.read_key_file().unwrap_or_else(generate_key);
+        match entry.set_password(&BASE64.encode(&key)) {
+            Ok(()) => {
+                // 密钥已转存到凭据管理器，删除本地密钥文件
+                if self.key_path.exists() {
+                    if let Err(e) = std::fs::remove_file(&self.key_path) {
+                        tracing::warn!("删除旧会话密钥文件失败: {}", e);
+                    }
+                }
+                Some(key)
+            }
+            Err(e) => {
+                tracing::warn!("写入系统凭据管理器失败，使用本地密钥文件: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 读取或生成本地密钥文件
+    fn file_key(&self) -> Result<Vec<u8>, SessionError> {
+        if let Some(key) = self.read_key_file() {
+            return Ok(key);
+        }
+
+        let key = generate_key();
+        self.write_key_file(&key)?;
+        Ok(key)
+    }
+
+    /// 读取本地密钥文件，并收紧旧版本创建的文件权限
+    fn read_key_file(&self) -> Option<Vec<u8>> {
+        let key = std::fs::read(&self.key_path).ok()?;
+        if key.len() != KEY_LEN {
+            return None;
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ =
+                std::fs::set_permissions(&self.key_path, std::fs::Permissions::from_mode(0o600));
+        }
+        Some(key)
+    }
+
+    /// 写入本地密钥文件（Unix 下仅当前用户可读写）
+    fn write_key_file(&self, key: &[u8]) -> Result<(), SessionError> {
+        if let Some(parent) = self.key_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options.open(&self.key_path)?.write_all(key)?;
+        Ok(())
+    }
+}
+
+/// 生成新的 AES-256 密钥
+fn generate_key() -> Vec<u8> {
+    Aes256Gcm::generate_key(OsRng).to_vec()
+}
+
+/// 计算会话状态
+fn session_status(cookies: &[Cookie], now: i64, invalidated: bool) -> SessionStatus {
+    let login_cookies: Vec<&Cookie> = cookies
+        .iter()
+        .filter(|c| LOGIN_COOKIE_NAMES.contains(&c.name.as_str()) && !c.value.is_empty())
+        .collect();
+    let expires_at = login_cookies.iter().filter_map(|c| c.expires).max();
+    let cookie_expired =
+        !login_cookies.is_empty() && login_cookies.iter().all(|c| c.is_expired(now));
+    let expired = invalidated || cookie_expired;
+
+    SessionStatus {
+        cookie_count: cookies.len(),
+        logged_in: !login_cookies.is_empty() && !expired,
+        expires_at,
+        expired,
+        expiring_soon: !expired && matches!(expires_at, Some(ts) if ts - now < EXPIRING_SOON_SECS),
+    }
+}
+
+fn is_douyin_domain(domain: &str) -> bool {
+    let domain = domain.trim_start_matches('.').to_lowercase();
+    domain == "douyin.com"
+        || domain.ends_with(".douyin.com")
+        || domain == "iesdouyin.com"
+        || domain.ends_with(".iesdouyin.com")
+}
+
+fn encrypt(key: &[u8], plain: &[u8]) -> Result<Vec<u8>, SessionError> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let mut output = nonce.to_vec();
+    output.extend(
+        cipher
+            .encrypt(&nonce, plain)
+            .map_err(|_| SessionError::CryptoFailed)?,
+    );
+    Ok(output)
+}
+
+fn decrypt(key: &[u8], data: &[u8]) -> Result<Vec<u8>, SessionError> {
+    if data.len() <= NONCE_LEN {
+        return Err(SessionError::CryptoFailed);
+    }
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| SessionError::CryptoFailed)
+}
+
+/// 全局会话管理器（保存在应用配置目录）
+static SESSION_MANAGER: Lazy<Option<Arc<SessionManager>>> = Lazy::new(|| {
+    get_app_paths()
+        .ok()
+        .map(|paths| Arc::new(SessionManager::load(&paths.config_dir)))
+});
+
+/// 获取全局会话管理器
+pub fn session_manager() -> Option<Arc<SessionManager>> {
+    SESSION_MANAGER.clone()
+}
+
+/// 获取抖音请求使用的 Cookie 请求头
+pub fn douyin_cookie_header() -> Option<String> {
+    session_manager().and_then(|m| m.cookie_header("www.douyin.com"))
+}
+
+/// 获取访问指定地址时使用的 Cookie 请求头
+///
+/// 只返回与地址主机匹配的 Cookie，视频 / 图片 CDN 等其他主机返回 None
+pub fn cookie_header_for_url(url: &str) -> Option<String> {
+    let host = host_of(url)?;
+    session_manager().and_then(|m| m.cookie_header(&host))
+}
+
+/// 解析地址的主机名（小写），无法解析时返回 None
+pub fn host_of(url: &str) -> Option<String> {
+    reqwest::Url::parse(url.trim())
+        .ok()?
+        .host_str()
+        .map(|host| host.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const NETSCAPE: &str = "# Netscape HTTP Cookie File\n\
+        .douyin.com\tTRUE\t/\tTRUE\t1900000000\tsessionid\tabc123\n\
+        #HttpOnly_.douyin.com\tTRUE\t/\tFALSE\t0\tttwid\txyz\n\
+        .example.com\tTRUE\t/\tFALSE\t1900000000\tother\t1\n";
+
+    #[test]
+    fn test_parse_netscape_and_json() {
+        let cookies = parse_cookies(NETSCAPE).unwrap();
+        assert_eq!(cookies.len(), 3);
+        assert_eq!(cookies[0].name, "sessionid");
+        assert_eq!(cookies[0].expires, Some(1_900_000_000));
+        assert!(cookies[0].secure);
+        assert!(cookies[1].http_only);
+        assert_eq!(cookies[1].expires, None);
+
+        let json = r#"[{"name":"sessionid","value":"v","domain":".douyin.com","expirationDate":1900000000.5,"httpOnly":true}]"#;
+        let cookies = parse_cookies(json).unwrap();
+        assert_eq!(cookies[0].expires, Some(1_900_000_000));
+        assert!(cookies[0].http_only);
+
+        assert!(parse_cookies("bad line").is_err());
+    }
+
+    #[test]
+    fn test_import_encrypts_and_reloads() {
+        let dir = TempDir::new().unwrap();
+        let manager = SessionManager::load_with_key_store(dir.path(), KeyStore::File);
+        assert_eq!(manager.import(NETSCAPE).unwrap(), 2);
+
+        let raw = std::fs::read(dir.path().join(SESSION_FILE)).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("abc123"));
+
+        let reloaded = SessionManager::load_with_key_store(dir.path(), KeyStore::File);
+        assert_eq!(
+            reloaded.cookie_header("www.douyin.com").as_deref(),
+            Some("sessionid=abc123; ttwid=xyz")
+        );
+        assert_eq!(reloaded.cookie_header("www.example.com"), None);
+
+        reloaded.mark_invalid();
+        assert!(reloaded.status().expired);
+        reloaded.clear().unwrap();
+        assert_eq!(reloaded.status().cookie_count, 0);
+        assert!(!reloaded.status().expired);
+    }
+
+    #[test]
+    fn test_cookie_scoped_to_url_host() {
+        let dir = TempDir::new().unwrap();
+        let manager = SessionManager::load_with_key_store(dir.path(), KeyStore::File);
+        manager.import(NETSCAPE).unwrap();
+
+        let header_for = |url: &str| host_of(url).and_then(|host| manager.cookie_header(&host));
+        assert!(header_for("https://www.douyin.com/video/7301234567890123456").is_some());
+        assert!(header_for("https://V.DOUYIN.COM/abc/").is_some());
+        // 视频 / 图片 CDN 与其他主机不携带登录 Cookie
+        assert!(header_for("https://v26-web.douyinvod.com/a.mp4?sig=1").is_none());
+        assert!(header_for("https://p3-pc-sign.douyinpic.com/img.webp").is_none());
+        assert!(header_for("https://evil.example.com/douyin.com").is_none());
+        assert_eq!(host_of("not a url"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_key_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let key_path = dir.path().join(KEY_FILE);
+        // 旧版本以默认权限创建的密钥文件
+        std::fs::write(&key_path, generate_key()).unwrap();
+        std::fs::set_permissions(&key_path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let manager = SessionManager::load_with_key_store(dir.path(), KeyStore::File);
+        manager.import(NETSCAPE).unwrap();
+        let mode = std::fs::metadata(&key_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let fresh = TempDir::new().unwrap();
+        let manager = SessionManager::load_with_key_store(fresh.path(), KeyStore::File);
+        manager.import(NETSCAPE).unwrap();
+        let mode = std::fs::metadata(fresh.path().join(KEY_FILE))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_session_status_expiry() {
+        let cookie = |expires| Cookie {
+            name: "sessionid".to_string(),
+            value: "v".to_string(),
+            domain: ".douyin.com".to_string(),
+            path: "/".to_string(),
+            expires,
+            secure: true,
+            http_only: true,
+        };
+        let now = 1_800_000_000;
+
+        let valid = session_status(&[cookie(Some(now + 30 * 24 * 3600))], now, false);
+        assert!(valid.logged_in && !valid.expired && !valid.expiring_soon);
+
+        let soon = session_status(&[cookie(Some(now + 3600))], now, false);
+        assert!(soon.logged_in && soon.expiring_soon);
+
+        let expired = session_status(&[cookie(Some(now - 1))], now, false);
+        assert!(!expired.logged_in && expired.expired);
+
+        let none = session_status(&[], now, false);
+        assert!(!none.logged_in && !none.expired);
+    }
+}
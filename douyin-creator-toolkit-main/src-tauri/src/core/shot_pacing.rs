@@ -0,0 +1,238 @@
+// 镜头节奏分析模块
+// 基于场景切换检测结果计算镜头数、平均/中位镜头时长、每分钟剪辑次数及首次剪辑时间，
+// 并支持按创作者汇总，用于竞品剪辑节奏对比
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 默认场景切换阈值
+pub const DEFAULT_SCENE_THRESHOLD: f32 = 0.3;
+/// 剪辑节奏时间轴的分桶长度（秒）
+const TIMELINE_BUCKET_SECS: f64 = 10.0;
+/// 过短的镜头视为误检（秒）
+const MIN_SHOT_SECS: f64 = 0.2;
+
+/// 单个视频的剪辑节奏指标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShotPacing {
+    /// 视频时长（秒）
+    pub duration_secs: f64,
+    /// 镜头数
+    pub shot_count: usize,
+    /// 平均镜头时长（秒）
+    pub average_shot_secs: f64,
+    /// 中位镜头时长（秒）
+    pub median_shot_secs: f64,
+    /// 每分钟剪辑次数
+    pub cuts_per_minute: f64,
+    /// 首次剪辑时间（秒），无剪辑时为 None
+    pub first_cut_secs: Option<f64>,
+    /// 剪辑节奏时间轴：每 10 秒一个分桶，值为折算后的每分钟剪辑次数
+    pub cuts_per_minute_timeline: Vec<f64>,
+    /// 剪辑点（秒）
+    pub cut_times: Vec<f64>,
+}
+
+/// 创作者作品集的剪辑节奏汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatorPacingSummary {
+    pub author: String,
+    pub video_count: usize,
+    pub average_shot_count: f64,
+    pub average_shot_secs: f64,
+    pub median_shot_secs: f64,
+    pub cuts_per_minute: f64,
+    /// 首次剪辑时间平均值（仅统计有剪辑的视频）
+    pub average_first_cut_secs: Option<f64>,
+}
+
+/// 根据剪辑点计算节奏指标
+///
+/// # Arguments
+/// * `cut_times` - 场景切换时间点（秒）
+/// * `duration_secs` - 视频时长（秒）
+pub fn analyze(cut_times: &[f64], duration_secs: f64) -> ShotPacing {
+    let duration_secs = duration_secs.max(0.0);
+
+    // 过滤越界点，并合并距离过近的误检
+    let mut cuts: Vec<f64> = Vec::new();
+    let mut sorted: Vec<f64> = cut_times
+        .iter()
+        .copied()
+        .filter(|t| *t > 0.0 && *t < duration_secs)
+        .collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    for t in sorted {
+        let previous = cuts.last().copied().unwrap_or(0.0);
+        if t - previous >= MIN_SHOT_SECS {
+            cuts.push(t);
+        }
+    }
+
+    let mut boundaries = Vec::with_capacity(cuts.len() + 2);
+    boundaries.push(0.0);
+    boundaries.extend(cuts.iter().copied());
+    boundaries.push(duration_secs);
+    let shots: Vec<f64> = boundaries
+        .windows(2)
+        .map(|w| w[1] - w[0])
+        .filter(|len| *len > 0.0)
+        .collect();
+
+    let minutes = duration_secs / 60.0;
+    let cuts_per_minute = if minutes > 0.0 {
+        cuts.len() as f64 / minutes
+    } else {
+        0.0
+    };
+
+    ShotPacing {
+        duration_secs,
+        shot_count: shots.len(),
+        average_shot_secs: mean(&shots).unwrap_or(0.0),
+        median_shot_secs: median(&shots).unwrap_or(0.0),
+        cuts_per_minute,
+        first_cut_secs: cuts.first().copied(),
+        cuts_per_minute_timeline: timeline(&cuts, duration_secs),
+        cut_times: cuts,
+    }
+}
+
+/// 按时间分桶统计剪辑密度，折算为每分钟剪辑次数
+fn timeline(cuts: &[f64], duration_secs: f64) -> Vec<f64> {
+    if duration_secs <= 0.0 {
+        return Vec::new();
+    }
+
+    let bucket_count = (duration_secs / TIMELINE_BUCKET_SECS).ceil() as usize;
+    let mut counts = vec![0usize; bucket_count];
+    for t in cuts {
+        let index = ((t / TIMELINE_BUCKET_SECS) as usize).min(bucket_count - 1);
+        counts[index] += 1;
+    }
+
+    counts
+        .iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let start = i as f64 * TIMELINE_BUCKET_SECS;
+            let span = (duration_secs - start).min(TIMELINE_BUCKET_SECS);
+            *count as f64 * 60.0 / span
+        })
+        .collect()
+}
+
+/// 汇总同一创作者的多个视频
+pub fn summarize(author: &str, videos: &[ShotPacing]) -> CreatorPacingSummary {
+    let shot_counts: Vec<f64> = videos.iter().map(|v| v.shot_count as f64).collect();
+    let averages: Vec<f64> = videos.iter().map(|v| v.average_shot_secs).collect();
+    let medians: Vec<f64> = videos.iter().map(|v| v.median_shot_secs).collect();
+    let first_cuts: Vec<f64> = videos.iter().filter_map(|v| v.first_cut_secs).collect();
+
+    // 每分钟剪辑次数按总时长加权，避免短视频权重过高
+    let total_minutes: f64 = videos.iter().map(|v| v.duration_secs / 60.0).sum();
+    let total_cuts: usize = videos.iter().map(|v| v.cut_times.len()).sum();
+
+    CreatorPacingSummary {
+        author: author.to_string(),
+        video_count: videos.len(),
+        average_shot_count: mean(&shot_counts).unwrap_or(0.0),
+        average_shot_secs: mean(&averages).unwrap_or(0.0),
+        median_shot_secs: median(&medians).unwrap_or(0.0),
+        cuts_per_minute: if total_minutes > 0.0 {
+            total_cuts as f64 / total_minutes
+        } else {
+            0.0
+        },
+        average_first_cut_secs: mean(&first_cuts),
+    }
+}
+
+/// 按创作者分组汇总，结果按作者名排序
+pub fn summarize_by_author(records: &[(String, ShotPacing)]) -> Vec<CreatorPacingSummary> {
+    let mut groups: BTreeMap<&str, Vec<ShotPacing>> = BTreeMap::new();
+    for (author, pacing) in records {
+        groups
+            .entry(author.as_str())
+            .or_default()
+            .push(pacing.clone());
+    }
+
+    groups
+        .into_iter()
+        .map(|(author, videos)| summarize(author, &videos))
+        .collect()
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mid = sorted.len() / 2;
+    if sorted.len() & 1 == 0 {
+        Some((sorted[mid - 1] + sorted[mid]) / 2.0)
+    } else {
+        Some(sorted[mid])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_basic_metrics() {
+        let pacing = analyze(&[2.0, 5.0, 6.0], 12.0);
+
+        assert_eq!(pacing.shot_count, 4);
+        assert_eq!(pacing.cut_times, vec![2.0, 5.0, 6.0]);
+        assert_eq!(pacing.first_cut_secs, Some(2.0));
+        assert!((pacing.average_shot_secs - 3.0).abs() < 1e-9);
+        // 镜头时长 2, 3, 1, 6 -> 中位数 2.5
+        assert!((pacing.median_shot_secs - 2.5).abs() < 1e-9);
+        assert!((pacing.cuts_per_minute - 15.0).abs() < 1e-9);
+        assert_eq!(pacing.cuts_per_minute_timeline.len(), 2);
+        assert!((pacing.cuts_per_minute_timeline[0] - 18.0).abs() < 1e-9);
+        assert!(pacing.cuts_per_minute_timeline[1].abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_analyze_filters_noise() {
+        let pacing = analyze(&[0.0, 3.0, 3.1, 20.0], 10.0);
+        assert_eq!(pacing.cut_times, vec![3.0]);
+        assert_eq!(pacing.shot_count, 2);
+
+        let single = analyze(&[], 30.0);
+        assert_eq!(single.shot_count, 1);
+        assert_eq!(single.first_cut_secs, None);
+        assert_eq!(single.cuts_per_minute, 0.0);
+    }
+
+    #[test]
+    fn test_summarize_by_author() {
+        let records = vec![
+            ("b".to_string(), analyze(&[30.0], 60.0)),
+            ("a".to_string(), analyze(&[10.0, 20.0], 30.0)),
+            ("a".to_string(), analyze(&[], 30.0)),
+        ];
+        let summaries = summarize_by_author(&records);
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].author, "a");
+        assert_eq!(summaries[0].video_count, 2);
+        assert!((summaries[0].cuts_per_minute - 2.0).abs() < 1e-9);
+        assert_eq!(summaries[0].average_first_cut_secs, Some(10.0));
+        assert!((summaries[1].cuts_per_minute - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,362 @@
+// 解析渠道健康度模块
+// 记录抖音解析各渠道的成功 / 失败 / 耗时，汇总健康状况，并把当前可用的渠道排到前面优先尝试
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 连续失败达到该次数视为渠道异常
+pub const DEGRADED_THRESHOLD: u32 = 3;
+/// 失败渠道超过该时长（秒）未再尝试时恢复默认顺序，给它重新验证的机会
+const RETRY_AFTER_SECS: i64 = 3600;
+/// 错误信息最多保留的字符数
+const MAX_ERROR_CHARS: usize = 200;
+/// 待持久化队列的最大长度，超出时丢弃最早的记录
+const MAX_PENDING: usize = 1000;
+
+/// 抖音解析渠道（声明顺序即默认尝试顺序）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseStrategy {
+    /// 本地 Sidecar（dy-mcp）
+    Sidecar,
+    /// 新版 detail 接口
+    DetailApi,
+    /// Share 页面 meta 信息
+    SharePage,
+    /// 旧版 iteminfo 接口
+    ItemInfo,
+    /// 作品页内嵌的 RENDER_DATA
+    RenderData,
+}
+
+impl ParseStrategy {
+    /// 全部渠道（默认顺序）
+    pub const ALL: [ParseStrategy; 5] = [
+        ParseStrategy::Sidecar,
+        ParseStrategy::DetailApi,
+        ParseStrategy::SharePage,
+        ParseStrategy::ItemInfo,
+        ParseStrategy::RenderData,
+    ];
+
+    /// 存储用名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParseStrategy::Sidecar => "sidecar",
+            ParseStrategy::DetailApi => "detail_api",
+            ParseStrategy::SharePage => "share_page",
+            ParseStrategy::ItemInfo => "item_info",
+            ParseStrategy::RenderData => "render_data",
+        }
+    }
+
+    /// 中文名称（用于通知）
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ParseStrategy::Sidecar => "本地 Sidecar",
+            ParseStrategy::DetailApi => "detail 接口",
+            ParseStrategy::SharePage => "Share 页面",
+            ParseStrategy::ItemInfo => "iteminfo 接口",
+            ParseStrategy::RenderData => "作品页数据",
+        }
+    }
+
+    /// 从存储用名称解析
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == name)
+    }
+
+    /// 默认顺序中的位置
+    fn default_rank(&self) -> usize {
+        Self::ALL.iter().position(|s| s == self).unwrap_or(0)
+    }
+}
+
+/// 一次渠道尝试
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StrategyAttempt {
+    pub strategy: ParseStrategy,
+    pub success: bool,
+    /// 耗时（毫秒）
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    /// 尝试时间（Unix 秒）
+    pub attempted_at: i64,
+}
+
+impl StrategyAttempt {
+    pub fn new(strategy: ParseStrategy, latency_ms: u64, error: Option<String>) -> Self {
+        Self {
+            strategy,
+            success: error.is_none(),
+            latency_ms,
+            error: error.map(|e| e.chars().take(MAX_ERROR_CHARS).collect()),
+            attempted_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// 渠道健康状况
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StrategyHealth {
+    pub strategy: ParseStrategy,
+    pub successes: u64,
+    pub failures: u64,
+    /// 成功率（无尝试时为 None）
+    pub success_rate: Option<f64>,
+    /// 平均耗时（毫秒）
+    pub avg_latency_ms: Option<u64>,
+    /// 截至最近一次尝试的连续失败次数
+    pub consecutive_failures: u32,
+    pub last_success_at: Option<i64>,
+    pub last_failure_at: Option<i64>,
+    pub last_error: Option<String>,
+    /// 连续失败达到阈值
+    pub degraded: bool,
+}
+
+/// 汇总渠道尝试记录（`attempts` 可以无序），按默认渠道顺序返回
+pub fn summarize(attempts: &[StrategyAttempt]) -> Vec<StrategyHealth> {
+    let mut sorted: Vec<&StrategyAttempt> = attempts.iter().collect();
+    sorted.sort_by_key(|a| a.attempted_at);
+
+    ParseStrategy::ALL
+        .iter()
+        .map(|strategy| {
+            let mut health = StrategyHealth {
+                strategy: *strategy,
+                successes: 0,
+                failures: 0,
+                success_rate: None,
+                avg_latency_ms: None,
+                consecutive_failures: 0,
+                last_success_at: None,
+                last_failure_at: None,
+                last_error: None,
+                degraded: false,
+            };
+            let mut total_latency = 0u64;
+
+            for attempt in sorted.iter().filter(|a| a.strategy == *strategy) {
+                total_latency += attempt.latency_ms;
+                if attempt.success {
+                    health.successes += 1;
+                    health.consecutive_failures = 0;
+                    health.last_success_at = Some(attempt.attempted_at);
+                } else {
+                    health.failures += 1;
+                    health.consecutive_failures += 1;
+                    health.last_failure_at = Some(attempt.attempted_at);
+                    health.last_error = attempt.error.clone();
+                }
+            }
+
+            let total = health.successes + health.failures;
+            if total > 0 {
+                health.success_rate = Some(health.successes as f64 / total as f64);
+                health.avg_latency_ms = Some(total_latency / total);
+            }
+            health.degraded = health.consecutive_failures >= DEGRADED_THRESHOLD;
+            health
+        })
+        .collect()
+}
+
+/// 各渠道的实时状态
+#[derive(Debug, Clone, Copy, Default)]
+struct LiveState {
+    consecutive_failures: u32,
+    last_failure_at: i64,
+}
+
+#[derive(Default)]
+struct TrackerInner {
+    live: HashMap<ParseStrategy, LiveState>,
+    /// 尚未持久化的尝试记录
+    pending: Vec<StrategyAttempt>,
+}
+
+/// 渠道健康度追踪器
+#[derive(Default)]
+pub struct StrategyHealthTracker {
+    inner: RwLock<TrackerInner>,
+}
+
+impl StrategyHealthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次尝试
+    pub fn record(&self, attempt: StrategyAttempt) {
+        let mut inner = self.inner.write();
+        apply(&mut inner.live, &attempt);
+        if inner.pending.len() >= MAX_PENDING {
+            inner.pending.remove(0);
+        }
+        inner.pending.push(attempt);
+    }
+
+    /// 用已持久化的历史记录恢复实时状态（不会再次进入待持久化队列）
+    pub fn restore(&self, attempts: &[StrategyAttempt]) {
+        let mut sorted: Vec<&StrategyAttempt> = attempts.iter().collect();
+        sorted.sort_by_key(|a| a.attempted_at);
+
+        let mut inner = self.inner.write();
+        for attempt in sorted {
+            apply(&mut inner.live, attempt);
+        }
+    }
+
+    /// 取出尚未持久化的尝试记录
+    pub fn take_pending(&self) -> Vec<StrategyAttempt> {
+        std::mem::take(&mut self.inner.write().pending)
+    }
+
+    /// 当前的渠道尝试顺序
+    pub fn ordered_strategies(&self) -> Vec<ParseStrategy> {
+        self.ordered_strategies_at(chrono::Utc::now().timestamp())
+    }
+
+    /// 指定时间的渠道尝试顺序：最近连续失败的渠道往后排，失败越多越靠后；
+    /// 失败后超过一段时间未再尝试的渠道回到默认位置
+    pub fn ordered_strategies_at(&self, now: i64) -> Vec<ParseStrategy> {
+        let inner = self.inner.read();
+        let mut strategies = ParseStrategy::ALL.to_vec();
+        strategies.sort_by_key(|s| {
+            let penalty = match inner.live.get(s) {
+                Some(state) if now - state.last_failure_at < RETRY_AFTER_SECS => {
+                    state.consecutive_failures
+                }
+                _ => 0,
+            };
+            (penalty, s.default_rank())
+        });
+        strategies
+    }
+}
+
+fn apply(live: &mut HashMap<ParseStrategy, LiveState>, attempt: &StrategyAttempt) {
+    let state = live.entry(attempt.strategy).or_default();
+    if attempt.success {
+        state.consecutive_failures = 0;
+    } else {
+        state.consecutive_failures += 1;
+        state.last_failure_at = attempt.attempted_at;
+    }
+}
+
+/// 全局追踪器（解析器默认使用）
+static GLOBAL_TRACKER: Lazy<Arc<StrategyHealthTracker>> =
+    Lazy::new(|| Arc::new(StrategyHealthTracker::new()));
+
+/// 获取全局追踪器
+pub fn global_tracker() -> Arc<StrategyHealthTracker> {
+    GLOBAL_TRACKER.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(strategy: ParseStrategy, success: bool, at: i64) -> StrategyAttempt {
+        StrategyAttempt {
+            strategy,
+            success,
+            latency_ms: 100,
+            error: (!success).then(|| "JSON 解析失败".to_string()),
+            attempted_at: at,
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let attempts = vec![
+            attempt(ParseStrategy::DetailApi, false, 30),
+            attempt(ParseStrategy::DetailApi, true, 10),
+            attempt(ParseStrategy::DetailApi, false, 20),
+            attempt(ParseStrategy::DetailApi, false, 40),
+            attempt(ParseStrategy::ItemInfo, true, 50),
+        ];
+        let summary = summarize(&attempts);
+
+        assert_eq!(summary.len(), ParseStrategy::ALL.len());
+        let detail = &summary[1];
+        assert_eq!(detail.strategy, ParseStrategy::DetailApi);
+        assert_eq!(detail.successes, 1);
+        assert_eq!(detail.failures, 3);
+        assert_eq!(detail.success_rate, Some(0.25));
+        assert_eq!(detail.avg_latency_ms, Some(100));
+        assert_eq!(detail.consecutive_failures, 3);
+        assert_eq!(detail.last_failure_at, Some(40));
+        assert!(detail.degraded);
+
+        assert!(summary[0].success_rate.is_none());
+        assert!(!summary[3].degraded);
+    }
+
+    #[test]
+    fn test_adaptive_order() {
+        let tracker = StrategyHealthTracker::new();
+        assert_eq!(
+            tracker.ordered_strategies_at(0),
+            ParseStrategy::ALL.to_vec()
+        );
+
+        tracker.record(attempt(ParseStrategy::Sidecar, false, 100));
+        tracker.record(attempt(ParseStrategy::Sidecar, false, 110));
+        tracker.record(attempt(ParseStrategy::DetailApi, false, 120));
+        tracker.record(attempt(ParseStrategy::SharePage, true, 130));
+
+        let order = tracker.ordered_strategies_at(200);
+        assert_eq!(
+            order,
+            vec![
+                ParseStrategy::SharePage,
+                ParseStrategy::ItemInfo,
+                ParseStrategy::RenderData,
+                ParseStrategy::DetailApi,
+                ParseStrategy::Sidecar,
+            ]
+        );
+
+        // 恢复成功后回到默认位置
+        tracker.record(attempt(ParseStrategy::Sidecar, true, 140));
+        assert_eq!(
+            tracker.ordered_strategies_at(200)[0],
+            ParseStrategy::Sidecar
+        );
+
+        // 失败太久没有再尝试的渠道重新获得机会
+        let later = 120 + RETRY_AFTER_SECS;
+        assert_eq!(
+            tracker.ordered_strategies_at(later),
+            ParseStrategy::ALL.to_vec()
+        );
+
+        assert_eq!(tracker.take_pending().len(), 5);
+        assert!(tracker.take_pending().is_empty());
+    }
+
+    #[test]
+    fn test_restore_and_names() {
+        let tracker = StrategyHealthTracker::new();
+        tracker.restore(&[
+            attempt(ParseStrategy::Sidecar, false, 10),
+            attempt(ParseStrategy::Sidecar, false, 20),
+        ]);
+        assert_eq!(
+            tracker.ordered_strategies_at(30).last(),
+            Some(&ParseStrategy::Sidecar)
+        );
+        assert!(tracker.take_pending().is_empty());
+
+        for strategy in ParseStrategy::ALL {
+            assert_eq!(ParseStrategy::parse(strategy.as_str()), Some(strategy));
+        }
+        assert_eq!(ParseStrategy::parse("unknown"), None);
+    }
+}
@@ -8,7 +8,11 @@ use std::sync::Arc;
 use thiserror::Error;
 use tokio::sync::RwLock;
 
+use crate::core::cover_selector::{self, CoverCandidate};
+use crate::core::duplicate_detector::{self, DuplicateMatch, MediaFingerprint};
+use crate::core::shot_pacing::{self, ShotPacing};
 use crate::utils::ffmpeg::{FfmpegError, FfmpegWrapper};
+use crate::utils::filename_template::SourceMetadata;
 
 /// 支持的视频格式
 pub const SUPPORTED_FORMATS: &[&str] = &["mp4", "mov", "avi", "mkv", "webm"];
@@ -32,6 +36,10 @@ pub enum VideoError {
     MetadataFailed(String),
     #[error("IO 错误: {0}")]
     IoError(String),
+    #[error("指纹计算失败: {0}")]
+    FingerprintFailed(String),
+    #[error("场景检测失败: {0}")]
+    SceneDetectionFailed(String),
 }
 
 impl From<FfmpegError> for VideoError {
@@ -341,6 +349,185 @@ impl VideoProcessor {
             .map_err(|e| VideoError::ThumbnailGenerationFailed(e.to_string()))
     }
 
+    /// 智能选择封面候选帧
+    ///
+    /// # Arguments
+    /// * `video_path` - 输入视频文件路径
+    /// * `count` - 需要的候选数量
+    /// * `width` - 候选缩略图宽度（可选）
+    ///
+    /// # Returns
+    /// * `Vec<CoverCandidate>` - 按得分从高到低排列的封面候选
+    pub async fn generate_cover_candidates(
+        &self,
+        video_path: &Path,
+        count: usize,
+        width: Option<u32>,
+    ) -> Result<Vec<CoverCandidate>, VideoError> {
+        Self::validate_video_file(video_path)?;
+
+        let count = count.max(1);
+        let ffmpeg = self.ffmpeg.read().await;
+        let metadata = ffmpeg
+            .get_video_metadata(video_path)
+            .map_err(|e| VideoError::MetadataFailed(e.to_string()))?;
+
+        let (w, h) = (
+            cover_selector::ANALYSIS_WIDTH,
+            cover_selector::ANALYSIS_HEIGHT,
+        );
+        let mut scored = Vec::new();
+        for time in cover_selector::sample_timestamps(metadata.duration_ms, count) {
+            // 单帧失败（如超出时长）直接跳过
+            let frame = match ffmpeg.extract_gray_frame(video_path, time, w, h) {
+                Ok(frame) => frame,
+                Err(_) => continue,
+            };
+            let next = ffmpeg
+                .extract_gray_frame(
+                    video_path,
+                    time + cover_selector::STABILITY_OFFSET_SECS,
+                    w,
+                    h,
+                )
+                .ok();
+
+            let score =
+                cover_selector::score_frame(&frame, next.as_deref(), w as usize, h as usize);
+            scored.push(CoverCandidate {
+                time_seconds: time,
+                score: score.total(),
+                sharpness: score.sharpness,
+                brightness: score.brightness,
+                contrast: score.contrast,
+                stability: score.stability,
+                thumbnail: None,
+            });
+        }
+
+        if scored.is_empty() {
+            return Err(VideoError::ThumbnailGenerationFailed(
+                "无法截取任何视频帧".to_string(),
+            ));
+        }
+
+        let min_gap = metadata.duration_ms as f32 / 1000.0 / (count * 2) as f32;
+        let mut selected = cover_selector::select_top(scored, count, min_gap);
+
+        for candidate in &mut selected {
+            candidate.thumbnail = ffmpeg
+                .generate_thumbnail_bytes(video_path, candidate.time_seconds, width)
+                .ok();
+        }
+
+        Ok(selected)
+    }
+
+    /// 计算视频的感知指纹（采样帧 pHash + 音频能量包络）
+    ///
+    /// 音频提取失败（如无音轨）时只使用画面指纹
+    pub async fn compute_fingerprint(
+        &self,
+        video_path: &Path,
+    ) -> Result<MediaFingerprint, VideoError> {
+        Self::validate_video_file(video_path)?;
+
+        let (duration_ms, frame_hashes) = {
+            let ffmpeg = self.ffmpeg.read().await;
+            let metadata = ffmpeg
+                .get_video_metadata(video_path)
+                .map_err(|e| VideoError::MetadataFailed(e.to_string()))?;
+
+            let size = duplicate_detector::HASH_FRAME_SIZE;
+            let hashes: Vec<u64> = duplicate_detector::hash_timestamps(metadata.duration_ms)
+                .into_iter()
+                .filter_map(|time| ffmpeg.extract_gray_frame(video_path, time, size, size).ok())
+                .map(|frame| duplicate_detector::perceptual_hash(&frame))
+                .collect();
+
+            (metadata.duration_ms, hashes)
+        };
+
+        if frame_hashes.is_empty() {
+            return Err(VideoError::FingerprintFailed(
+                "无法截取任何视频帧".to_string(),
+            ));
+        }
+
+        let stem = video_path
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .unwrap_or("audio");
+        // 临时文件名带随机后缀，避免同名视频并发计算时互相覆盖
+        let wav_path = self.temp_dir.join(format!(
+            "{}_{}_fingerprint.wav",
+            stem,
+            uuid::Uuid::new_v4().simple()
+        ));
+        let (audio_bits, audio_len) = match self.extract_audio(video_path, Some(&wav_path)).await {
+            Ok(_) => {
+                let result = duplicate_detector::read_wav_samples(&wav_path)
+                    .map(|(samples, rate)| duplicate_detector::audio_fingerprint(&samples, rate))
+                    .unwrap_or_default();
+                let _ = std::fs::remove_file(&wav_path);
+                result
+            }
+            Err(_) => (Vec::new(), 0),
+        };
+
+        Ok(MediaFingerprint {
+            path: video_path.to_string_lossy().to_string(),
+            duration_ms,
+            frame_hashes,
+            audio_bits,
+            audio_len,
+        })
+    }
+
+    /// 在已有指纹库中查找近似重复视频（转写前调用以跳过重复内容）
+    ///
+    /// # Returns
+    /// * 目标视频的指纹，以及相似度不低于阈值的匹配列表
+    pub async fn find_duplicates(
+        &self,
+        video_path: &Path,
+        library: &[MediaFingerprint],
+        threshold: f32,
+    ) -> Result<(MediaFingerprint, Vec<DuplicateMatch>), VideoError> {
+        let fingerprint = self.compute_fingerprint(video_path).await?;
+        let matches = duplicate_detector::find_matches(&fingerprint, library, threshold);
+        Ok((fingerprint, matches))
+    }
+
+    /// 分析镜头节奏（镜头数、镜头时长、剪辑密度）
+    ///
+    /// # Arguments
+    /// * `video_path` - 视频文件路径
+    /// * `threshold` - 场景切换阈值，None 时使用默认值
+    pub async fn analyze_shot_pacing(
+        &self,
+        video_path: &Path,
+        threshold: Option<f32>,
+    ) -> Result<ShotPacing, VideoError> {
+        Self::validate_video_file(video_path)?;
+
+        let ffmpeg = self.ffmpeg.read().await;
+        let metadata = ffmpeg
+            .get_video_metadata(video_path)
+            .map_err(|e| VideoError::MetadataFailed(e.to_string()))?;
+        let cut_times = ffmpeg
+            .detect_scene_changes(
+                video_path,
+                threshold.unwrap_or(shot_pacing::DEFAULT_SCENE_THRESHOLD),
+            )
+            .map_err(|e| VideoError::SceneDetectionFailed(e.to_string()))?;
+
+        Ok(shot_pacing::analyze(
+            &cut_times,
+            metadata.duration_ms as f64 / 1000.0,
+        ))
+    }
+
     /// 批量处理视频
     ///
     /// # Arguments
@@ -425,14 +612,15 @@ impl VideoProcessor {
     where
         F: Fn(f32) + Send + Sync,
     {
+        use crate::utils::http_client::{client_builder, HttpService};
+        use crate::utils::rate_limiter;
         use futures_util::StreamExt;
-        use reqwest::Client;
         use std::io::Write;
 
         // 创建 HTTP 客户端
         // 注意：使用与 Python 解析服务相同的移动端 UA，因为下载链接可能是针对该 UA 生成的
         // 开启 cookie_store 以自动处理重定向过程中的 cookie
-        let client = Client::builder()
+        let client = client_builder(HttpService::Douyin)
             .user_agent("Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) EdgiOS/121.0.2277.107 Version/17.0 Mobile/15E148 Safari/604.1")
             .cookie_store(true)
             .timeout(std::time::Duration::from_secs(300))
@@ -453,11 +641,19 @@ impl VideoProcessor {
             request = request.header("Range", format!("bytes={}-", downloaded_size));
         }
 
-        // 发送请求
+        // 发送请求（与其他抖音请求共用限速）
+        let limiter = rate_limiter::global_limiter();
+        limiter
+            .acquire(url)
+            .await
+            .map_err(|e| VideoError::DownloadFailed(e.to_string()))?;
         let response = request
             .send()
             .await
             .map_err(|e| VideoError::DownloadFailed(format!("请求失败: {}", e)))?;
+        limiter
+            .check_response(url, response.status().as_u16(), "")
+            .map_err(|e| VideoError::DownloadFailed(e.to_string()))?;
 
         // 检查响应状态
         if !response.status().is_success() && response.status().as_u16() != 206 {
@@ -507,6 +703,22 @@ impl VideoProcessor {
         Ok(())
     }
 
+    /// 为下载的视频写入来源元数据（标题、作者、aweme_id、来源链接、发布日期）
+    ///
+    /// 使用流复制，不重新编码
+    pub async fn embed_source_metadata(
+        &self,
+        video_path: &Path,
+        source: &SourceMetadata,
+    ) -> Result<(), VideoError> {
+        Self::validate_video_file(video_path)?;
+
+        let ffmpeg = self.ffmpeg.read().await;
+        ffmpeg
+            .embed_metadata(video_path, &source.to_ffmpeg_metadata())
+            .map_err(|e| VideoError::IoError(e.to_string()))
+    }
+
     /// 批量下载视频
     ///
     /// # Arguments
@@ -1,6 +1,8 @@
 // 配置管理模块
 
+use crate::ai::service::OpenAiCompatibleConfig;
 use crate::data::database::{Database, DbError};
+use crate::utils::http_client::{self, NetworkSettings};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -40,10 +42,25 @@ pub struct AppConfig {
     pub openai_api_key: Option<String>,
     pub deepseek_api_key: Option<String>,
     pub lm_studio_url: String,
+    /// 自定义 OpenAI 兼容服务
+    #[serde(default)]
+    pub custom_ai_provider: OpenAiCompatibleConfig,
 
     // 网络设置
     pub request_interval: u64,
     pub max_retries: u32,
+
+    // 下载设置
+    #[serde(default = "default_filename_template")]
+    pub download_filename_template: String,
+
+    // 代理与超时设置
+    #[serde(default)]
+    pub network: NetworkSettings,
+}
+
+fn default_filename_template() -> String {
+    crate::utils::filename_template::DEFAULT_FILENAME_TEMPLATE.to_string()
 }
 
 impl Default for AppConfig {
@@ -61,8 +78,11 @@ impl Default for AppConfig {
             openai_api_key: None,
             deepseek_api_key: None,
             lm_studio_url: "http://localhost:1234".to_string(),
+            custom_ai_provider: OpenAiCompatibleConfig::default(),
             request_interval: 1000,
             max_retries: 3,
+            download_filename_template: default_filename_template(),
+            network: NetworkSettings::default(),
         }
     }
 }
@@ -81,8 +101,11 @@ pub mod config_keys {
     pub const OPENAI_API_KEY: &str = "openai_api_key";
     pub const DEEPSEEK_API_KEY: &str = "deepseek_api_key";
     pub const LM_STUDIO_URL: &str = "lm_studio_url";
+    pub const CUSTOM_AI_PROVIDER: &str = "custom_ai_provider";
     pub const REQUEST_INTERVAL: &str = "request_interval";
     pub const MAX_RETRIES: &str = "max_retries";
+    pub const DOWNLOAD_FILENAME_TEMPLATE: &str = "download_filename_template";
+    pub const NETWORK_SETTINGS: &str = "network_settings";
 }
 
 /// 配置管理器
@@ -164,6 +187,10 @@ impl ConfigManager {
             config.lm_studio_url = value;
         }
 
+        if let Some(value) = self.db.get_config(config_keys::CUSTOM_AI_PROVIDER)? {
+            config.custom_ai_provider = serde_json::from_str(&value).unwrap_or_default();
+        }
+
         if let Some(value) = self.db.get_config(config_keys::REQUEST_INTERVAL)? {
             config.request_interval = value.parse().unwrap_or(1000);
         }
@@ -172,6 +199,18 @@ impl ConfigManager {
             config.max_retries = value.parse().unwrap_or(3);
         }
 
+        if let Some(value) = self
+            .db
+            .get_config(config_keys::DOWNLOAD_FILENAME_TEMPLATE)?
+        {
+            config.download_filename_template = value;
+        }
+
+        if let Some(value) = self.db.get_config(config_keys::NETWORK_SETTINGS)? {
+            config.network = serde_json::from_str(&value).unwrap_or_default();
+        }
+        http_client::set_network_settings(config.network.clone());
+
         Ok(())
     }
 
@@ -214,12 +253,24 @@ impl ConfigManager {
         )?;
         self.db
             .set_config(config_keys::LM_STUDIO_URL, &config.lm_studio_url)?;
+        let custom_ai_provider = serde_json::to_string(&config.custom_ai_provider)
+            .map_err(|e| ConfigError::SerializationError(e.to_string()))?;
+        self.db
+            .set_config(config_keys::CUSTOM_AI_PROVIDER, &custom_ai_provider)?;
         self.db.set_config(
             config_keys::REQUEST_INTERVAL,
             &config.request_interval.to_string(),
         )?;
         self.db
             .set_config(config_keys::MAX_RETRIES, &config.max_retries.to_string())?;
+        self.db.set_config(
+            config_keys::DOWNLOAD_FILENAME_TEMPLATE,
+            &config.download_filename_template,
+        )?;
+        let network = serde_json::to_string(&config.network)
+            .map_err(|e| ConfigError::SerializationError(e.to_string()))?;
+        self.db
+            .set_config(config_keys::NETWORK_SETTINGS, &network)?;
 
         Ok(())
     }
@@ -234,6 +285,7 @@ impl ConfigManager {
         {
             let mut config = self.config.write();
             *config = new_config;
+            http_client::set_network_settings(config.network.clone());
         }
         self.save()
     }
@@ -254,8 +306,15 @@ impl ConfigManager {
             config_keys::OPENAI_API_KEY => config.openai_api_key.clone(),
             config_keys::DEEPSEEK_API_KEY => config.deepseek_api_key.clone(),
             config_keys::LM_STUDIO_URL => Some(config.lm_studio_url.clone()),
+            config_keys::CUSTOM_AI_PROVIDER => {
+                serde_json::to_string(&config.custom_ai_provider).ok()
+            }
             config_keys::REQUEST_INTERVAL => Some(config.request_interval.to_string()),
             config_keys::MAX_RETRIES => Some(config.max_retries.to_string()),
+            config_keys::DOWNLOAD_FILENAME_TEMPLATE => {
+                Some(config.download_filename_template.clone())
+            }
+            config_keys::NETWORK_SETTINGS => serde_json::to_string(&config.network).ok(),
             _ => None,
         }
     }
@@ -315,6 +374,11 @@ impl ConfigManager {
                     };
                 }
                 config_keys::LM_STUDIO_URL => config.lm_studio_url = value.to_string(),
+                config_keys::CUSTOM_AI_PROVIDER => {
+                    config.custom_ai_provider = serde_json::from_str(value).map_err(|e| {
+                        ConfigError::ParseFailed(format!("无法解析自定义 AI 服务: {}", e))
+                    })?;
+                }
                 config_keys::REQUEST_INTERVAL => {
                     config.request_interval = value.parse().map_err(|_| {
                         ConfigError::ParseFailed(format!("无法解析数字: {}", value))
@@ -325,6 +389,15 @@ impl ConfigManager {
                         ConfigError::ParseFailed(format!("无法解析数字: {}", value))
                     })?;
                 }
+                config_keys::DOWNLOAD_FILENAME_TEMPLATE => {
+                    config.download_filename_template = value.to_string()
+                }
+                config_keys::NETWORK_SETTINGS => {
+                    config.network = serde_json::from_str(value).map_err(|e| {
+                        ConfigError::ParseFailed(format!("无法解析网络设置: {}", e))
+                    })?;
+                    http_client::set_network_settings(config.network.clone());
+                }
                 _ => {
                     return Err(ConfigError::SaveFailed(format!("未知配置项: {}", key)));
                 }
@@ -476,4 +549,27 @@ mod tests {
         let config = manager.get();
         assert_eq!(config.theme, "system");
     }
+
+    #[test]
+    fn test_custom_ai_provider_persistence() {
+        let db = create_test_db();
+        let manager = ConfigManager::new(db.clone()).unwrap();
+
+        let mut config = manager.get();
+        config.ai_provider = "custom".to_string();
+        config.custom_ai_provider.base_url = "http://localhost:11434/v1".to_string();
+        config.custom_ai_provider.model = "qwen2.5:7b".to_string();
+        config
+            .custom_ai_provider
+            .headers
+            .insert("X-Gateway-Token".to_string(), "token".to_string());
+        manager.update(config.clone()).unwrap();
+
+        // 重新从数据库加载
+        let reloaded = ConfigManager::new(db).unwrap().get();
+        assert_eq!(reloaded.custom_ai_provider, config.custom_ai_provider);
+        assert!(manager
+            .set_value(config_keys::CUSTOM_AI_PROVIDER, "not json")
+            .is_err());
+    }
 }
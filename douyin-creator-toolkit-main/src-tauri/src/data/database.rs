@@ -3,6 +3,7 @@
 use r2d2::{Pool, PooledConnection};
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::params;
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
 use thiserror::Error;
@@ -91,10 +92,54 @@ impl Database {
         
         // 执行 schema 创建
         conn.execute_batch(SCHEMA_SQL)?;
+
+        // 旧版本创建的表缺少的列
+        Self::add_column_if_missing(
+            &conn,
+            "shot_pacing",
+            "threshold",
+            "REAL NOT NULL DEFAULT 0.3",
+        )?;
+        Self::add_column_if_missing(
+            &conn,
+            "tracked_videos",
+            "failed_attempts",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+        Self::add_column_if_missing(&conn, "tracked_videos", "last_attempt_at", "INTEGER")?;
+        // 旧版本在入队时即记录文件，视为已处理
+        Self::add_column_if_missing(
+            &conn,
+            "watched_files",
+            "status",
+            "TEXT NOT NULL DEFAULT 'completed'",
+        )?;
         
         Ok(())
     }
 
+    /// 为已存在的表补充新增列（CREATE TABLE IF NOT EXISTS 不会修改旧表）
+    fn add_column_if_missing(
+        conn: &rusqlite::Connection,
+        table: &str,
+        column: &str,
+        definition: &str,
+    ) -> Result<(), DbError> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .filter_map(|name| name.ok())
+            .any(|name| name == column);
+
+        if !exists {
+            conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                [],
+            )?;
+        }
+        Ok(())
+    }
+
     /// 获取配置值
     pub fn get_config(&self, key: &str) -> Result<Option<String>, DbError> {
         let conn = self.get_connection()?;
@@ -179,37 +224,1018 @@ impl Database {
                 params![status, id],
             )?;
         }
-        
+        
+        Ok(())
+    }
+
+    /// 获取历史记录
+    pub fn get_history(&self, limit: i32) -> Result<Vec<HistoryRecord>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, type, source, result, status, created_at, completed_at 
+             FROM history ORDER BY created_at DESC LIMIT ?"
+        )?;
+        
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok(HistoryRecord {
+                id: row.get(0)?,
+                history_type: row.get(1)?,
+                source: row.get(2)?,
+                result: row.get(3)?,
+                status: row.get(4)?,
+                created_at: row.get(5)?,
+                completed_at: row.get(6)?,
+            })
+        })?;
+        
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+        
+        Ok(records)
+    }
+
+    /// 检查监视文件夹中的文件是否已入队或已处理（路径、大小、修改时间均一致）
+    pub fn is_watched_file_processed(
+        &self,
+        path: &str,
+        file_size: u64,
+        modified_at: i64,
+    ) -> Result<bool, DbError> {
+        let conn = self.get_connection()?;
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM watched_files WHERE path = ? AND file_size = ? AND modified_at = ?",
+            params![path, file_size as i64, modified_at],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// 记录监视文件夹中已入队的文件（状态为 pending，任务结束后更新）
+    pub fn add_watched_file(
+        &self,
+        path: &str,
+        file_size: u64,
+        modified_at: i64,
+        task_id: &str,
+    ) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO watched_files (path, file_size, modified_at, task_id, status, created_at) VALUES (?, ?, ?, ?, 'pending', datetime('now'))",
+            params![path, file_size as i64, modified_at, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// 更新监视文件的处理状态：pending / completed / failed（文件已被替换时不更新）
+    pub fn set_watched_file_status(
+        &self,
+        path: &str,
+        file_size: u64,
+        modified_at: i64,
+        status: &str,
+    ) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE watched_files SET status = ? WHERE path = ? AND file_size = ? AND modified_at = ?",
+            params![status, path, file_size as i64, modified_at],
+        )?;
+        Ok(())
+    }
+
+    /// 获取尚未处理完成的监视文件（入队后应用退出或转写失败）
+    pub fn get_unfinished_watched_files(&self) -> Result<Vec<WatchedFileRecord>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT path, file_size, modified_at, task_id, status, created_at
+             FROM watched_files WHERE status != 'completed' ORDER BY created_at",
+        )?;
+
+        let rows = stmt.query_map([], Self::map_watched_file)?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+
+        Ok(records)
+    }
+
+    /// 获取监视文件夹处理记录
+    pub fn get_watched_files(&self, limit: i32) -> Result<Vec<WatchedFileRecord>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT path, file_size, modified_at, task_id, status, created_at
+             FROM watched_files ORDER BY created_at DESC LIMIT ?"
+        )?;
+
+        let rows = stmt.query_map(params![limit], Self::map_watched_file)?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+
+        Ok(records)
+    }
+
+    /// 将查询行转换为监视文件记录
+    fn map_watched_file(row: &rusqlite::Row) -> rusqlite::Result<WatchedFileRecord> {
+        Ok(WatchedFileRecord {
+            path: row.get(0)?,
+            file_size: row.get::<_, i64>(1)? as u64,
+            modified_at: row.get(2)?,
+            task_id: row.get(3)?,
+            status: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    }
+
+    /// 获取缓存的媒体指纹（文件大小和修改时间一致时才有效）
+    pub fn get_media_fingerprint(
+        &self,
+        path: &str,
+        file_size: u64,
+        modified_at: i64,
+    ) -> Result<Option<String>, DbError> {
+        let conn = self.get_connection()?;
+        let result = conn.query_row(
+            "SELECT fingerprint FROM media_fingerprints WHERE path = ? AND file_size = ? AND modified_at = ?",
+            params![path, file_size as i64, modified_at],
+            |row| row.get::<_, String>(0),
+        );
+
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DbError::QueryFailed(e.to_string())),
+        }
+    }
+
+    /// 保存媒体指纹（JSON）
+    pub fn save_media_fingerprint(
+        &self,
+        path: &str,
+        file_size: u64,
+        modified_at: i64,
+        fingerprint: &str,
+    ) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO media_fingerprints (path, file_size, modified_at, fingerprint, created_at) VALUES (?, ?, ?, ?, datetime('now'))",
+            params![path, file_size as i64, modified_at, fingerprint],
+        )?;
+        Ok(())
+    }
+
+    /// 获取所有媒体指纹 (path, fingerprint JSON)
+    pub fn list_media_fingerprints(&self) -> Result<Vec<(String, String)>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare("SELECT path, fingerprint FROM media_fingerprints")?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut fingerprints = Vec::new();
+        for row in rows {
+            fingerprints.push(row?);
+        }
+
+        Ok(fingerprints)
+    }
+
+    /// 删除媒体指纹
+    pub fn delete_media_fingerprint(&self, path: &str) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute("DELETE FROM media_fingerprints WHERE path = ?", params![path])?;
+        Ok(())
+    }
+
+    /// 获取缓存的镜头节奏指标（文件大小、修改时间和场景切换阈值一致时才有效）
+    pub fn get_shot_pacing(
+        &self,
+        path: &str,
+        file_size: u64,
+        modified_at: i64,
+        threshold: f32,
+    ) -> Result<Option<String>, DbError> {
+        let conn = self.get_connection()?;
+        let result = conn.query_row(
+            "SELECT metrics FROM shot_pacing WHERE path = ? AND file_size = ? AND modified_at = ? AND ABS(threshold - ?) < 1e-6",
+            params![path, file_size as i64, modified_at, threshold as f64],
+            |row| row.get::<_, String>(0),
+        );
+
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DbError::QueryFailed(e.to_string())),
+        }
+    }
+
+    /// 保存镜头节奏指标（JSON），每个视频只保留最近一次分析使用的阈值及结果
+    pub fn save_shot_pacing(
+        &self,
+        path: &str,
+        author: &str,
+        file_size: u64,
+        modified_at: i64,
+        threshold: f32,
+        metrics: &str,
+    ) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO shot_pacing (path, author, file_size, modified_at, threshold, metrics, created_at) VALUES (?, ?, ?, ?, ?, ?, datetime('now'))",
+            params![path, author, file_size as i64, modified_at, threshold as f64, metrics],
+        )?;
+        Ok(())
+    }
+
+    /// 更新已保存镜头节奏的创作者（命中缓存时调用方指定了不同的创作者）
+    pub fn set_shot_pacing_author(&self, path: &str, author: &str) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE shot_pacing SET author = ? WHERE path = ? AND author != ?",
+            params![author, path, author],
+        )?;
+        Ok(())
+    }
+
+    /// 获取镜头节奏指标 (path, author, metrics JSON)，可按创作者过滤
+    pub fn list_shot_pacing(
+        &self,
+        author: Option<&str>,
+    ) -> Result<Vec<(String, String, String)>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT path, author, metrics FROM shot_pacing WHERE ?1 IS NULL OR author = ?1 ORDER BY author, path",
+        )?;
+
+        let rows = stmt.query_map(params![author], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+
+        Ok(records)
+    }
+
+    /// 保存评论（按 cid 覆盖已有记录），返回写入条数
+    pub fn save_comments(&self, comments: &[CommentRecord]) -> Result<usize, DbError> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO comments (cid, aweme_id, parent_id, text, author, author_id, likes, reply_count, create_time, ip_location, fetched_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))",
+            )?;
+            for c in comments {
+                stmt.execute(params![
+                    c.cid,
+                    c.aweme_id,
+                    c.parent_id,
+                    c.text,
+                    c.author,
+                    c.author_id,
+                    c.likes as i64,
+                    c.reply_count as i64,
+                    c.create_time as i64,
+                    c.ip_location,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(comments.len())
+    }
+
+    /// 获取作品的全部评论（一级评论在前，按点赞数降序）
+    pub fn list_comments(&self, aweme_id: &str) -> Result<Vec<CommentRecord>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT cid, aweme_id, parent_id, text, author, author_id, likes, reply_count, create_time, ip_location FROM comments WHERE aweme_id = ? ORDER BY parent_id IS NOT NULL, likes DESC, create_time",
+        )?;
+
+        let rows = stmt.query_map(params![aweme_id], |row| {
+            Ok(CommentRecord {
+                cid: row.get(0)?,
+                aweme_id: row.get(1)?,
+                parent_id: row.get(2)?,
+                text: row.get(3)?,
+                author: row.get(4)?,
+                author_id: row.get(5)?,
+                likes: row.get::<_, i64>(6)? as u64,
+                reply_count: row.get::<_, i64>(7)? as u64,
+                create_time: row.get::<_, i64>(8)? as u64,
+                ip_location: row.get(9)?,
+            })
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+
+        Ok(records)
+    }
+
+    /// 删除作品的全部评论
+    pub fn delete_comments(&self, aweme_id: &str) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute("DELETE FROM comments WHERE aweme_id = ?", params![aweme_id])?;
+        Ok(())
+    }
+
+    /// 添加或更新监控账号（保留上次检查时间）
+    pub fn upsert_tracked_account(
+        &self,
+        user_id: &str,
+        nickname: &str,
+        enabled: bool,
+        auto_pipeline: bool,
+    ) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO tracked_accounts (user_id, nickname, enabled, auto_pipeline) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(user_id) DO UPDATE SET nickname = ?2, enabled = ?3, auto_pipeline = ?4",
+            params![user_id, nickname, enabled, auto_pipeline],
+        )?;
+        Ok(())
+    }
+
+    /// 删除监控账号及其作品记录
+    pub fn remove_tracked_account(&self, user_id: &str) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "DELETE FROM account_videos WHERE user_id = ?",
+            params![user_id],
+        )?;
+        conn.execute(
+            "DELETE FROM tracked_accounts WHERE user_id = ?",
+            params![user_id],
+        )?;
+        Ok(())
+    }
+
+    /// 获取全部监控账号
+    pub fn list_tracked_accounts(&self) -> Result<Vec<TrackedAccountRecord>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT user_id, nickname, enabled, auto_pipeline, last_checked_at, created_at FROM tracked_accounts ORDER BY created_at",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(TrackedAccountRecord {
+                user_id: row.get(0)?,
+                nickname: row.get(1)?,
+                enabled: row.get(2)?,
+                auto_pipeline: row.get(3)?,
+                last_checked_at: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+
+        Ok(records)
+    }
+
+    /// 记录账号的检查时间（Unix 秒）
+    pub fn set_account_checked(&self, user_id: &str, checked_at: i64) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE tracked_accounts SET last_checked_at = ? WHERE user_id = ?",
+            params![checked_at, user_id],
+        )?;
+        Ok(())
+    }
+
+    /// 获取账号已记录的作品 ID
+    pub fn list_account_video_ids(&self, user_id: &str) -> Result<Vec<String>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare("SELECT video_id FROM account_videos WHERE user_id = ?")?;
+
+        let rows = stmt.query_map(params![user_id], |row| row.get::<_, String>(0))?;
+
+        let mut ids = Vec::new();
+        for row in rows {
+            ids.push(row?);
+        }
+
+        Ok(ids)
+    }
+
+    /// 记录账号作品（已存在时忽略），返回是否为新插入的记录
+    pub fn add_account_video(&self, video: &AccountVideoRecord) -> Result<bool, DbError> {
+        let conn = self.get_connection()?;
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO account_videos (video_id, user_id, title, video_url, cover_url, create_time, status, result) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                video.video_id,
+                video.user_id,
+                video.title,
+                video.video_url,
+                video.cover_url,
+                video.create_time,
+                video.status,
+                video.result,
+            ],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// 更新账号作品的处理状态
+    pub fn update_account_video_status(
+        &self,
+        video_id: &str,
+        status: &str,
+        result: Option<&str>,
+    ) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE account_videos SET status = ?, result = ? WHERE video_id = ?",
+            params![status, result, video_id],
+        )?;
+        Ok(())
+    }
+
+    /// 将仍处于 processing 的作品标记为失败，返回更新的条数
+    ///
+    /// 任务队列只保存在内存中，应用退出后未完成的自动处理不会再继续，启动时调用
+    pub fn fail_processing_account_videos(&self, error: &str) -> Result<usize, DbError> {
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE account_videos SET status = 'failed', result = ? WHERE status = 'processing'",
+            params![error],
+        )?;
+        Ok(updated)
+    }
+
+    /// 获取账号作品记录（最新发现的在前），可按账号过滤
+    pub fn list_account_videos(
+        &self,
+        user_id: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<AccountVideoRecord>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT video_id, user_id, title, video_url, cover_url, create_time, status, result, discovered_at FROM account_videos WHERE ?1 IS NULL OR user_id = ?1 ORDER BY discovered_at DESC, create_time DESC LIMIT ?2",
+        )?;
+
+        let rows = stmt.query_map(params![user_id, limit], |row| {
+            Ok(AccountVideoRecord {
+                video_id: row.get(0)?,
+                user_id: row.get(1)?,
+                title: row.get(2)?,
+                video_url: row.get(3)?,
+                cover_url: row.get(4)?,
+                create_time: row.get(5)?,
+                status: row.get(6)?,
+                result: row.get(7)?,
+                discovered_at: row.get(8)?,
+            })
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+
+        Ok(records)
+    }
+
+    /// 添加或更新数据追踪的作品（保留已有作品的启用状态，清除失败计数）
+    pub fn upsert_tracked_video(
+        &self,
+        aweme_id: &str,
+        title: &str,
+        author: &str,
+        create_time: Option<i64>,
+    ) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO tracked_videos (aweme_id, title, author, create_time) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(aweme_id) DO UPDATE SET title = ?2, author = ?3, create_time = COALESCE(?4, create_time), failed_attempts = 0",
+            params![aweme_id, title, author, create_time],
+        )?;
+        Ok(())
+    }
+
+    /// 停止追踪作品并删除其采样数据
+    pub fn remove_tracked_video(&self, aweme_id: &str) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "DELETE FROM video_metrics WHERE aweme_id = ?",
+            params![aweme_id],
+        )?;
+        conn.execute(
+            "DELETE FROM tracked_videos WHERE aweme_id = ?",
+            params![aweme_id],
+        )?;
+        Ok(())
+    }
+
+    /// 启用或暂停作品的数据采样
+    pub fn set_tracked_video_enabled(&self, aweme_id: &str, enabled: bool) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE tracked_videos SET enabled = ? WHERE aweme_id = ?",
+            params![enabled, aweme_id],
+        )?;
+        Ok(())
+    }
+
+    /// 记录一次采样尝试，成功时清除失败计数，失败时累加
+    pub fn record_sample_attempt(
+        &self,
+        aweme_id: &str,
+        attempted_at: i64,
+        success: bool,
+    ) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE tracked_videos SET last_attempt_at = ?1,
+                    failed_attempts = CASE WHEN ?2 THEN 0 ELSE failed_attempts + 1 END
+             WHERE aweme_id = ?3",
+            params![attempted_at, success, aweme_id],
+        )?;
+        Ok(())
+    }
+
+    /// 获取全部数据追踪的作品（含最近一次采样时间）
+    pub fn list_tracked_videos(&self) -> Result<Vec<TrackedVideoRecord>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT v.aweme_id, v.title, v.author, v.create_time, v.enabled, v.created_at,
+                    (SELECT MAX(sampled_at) FROM video_metrics m WHERE m.aweme_id = v.aweme_id),
+                    v.failed_attempts, v.last_attempt_at
+             FROM tracked_videos v ORDER BY v.created_at DESC",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(TrackedVideoRecord {
+                aweme_id: row.get(0)?,
+                title: row.get(1)?,
+                author: row.get(2)?,
+                create_time: row.get(3)?,
+                enabled: row.get(4)?,
+                created_at: row.get(5)?,
+                last_sampled_at: row.get(6)?,
+                failed_attempts: row.get(7)?,
+                last_attempt_at: row.get(8)?,
+            })
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+
+        Ok(records)
+    }
+
+    /// 保存一次互动数据采样
+    pub fn add_metric_sample(&self, sample: &MetricSampleRecord) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO video_metrics (aweme_id, sampled_at, likes, comments, shares, collects, plays) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![
+                sample.aweme_id,
+                sample.sampled_at,
+                sample.likes as i64,
+                sample.comments as i64,
+                sample.shares as i64,
+                sample.collects as i64,
+                sample.plays as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 获取作品的互动数据采样（按时间升序）
+    pub fn list_metric_samples(&self, aweme_id: &str) -> Result<Vec<MetricSampleRecord>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT aweme_id, sampled_at, likes, comments, shares, collects, plays FROM video_metrics WHERE aweme_id = ? ORDER BY sampled_at",
+        )?;
+
+        let rows = stmt.query_map(params![aweme_id], |row| {
+            Ok(MetricSampleRecord {
+                aweme_id: row.get(0)?,
+                sampled_at: row.get(1)?,
+                likes: row.get::<_, i64>(2)? as u64,
+                comments: row.get::<_, i64>(3)? as u64,
+                shares: row.get::<_, i64>(4)? as u64,
+                collects: row.get::<_, i64>(5)? as u64,
+                plays: row.get::<_, i64>(6)? as u64,
+            })
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+
+        Ok(records)
+    }
+
+    /// 批量保存解析渠道尝试记录
+    pub fn add_strategy_attempts(
+        &self,
+        attempts: &[StrategyAttemptRecord],
+    ) -> Result<usize, DbError> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO parser_strategy_attempts (strategy, success, latency_ms, error, attempted_at) VALUES (?, ?, ?, ?, ?)",
+            )?;
+            for a in attempts {
+                stmt.execute(params![
+                    a.strategy,
+                    a.success,
+                    a.latency_ms as i64,
+                    a.error,
+                    a.attempted_at,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(attempts.len())
+    }
+
+    /// 获取指定时间之后的解析渠道尝试记录（按时间升序）
+    pub fn list_strategy_attempts(
+        &self,
+        since: i64,
+    ) -> Result<Vec<StrategyAttemptRecord>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT strategy, success, latency_ms, error, attempted_at FROM parser_strategy_attempts WHERE attempted_at >= ? ORDER BY attempted_at, id",
+        )?;
+
+        let rows = stmt.query_map(params![since], |row| {
+            Ok(StrategyAttemptRecord {
+                strategy: row.get(0)?,
+                success: row.get(1)?,
+                latency_ms: row.get::<_, i64>(2)? as u64,
+                error: row.get(3)?,
+                attempted_at: row.get(4)?,
+            })
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+
+        Ok(records)
+    }
+
+    /// 删除指定时间之前的解析渠道尝试记录，返回删除条数
+    pub fn prune_strategy_attempts(&self, before: i64) -> Result<usize, DbError> {
+        let conn = self.get_connection()?;
+        let deleted = conn.execute(
+            "DELETE FROM parser_strategy_attempts WHERE attempted_at < ?",
+            params![before],
+        )?;
+        Ok(deleted)
+    }
+
+    /// 记录通过批量导入解析过的作品
+    pub fn add_imported_videos(&self, videos: &[ImportedVideoRecord]) -> Result<usize, DbError> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO imported_videos (aweme_id, link, title, imported_at) VALUES (?, ?, ?, datetime('now'))",
+            )?;
+            for v in videos {
+                stmt.execute(params![v.aweme_id, v.link, v.title])?;
+            }
+        }
+        tx.commit()?;
+        Ok(videos.len())
+    }
+
+    /// 保存从话题 / 音乐页采集的作品，已存在时只刷新作品信息，保留处理状态
+    pub fn save_collected_videos(&self, videos: &[CollectedVideoRecord]) -> Result<usize, DbError> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO collected_videos (source_type, source_id, aweme_id, title, author, video_url, cover_url, create_time, likes, status) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(source_type, source_id, aweme_id) DO UPDATE SET title = excluded.title, author = excluded.author, video_url = excluded.video_url, cover_url = excluded.cover_url, likes = excluded.likes",
+            )?;
+            for v in videos {
+                stmt.execute(params![
+                    v.source_type,
+                    v.source_id,
+                    v.aweme_id,
+                    v.title,
+                    v.author,
+                    v.video_url,
+                    v.cover_url,
+                    v.create_time,
+                    v.likes,
+                    v.status,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(videos.len())
+    }
+
+    /// 更新采集作品的处理状态
+    pub fn update_collected_video_status(
+        &self,
+        source_type: &str,
+        source_id: &str,
+        aweme_id: &str,
+        status: &str,
+        task_id: Option<&str>,
+    ) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE collected_videos SET status = ?, task_id = ? WHERE source_type = ? AND source_id = ? AND aweme_id = ?",
+            params![status, task_id, source_type, source_id, aweme_id],
+        )?;
         Ok(())
     }
 
-    /// 获取历史记录
-    pub fn get_history(&self, limit: i32) -> Result<Vec<HistoryRecord>, DbError> {
+    /// 获取采集的作品（最新采集的在前，同批按点赞数排序），可按来源过滤
+    pub fn list_collected_videos(
+        &self,
+        source_type: Option<&str>,
+        source_id: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<CollectedVideoRecord>, DbError> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, type, source, result, status, created_at, completed_at 
-             FROM history ORDER BY created_at DESC LIMIT ?"
+            "SELECT source_type, source_id, aweme_id, title, author, video_url, cover_url, create_time, likes, status, task_id, collected_at FROM collected_videos WHERE (?1 IS NULL OR source_type = ?1) AND (?2 IS NULL OR source_id = ?2) ORDER BY collected_at DESC, likes DESC LIMIT ?3",
         )?;
-        
-        let rows = stmt.query_map(params![limit], |row| {
-            Ok(HistoryRecord {
-                id: row.get(0)?,
-                history_type: row.get(1)?,
-                source: row.get(2)?,
-                result: row.get(3)?,
-                status: row.get(4)?,
-                created_at: row.get(5)?,
-                completed_at: row.get(6)?,
+
+        let rows = stmt.query_map(params![source_type, source_id, limit], |row| {
+            Ok(CollectedVideoRecord {
+                source_type: row.get(0)?,
+                source_id: row.get(1)?,
+                aweme_id: row.get(2)?,
+                title: row.get(3)?,
+                author: row.get(4)?,
+                video_url: row.get(5)?,
+                cover_url: row.get(6)?,
+                create_time: row.get(7)?,
+                likes: row.get(8)?,
+                status: row.get(9)?,
+                task_id: row.get(10)?,
+                collected_at: row.get(11)?,
             })
         })?;
-        
+
         let mut records = Vec::new();
         for row in rows {
             records.push(row?);
         }
-        
+
         Ok(records)
     }
+
+    /// 获取资料库中已有的作品 ID（批量导入的作品，以及监控账号、话题 / 音乐采集中已下载的作品）
+    ///
+    /// 监控基线、互动追踪和只抓取过评论的作品没有下载到本地，不计入资料库
+    pub fn list_library_aweme_ids(&self) -> Result<HashSet<String>, DbError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT aweme_id FROM imported_videos
+             UNION SELECT video_id FROM account_videos WHERE status = 'completed'
+             UNION SELECT aweme_id FROM collected_videos WHERE status IN ('queued', 'skipped')",
+        )?;
+
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut ids = HashSet::new();
+        for row in rows {
+            ids.insert(row?);
+        }
+
+        Ok(ids)
+    }
+
+    /// 获取作品的缓存解析结果
+    pub fn get_parse_cache(&self, aweme_id: &str) -> Result<Option<ParseCacheRecord>, DbError> {
+        let conn = self.get_connection()?;
+        let result = conn.query_row(
+            "SELECT aweme_id, data, fetched_at FROM parse_cache WHERE aweme_id = ?",
+            params![aweme_id],
+            |row| {
+                Ok(ParseCacheRecord {
+                    aweme_id: row.get(0)?,
+                    data: row.get(1)?,
+                    fetched_at: row.get(2)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(record) => Ok(Some(record)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DbError::QueryFailed(e.to_string())),
+        }
+    }
+
+    /// 保存作品的解析结果缓存
+    pub fn save_parse_cache(&self, record: &ParseCacheRecord) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO parse_cache (aweme_id, data, fetched_at) VALUES (?, ?, ?)",
+            params![record.aweme_id, record.data, record.fetched_at],
+        )?;
+        Ok(())
+    }
+
+    /// 查询链接对应的作品 ID
+    pub fn get_cached_link(&self, link: &str) -> Result<Option<String>, DbError> {
+        let conn = self.get_connection()?;
+        let result = conn.query_row(
+            "SELECT aweme_id FROM parse_cache_links WHERE link = ?",
+            params![link],
+            |row| row.get::<_, String>(0),
+        );
+
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DbError::QueryFailed(e.to_string())),
+        }
+    }
+
+    /// 记录链接对应的作品 ID
+    pub fn save_cached_link(&self, link: &str, aweme_id: &str) -> Result<(), DbError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO parse_cache_links (link, aweme_id, created_at) VALUES (?, ?, datetime('now'))",
+            params![link, aweme_id],
+        )?;
+        Ok(())
+    }
+
+    /// 清空解析结果缓存与链接映射，返回删除的结果条数
+    pub fn clear_parse_cache(&self) -> Result<usize, DbError> {
+        let conn = self.get_connection()?;
+        let deleted = conn.execute("DELETE FROM parse_cache", [])?;
+        conn.execute("DELETE FROM parse_cache_links", [])?;
+        Ok(deleted)
+    }
+}
+
+/// 视频评论记录
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CommentRecord {
+    pub cid: String,
+    pub aweme_id: String,
+    pub parent_id: Option<String>,
+    pub text: String,
+    pub author: String,
+    pub author_id: String,
+    pub likes: u64,
+    pub reply_count: u64,
+    pub create_time: u64,
+    pub ip_location: String,
+}
+
+/// 互动数据追踪的作品
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TrackedVideoRecord {
+    pub aweme_id: String,
+    pub title: String,
+    pub author: String,
+    /// 发布时间（Unix 秒）
+    pub create_time: Option<i64>,
+    pub enabled: bool,
+    /// 最近一次采样时间（Unix 秒）
+    pub last_sampled_at: Option<i64>,
+    /// 连续采样失败次数
+    pub failed_attempts: u32,
+    /// 最近一次尝试采样的时间（Unix 秒，无论成功与否）
+    pub last_attempt_at: Option<i64>,
+    pub created_at: String,
+}
+
+/// 互动数据采样
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MetricSampleRecord {
+    pub aweme_id: String,
+    /// 采样时间（Unix 秒）
+    pub sampled_at: i64,
+    pub likes: u64,
+    pub comments: u64,
+    pub shares: u64,
+    pub collects: u64,
+    pub plays: u64,
+}
+
+/// 解析渠道尝试记录
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StrategyAttemptRecord {
+    pub strategy: String,
+    pub success: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    /// 尝试时间（Unix 秒）
+    pub attempted_at: i64,
+}
+
+/// 批量导入的作品记录
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ImportedVideoRecord {
+    pub aweme_id: String,
+    /// 导入时使用的链接
+    pub link: String,
+    pub title: String,
+}
+
+/// 从话题 / 音乐页采集的作品
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CollectedVideoRecord {
+    /// 来源类型：challenge / music
+    pub source_type: String,
+    /// 话题 ID 或音乐 ID
+    pub source_id: String,
+    pub aweme_id: String,
+    pub title: String,
+    pub author: String,
+    pub video_url: String,
+    pub cover_url: Option<String>,
+    /// 发布时间（Unix 秒）
+    pub create_time: Option<i64>,
+    pub likes: i64,
+    /// 处理状态：new / queued（已加入转写队列）/ skipped（图文作品）/ failed
+    pub status: String,
+    /// 转写任务 ID
+    pub task_id: Option<String>,
+    pub collected_at: String,
+}
+
+/// 解析结果缓存记录
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ParseCacheRecord {
+    pub aweme_id: String,
+    /// 解析结果（JSON）
+    pub data: String,
+    /// 解析时间（Unix 秒）
+    pub fetched_at: i64,
+}
+
+/// 监控账号记录
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TrackedAccountRecord {
+    pub user_id: String,
+    pub nickname: String,
+    pub enabled: bool,
+    /// 发现新作品后是否自动下载、转写并分析
+    pub auto_pipeline: bool,
+    /// 上次检查时间（Unix 秒），从未检查时为 None
+    pub last_checked_at: Option<i64>,
+    pub created_at: String,
+}
+
+/// 监控账号发现的作品
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AccountVideoRecord {
+    pub video_id: String,
+    pub user_id: String,
+    pub title: String,
+    pub video_url: String,
+    pub cover_url: Option<String>,
+    /// 发布时间（Unix 秒）
+    pub create_time: Option<i64>,
+    /// 处理状态：existing（开始监控前已发布）/ new / processing / completed / failed
+    pub status: String,
+    /// 自动处理结果（JSON）或错误信息
+    pub result: Option<String>,
+    pub discovered_at: String,
+}
+
+/// 监视文件夹处理记录
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WatchedFileRecord {
+    pub path: String,
+    pub file_size: u64,
+    pub modified_at: i64,
+    pub task_id: String,
+    /// 处理状态：pending（已入队）/ completed / failed
+    pub status: String,
+    pub created_at: String,
 }
 
 /// 历史记录结构
@@ -263,12 +1289,159 @@ CREATE TABLE IF NOT EXISTS ai_usage (
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
 );
 
+-- 监视文件夹已处理文件
+CREATE TABLE IF NOT EXISTS watched_files (
+    path TEXT PRIMARY KEY,
+    file_size INTEGER NOT NULL,
+    modified_at INTEGER NOT NULL,
+    task_id TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 媒体指纹（重复检测）
+CREATE TABLE IF NOT EXISTS media_fingerprints (
+    path TEXT PRIMARY KEY,
+    file_size INTEGER NOT NULL,
+    modified_at INTEGER NOT NULL,
+    fingerprint TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 镜头节奏指标
+CREATE TABLE IF NOT EXISTS shot_pacing (
+    path TEXT PRIMARY KEY,
+    author TEXT NOT NULL DEFAULT '',
+    file_size INTEGER NOT NULL,
+    modified_at INTEGER NOT NULL,
+    threshold REAL NOT NULL DEFAULT 0.3,
+    metrics TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 视频评论
+CREATE TABLE IF NOT EXISTS comments (
+    cid TEXT PRIMARY KEY,
+    aweme_id TEXT NOT NULL,
+    parent_id TEXT,
+    text TEXT NOT NULL,
+    author TEXT NOT NULL DEFAULT '',
+    author_id TEXT NOT NULL DEFAULT '',
+    likes INTEGER NOT NULL DEFAULT 0,
+    reply_count INTEGER NOT NULL DEFAULT 0,
+    create_time INTEGER NOT NULL DEFAULT 0,
+    ip_location TEXT NOT NULL DEFAULT '',
+    fetched_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 监控账号
+CREATE TABLE IF NOT EXISTS tracked_accounts (
+    user_id TEXT PRIMARY KEY,
+    nickname TEXT NOT NULL DEFAULT '',
+    enabled INTEGER NOT NULL DEFAULT 1,
+    auto_pipeline INTEGER NOT NULL DEFAULT 0,
+    last_checked_at INTEGER,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 监控账号发现的作品
+CREATE TABLE IF NOT EXISTS account_videos (
+    video_id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    title TEXT NOT NULL DEFAULT '',
+    video_url TEXT NOT NULL,
+    cover_url TEXT,
+    create_time INTEGER,
+    status TEXT NOT NULL DEFAULT 'new',
+    result TEXT,
+    discovered_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 互动数据追踪的作品
+CREATE TABLE IF NOT EXISTS tracked_videos (
+    aweme_id TEXT PRIMARY KEY,
+    title TEXT NOT NULL DEFAULT '',
+    author TEXT NOT NULL DEFAULT '',
+    create_time INTEGER,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    last_attempt_at INTEGER,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 互动数据采样
+CREATE TABLE IF NOT EXISTS video_metrics (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    aweme_id TEXT NOT NULL,
+    sampled_at INTEGER NOT NULL,
+    likes INTEGER NOT NULL DEFAULT 0,
+    comments INTEGER NOT NULL DEFAULT 0,
+    shares INTEGER NOT NULL DEFAULT 0,
+    collects INTEGER NOT NULL DEFAULT 0,
+    plays INTEGER NOT NULL DEFAULT 0
+);
+
+-- 解析渠道尝试记录
+CREATE TABLE IF NOT EXISTS parser_strategy_attempts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    strategy TEXT NOT NULL,
+    success INTEGER NOT NULL,
+    latency_ms INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    attempted_at INTEGER NOT NULL
+);
+
+-- 批量导入解析过的作品
+CREATE TABLE IF NOT EXISTS imported_videos (
+    aweme_id TEXT PRIMARY KEY,
+    link TEXT NOT NULL,
+    title TEXT NOT NULL DEFAULT '',
+    imported_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 从话题 / 音乐页采集的作品
+CREATE TABLE IF NOT EXISTS collected_videos (
+    source_type TEXT NOT NULL,
+    source_id TEXT NOT NULL,
+    aweme_id TEXT NOT NULL,
+    title TEXT NOT NULL DEFAULT '',
+    author TEXT NOT NULL DEFAULT '',
+    video_url TEXT NOT NULL DEFAULT '',
+    cover_url TEXT,
+    create_time INTEGER,
+    likes INTEGER NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'new',
+    task_id TEXT,
+    collected_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (source_type, source_id, aweme_id)
+);
+
+-- 链接解析结果缓存
+CREATE TABLE IF NOT EXISTS parse_cache (
+    aweme_id TEXT PRIMARY KEY,
+    data TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL
+);
+
+-- 链接到作品 ID 的映射（短链接跳转结果）
+CREATE TABLE IF NOT EXISTS parse_cache_links (
+    link TEXT PRIMARY KEY,
+    aweme_id TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
 -- 创建索引
 CREATE INDEX IF NOT EXISTS idx_history_type ON history(type);
 CREATE INDEX IF NOT EXISTS idx_history_status ON history(status);
 CREATE INDEX IF NOT EXISTS idx_history_created_at ON history(created_at);
 CREATE INDEX IF NOT EXISTS idx_documents_category ON documents(category);
 CREATE INDEX IF NOT EXISTS idx_ai_usage_provider ON ai_usage(provider);
+CREATE INDEX IF NOT EXISTS idx_shot_pacing_author ON shot_pacing(author);
+CREATE INDEX IF NOT EXISTS idx_comments_aweme ON comments(aweme_id);
+CREATE INDEX IF NOT EXISTS idx_account_videos_user ON account_videos(user_id);
+CREATE INDEX IF NOT EXISTS idx_video_metrics_aweme ON video_metrics(aweme_id, sampled_at);
+CREATE INDEX IF NOT EXISTS idx_parser_strategy_attempts_at ON parser_strategy_attempts(attempted_at);
+CREATE INDEX IF NOT EXISTS idx_collected_videos_aweme ON collected_videos(aweme_id);
 "#;
 
 #[cfg(test)]
@@ -328,4 +1501,430 @@ mod tests {
         assert_eq!(history[0].id, "test-id-1");
         assert_eq!(history[0].status, "completed");
     }
+
+    #[test]
+    fn test_watched_file_operations() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::init(db_path.to_str().unwrap()).unwrap();
+
+        assert!(!db.is_watched_file_processed("/in/a.mp4", 100, 1).unwrap());
+
+        db.add_watched_file("/in/a.mp4", 100, 1, "task-1").unwrap();
+        assert!(db.is_watched_file_processed("/in/a.mp4", 100, 1).unwrap());
+
+        // 文件被替换后视为新文件
+        assert!(!db.is_watched_file_processed("/in/a.mp4", 200, 2).unwrap());
+
+        let records = db.get_watched_files(10).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].task_id, "task-1");
+        assert_eq!(records[0].status, "pending");
+
+        // 任务结束前视为未完成，转写成功后不再出现在待恢复列表中
+        assert_eq!(db.get_unfinished_watched_files().unwrap().len(), 1);
+        db.set_watched_file_status("/in/a.mp4", 200, 2, "completed").unwrap();
+        assert_eq!(db.get_unfinished_watched_files().unwrap().len(), 1);
+        db.set_watched_file_status("/in/a.mp4", 100, 1, "completed").unwrap();
+        assert!(db.get_unfinished_watched_files().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_media_fingerprint_operations() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::init(db_path.to_str().unwrap()).unwrap();
+
+        db.save_media_fingerprint("/a.mp4", 10, 1, "{}").unwrap();
+        assert_eq!(
+            db.get_media_fingerprint("/a.mp4", 10, 1).unwrap(),
+            Some("{}".to_string())
+        );
+        // 文件变化后缓存失效
+        assert_eq!(db.get_media_fingerprint("/a.mp4", 11, 1).unwrap(), None);
+
+        assert_eq!(db.list_media_fingerprints().unwrap().len(), 1);
+        db.delete_media_fingerprint("/a.mp4").unwrap();
+        assert!(db.list_media_fingerprints().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_shot_pacing_legacy_table_migration() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE shot_pacing (path TEXT PRIMARY KEY, author TEXT NOT NULL DEFAULT '', file_size INTEGER NOT NULL, modified_at INTEGER NOT NULL, metrics TEXT NOT NULL, created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP);
+                 INSERT INTO shot_pacing (path, file_size, modified_at, metrics) VALUES ('/a.mp4', 10, 1, '{}');",
+            )
+            .unwrap();
+        }
+
+        let db = Database::init(db_path.to_str().unwrap()).unwrap();
+        assert_eq!(
+            db.get_shot_pacing("/a.mp4", 10, 1, 0.3).unwrap(),
+            Some("{}".to_string())
+        );
+    }
+
+    #[test]
+    fn test_shot_pacing_operations() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::init(db_path.to_str().unwrap()).unwrap();
+
+        db.save_shot_pacing("/a.mp4", "作者A", 10, 1, 0.3, "{}").unwrap();
+        db.save_shot_pacing("/b.mp4", "作者B", 20, 1, 0.3, "{}").unwrap();
+        assert_eq!(
+            db.get_shot_pacing("/a.mp4", 10, 1, 0.3).unwrap(),
+            Some("{}".to_string())
+        );
+        assert_eq!(db.get_shot_pacing("/a.mp4", 10, 2, 0.3).unwrap(), None);
+        // 不同阈值的结果不能复用
+        assert_eq!(db.get_shot_pacing("/a.mp4", 10, 1, 0.5).unwrap(), None);
+        db.save_shot_pacing("/a.mp4", "作者A", 10, 1, 0.5, "{\"x\":1}").unwrap();
+        assert_eq!(db.get_shot_pacing("/a.mp4", 10, 1, 0.3).unwrap(), None);
+        assert_eq!(
+            db.get_shot_pacing("/a.mp4", 10, 1, 0.5).unwrap(),
+            Some("{\"x\":1}".to_string())
+        );
+
+        assert_eq!(db.list_shot_pacing(None).unwrap().len(), 2);
+        let by_author = db.list_shot_pacing(Some("作者B")).unwrap();
+        assert_eq!(by_author.len(), 1);
+        assert_eq!(by_author[0].0, "/b.mp4");
+
+        db.set_shot_pacing_author("/b.mp4", "作者C").unwrap();
+        assert!(db.list_shot_pacing(Some("作者B")).unwrap().is_empty());
+        assert_eq!(db.list_shot_pacing(Some("作者C")).unwrap().len(), 1);
+        // 只修改创作者，已有结果仍可命中
+        assert_eq!(
+            db.get_shot_pacing("/b.mp4", 20, 1, 0.3).unwrap(),
+            Some("{}".to_string())
+        );
+    }
+
+    #[test]
+    fn test_comment_operations() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::init(db_path.to_str().unwrap()).unwrap();
+
+        let comment = |cid: &str, parent: Option<&str>, likes: u64| CommentRecord {
+            cid: cid.to_string(),
+            aweme_id: "100".to_string(),
+            parent_id: parent.map(|p| p.to_string()),
+            text: "好看".to_string(),
+            author: "用户".to_string(),
+            author_id: "u1".to_string(),
+            likes,
+            reply_count: 0,
+            create_time: 1_700_000_000,
+            ip_location: "北京".to_string(),
+        };
+
+        let saved = db
+            .save_comments(&[
+                comment("r1", Some("c1"), 99),
+                comment("c1", None, 5),
+                comment("c2", None, 8),
+            ])
+            .unwrap();
+        assert_eq!(saved, 3);
+        // 重复抓取时覆盖旧数据
+        db.save_comments(&[comment("c1", None, 6)]).unwrap();
+
+        let records = db.list_comments("100").unwrap();
+        let ids: Vec<&str> = records.iter().map(|r| r.cid.as_str()).collect();
+        assert_eq!(ids, vec!["c2", "c1", "r1"]);
+        assert_eq!(records[1].likes, 6);
+        assert_eq!(records[2].parent_id.as_deref(), Some("c1"));
+
+        db.delete_comments("100").unwrap();
+        assert!(db.list_comments("100").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tracked_account_operations() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::init(db_path.to_str().unwrap()).unwrap();
+
+        db.upsert_tracked_account("sec-1", "创作者A", true, false)
+            .unwrap();
+        db.set_account_checked("sec-1", 1_700_000_000).unwrap();
+        // 更新设置时保留检查时间
+        db.upsert_tracked_account("sec-1", "创作者A", true, true)
+            .unwrap();
+
+        let accounts = db.list_tracked_accounts().unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert!(accounts[0].auto_pipeline);
+        assert_eq!(accounts[0].last_checked_at, Some(1_700_000_000));
+
+        let video = AccountVideoRecord {
+            video_id: "100".to_string(),
+            user_id: "sec-1".to_string(),
+            title: "新作品".to_string(),
+            video_url: "https://www.douyin.com/video/100".to_string(),
+            cover_url: None,
+            create_time: Some(1_700_000_100),
+            status: "new".to_string(),
+            result: None,
+            discovered_at: String::new(),
+        };
+        assert!(db.add_account_video(&video).unwrap());
+        assert!(!db.add_account_video(&video).unwrap());
+        assert_eq!(db.list_account_video_ids("sec-1").unwrap(), vec!["100"]);
+
+        db.update_account_video_status("100", "processing", None)
+            .unwrap();
+        assert_eq!(db.fail_processing_account_videos("中断").unwrap(), 1);
+        let videos = db.list_account_videos(Some("sec-1"), 10).unwrap();
+        assert_eq!(videos[0].status, "failed");
+        assert_eq!(videos[0].result.as_deref(), Some("中断"));
+
+        db.update_account_video_status("100", "completed", Some("{}"))
+            .unwrap();
+        assert_eq!(db.fail_processing_account_videos("中断").unwrap(), 0);
+        let videos = db.list_account_videos(Some("sec-1"), 10).unwrap();
+        assert_eq!(videos[0].status, "completed");
+        assert_eq!(videos[0].result.as_deref(), Some("{}"));
+        assert!(db
+            .list_account_videos(Some("sec-2"), 10)
+            .unwrap()
+            .is_empty());
+
+        db.remove_tracked_account("sec-1").unwrap();
+        assert!(db.list_tracked_accounts().unwrap().is_empty());
+        assert!(db.list_account_videos(None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_video_metric_operations() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::init(db_path.to_str().unwrap()).unwrap();
+
+        db.upsert_tracked_video("100", "作品", "作者", Some(1_700_000_000))
+            .unwrap();
+        // 未知发布时间时保留原值
+        db.upsert_tracked_video("100", "新标题", "作者", None)
+            .unwrap();
+
+        let sample = |sampled_at: i64, likes: u64| MetricSampleRecord {
+            aweme_id: "100".to_string(),
+            sampled_at,
+            likes,
+            comments: 1,
+            shares: 2,
+            collects: 3,
+            plays: 0,
+        };
+        db.add_metric_sample(&sample(1_700_007_200, 50)).unwrap();
+        db.add_metric_sample(&sample(1_700_003_600, 10)).unwrap();
+
+        let videos = db.list_tracked_videos().unwrap();
+        assert_eq!(videos.len(), 1);
+        assert_eq!(videos[0].title, "新标题");
+        assert_eq!(videos[0].create_time, Some(1_700_000_000));
+        assert_eq!(videos[0].last_sampled_at, Some(1_700_007_200));
+
+        let samples = db.list_metric_samples("100").unwrap();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].likes, 10);
+
+        db.record_sample_attempt("100", 1_700_010_000, false)
+            .unwrap();
+        db.record_sample_attempt("100", 1_700_013_600, false)
+            .unwrap();
+        let videos = db.list_tracked_videos().unwrap();
+        assert_eq!(videos[0].failed_attempts, 2);
+        assert_eq!(videos[0].last_attempt_at, Some(1_700_013_600));
+        db.record_sample_attempt("100", 1_700_017_200, true)
+            .unwrap();
+        assert_eq!(db.list_tracked_videos().unwrap()[0].failed_attempts, 0);
+
+        db.set_tracked_video_enabled("100", false).unwrap();
+        assert!(!db.list_tracked_videos().unwrap()[0].enabled);
+        // 重新加入追踪不会恢复用户暂停的采样
+        db.upsert_tracked_video("100", "新标题", "作者", None)
+            .unwrap();
+        assert!(!db.list_tracked_videos().unwrap()[0].enabled);
+
+        db.remove_tracked_video("100").unwrap();
+        assert!(db.list_tracked_videos().unwrap().is_empty());
+        assert!(db.list_metric_samples("100").unwrap().is_empty());
+    }
+    #[test]
+    fn test_strategy_attempt_operations() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::init(db_path.to_str().unwrap()).unwrap();
+
+        let attempt =
+            |strategy: &str, error: Option<&str>, attempted_at: i64| StrategyAttemptRecord {
+                strategy: strategy.to_string(),
+                success: error.is_none(),
+                latency_ms: 120,
+                error: error.map(|e| e.to_string()),
+                attempted_at,
+            };
+        let saved = db
+            .add_strategy_attempts(&[
+                attempt("sidecar", Some("连接失败"), 200),
+                attempt("detail_api", None, 100),
+                attempt("item_info", None, 300),
+            ])
+            .unwrap();
+        assert_eq!(saved, 3);
+
+        let recent = db.list_strategy_attempts(150).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].strategy, "sidecar");
+        assert!(!recent[0].success);
+        assert_eq!(recent[0].error.as_deref(), Some("连接失败"));
+        assert_eq!(recent[0].latency_ms, 120);
+
+        assert_eq!(db.prune_strategy_attempts(250).unwrap(), 2);
+        assert_eq!(db.list_strategy_attempts(0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_library_aweme_ids() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::init(db_path.to_str().unwrap()).unwrap();
+        assert!(db.list_library_aweme_ids().unwrap().is_empty());
+
+        let saved = db
+            .add_imported_videos(&[
+                ImportedVideoRecord {
+                    aweme_id: "100".to_string(),
+                    link: "https://v.douyin.com/abc/".to_string(),
+                    title: "第一条".to_string(),
+                },
+                ImportedVideoRecord {
+                    aweme_id: "200".to_string(),
+                    link: "https://www.douyin.com/video/200".to_string(),
+                    title: String::new(),
+                },
+            ])
+            .unwrap();
+        assert_eq!(saved, 2);
+        db.upsert_tracked_video("300", "追踪", "作者", None)
+            .unwrap();
+
+        // 监控账号的作品只有处理完成（已下载）后才计入资料库
+        let account_video = |video_id: &str, status: &str| AccountVideoRecord {
+            video_id: video_id.to_string(),
+            user_id: "sec-1".to_string(),
+            title: String::new(),
+            video_url: format!("https://www.douyin.com/video/{}", video_id),
+            cover_url: None,
+            create_time: None,
+            status: status.to_string(),
+            result: None,
+            discovered_at: String::new(),
+        };
+        db.add_account_video(&account_video("400", "existing"))
+            .unwrap();
+        db.add_account_video(&account_video("500", "new")).unwrap();
+        db.update_account_video_status("500", "completed", None)
+            .unwrap();
+
+        let ids = db.list_library_aweme_ids().unwrap();
+        assert_eq!(ids.len(), 3);
+        assert!(ids.contains("100") && ids.contains("200") && ids.contains("500"));
+        assert!(!ids.contains("300") && !ids.contains("400"));
+    }
+
+    #[test]
+    fn test_collected_video_operations() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::init(db_path.to_str().unwrap()).unwrap();
+
+        let video = |aweme_id: &str, likes: i64| CollectedVideoRecord {
+            source_type: "challenge".to_string(),
+            source_id: "1580000000000001".to_string(),
+            aweme_id: aweme_id.to_string(),
+            title: "露营装备".to_string(),
+            author: "露营小王".to_string(),
+            video_url: format!("https://www.douyin.com/video/{}", aweme_id),
+            cover_url: None,
+            create_time: Some(1_700_000_000),
+            likes,
+            status: "new".to_string(),
+            task_id: None,
+            collected_at: String::new(),
+        };
+        assert_eq!(
+            db.save_collected_videos(&[video("100", 10), video("200", 50)])
+                .unwrap(),
+            2
+        );
+        db.update_collected_video_status(
+            "challenge",
+            "1580000000000001",
+            "100",
+            "queued",
+            Some("task-1"),
+        )
+        .unwrap();
+        // 重新采集时刷新点赞数，保留处理状态
+        db.save_collected_videos(&[video("100", 99)]).unwrap();
+
+        let videos = db
+            .list_collected_videos(Some("challenge"), Some("1580000000000001"), 10)
+            .unwrap();
+        assert_eq!(videos.len(), 2);
+        assert_eq!(videos[0].aweme_id, "100");
+        assert_eq!(videos[0].likes, 99);
+        assert_eq!(videos[0].status, "queued");
+        assert_eq!(videos[0].task_id.as_deref(), Some("task-1"));
+        assert!(db
+            .list_collected_videos(Some("music"), None, 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_cache_operations() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::init(db_path.to_str().unwrap()).unwrap();
+        assert!(db.get_parse_cache("100").unwrap().is_none());
+
+        let mut record = ParseCacheRecord {
+            aweme_id: "100".to_string(),
+            data: "{\"title\":\"旧\"}".to_string(),
+            fetched_at: 1000,
+        };
+        db.save_parse_cache(&record).unwrap();
+        record.data = "{\"title\":\"新\"}".to_string();
+        record.fetched_at = 2000;
+        db.save_parse_cache(&record).unwrap();
+
+        let cached = db.get_parse_cache("100").unwrap().unwrap();
+        assert_eq!(cached.fetched_at, 2000);
+        assert!(cached.data.contains("新"));
+
+        db.save_cached_link("https://v.douyin.com/abc", "100")
+            .unwrap();
+        assert_eq!(
+            db.get_cached_link("https://v.douyin.com/abc")
+                .unwrap()
+                .as_deref(),
+            Some("100")
+        );
+
+        assert_eq!(db.clear_parse_cache().unwrap(), 1);
+        assert!(db.get_parse_cache("100").unwrap().is_none());
+        assert!(db
+            .get_cached_link("https://v.douyin.com/abc")
+            .unwrap()
+            .is_none());
+    }
 }
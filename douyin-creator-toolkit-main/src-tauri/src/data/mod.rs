@@ -5,5 +5,9 @@ pub mod config;
 pub mod task_queue;
 
 // 重新导出常用类型
-pub use database::{Database, DbError, HistoryRecord};
+pub use database::{
+    AccountVideoRecord, CollectedVideoRecord, CommentRecord, Database, DbError, HistoryRecord,
+    ImportedVideoRecord, MetricSampleRecord, ParseCacheRecord, StrategyAttemptRecord,
+    TrackedAccountRecord, TrackedVideoRecord, WatchedFileRecord,
+};
 pub use config::{AppConfig, ConfigError, ConfigManager, config_keys, get_default_db_path};
@@ -53,6 +53,14 @@ impl TaskType {
             TaskType::AiAnalysis { .. } => "AI 分析".to_string(),
         }
     }
+
+    /// 是否需要访问抖音（限流熔断时暂停）
+    pub fn is_network(&self) -> bool {
+        matches!(
+            self,
+            TaskType::LinkParsing { .. } | TaskType::VideoDownload { .. }
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -151,6 +159,8 @@ pub struct TaskQueue {
     current: Arc<RwLock<Option<Task>>>,
     history: Arc<RwLock<Vec<Task>>>,
     max_history: usize,
+    /// 因限流熔断而暂停的网络任务 ID
+    network_paused: Arc<RwLock<Vec<String>>>,
 }
 
 impl TaskQueue {
@@ -161,6 +171,7 @@ impl TaskQueue {
             current: Arc::new(RwLock::new(None)),
             history: Arc::new(RwLock::new(Vec::new())),
             max_history: 100,
+            network_paused: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
@@ -171,6 +182,7 @@ impl TaskQueue {
             current: Arc::new(RwLock::new(None)),
             history: Arc::new(RwLock::new(Vec::new())),
             max_history,
+            network_paused: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
@@ -199,21 +211,69 @@ impl TaskQueue {
         ids
     }
 
+    /// 添加任务并直接标记为运行中（由调用方自行执行，后台执行器不会领取）
+    pub async fn add_started_task(&self, task_type: TaskType) -> String {
+        let mut task = Task::new(task_type);
+        task.start();
+        let id = task.id.clone();
+
+        let mut tasks = self.tasks.write().await;
+        tasks.push_back(task);
+
+        id
+    }
+
+    /// 领取队列中第一个待处理任务并标记为运行中（供后台执行器使用）
+    ///
+    /// `skip_network` 为 true 时跳过网络任务（限流熔断期间），它们保持待处理状态
+    pub async fn claim_next_pending(&self, skip_network: bool) -> Option<Task> {
+        let mut tasks = self.tasks.write().await;
+        let task = tasks.iter_mut().find(|t| {
+            t.status == TaskStatus::Pending && !(skip_network && t.task_type.is_network())
+        })?;
+        task.start();
+        Some(task.clone())
+    }
+
+    /// 将失败或已取消的历史任务重新加入队列，返回新任务 ID
+    pub async fn retry_task(&self, task_id: &str) -> Result<String, QueueError> {
+        let task_type = {
+            let history = self.history.read().await;
+            let task = history
+                .iter()
+                .find(|t| t.id == task_id)
+                .ok_or_else(|| QueueError::TaskNotFound(task_id.to_string()))?;
+            if !matches!(task.status, TaskStatus::Failed(_) | TaskStatus::Cancelled) {
+                return Err(QueueError::InvalidStateTransition(format!(
+                    "任务状态 {:?} 不能重试",
+                    task.status
+                )));
+            }
+            task.task_type.clone()
+        };
+
+        Ok(self.add_task(task_type).await)
+    }
+
     /// 暂停任务
     pub async fn pause_task(&self, task_id: &str) -> Result<(), QueueError> {
         let mut current = self.current.write().await;
+        let mut tasks = self.tasks.write().await;
 
-        if let Some(ref mut task) = *current {
-            if task.id == task_id {
-                if task.status.can_pause() {
-                    task.status = TaskStatus::Paused;
-                    return Ok(());
-                } else {
-                    return Err(QueueError::InvalidStateTransition(format!(
-                        "任务状态 {:?} 不能暂停",
-                        task.status
-                    )));
-                }
+        // 当前任务或按 ID 并发执行的任务
+        if let Some(task) = current
+            .iter_mut()
+            .chain(tasks.iter_mut())
+            .find(|t| t.id == task_id)
+        {
+            if task.status.can_pause() {
+                task.status = TaskStatus::Paused;
+                return Ok(());
+            } else {
+                return Err(QueueError::InvalidStateTransition(format!(
+                    "任务状态 {:?} 不能暂停",
+                    task.status
+                )));
             }
         }
 
@@ -223,18 +283,22 @@ impl TaskQueue {
     /// 继续任务
     pub async fn resume_task(&self, task_id: &str) -> Result<(), QueueError> {
         let mut current = self.current.write().await;
+        let mut tasks = self.tasks.write().await;
 
-        if let Some(ref mut task) = *current {
-            if task.id == task_id {
-                if task.status.can_resume() {
-                    task.status = TaskStatus::Running;
-                    return Ok(());
-                } else {
-                    return Err(QueueError::InvalidStateTransition(format!(
-                        "任务状态 {:?} 不能继续",
-                        task.status
-                    )));
-                }
+        // 当前任务或按 ID 并发执行的任务
+        if let Some(task) = current
+            .iter_mut()
+            .chain(tasks.iter_mut())
+            .find(|t| t.id == task_id)
+        {
+            if task.status.can_resume() {
+                task.status = TaskStatus::Running;
+                return Ok(());
+            } else {
+                return Err(QueueError::InvalidStateTransition(format!(
+                    "任务状态 {:?} 不能继续",
+                    task.status
+                )));
             }
         }
 
@@ -484,6 +548,43 @@ impl TaskQueue {
         self.tasks.write().await.clear();
     }
 
+    /// 暂停正在运行的网络任务（限流熔断时调用），返回本次新暂停的任务 ID
+    pub async fn pause_network_tasks(&self) -> Vec<String> {
+        let mut paused = Vec::new();
+        {
+            let mut current = self.current.write().await;
+            let mut tasks = self.tasks.write().await;
+            for task in current.iter_mut().chain(tasks.iter_mut()) {
+                if task.task_type.is_network() && task.status.can_pause() {
+                    task.status = TaskStatus::Paused;
+                    paused.push(task.id.clone());
+                }
+            }
+        }
+
+        self.network_paused
+            .write()
+            .await
+            .extend(paused.iter().cloned());
+        paused
+    }
+
+    /// 恢复因限流熔断暂停的网络任务，返回恢复的任务 ID（用户手动暂停的任务不受影响）
+    pub async fn resume_network_tasks(&self) -> Vec<String> {
+        let ids = std::mem::take(&mut *self.network_paused.write().await);
+        let mut resumed = Vec::new();
+
+        let mut current = self.current.write().await;
+        let mut tasks = self.tasks.write().await;
+        for task in current.iter_mut().chain(tasks.iter_mut()) {
+            if ids.contains(&task.id) && task.status.can_resume() {
+                task.status = TaskStatus::Running;
+                resumed.push(task.id.clone());
+            }
+        }
+        resumed
+    }
+
     /// 添加到历史记录
     async fn add_to_history(&self, task: Task) {
         let mut history = self.history.write().await;
@@ -624,4 +725,140 @@ mod tests {
         let status = queue.get_task_status(&id).await;
         assert_eq!(status, Some(TaskStatus::Cancelled));
     }
+
+    #[tokio::test]
+    async fn test_claim_skips_started_tasks() {
+        let queue = TaskQueue::new();
+
+        let inline = queue
+            .add_started_task(TaskType::AiAnalysis {
+                content: "inline".to_string(),
+                video_id: "v1".to_string(),
+            })
+            .await;
+        let queued = queue
+            .add_task(TaskType::AiAnalysis {
+                content: "queued".to_string(),
+                video_id: "v2".to_string(),
+            })
+            .await;
+
+        let claimed = queue.claim_next_pending(false).await.unwrap();
+        assert_eq!(claimed.id, queued);
+        assert_eq!(claimed.status, TaskStatus::Running);
+        assert!(queue.claim_next_pending(false).await.is_none());
+        assert_eq!(
+            queue.get_task_status(&inline).await,
+            Some(TaskStatus::Running)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_claim_skips_network_tasks_while_paused() {
+        let queue = TaskQueue::new();
+
+        let download = queue
+            .add_task(TaskType::VideoDownload {
+                url: "https://www.douyin.com/video/1".to_string(),
+                output_path: "/tmp/1.mp4".to_string(),
+                video_name: "1.mp4".to_string(),
+            })
+            .await;
+        let analysis = queue
+            .add_task(TaskType::AiAnalysis {
+                content: "test".to_string(),
+                video_id: "v1".to_string(),
+            })
+            .await;
+
+        // 熔断期间先领取本地任务，网络任务保持待处理
+        assert_eq!(queue.claim_next_pending(true).await.unwrap().id, analysis);
+        assert!(queue.claim_next_pending(true).await.is_none());
+        assert_eq!(
+            queue.get_task_status(&download).await,
+            Some(TaskStatus::Pending)
+        );
+        assert_eq!(queue.claim_next_pending(false).await.unwrap().id, download);
+    }
+
+    #[tokio::test]
+    async fn test_pause_concurrent_task() {
+        let queue = TaskQueue::new();
+
+        let id = queue
+            .add_started_task(TaskType::AiAnalysis {
+                content: "test".to_string(),
+                video_id: "v1".to_string(),
+            })
+            .await;
+
+        queue.pause_task(&id).await.unwrap();
+        assert_eq!(queue.get_task_status(&id).await, Some(TaskStatus::Paused));
+        assert!(queue.pause_task(&id).await.is_err());
+        queue.resume_task(&id).await.unwrap();
+        assert_eq!(queue.get_task_status(&id).await, Some(TaskStatus::Running));
+    }
+
+    #[tokio::test]
+    async fn test_retry_failed_task() {
+        let queue = TaskQueue::new();
+
+        let id = queue
+            .add_task(TaskType::AiAnalysis {
+                content: "test".to_string(),
+                video_id: "v1".to_string(),
+            })
+            .await;
+        assert!(queue.retry_task(&id).await.is_err());
+
+        queue.start_task_by_id(&id).await.unwrap();
+        queue.fail_task_by_id(&id, "boom".to_string()).await;
+
+        let retried = queue.retry_task(&id).await.unwrap();
+        assert_ne!(retried, id);
+        assert_eq!(
+            queue.get_task_status(&retried).await,
+            Some(TaskStatus::Pending)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pause_network_tasks() {
+        let queue = TaskQueue::new();
+
+        let download = queue
+            .add_task(TaskType::VideoDownload {
+                url: "https://www.douyin.com/video/1".to_string(),
+                output_path: "/tmp/1.mp4".to_string(),
+                video_name: "1.mp4".to_string(),
+            })
+            .await;
+        let analysis = queue
+            .add_task(TaskType::AiAnalysis {
+                content: "test".to_string(),
+                video_id: "v1".to_string(),
+            })
+            .await;
+        queue.start_task_by_id(&download).await.unwrap();
+        queue.start_task_by_id(&analysis).await.unwrap();
+
+        // 只暂停网络任务，重复调用不会重复记录
+        assert_eq!(queue.pause_network_tasks().await, vec![download.clone()]);
+        assert!(queue.pause_network_tasks().await.is_empty());
+        assert_eq!(
+            queue.get_task_status(&download).await,
+            Some(TaskStatus::Paused)
+        );
+        assert_eq!(
+            queue.get_task_status(&analysis).await,
+            Some(TaskStatus::Running)
+        );
+
+        assert_eq!(queue.resume_network_tasks().await, vec![download.clone()]);
+        assert_eq!(
+            queue.get_task_status(&download).await,
+            Some(TaskStatus::Running)
+        );
+        assert!(queue.resume_network_tasks().await.is_empty());
+    }
 }
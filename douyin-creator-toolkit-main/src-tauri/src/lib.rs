@@ -59,11 +59,33 @@ pub fn run() {
         .plugin(tauri_plugin_notification::init())
         .setup(|app| {
             // 初始化数据层（数据库和配置管理器）- 放入后台线程避免阻塞 UI
+            let watcher_handle = app.handle().clone();
             tauri::async_runtime::spawn_blocking(move || {
                 if let Err(e) = commands::settings::init_data_layer() {
                     eprintln!("数据层初始化失败: {}", e);
                     // 继续运行，但功能可能受限
                 }
+
+                // 链接解析结果缓存改为保存到数据库
+                commands::mcp::install_parse_cache();
+
+                // 启动监视文件夹（依赖数据库中的配置和处理记录）
+                commands::watcher::start_folder_watcher(watcher_handle.clone());
+
+                // 启动账号监控（依赖数据库中的账号和作品记录）
+                commands::monitor::start_account_monitor(watcher_handle.clone());
+
+                // 启动互动数据采样（依赖数据库中的追踪作品）
+                commands::metrics::start_metrics_sampler(watcher_handle.clone());
+
+                // 启动解析渠道健康度记录（依赖数据库中的历史尝试记录）
+                commands::parser_health::start_parser_health_recorder(watcher_handle.clone());
+
+                // 启动任务执行器（执行队列中的待处理任务，包括新作品自动处理）
+                commands::task_queue::start_task_runner(watcher_handle.clone());
+
+                // 启动限流熔断监控（熔断时暂停队列中的网络任务）
+                commands::rate_limit::start_rate_limit_monitor(watcher_handle);
             });
 
             // 初始化系统托盘
@@ -129,6 +151,13 @@ pub fn run() {
             commands::video::transcribe_video,
             commands::video::transcribe_videos_batch,
             commands::video::generate_thumbnail,
+            commands::video::generate_cover_candidates,
+            commands::video::extract_on_screen_text,
+            commands::video::check_video_duplicates,
+            commands::video::list_duplicate_clusters,
+            commands::video::analyze_shot_pacing,
+            commands::video::list_shot_pacing,
+            commands::video::compare_creator_pacing,
             commands::video::cleanup_temp_files,
             commands::video::export_transcripts_to_docx,
             commands::video::export_transcripts_to_txt,
@@ -147,15 +176,28 @@ pub fn run() {
             commands::mcp::parse_douyin_links_batch,
             commands::mcp::search_douyin_videos,
             commands::mcp::get_user_videos,
+            commands::mcp::get_user_videos_page,
             commands::mcp::update_mcp_config,
             commands::mcp::get_mcp_config,
+            commands::mcp::clear_parse_cache,
             commands::mcp::check_dy_mcp_health,
             commands::mcp::check_undoom_mcp_health,
             commands::mcp::validate_douyin_link,
             commands::mcp::extract_douyin_content,
+            commands::mcp::import_douyin_cookies,
+            commands::mcp::get_douyin_session_status,
+            commands::mcp::clear_douyin_session,
+            commands::mcp::fetch_douyin_comments,
+            commands::mcp::get_douyin_comments,
+            commands::mcp::analyze_douyin_comments,
+            commands::mcp::export_douyin_comments_csv,
+            // 批量链接导入命令
+            commands::link_import::preview_link_import,
+            commands::link_import::import_links,
             // AI 分析命令
             commands::ai::analyze_content,
             commands::ai::check_lm_studio,
+            commands::ai::check_custom_ai_provider,
             commands::ai::init_knowledge_base,
             commands::ai::add_document_to_kb,
             commands::ai::search_knowledge_base,
@@ -170,7 +212,13 @@ pub fn run() {
             commands::ai::download_embedding_model,
             commands::ai::delete_embedding_model,
             commands::ai::open_embedding_models_dir,
+            commands::ai::get_ocr_model_status,
+            commands::ai::download_ocr_model,
+            commands::ai::delete_ocr_model,
             commands::ai::chat_with_ai,
+            commands::ai::chat_with_ai_stream,
+            commands::ai::analyze_content_stream,
+            commands::ai::cancel_ai_stream,
             // 设置命令
             commands::settings::get_settings,
             commands::settings::save_settings,
@@ -195,6 +243,7 @@ pub fn run() {
             commands::task_queue::add_link_parsing_task,
             commands::task_queue::add_download_task,
             commands::task_queue::add_analysis_task,
+            commands::task_queue::retry_task,
             commands::task_queue::pause_task,
             commands::task_queue::resume_task,
             commands::task_queue::cancel_task,
@@ -207,6 +256,44 @@ pub fn run() {
             commands::task_queue::get_current_task,
             commands::task_queue::clear_task_history,
             commands::task_queue::clear_pending_tasks,
+            // 监视文件夹命令
+            commands::watcher::get_watch_config,
+            commands::watcher::save_watch_config,
+            commands::watcher::get_watched_files,
+            // 账号监控命令
+            commands::monitor::get_monitor_config,
+            commands::monitor::save_monitor_config,
+            commands::monitor::add_tracked_account,
+            commands::monitor::remove_tracked_account,
+            commands::monitor::list_tracked_accounts,
+            commands::monitor::list_account_videos,
+            commands::monitor::check_tracked_accounts_now,
+            // 互动数据追踪命令
+            commands::metrics::track_video_metrics,
+            commands::metrics::untrack_video_metrics,
+            commands::metrics::set_video_metrics_enabled,
+            commands::metrics::list_video_metrics,
+            commands::metrics::get_video_metric_curve,
+            commands::metrics::sample_video_metrics_now,
+            // 解析渠道健康度命令
+            commands::parser_health::get_parser_health,
+            // 多平台解析命令
+            commands::platform::detect_video_platform,
+            commands::platform::list_supported_platforms,
+            commands::platform::parse_video_link,
+            // 请求限速命令
+            commands::rate_limit::get_rate_limit_status,
+            commands::rate_limit::reset_rate_limiter,
+            // 直播录制命令
+            commands::live::resolve_live_room,
+            commands::live::start_live_recording,
+            commands::live::stop_live_recording,
+            commands::live::list_live_recordings,
+            // 话题 / 音乐作品采集命令
+            commands::collection::fetch_challenge_videos,
+            commands::collection::fetch_music_videos,
+            commands::collection::list_collected_videos,
+            commands::collection::transcribe_collected_videos,
             // 托盘命令
             commands::tray::show_window,
             commands::tray::hide_to_tray,
@@ -276,6 +276,11 @@ impl FfmpegWrapper {
         &self.ffprobe_path
     }
 
+    /// 创建 FFmpeg 命令（Windows 下隐藏控制台窗口），供需要自行管理长时间运行进程的调用方使用
+    pub fn ffmpeg_command(&self) -> Command {
+        Self::create_hidden_command(&self.ffmpeg_path)
+    }
+
     /// 检查 FFmpeg 是否可用
     pub fn is_available(&self) -> bool {
         Self::create_hidden_command(&self.ffmpeg_path)
@@ -730,6 +735,215 @@ impl FfmpegWrapper {
         Ok(output.stdout)
     }
 
+    /// 截取单帧灰度原始像素（用于画面质量分析）
+    ///
+    /// # Arguments
+    /// * `input` - 输入视频文件路径
+    /// * `time_seconds` - 截取时间点（秒）
+    /// * `width` - 缩放后的宽度
+    /// * `height` - 缩放后的高度
+    ///
+    /// # Returns
+    /// * `Vec<u8>` - 按行排列的 8 位灰度像素，长度为 width * height
+    pub fn extract_gray_frame(
+        &self,
+        input: &Path,
+        time_seconds: f32,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>, FfmpegError> {
+        self.extract_raw_frame(input, time_seconds, width, height, "gray", 1)
+    }
+
+    /// 截取单帧 BGR 原始像素（用于 OCR）
+    ///
+    /// # Returns
+    /// * `Vec<u8>` - 按行排列的 BGR24 像素，长度为 width * height * 3
+    pub fn extract_bgr_frame(
+        &self,
+        input: &Path,
+        time_seconds: f32,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>, FfmpegError> {
+        self.extract_raw_frame(input, time_seconds, width, height, "bgr24", 3)
+    }
+
+    /// 按指定像素格式截取单帧原始像素
+    fn extract_raw_frame(
+        &self,
+        input: &Path,
+        time_seconds: f32,
+        width: u32,
+        height: u32,
+        pix_fmt: &str,
+        bytes_per_pixel: usize,
+    ) -> Result<Vec<u8>, FfmpegError> {
+        if !input.exists() {
+            return Err(FfmpegError::FileNotFound(input.display().to_string()));
+        }
+
+        let output = Self::create_hidden_command(&self.ffmpeg_path)
+            .args([
+                "-ss",
+                &time_seconds.to_string(),
+                "-i",
+                input.to_str().unwrap(),
+                "-vframes",
+                "1",
+                "-vf",
+                &format!("scale={}:{}", width, height),
+                "-pix_fmt",
+                pix_fmt,
+                "-f",
+                "rawvideo",
+                "-",
+            ])
+            .output()
+            .map_err(|e| FfmpegError::ExecutionFailed(e.to_string()))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(FfmpegError::ExecutionFailed(format!(
+                "帧提取失败: {}",
+                stderr.lines().last().unwrap_or("未知错误")
+            )));
+        }
+
+        let expected = width as usize * height as usize * bytes_per_pixel;
+        if output.stdout.len() < expected {
+            return Err(FfmpegError::ParseFailed(format!(
+                "帧数据长度不足: {} < {}",
+                output.stdout.len(),
+                expected
+            )));
+        }
+
+        let mut pixels = output.stdout;
+        pixels.truncate(expected);
+        Ok(pixels)
+    }
+
+    /// 场景切换检测
+    ///
+    /// # Arguments
+    /// * `input` - 输入视频文件路径
+    /// * `threshold` - 场景变化阈值（0.0 - 1.0，越小越敏感）
+    ///
+    /// # Returns
+    /// * `Vec<f64>` - 每个镜头切换点的时间（秒），升序
+    pub fn detect_scene_changes(
+        &self,
+        input: &Path,
+        threshold: f32,
+    ) -> Result<Vec<f64>, FfmpegError> {
+        if !input.exists() {
+            return Err(FfmpegError::FileNotFound(input.display().to_string()));
+        }
+
+        let output = Self::create_hidden_command(&self.ffmpeg_path)
+            .args([
+                "-i",
+                input.to_str().unwrap(),
+                "-vf",
+                &format!("select='gt(scene,{})',showinfo", threshold),
+                "-an",
+                "-f",
+                "null",
+                "-",
+            ])
+            .output()
+            .map_err(|e| FfmpegError::ExecutionFailed(e.to_string()))?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() {
+            return Err(FfmpegError::ExecutionFailed(format!(
+                "场景检测失败: {}",
+                stderr.lines().last().unwrap_or("未知错误")
+            )));
+        }
+
+        Ok(Self::parse_scene_timestamps(&stderr))
+    }
+
+    /// 从 showinfo 滤镜输出中解析 pts_time
+    fn parse_scene_timestamps(stderr: &str) -> Vec<f64> {
+        let mut timestamps: Vec<f64> = stderr
+            .lines()
+            .filter(|line| line.contains("showinfo"))
+            .filter_map(|line| {
+                let start = line.find("pts_time:")? + "pts_time:".len();
+                line[start..].split_whitespace().next()?.parse().ok()
+            })
+            .collect();
+
+        timestamps.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        timestamps.dedup();
+        timestamps
+    }
+
+    /// 为媒体文件写入元数据（流复制，不重新编码），原地替换文件
+    ///
+    /// # Arguments
+    /// * `input` - 视频或音频文件路径
+    /// * `metadata` - 元数据键值对（如 title、artist、date）
+    pub fn embed_metadata(
+        &self,
+        input: &Path,
+        metadata: &[(String, String)],
+    ) -> Result<(), FfmpegError> {
+        if !input.exists() {
+            return Err(FfmpegError::FileNotFound(input.display().to_string()));
+        }
+
+        let ext = input.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+        let temp_output = input.with_extension(format!("tagging.{}", ext));
+
+        let mut args: Vec<String> = vec![
+            "-i".to_string(),
+            input.to_str().unwrap().to_string(),
+            "-map".to_string(),
+            "0".to_string(),
+            "-c".to_string(),
+            "copy".to_string(),
+            "-map_metadata".to_string(),
+            "0".to_string(),
+        ];
+        for (key, value) in metadata {
+            args.push("-metadata".to_string());
+            args.push(format!("{}={}", key, value));
+        }
+        // MP4 / MOV 保留自定义键（如 aweme_id），并把 moov 前置便于边下边播；
+        // 其他封装（如 WAV）不支持该选项
+        if matches!(ext.to_lowercase().as_str(), "mp4" | "mov" | "m4a") {
+            args.push("-movflags".to_string());
+            args.push("use_metadata_tags+faststart".to_string());
+        }
+        args.extend([
+            "-y".to_string(),
+            temp_output.to_str().unwrap().to_string(),
+        ]);
+
+        let output = Self::create_hidden_command(&self.ffmpeg_path)
+            .args(&args)
+            .output()
+            .map_err(|e| FfmpegError::ExecutionFailed(e.to_string()))?;
+
+        if !output.status.success() {
+            let _ = std::fs::remove_file(&temp_output);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(FfmpegError::ExecutionFailed(format!(
+                "写入元数据失败: {}",
+                stderr.lines().last().unwrap_or("未知错误")
+            )));
+        }
+
+        std::fs::rename(&temp_output, input).map_err(|e| {
+            let _ = std::fs::remove_file(&temp_output);
+            FfmpegError::ExecutionFailed(format!("替换文件失败: {}", e))
+        })
+    }
+
     /// 检查文件是否为支持的视频格式
     pub fn is_supported_video_format(path: &Path) -> bool {
         const SUPPORTED_FORMATS: &[&str] = &["mp4", "mov", "avi", "mkv", "webm"];
@@ -791,6 +1005,20 @@ mod tests {
         assert_eq!(FfmpegWrapper::parse_frame_rate("29.97"), Some(29.97));
     }
 
+    #[test]
+    fn test_parse_scene_timestamps() {
+        let stderr = "\
+[Parsed_showinfo_1 @ 0x1] n:   0 pts:  64000 pts_time:2.5     duration:512 fmt:yuv420p
+frame=  120 fps=0.0 q=-0.0 size=N/A time=00:00:04.00
+[Parsed_showinfo_1 @ 0x1] n:   1 pts: 179200 pts_time:7       duration:512 fmt:yuv420p
+";
+        assert_eq!(
+            FfmpegWrapper::parse_scene_timestamps(stderr),
+            vec![2.5, 7.0]
+        );
+        assert!(FfmpegWrapper::parse_scene_timestamps("").is_empty());
+    }
+
     #[test]
     fn test_is_supported_video_format() {
         assert!(FfmpegWrapper::is_supported_video_format(Path::new(
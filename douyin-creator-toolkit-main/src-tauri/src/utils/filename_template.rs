@@ -0,0 +1,149 @@
+// 下载文件命名模板
+// 根据视频来源信息渲染形如 `{author}/{date}_{title}_{aweme_id}.mp4` 的相对路径
+
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::utils::paths::PathResolver;
+
+/// 默认命名模板
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{author}/{date}_{title}_{aweme_id}.mp4";
+
+/// 标题在文件名中的最大字符数
+const MAX_TITLE_CHARS: usize = 60;
+
+/// 视频来源信息（用于命名和写入 MP4 元数据）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceMetadata {
+    pub aweme_id: String,
+    pub title: String,
+    pub author: String,
+    pub source_url: String,
+    /// 发布时间（Unix 秒）
+    pub create_time: Option<u64>,
+}
+
+impl SourceMetadata {
+    /// 发布日期 YYYY-MM-DD
+    pub fn publish_date(&self) -> Option<String> {
+        self.create_time
+            .and_then(|ts| Local.timestamp_opt(ts as i64, 0).single())
+            .map(|dt| dt.format("%Y-%m-%d").to_string())
+    }
+
+    /// 转换为 ffmpeg `-metadata` 键值对
+    pub fn to_ffmpeg_metadata(&self) -> Vec<(String, String)> {
+        let mut pairs = vec![
+            ("title".to_string(), self.title.clone()),
+            ("artist".to_string(), self.author.clone()),
+            ("comment".to_string(), self.source_url.clone()),
+            ("aweme_id".to_string(), self.aweme_id.clone()),
+            ("source_url".to_string(), self.source_url.clone()),
+        ];
+        if let Some(date) = self.publish_date() {
+            pairs.push(("date".to_string(), date));
+        }
+        pairs.retain(|(_, v)| !v.is_empty());
+        pairs
+    }
+}
+
+/// 渲染命名模板，返回相对路径
+///
+/// 支持的占位符：`{author}` `{title}` `{aweme_id}` `{date}`
+/// 每一级目录/文件名都会经过 `PathResolver::sanitize_filename` 处理
+pub fn render_template(template: &str, meta: &SourceMetadata) -> PathBuf {
+    let template = if template.trim().is_empty() {
+        DEFAULT_FILENAME_TEMPLATE
+    } else {
+        template
+    };
+
+    let title: String = meta.title.trim().chars().take(MAX_TITLE_CHARS).collect();
+    let replace = |segment: &str| {
+        segment
+            .replace("{author}", or_unknown(&meta.author))
+            .replace("{title}", or_unknown(&title))
+            .replace("{aweme_id}", or_unknown(&meta.aweme_id))
+            .replace(
+                "{date}",
+                &meta.publish_date().unwrap_or_else(|| "unknown".to_string()),
+            )
+    };
+
+    let mut path = PathBuf::new();
+    for segment in template.split(['/', '\\']) {
+        let rendered = replace(segment);
+        if rendered.trim().is_empty() || rendered == "." || rendered == ".." {
+            continue;
+        }
+        path.push(PathResolver::sanitize_filename(rendered.trim()));
+    }
+
+    if path.extension().is_none() {
+        path.set_extension("mp4");
+    }
+    path
+}
+
+/// 在输出目录下按模板解析完整路径
+pub fn resolve_output_path(output_dir: &Path, template: &str, meta: &SourceMetadata) -> PathBuf {
+    output_dir.join(render_template(template, meta))
+}
+
+fn or_unknown(value: &str) -> &str {
+    if value.trim().is_empty() {
+        "unknown"
+    } else {
+        value.trim()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> SourceMetadata {
+        SourceMetadata {
+            aweme_id: "7300000000000000000".to_string(),
+            title: "好物分享: 第1期?".to_string(),
+            author: "创作者/A".to_string(),
+            source_url: "https://v.douyin.com/abc/".to_string(),
+            create_time: Some(1_700_000_000),
+        }
+    }
+
+    #[test]
+    fn test_render_default_template() {
+        let meta = sample();
+        let path = render_template(DEFAULT_FILENAME_TEMPLATE, &meta);
+        let date = meta.publish_date().unwrap();
+
+        assert_eq!(
+            path,
+            PathBuf::from("创作者_A")
+                .join(format!("{}_好物分享_ 第1期__7300000000000000000.mp4", date))
+        );
+    }
+
+    #[test]
+    fn test_render_rejects_traversal_and_adds_extension() {
+        let meta = SourceMetadata {
+            title: "..".to_string(),
+            ..Default::default()
+        };
+        let path = render_template("../{title}/{aweme_id}", &meta);
+        assert_eq!(path, PathBuf::from("unknown.mp4"));
+    }
+
+    #[test]
+    fn test_to_ffmpeg_metadata_skips_empty() {
+        let meta = SourceMetadata {
+            title: "标题".to_string(),
+            ..Default::default()
+        };
+        let pairs = meta.to_ffmpeg_metadata();
+        assert_eq!(pairs, vec![("title".to_string(), "标题".to_string())]);
+    }
+}
@@ -0,0 +1,271 @@
+// HTTP 客户端工厂
+// 所有对外 HTTP 请求统一从这里创建 reqwest 客户端，应用代理、按服务绕过、自定义 CA 证书和超时设置
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use reqwest::{Certificate, Client, ClientBuilder, NoProxy, Proxy};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::warn;
+
+use super::rate_limiter::{self, RateLimitSettings};
+
+/// 始终直连的本机地址
+const LOCAL_HOSTS: &str = "localhost,127.0.0.1,::1";
+/// 支持的代理协议
+const PROXY_SCHEMES: [&str; 4] = ["http://", "https://", "socks5://", "socks5h://"];
+
+#[derive(Error, Debug)]
+pub enum HttpClientError {
+    #[error("代理地址无效: {0}")]
+    InvalidProxy(String),
+    #[error("CA 证书加载失败: {0}")]
+    InvalidCertificate(String),
+}
+
+/// 发起 HTTP 请求的服务类别（用于按服务绕过代理）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpService {
+    /// 抖音页面、接口与视频 CDN
+    Douyin,
+    /// 快手、B 站等其他视频平台
+    OtherPlatforms,
+    /// AI 服务提供者
+    Ai,
+    /// 模型下载
+    ModelDownload,
+    /// 本机 Sidecar / MCP 服务（始终直连，且不设读取超时）
+    Sidecar,
+}
+
+/// 网络设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// 代理地址，支持 http / https / socks5 / socks5h，为空时沿用系统环境变量
+    pub proxy_url: String,
+    /// 不走代理的服务
+    pub bypass_services: Vec<HttpService>,
+    /// 不走代理的主机（如 `internal.example.com`、`.example.com`、`10.0.0.0/8`）
+    pub no_proxy_hosts: Vec<String>,
+    /// 自定义 CA 证书（PEM，可包含多个证书）路径
+    pub ca_bundle_path: String,
+    /// 连接超时（秒）
+    pub connect_timeout_secs: u64,
+    /// 读取超时（秒），0 表示不限制
+    pub read_timeout_secs: u64,
+    /// 抖音请求限速
+    pub rate_limit: RateLimitSettings,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            proxy_url: String::new(),
+            bypass_services: Vec::new(),
+            no_proxy_hosts: Vec::new(),
+            ca_bundle_path: String::new(),
+            connect_timeout_secs: 10,
+            read_timeout_secs: 0,
+            rate_limit: RateLimitSettings::default(),
+        }
+    }
+}
+
+impl NetworkSettings {
+    /// 指定服务实际使用的代理地址
+    pub fn proxy_for(&self, service: HttpService) -> Option<&str> {
+        let proxy = self.proxy_url.trim();
+        if proxy.is_empty()
+            || service == HttpService::Sidecar
+            || self.bypass_services.contains(&service)
+        {
+            None
+        } else {
+            Some(proxy)
+        }
+    }
+
+    /// 校验代理地址与证书路径
+    pub fn validate(&self) -> Result<(), HttpClientError> {
+        let proxy = self.proxy_url.trim();
+        if !proxy.is_empty() {
+            let lower = proxy.to_lowercase();
+            if !PROXY_SCHEMES.iter().any(|scheme| lower.starts_with(scheme)) {
+                return Err(HttpClientError::InvalidProxy(format!(
+                    "{}（仅支持 http/https/socks5/socks5h）",
+                    proxy
+                )));
+            }
+            Proxy::all(proxy).map_err(|e| HttpClientError::InvalidProxy(e.to_string()))?;
+        }
+
+        if !self.ca_bundle_path.trim().is_empty() {
+            load_certificates(self.ca_bundle_path.trim())?;
+        }
+        Ok(())
+    }
+
+    /// 生成 no_proxy 列表（始终包含本机地址）
+    fn no_proxy_list(&self) -> String {
+        std::iter::once(LOCAL_HOSTS.to_string())
+            .chain(
+                self.no_proxy_hosts
+                    .iter()
+                    .map(|h| h.trim().to_string())
+                    .filter(|h| !h.is_empty()),
+            )
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// 全局网络设置（配置加载或更新时同步）
+static NETWORK_SETTINGS: Lazy<RwLock<NetworkSettings>> =
+    Lazy::new(|| RwLock::new(NetworkSettings::default()));
+
+/// 更新全局网络设置，之后新建的客户端生效；限速设置立即生效
+pub fn set_network_settings(settings: NetworkSettings) {
+    rate_limiter::global_limiter().update_settings(settings.rate_limit.clone());
+    *NETWORK_SETTINGS.write() = settings;
+}
+
+/// 获取当前网络设置
+pub fn network_settings() -> NetworkSettings {
+    NETWORK_SETTINGS.read().clone()
+}
+
+/// 按给定设置创建客户端构建器
+pub fn builder_with_settings(
+    settings: &NetworkSettings,
+    service: HttpService,
+) -> Result<ClientBuilder, HttpClientError> {
+    let mut builder = Client::builder()
+        .connect_timeout(Duration::from_secs(settings.connect_timeout_secs.max(1)));
+
+    if service == HttpService::Sidecar {
+        // 本机服务不走代理；下载接口在文件写完后才响应，不能设置读取超时
+        return Ok(builder.no_proxy());
+    }
+
+    if settings.read_timeout_secs > 0 {
+        builder = builder.read_timeout(Duration::from_secs(settings.read_timeout_secs));
+    }
+
+    if let Some(proxy_url) = settings.proxy_for(service) {
+        let proxy = Proxy::all(proxy_url)
+            .map_err(|e| HttpClientError::InvalidProxy(e.to_string()))?
+            .no_proxy(NoProxy::from_string(&settings.no_proxy_list()));
+        builder = builder.proxy(proxy);
+    } else if settings.bypass_services.contains(&service) {
+        builder = builder.no_proxy();
+    }
+
+    if !settings.ca_bundle_path.trim().is_empty() {
+        for cert in load_certificates(settings.ca_bundle_path.trim())? {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    Ok(builder)
+}
+
+/// 按全局网络设置创建客户端构建器，调用方可继续设置 UA、总超时等
+///
+/// 设置无效时记录警告并回退为仅带连接超时的构建器
+pub fn client_builder(service: HttpService) -> ClientBuilder {
+    let settings = network_settings();
+    builder_with_settings(&settings, service).unwrap_or_else(|e| {
+        warn!("网络设置无效，使用默认网络配置: {}", e);
+        Client::builder().connect_timeout(Duration::from_secs(settings.connect_timeout_secs.max(1)))
+    })
+}
+
+/// 按全局网络设置创建客户端
+pub fn build_client(service: HttpService) -> Client {
+    client_builder(service)
+        .build()
+        .unwrap_or_else(|_| Client::new())
+}
+
+/// 需要转发给 Sidecar 的网络设置请求头（Sidecar 代为访问抖音时使用）
+pub fn sidecar_forward_headers() -> Vec<(&'static str, String)> {
+    let settings = network_settings();
+    let mut headers = Vec::new();
+    if let Some(proxy) = settings.proxy_for(HttpService::Douyin) {
+        headers.push(("X-Proxy-Url", proxy.to_string()));
+    }
+    if !settings.ca_bundle_path.trim().is_empty() {
+        headers.push(("X-CA-Bundle", settings.ca_bundle_path.trim().to_string()));
+    }
+    headers
+}
+
+fn load_certificates(path: &str) -> Result<Vec<Certificate>, HttpClientError> {
+    let pem = std::fs::read(path)
+        .map_err(|e| HttpClientError::InvalidCertificate(format!("{}: {}", path, e)))?;
+    let certs = Certificate::from_pem_bundle(&pem)
+        .map_err(|e| HttpClientError::InvalidCertificate(e.to_string()))?;
+    if certs.is_empty() {
+        return Err(HttpClientError::InvalidCertificate(format!(
+            "{} 中没有证书",
+            path
+        )));
+    }
+    Ok(certs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_for_respects_bypass() {
+        let settings = NetworkSettings {
+            proxy_url: " socks5://127.0.0.1:1080 ".to_string(),
+            bypass_services: vec![HttpService::Ai],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            settings.proxy_for(HttpService::Douyin),
+            Some("socks5://127.0.0.1:1080")
+        );
+        assert_eq!(settings.proxy_for(HttpService::Ai), None);
+        assert_eq!(settings.proxy_for(HttpService::Sidecar), None);
+        assert_eq!(
+            NetworkSettings::default().proxy_for(HttpService::Douyin),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut settings = NetworkSettings {
+            proxy_url: "http://proxy.corp:8080".to_string(),
+            no_proxy_hosts: vec![".corp".to_string(), " ".to_string()],
+            ..Default::default()
+        };
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.no_proxy_list(), "localhost,127.0.0.1,::1,.corp");
+        assert!(builder_with_settings(&settings, HttpService::Douyin)
+            .unwrap()
+            .build()
+            .is_ok());
+
+        settings.proxy_url = "ftp://proxy.corp".to_string();
+        assert!(matches!(
+            settings.validate(),
+            Err(HttpClientError::InvalidProxy(_))
+        ));
+
+        settings.proxy_url.clear();
+        settings.ca_bundle_path = "/nonexistent/ca.pem".to_string();
+        assert!(matches!(
+            settings.validate(),
+            Err(HttpClientError::InvalidCertificate(_))
+        ));
+    }
+}
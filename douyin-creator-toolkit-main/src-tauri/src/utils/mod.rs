@@ -1,6 +1,9 @@
 // 工具函数模块
 
 pub mod ffmpeg;
+pub mod filename_template;
 pub mod gpu;
+pub mod http_client;
 pub mod logger;
 pub mod paths;
+pub mod rate_limiter;
@@ -0,0 +1,438 @@
+// 请求限速模块
+// 所有访问抖音的请求共用按主机划分的令牌桶，等待时附加随机抖动；遇到 429 或验证码页时按主机指数退避，连续受限后熔断暂停网络请求
+
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// 默认同一主机的请求间隔（毫秒）
+pub const DEFAULT_INTERVAL_MS: u64 = 1000;
+/// 默认允许连续发出的请求数
+pub const DEFAULT_BURST: u32 = 3;
+/// 默认随机抖动上限（毫秒）
+pub const DEFAULT_JITTER_MS: u64 = 300;
+/// 默认连续受限多少次后熔断
+pub const DEFAULT_CIRCUIT_THRESHOLD: u32 = 3;
+/// 默认熔断时长（秒）
+pub const DEFAULT_CIRCUIT_COOLDOWN_SECS: u64 = 600;
+/// 首次受限时的退避时长（秒），之后每次翻倍
+const BASE_BACKOFF_SECS: u64 = 5;
+/// 单个主机的最长退避时长（秒）
+const MAX_BACKOFF_SECS: u64 = 300;
+/// 验证码页特征
+const CAPTCHA_MARKERS: [&str; 2] = ["验证码中间页", "/verifycenter/"];
+/// 超过该长度的响应视为正常页面，不再检查验证码特征
+const CAPTCHA_PAGE_MAX_LEN: usize = 64 * 1024;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RateLimitError {
+    #[error("请求过于频繁，已暂停抖音请求，约 {0} 秒后恢复")]
+    CircuitOpen(u64),
+    #[error("{0} 返回限流或验证码页面，已自动退避")]
+    Throttled(String),
+}
+
+/// 限速设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitSettings {
+    /// 是否启用限速
+    pub enabled: bool,
+    /// 同一主机的请求间隔（毫秒），0 表示不限制频率（仍会退避与熔断）
+    pub interval_ms: u64,
+    /// 允许连续发出的请求数
+    pub burst: u32,
+    /// 需要等待时附加的随机抖动上限（毫秒）
+    pub jitter_ms: u64,
+    /// 连续受限多少次后熔断
+    pub circuit_threshold: u32,
+    /// 熔断时长（秒）
+    pub circuit_cooldown_secs: u64,
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: DEFAULT_INTERVAL_MS,
+            burst: DEFAULT_BURST,
+            jitter_ms: DEFAULT_JITTER_MS,
+            circuit_threshold: DEFAULT_CIRCUIT_THRESHOLD,
+            circuit_cooldown_secs: DEFAULT_CIRCUIT_COOLDOWN_SECS,
+        }
+    }
+}
+
+/// 限速器状态（用于前端展示）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitStatus {
+    pub enabled: bool,
+    /// 是否处于熔断状态
+    pub circuit_open: bool,
+    /// 距离熔断结束的秒数
+    pub resume_in_secs: u64,
+    /// 正在退避的主机
+    pub throttled_hosts: Vec<String>,
+}
+
+/// 单个主机的令牌桶与退避状态
+struct HostState {
+    /// 可用令牌，预约后可能为负数
+    tokens: f64,
+    last_refill: Instant,
+    backoff_until: Option<Instant>,
+    /// 连续受限次数
+    strikes: u32,
+}
+
+#[derive(Default)]
+struct LimiterState {
+    hosts: HashMap<String, HostState>,
+    /// 所有主机累计的连续受限次数，任一请求成功后清零
+    consecutive_throttles: u32,
+    circuit_open_until: Option<Instant>,
+}
+
+/// 请求限速器
+pub struct RateLimiter {
+    settings: RwLock<RateLimitSettings>,
+    state: Mutex<LimiterState>,
+}
+
+impl RateLimiter {
+    pub fn new(settings: RateLimitSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            state: Mutex::new(LimiterState::default()),
+        }
+    }
+
+    /// 创建不限速的限速器（测试与本地模拟服务使用）
+    pub fn disabled() -> Self {
+        Self::new(RateLimitSettings {
+            enabled: false,
+            ..Default::default()
+        })
+    }
+
+    /// 获取当前设置
+    pub fn settings(&self) -> RateLimitSettings {
+        self.settings.read().clone()
+    }
+
+    /// 更新设置，已有的退避与熔断状态保留
+    pub fn update_settings(&self, settings: RateLimitSettings) {
+        *self.settings.write() = settings;
+    }
+
+    /// 等待请求令牌；熔断期间立即返回错误
+    pub async fn acquire(&self, url: &str) -> Result<(), RateLimitError> {
+        let wait = self.reserve(&host_key(url), Instant::now())?;
+        if !wait.is_zero() {
+            let jitter_ms = self.settings.read().jitter_ms;
+            tokio::time::sleep(wait + jitter(jitter_ms)).await;
+        }
+        Ok(())
+    }
+
+    /// 检查响应：429 或验证码页时记录受限并返回错误，成功响应清除退避
+    ///
+    /// `text` 为响应正文或重定向后的地址，不需要检查时传空字符串
+    pub fn check_response(&self, url: &str, status: u16, text: &str) -> Result<(), RateLimitError> {
+        if status == 429 || is_captcha_page(text) {
+            self.record_throttled(url);
+            return Err(RateLimitError::Throttled(host_key(url)));
+        }
+        if (200..400).contains(&status) {
+            self.record_success(url);
+        }
+        Ok(())
+    }
+
+    /// 记录一次受限响应
+    pub fn record_throttled(&self, url: &str) {
+        self.record_throttled_at(&host_key(url), Instant::now());
+    }
+
+    /// 记录一次成功响应
+    pub fn record_success(&self, url: &str) {
+        let mut state = self.state.lock();
+        state.consecutive_throttles = 0;
+        if let Some(host) = state.hosts.get_mut(&host_key(url)) {
+            host.strikes = 0;
+            host.backoff_until = None;
+        }
+    }
+
+    /// 熔断期间等待恢复（批量任务在每一项开始前调用）
+    pub async fn wait_until_closed(&self) {
+        loop {
+            let remaining = {
+                let state = self.state.lock();
+                state
+                    .circuit_open_until
+                    .map(|until| until.saturating_duration_since(Instant::now()))
+                    .unwrap_or_default()
+            };
+            if remaining.is_zero() || !self.settings.read().enabled {
+                return;
+            }
+            tokio::time::sleep(remaining).await;
+        }
+    }
+
+    /// 获取当前状态
+    pub fn status(&self) -> RateLimitStatus {
+        self.status_at(Instant::now())
+    }
+
+    /// 清除所有退避与熔断状态
+    pub fn reset(&self) {
+        *self.state.lock() = LimiterState::default();
+    }
+
+    /// 预约一个令牌，返回需要等待的时长
+    fn reserve(&self, key: &str, now: Instant) -> Result<Duration, RateLimitError> {
+        let settings = self.settings.read().clone();
+        if !settings.enabled {
+            return Ok(Duration::ZERO);
+        }
+
+        let mut state = self.state.lock();
+        if let Some(until) = state.circuit_open_until {
+            if now < until {
+                return Err(RateLimitError::CircuitOpen(ceil_secs(until - now)));
+            }
+            state.circuit_open_until = None;
+        }
+
+        let burst = settings.burst.max(1) as f64;
+        let host = state
+            .hosts
+            .entry(key.to_string())
+            .or_insert_with(|| HostState {
+                tokens: burst,
+                last_refill: now,
+                backoff_until: None,
+                strikes: 0,
+            });
+
+        let mut wait = host
+            .backoff_until
+            .map(|until| until.saturating_duration_since(now))
+            .unwrap_or_default();
+
+        if settings.interval_ms > 0 {
+            let interval = settings.interval_ms as f64;
+            let elapsed_ms = now
+                .saturating_duration_since(host.last_refill)
+                .as_secs_f64()
+                * 1000.0;
+            host.tokens = (host.tokens + elapsed_ms / interval).min(burst) - 1.0;
+            host.last_refill = now;
+            if host.tokens < 0.0 {
+                wait = wait.max(Duration::from_millis(
+                    (-host.tokens * interval).ceil() as u64
+                ));
+            }
+        }
+
+        Ok(wait)
+    }
+
+    fn record_throttled_at(&self, key: &str, now: Instant) {
+        let settings = self.settings.read().clone();
+        let mut state = self.state.lock();
+
+        let host = state
+            .hosts
+            .entry(key.to_string())
+            .or_insert_with(|| HostState {
+                tokens: 0.0,
+                last_refill: now,
+                backoff_until: None,
+                strikes: 0,
+            });
+        host.strikes += 1;
+        let backoff = BASE_BACKOFF_SECS
+            .saturating_mul(1 << (host.strikes - 1).min(16))
+            .min(MAX_BACKOFF_SECS);
+        host.backoff_until = Some(now + Duration::from_secs(backoff));
+
+        state.consecutive_throttles += 1;
+        if state.consecutive_throttles >= settings.circuit_threshold.max(1) {
+            state.consecutive_throttles = 0;
+            state.circuit_open_until =
+                Some(now + Duration::from_secs(settings.circuit_cooldown_secs));
+        }
+    }
+
+    fn status_at(&self, now: Instant) -> RateLimitStatus {
+        let enabled = self.settings.read().enabled;
+        let state = self.state.lock();
+        let resume_in = state
+            .circuit_open_until
+            .map(|until| until.saturating_duration_since(now))
+            .unwrap_or_default();
+        let mut throttled_hosts: Vec<String> = state
+            .hosts
+            .iter()
+            .filter(|(_, host)| host.backoff_until.is_some_and(|until| until > now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        throttled_hosts.sort();
+
+        RateLimitStatus {
+            enabled,
+            circuit_open: enabled && !resume_in.is_zero(),
+            resume_in_secs: ceil_secs(resume_in),
+            throttled_hosts,
+        }
+    }
+}
+
+/// 判断响应是否为抖音的验证码页
+pub fn is_captcha_page(text: &str) -> bool {
+    text.len() <= CAPTCHA_PAGE_MAX_LEN && CAPTCHA_MARKERS.iter().any(|m| text.contains(m))
+}
+
+/// 限速的主机键（`host:port`），无法解析时使用原始地址
+fn host_key(url: &str) -> String {
+    match reqwest::Url::parse(url.trim()) {
+        Ok(parsed) => match (parsed.host_str(), parsed.port_or_known_default()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            _ => url.trim().to_string(),
+        },
+        Err(_) => url.trim().to_string(),
+    }
+}
+
+/// 不超过 `max_ms` 的随机抖动
+fn jitter(max_ms: u64) -> Duration {
+    if max_ms == 0 {
+        return Duration::ZERO;
+    }
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    Duration::from_millis(nanos % (max_ms + 1))
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_millis().div_ceil(1000) as u64
+}
+
+/// 全局限速器（网络设置加载或更新时同步）
+static GLOBAL_LIMITER: Lazy<Arc<RateLimiter>> =
+    Lazy::new(|| Arc::new(RateLimiter::new(RateLimitSettings::default())));
+
+/// 获取全局限速器
+pub fn global_limiter() -> Arc<RateLimiter> {
+    GLOBAL_LIMITER.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter() -> RateLimiter {
+        RateLimiter::new(RateLimitSettings {
+            interval_ms: 1000,
+            burst: 2,
+            circuit_threshold: 3,
+            circuit_cooldown_secs: 60,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = limiter();
+        let start = Instant::now();
+        let key = host_key("https://www.douyin.com/video/1");
+        assert_eq!(key, "www.douyin.com:443");
+
+        // 前两个请求立即发出，第三个需要等一个间隔
+        assert_eq!(limiter.reserve(&key, start).unwrap(), Duration::ZERO);
+        assert_eq!(limiter.reserve(&key, start).unwrap(), Duration::ZERO);
+        assert_eq!(
+            limiter.reserve(&key, start).unwrap(),
+            Duration::from_millis(1000)
+        );
+        // 其他主机互不影响
+        assert_eq!(
+            limiter.reserve("www.iesdouyin.com:443", start).unwrap(),
+            Duration::ZERO
+        );
+        // 令牌随时间恢复
+        let later = start + Duration::from_secs(5);
+        assert_eq!(limiter.reserve(&key, later).unwrap(), Duration::ZERO);
+
+        assert_eq!(
+            RateLimiter::disabled().reserve(&key, start).unwrap(),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_backoff_and_circuit() {
+        let limiter = limiter();
+        let now = Instant::now();
+        let key = "www.douyin.com:443";
+
+        limiter.record_throttled_at(key, now);
+        assert_eq!(limiter.reserve(key, now).unwrap(), Duration::from_secs(5));
+        limiter.record_throttled_at(key, now);
+        assert_eq!(limiter.reserve(key, now).unwrap(), Duration::from_secs(10));
+        assert_eq!(
+            limiter.status_at(now).throttled_hosts,
+            vec![key.to_string()]
+        );
+
+        // 连续第三次受限后熔断
+        limiter.record_throttled_at(key, now);
+        assert_eq!(
+            limiter.reserve("other:443", now),
+            Err(RateLimitError::CircuitOpen(60))
+        );
+        let status = limiter.status_at(now);
+        assert!(status.circuit_open);
+        assert_eq!(status.resume_in_secs, 60);
+
+        // 熔断结束后恢复，成功响应清除退避
+        let later = now + Duration::from_secs(61);
+        assert!(limiter.reserve("other:443", later).is_ok());
+        assert!(!limiter.status_at(later).circuit_open);
+        limiter.record_success("https://www.douyin.com/");
+        assert!(limiter.status_at(now).throttled_hosts.is_empty());
+    }
+
+    #[test]
+    fn test_check_response() {
+        let limiter = limiter();
+        let url = "https://www.douyin.com/aweme/v1/web/aweme/detail/";
+
+        assert!(limiter.check_response(url, 200, "{}").is_ok());
+        assert!(matches!(
+            limiter.check_response(url, 429, ""),
+            Err(RateLimitError::Throttled(host)) if host == "www.douyin.com:443"
+        ));
+        assert!(limiter
+            .check_response(url, 200, "<title>验证码中间页</title>")
+            .is_err());
+        assert!(limiter
+            .check_response(url, 302, "https://www.douyin.com/verifycenter/captcha")
+            .is_err());
+        // 普通错误不计入限流
+        assert!(limiter.check_response(url, 404, "").is_ok());
+        assert!(!is_captcha_page(
+            &"验证码中间页".repeat(CAPTCHA_PAGE_MAX_LEN)
+        ));
+    }
+}
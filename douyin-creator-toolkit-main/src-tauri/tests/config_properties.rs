@@ -9,7 +9,9 @@ use std::sync::Arc;
 use tempfile::tempdir;
 
 // 导入被测试的模块
-use douyin_creator_tools_lib::data::{AppConfig, ConfigManager, Database};
+use douyin_creator_tools_lib::ai::service::OpenAiCompatibleConfig;
+use douyin_creator_tools_lib::data::{config_keys, AppConfig, ConfigManager, Database};
+use douyin_creator_tools_lib::utils::http_client::{HttpService, NetworkSettings};
 
 /// 创建测试数据库
 fn create_test_db() -> Arc<Database> {
@@ -62,6 +64,57 @@ fn optional_api_key_strategy() -> impl Strategy<Value = Option<String>> {
     prop_oneof![Just(None), "[a-zA-Z0-9]{16,64}".prop_map(Some),]
 }
 
+/// 生成自定义 OpenAI 兼容服务配置
+fn custom_ai_provider_strategy() -> impl Strategy<Value = OpenAiCompatibleConfig> {
+    (
+        url_strategy(),
+        optional_api_key_strategy(),
+        "[a-z0-9.:-]{1,24}",
+    )
+        .prop_map(|(base_url, api_key, model)| OpenAiCompatibleConfig {
+            base_url,
+            api_key,
+            model,
+            ..OpenAiCompatibleConfig::default()
+        })
+}
+
+/// 生成下载文件命名模板
+fn filename_template_strategy() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("{author}/{date}_{title}_{aweme_id}.mp4".to_string()),
+        Just("{aweme_id}.mp4".to_string()),
+        Just("{date}/{author}_{title}".to_string()),
+    ]
+}
+
+/// 生成代理与超时设置
+fn network_settings_strategy() -> impl Strategy<Value = NetworkSettings> {
+    (
+        prop_oneof![
+            Just("".to_string()),
+            Just("http://127.0.0.1:7890".to_string()),
+            Just("socks5h://127.0.0.1:1080".to_string()),
+        ],
+        any::<bool>(),
+        1u64..=60u64,
+        0u64..=300u64,
+    )
+        .prop_map(
+            |(proxy_url, bypass_ai, connect_timeout_secs, read_timeout_secs)| NetworkSettings {
+                proxy_url,
+                bypass_services: if bypass_ai {
+                    vec![HttpService::Ai]
+                } else {
+                    Vec::new()
+                },
+                connect_timeout_secs,
+                read_timeout_secs,
+                ..NetworkSettings::default()
+            },
+        )
+}
+
 /// 生成完整的 AppConfig
 fn app_config_strategy() -> impl Strategy<Value = AppConfig> {
     (
@@ -83,6 +136,11 @@ fn app_config_strategy() -> impl Strategy<Value = AppConfig> {
             100u64..=10000u64,
             1u32..=10u32,
         ),
+        (
+            custom_ai_provider_strategy(),
+            filename_template_strategy(),
+            network_settings_strategy(),
+        ),
     )
         .prop_map(
             |(
@@ -104,6 +162,7 @@ fn app_config_strategy() -> impl Strategy<Value = AppConfig> {
                     request_interval,
                     max_retries,
                 ),
+                (custom_ai_provider, download_filename_template, network),
             )| {
                 AppConfig {
                     default_export_path,
@@ -118,8 +177,11 @@ fn app_config_strategy() -> impl Strategy<Value = AppConfig> {
                     openai_api_key,
                     deepseek_api_key,
                     lm_studio_url,
+                    custom_ai_provider,
                     request_interval,
                     max_retries,
+                    download_filename_template,
+                    network,
                 }
             },
         )
@@ -161,8 +223,14 @@ proptest! {
         prop_assert_eq!(config.openai_api_key, loaded_config.openai_api_key);
         prop_assert_eq!(config.deepseek_api_key, loaded_config.deepseek_api_key);
         prop_assert_eq!(config.lm_studio_url, loaded_config.lm_studio_url);
+        prop_assert_eq!(config.custom_ai_provider, loaded_config.custom_ai_provider);
         prop_assert_eq!(config.request_interval, loaded_config.request_interval);
         prop_assert_eq!(config.max_retries, loaded_config.max_retries);
+        prop_assert_eq!(
+            config.download_filename_template,
+            loaded_config.download_filename_template
+        );
+        prop_assert_eq!(config.network, loaded_config.network);
     }
 
     /// **Feature: tauri-refactor, Property 7: 单个配置项持久化一致性**
@@ -175,6 +243,7 @@ proptest! {
         theme in theme_strategy(),
         gpu_threads in 1u32..=32u32,
         request_interval in 100u64..=10000u64,
+        network in network_settings_strategy(),
     ) {
         let db = create_test_db();
         let manager = ConfigManager::new(db).unwrap();
@@ -193,6 +262,14 @@ proptest! {
         manager.set_value("request_interval", &request_interval.to_string()).unwrap();
         let loaded_interval = manager.get_value("request_interval").unwrap();
         prop_assert_eq!(request_interval.to_string(), loaded_interval);
+
+        // 测试 network_settings（JSON 格式）
+        let network_json = serde_json::to_string(&network).unwrap();
+        manager.set_value(config_keys::NETWORK_SETTINGS, &network_json).unwrap();
+        let loaded_network = manager.get_value(config_keys::NETWORK_SETTINGS).unwrap();
+        let loaded_network: NetworkSettings = serde_json::from_str(&loaded_network).unwrap();
+        prop_assert_eq!(&network, &loaded_network);
+        prop_assert_eq!(network, manager.get().network);
     }
 
     /// **Feature: tauri-refactor, Property 7: JSON 序列化 round-trip**
@@ -243,8 +320,19 @@ mod tests {
             openai_api_key: None,
             deepseek_api_key: None,
             lm_studio_url: "http://localhost:8080".to_string(),
+            custom_ai_provider: OpenAiCompatibleConfig {
+                base_url: "http://localhost:8000/v1".to_string(),
+                model: "qwen2.5-7b-instruct".to_string(),
+                ..OpenAiCompatibleConfig::default()
+            },
             request_interval: 2000,
             max_retries: 5,
+            download_filename_template: "{aweme_id}.mp4".to_string(),
+            network: NetworkSettings {
+                proxy_url: "http://127.0.0.1:7890".to_string(),
+                connect_timeout_secs: 5,
+                ..NetworkSettings::default()
+            },
         };
 
         manager.update(config.clone()).unwrap();
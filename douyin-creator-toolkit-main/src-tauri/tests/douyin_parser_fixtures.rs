@@ -0,0 +1,478 @@
+// 抖音解析渠道离线回归测试
+// 通过本地模拟 HTTP 服务回放录制的 HTML / JSON 样本，逐一验证各解析渠道的数据提取
+//
+// 样本位于 tests/fixtures/douyin。抖音调整页面或接口结构时，重新录制对应样本并更新断言即可，
+// 测试过程不访问外部网络。
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use douyin_creator_tools_lib::core::douyin_parser::{
+    ChallengeSort, ContentType, DouyinEndpoints, DouyinError, DouyinParser, PublishTimeFilter,
+    SearchQuery, SearchSort,
+};
+use douyin_creator_tools_lib::core::strategy_health::{ParseStrategy, StrategyHealthTracker};
+use douyin_creator_tools_lib::utils::rate_limiter::RateLimiter;
+
+/// 样本中的作品 ID
+const AWEME_ID: &str = "7301234567890123456";
+
+/// 模拟路由：请求路径（不含查询参数）、Content-Type、响应体
+type Routes = Vec<(String, &'static str, String)>;
+
+/// 读取样本文件
+fn fixture(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("douyin")
+        .join(name);
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("读取样本 {} 失败: {}", path.display(), e))
+}
+
+/// 构造一条返回样本内容的路由
+fn route(
+    path: &str,
+    content_type: &'static str,
+    fixture_name: &str,
+) -> (String, &'static str, String) {
+    (path.to_string(), content_type, fixture(fixture_name))
+}
+
+/// 启动本地模拟服务并返回服务地址，未配置的路径返回 404
+fn start_mock_server(routes: Routes) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("无法启动模拟服务");
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            handle_request(stream, &routes);
+        }
+    });
+
+    base_url
+}
+
+/// 处理一次请求（仅支持无请求体的 GET）
+fn handle_request(mut stream: TcpStream, routes: &Routes) {
+    let mut reader = match stream.try_clone() {
+        Ok(s) => BufReader::new(s),
+        Err(_) => return,
+    };
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    // 跳过请求头，空行（\r\n）表示请求头结束
+    let mut header = String::new();
+    while reader
+        .read_line(&mut header)
+        .map(|n| n > 2)
+        .unwrap_or(false)
+    {
+        header.clear();
+    }
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let path = target.split('?').next().unwrap_or(target);
+    let (status, content_type, body) = match routes.iter().find(|(p, _, _)| p == path) {
+        Some((_, content_type, body)) => ("200 OK", *content_type, body.as_str()),
+        None => ("404 Not Found", "text/plain", "not found"),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
+
+/// 创建所有服务地址都指向模拟服务的解析器（不使用代理和登录 Cookie）
+fn parser_for(base_url: &str) -> DouyinParser {
+    parser_with_tracker(base_url, Arc::new(StrategyHealthTracker::new()))
+}
+
+/// 创建使用指定健康度追踪器的解析器，避免测试之间共享渠道顺序；本地模拟服务不限速
+fn parser_with_tracker(base_url: &str, tracker: Arc<StrategyHealthTracker>) -> DouyinParser {
+    let client = reqwest::Client::builder().no_proxy().build().unwrap();
+    DouyinParser::with_client(client)
+        .with_cookie(None)
+        .with_endpoints(DouyinEndpoints {
+            sidecar: base_url.to_string(),
+            web: base_url.to_string(),
+            iesdouyin: base_url.to_string(),
+        })
+        .with_health_tracker(tracker)
+        .with_rate_limiter(Arc::new(RateLimiter::disabled()))
+}
+
+fn sidecar_route() -> (String, &'static str, String) {
+    route("/parse", "application/json", "sidecar_parse.json")
+}
+
+fn detail_route() -> (String, &'static str, String) {
+    route(
+        "/aweme/v1/web/aweme/detail/",
+        "application/json",
+        "aweme_detail.json",
+    )
+}
+
+fn share_route() -> (String, &'static str, String) {
+    route(
+        &format!("/share/video/{}", AWEME_ID),
+        "text/html",
+        "share_video.html",
+    )
+}
+
+fn iteminfo_route() -> (String, &'static str, String) {
+    route(
+        "/web/api/v2/aweme/iteminfo/",
+        "application/json",
+        "iteminfo.json",
+    )
+}
+
+fn video_page_route() -> (String, &'static str, String) {
+    route(
+        &format!("/video/{}", AWEME_ID),
+        "text/html",
+        "video_page.html",
+    )
+}
+
+#[tokio::test]
+async fn test_sidecar_channel() {
+    let base_url = start_mock_server(vec![sidecar_route()]);
+    let data = parser_for(&base_url)
+        .fetch_video_from_page(AWEME_ID)
+        .await
+        .unwrap();
+
+    assert_eq!(data.aweme_id, AWEME_ID);
+    assert_eq!(data.title, "Sidecar 标题");
+    assert_eq!(data.author, "露营小王");
+    assert_eq!(data.content_type, ContentType::Video);
+    assert_eq!(
+        data.no_watermark_url,
+        "https://v26.douyinvod.com/sidecar.mp4"
+    );
+    assert_eq!(data.likes, 12000);
+    assert_eq!(data.collects, 890);
+}
+
+#[tokio::test]
+async fn test_detail_api_channel() {
+    let base_url = start_mock_server(vec![detail_route()]);
+    let data = parser_for(&base_url)
+        .fetch_video_from_page(AWEME_ID)
+        .await
+        .unwrap();
+
+    assert_eq!(data.title, "周末露营 vlog #露营 #户外");
+    assert_eq!(data.author, "露营小王");
+    assert_eq!(data.author_id, "camping_wang");
+    assert_eq!(data.likes, 12000);
+    assert_eq!(data.comments, 345);
+    assert_eq!(data.shares, 67);
+    assert_eq!(data.collects, 890);
+    assert_eq!(data.duration, 15);
+    assert_eq!(data.create_time, 1_700_000_000);
+    assert_eq!(
+        data.no_watermark_url,
+        "https://aweme.snssdk.com/aweme/v1/play/?video_id=v0200"
+    );
+    assert_eq!(data.cover_url, "https://p3-sign.douyinpic.com/cover.jpeg");
+    assert_eq!(data.hashtags, vec!["露营", "户外"]);
+    assert_eq!(data.ip_location, "浙江");
+    assert_eq!(data.music_url, "https://sf.douyinstatic.com/music.mp3");
+    assert_eq!(data.bit_rates.len(), 1);
+    assert_eq!(data.bit_rates[0].height, 1920);
+}
+
+#[tokio::test]
+async fn test_share_page_channel() {
+    let base_url = start_mock_server(vec![share_route()]);
+    let data = parser_for(&base_url)
+        .fetch_video_from_page(AWEME_ID)
+        .await
+        .unwrap();
+
+    // Share 页面只有 meta 信息，没有视频地址与统计数据
+    assert_eq!(data.aweme_id, AWEME_ID);
+    assert_eq!(data.title, "分享页标题");
+    assert_eq!(
+        data.cover_url,
+        "https://p3-sign.douyinpic.com/share-cover.jpeg"
+    );
+    assert!(data.video_url.is_empty());
+    assert_eq!(data.likes, 0);
+}
+
+#[tokio::test]
+async fn test_iteminfo_channel() {
+    let base_url = start_mock_server(vec![iteminfo_route()]);
+    let data = parser_for(&base_url)
+        .fetch_video_from_page(AWEME_ID)
+        .await
+        .unwrap();
+
+    assert_eq!(data.title, "旧版接口标题");
+    assert_eq!(data.author_id, "camping_wang");
+    assert_eq!(data.comments, 345);
+    assert_eq!(
+        data.no_watermark_url,
+        "https://aweme.snssdk.com/aweme/v1/play/?video_id=v0200"
+    );
+}
+
+#[tokio::test]
+async fn test_render_data_channel() {
+    let base_url = start_mock_server(vec![video_page_route()]);
+    let data = parser_for(&base_url)
+        .fetch_video_from_page(AWEME_ID)
+        .await
+        .unwrap();
+
+    assert_eq!(data.title, "作品页标题");
+    assert_eq!(data.author, "露营小王");
+    assert_eq!(data.likes, 12000);
+    assert_eq!(data.width, 1080);
+    // 页面数据优先使用码率档位中的无水印地址
+    assert_eq!(data.no_watermark_url, "https://v26.douyinvod.com/1080.mp4");
+    assert_eq!(data.hashtags, vec!["露营", "户外"]);
+}
+
+#[tokio::test]
+async fn test_channel_priority() {
+    // 所有渠道都可用时按 Sidecar、detail API、Share 页面、iteminfo、作品页的顺序取第一个成功的结果
+    let base_url = start_mock_server(vec![
+        video_page_route(),
+        iteminfo_route(),
+        share_route(),
+        detail_route(),
+        sidecar_route(),
+    ]);
+    let parser = parser_for(&base_url);
+    let data = parser.fetch_video_from_page(AWEME_ID).await.unwrap();
+    assert_eq!(data.title, "Sidecar 标题");
+
+    let base_url = start_mock_server(vec![video_page_route(), iteminfo_route(), share_route()]);
+    let data = parser_for(&base_url)
+        .fetch_video_from_page(AWEME_ID)
+        .await
+        .unwrap();
+    assert_eq!(data.title, "分享页标题");
+}
+
+#[tokio::test]
+async fn test_adaptive_channel_order() {
+    let tracker = Arc::new(StrategyHealthTracker::new());
+
+    // 只有 iteminfo 可用：前面的渠道各失败一次
+    let base_url = start_mock_server(vec![iteminfo_route()]);
+    let data = parser_with_tracker(&base_url, tracker.clone())
+        .fetch_video_from_page(AWEME_ID)
+        .await
+        .unwrap();
+    assert_eq!(data.title, "旧版接口标题");
+    assert_eq!(tracker.take_pending().len(), 4);
+    assert_eq!(tracker.ordered_strategies()[0], ParseStrategy::ItemInfo);
+
+    // 所有渠道恢复后，仍优先使用最近成功的渠道
+    let base_url = start_mock_server(vec![sidecar_route(), detail_route(), iteminfo_route()]);
+    let data = parser_with_tracker(&base_url, tracker.clone())
+        .fetch_video_from_page(AWEME_ID)
+        .await
+        .unwrap();
+    assert_eq!(data.title, "旧版接口标题");
+    assert_eq!(tracker.take_pending().len(), 1);
+}
+
+#[tokio::test]
+async fn test_all_channels_failed() {
+    let base_url = start_mock_server(Vec::new());
+    let result = parser_for(&base_url).fetch_video_from_page(AWEME_ID).await;
+    assert!(matches!(result, Err(DouyinError::ParseError(_))));
+}
+
+#[tokio::test]
+async fn test_fetch_comments() {
+    let base_url = start_mock_server(vec![route(
+        "/aweme/v1/web/comment/list/",
+        "application/json",
+        "comment_list.json",
+    )]);
+    let comments = parser_for(&base_url)
+        .fetch_comments(AWEME_ID, 10, true)
+        .await
+        .unwrap();
+
+    assert_eq!(comments.len(), 2);
+    assert_eq!(comments[0].cid, "7302000000000000001");
+    assert_eq!(comments[0].aweme_id, AWEME_ID);
+    assert_eq!(comments[0].author_id, "MS4wLjABAAAA_ming");
+    assert_eq!(comments[0].likes, 88);
+    assert_eq!(comments[1].ip_location, "北京");
+}
+
+#[tokio::test]
+async fn test_fetch_comments_http_error() {
+    let base_url = start_mock_server(Vec::new());
+    let result = parser_for(&base_url)
+        .fetch_comments(AWEME_ID, 10, false)
+        .await;
+    assert!(matches!(result, Err(DouyinError::NetworkError(_))));
+}
+
+#[tokio::test]
+async fn test_search_videos() {
+    let base_url = start_mock_server(vec![route(
+        "/douyin_web/aweme/v1/web/search/item/",
+        "application/json",
+        "search_item.json",
+    )]);
+    let parser = parser_for(&base_url);
+
+    let page = parser
+        .search_videos(&SearchQuery {
+            keyword: "露营".to_string(),
+            sort: SearchSort::MostLiked,
+            publish_time: PublishTimeFilter::Week,
+            count: 2,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    // 非作品类型的结果（如用户卡片）被跳过
+    assert_eq!(page.videos.len(), 2);
+    assert!(page.has_more);
+    assert_eq!(page.cursor, 10);
+    assert_eq!(page.videos[0].aweme_id, "7302000000000000101");
+    assert_eq!(page.videos[0].title, "新手露营装备清单 #露营");
+    assert_eq!(page.videos[0].likes, 5600);
+    assert_eq!(page.videos[1].author, "山野阿飞");
+
+    // 模拟服务每页返回相同游标，翻页在游标不前进时停止
+    let page = parser
+        .search_videos(&SearchQuery {
+            keyword: "露营".to_string(),
+            count: 5,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.videos.len(), 4);
+    assert!(!page.has_more);
+
+    let empty = parser.search_videos(&SearchQuery::default()).await;
+    assert!(matches!(empty, Err(DouyinError::ParseError(_))));
+}
+
+#[tokio::test]
+async fn test_fetch_user_videos() {
+    let base_url = start_mock_server(vec![route(
+        "/douyin_web/aweme/v1/web/aweme/post/",
+        "application/json",
+        "user_post.json",
+    )]);
+    let page = parser_for(&base_url)
+        .fetch_user_videos(
+            "https://www.douyin.com/user/MS4wLjABAAAAcamping_wang?from_tab_name=main",
+            0,
+            20,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(page.videos.len(), 2);
+    assert!(!page.has_more);
+    assert_eq!(page.cursor, 1_700_250_000_000);
+    assert_eq!(page.videos[0].aweme_id, "7303000000000000201");
+    assert_eq!(page.videos[1].title, "帐篷搭建教程");
+    assert_eq!(page.videos[1].create_time, 1_700_250_000);
+}
+
+#[tokio::test]
+async fn test_unsigned_list_response() {
+    // 签名未生效时列表接口返回空响应，不应被当作登录失效
+    let base_url = start_mock_server(vec![(
+        "/douyin_web/aweme/v1/web/aweme/post/".to_string(),
+        "application/json",
+        String::new(),
+    )]);
+    let result = parser_for(&base_url)
+        .fetch_user_videos("MS4wLjABAAAAcamping_wang", 0, 20)
+        .await;
+    assert!(matches!(result, Err(DouyinError::SignatureRequired)));
+}
+
+#[tokio::test]
+async fn test_fetch_challenge_videos() {
+    let base_url = start_mock_server(vec![route(
+        "/douyin_web/aweme/v1/web/challenge/aweme/",
+        "application/json",
+        "challenge_aweme.json",
+    )]);
+    let parser = parser_for(&base_url);
+    let page = parser
+        .fetch_challenge_videos(
+            "https://www.douyin.com/hashtag/1580000000000001",
+            ChallengeSort::Hot,
+            0,
+            2,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(page.videos.len(), 2);
+    assert!(page.has_more);
+    assert_eq!(page.cursor, 2);
+    assert_eq!(page.videos[0].aweme_id, "7303000000000000301");
+    assert_eq!(page.videos[0].likes, 56000);
+    assert_eq!(page.videos[1].author, "露营小王");
+
+    assert_eq!(
+        parser
+            .resolve_challenge_id("1580000000000001")
+            .await
+            .unwrap(),
+        "1580000000000001"
+    );
+    assert!(matches!(
+        parser.resolve_challenge_id("露营").await,
+        Err(DouyinError::InvalidLink(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_fetch_music_videos() {
+    let base_url = start_mock_server(vec![route(
+        "/douyin_web/aweme/v1/web/music/aweme/",
+        "application/json",
+        "music_aweme.json",
+    )]);
+    let parser = parser_for(&base_url);
+    let page = parser
+        .fetch_music_videos(
+            "https://www.iesdouyin.com/share/music/7200000000000000001/",
+            0,
+            20,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(page.videos.len(), 1);
+    assert!(!page.has_more);
+    assert_eq!(page.videos[0].aweme_id, "7303000000000000401");
+    assert_eq!(page.videos[0].title, "用这首歌记录秋天的营地");
+}
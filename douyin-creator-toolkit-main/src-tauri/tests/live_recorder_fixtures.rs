@@ -0,0 +1,252 @@
+// 直播录制离线测试
+// 通过本地模拟服务回放直播间接口样本，并用 FFmpeg 生成的 HLS 流代替直播 CDN，验证拉流地址解析与分段续录
+//
+// 录制测试需要本机安装 FFmpeg，未安装时跳过；测试过程不访问外部网络。
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use douyin_creator_tools_lib::core::live_recorder::{
+    LiveEndpoints, LiveRecorder, LiveResolver, RecordOptions, RecorderEvent, StopReason,
+    StreamFormat,
+};
+use douyin_creator_tools_lib::utils::ffmpeg::FfmpegWrapper;
+use douyin_creator_tools_lib::utils::rate_limiter::RateLimiter;
+
+/// 样本中的直播间地址
+const LIVE_LINK: &str = "https://live.douyin.com/123456789";
+
+/// 模拟路由：请求路径（不含查询参数）、Content-Type、响应体
+type Routes = Vec<(String, String, Vec<u8>)>;
+
+/// 读取直播间接口样本，并把其中的 `{BASE_URL}` 替换为模拟服务地址
+fn room_route(base_url: &str) -> (String, String, Vec<u8>) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("douyin")
+        .join("live_room_enter.json");
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("读取样本 {} 失败: {}", path.display(), e));
+    (
+        "/webcast/room/web/enter/".to_string(),
+        "application/json".to_string(),
+        content.replace("{BASE_URL}", base_url).into_bytes(),
+    )
+}
+
+/// 启动本地模拟服务，路由在得到服务地址后生成；未配置的路径返回 404
+fn start_mock_server(build_routes: impl FnOnce(&str) -> Routes) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("无法启动模拟服务");
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let routes = build_routes(&base_url);
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            handle_request(stream, &routes);
+        }
+    });
+
+    base_url
+}
+
+/// 处理一次请求（仅支持无请求体的 GET）
+fn handle_request(mut stream: TcpStream, routes: &Routes) {
+    let mut reader = match stream.try_clone() {
+        Ok(s) => BufReader::new(s),
+        Err(_) => return,
+    };
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    // 跳过请求头，空行（\r\n）表示请求头结束
+    let mut header = String::new();
+    while reader
+        .read_line(&mut header)
+        .map(|n| n > 2)
+        .unwrap_or(false)
+    {
+        header.clear();
+    }
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let path = target.split('?').next().unwrap_or(target);
+    let (status, content_type, body) = match routes.iter().find(|(p, _, _)| p == path) {
+        Some((_, content_type, body)) => ("200 OK", content_type.as_str(), body.as_slice()),
+        None => ("404 Not Found", "text/plain", b"not found".as_slice()),
+    };
+
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(body);
+}
+
+/// 创建指向模拟服务的解析器（不使用代理、登录 Cookie 和限速）
+fn resolver_for(base_url: &str) -> LiveResolver {
+    let client = reqwest::Client::builder().no_proxy().build().unwrap();
+    LiveResolver::with_client(client)
+        .with_cookie(None)
+        .with_endpoints(LiveEndpoints {
+            web: base_url.to_string(),
+        })
+        .with_rate_limiter(Arc::new(RateLimiter::disabled()))
+}
+
+/// 用 FFmpeg 生成一段 6 秒的 HLS 点播流，作为直播 CDN 的替身
+fn generate_hls(ffmpeg: &FfmpegWrapper, dir: &Path) -> Vec<(String, String, Vec<u8>)> {
+    let status = std::process::Command::new(ffmpeg.ffmpeg_path())
+        .args([
+            "-hide_banner",
+            "-loglevel",
+            "error",
+            "-f",
+            "lavfi",
+            "-i",
+            "testsrc=size=160x120:rate=10",
+            "-f",
+            "lavfi",
+            "-i",
+            "sine=frequency=440",
+            "-t",
+            "6",
+            "-c:v",
+            "mpeg2video",
+            "-g",
+            "10",
+            "-c:a",
+            "mp2",
+            "-f",
+            "hls",
+            "-hls_time",
+            "1",
+            "-hls_playlist_type",
+            "vod",
+        ])
+        .arg(dir.join("index.m3u8"))
+        .status()
+        .expect("无法运行 FFmpeg");
+    assert!(status.success(), "生成测试 HLS 流失败");
+
+    std::fs::read_dir(dir)
+        .unwrap()
+        .flatten()
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let content_type = if name.ends_with(".m3u8") {
+                "application/vnd.apple.mpegurl"
+            } else {
+                "video/mp2t"
+            };
+            (
+                format!("/stage/stream-fhd/{}", name),
+                content_type.to_string(),
+                std::fs::read(entry.path()).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_resolve_live_room() {
+    let base_url = start_mock_server(|base_url| vec![room_route(base_url)]);
+    let room = resolver_for(&base_url).resolve(LIVE_LINK).await.unwrap();
+
+    assert_eq!(room.web_rid, "123456789");
+    assert_eq!(room.room_id, "7302999999999999999");
+    assert_eq!(room.title, "露营夜话直播");
+    assert_eq!(room.anchor, "露营小王");
+    assert!(room.is_live);
+    assert_eq!(room.streams.len(), 5);
+    // 默认优先 FLV 的最高清晰度
+    assert_eq!(
+        room.best_stream(None).unwrap().url,
+        format!("{}/stage/stream-fhd.flv", base_url)
+    );
+    assert_eq!(
+        room.best_stream(Some(StreamFormat::Hls)).unwrap().url,
+        format!("{}/stage/stream-fhd/index.m3u8", base_url)
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_invalid_link() {
+    let base_url = start_mock_server(|_| Vec::new());
+    let resolver = resolver_for(&base_url);
+
+    assert!(resolver.resolve("随便一段文字").await.is_err());
+    // 直播间接口不可用
+    assert!(resolver.resolve(LIVE_LINK).await.is_err());
+    // 直接的拉流地址无需请求接口
+    let room = resolver
+        .resolve("http://127.0.0.1:1/live/index.m3u8")
+        .await
+        .unwrap();
+    assert!(room.is_live);
+    assert_eq!(room.streams[0].format, StreamFormat::Hls);
+}
+
+#[tokio::test]
+async fn test_record_segments_and_reconnect() {
+    let ffmpeg = match FfmpegWrapper::new() {
+        Ok(ffmpeg) => ffmpeg,
+        Err(e) => {
+            eprintln!("跳过直播录制测试: {}", e);
+            return;
+        }
+    };
+
+    let hls_dir = tempfile::tempdir().unwrap();
+    let hls_routes = generate_hls(&ffmpeg, hls_dir.path());
+    let base_url = start_mock_server(move |base_url| {
+        let mut routes = hls_routes;
+        routes.push(room_route(base_url));
+        routes
+    });
+
+    let output_dir = tempfile::tempdir().unwrap();
+    let mut options = RecordOptions::new(output_dir.path().to_path_buf(), "live");
+    options.segment_secs = 2;
+    options.prefer_format = Some(StreamFormat::Hls);
+    options.reconnect_delay_secs = 0;
+    options.offline_timeout_secs = 60;
+
+    let recorder = LiveRecorder::new(ffmpeg, resolver_for(&base_url), options);
+    let stop = recorder.stop_handle();
+    let mut events = Vec::new();
+    // 点播流播放完即断流，第二场结束后停止
+    let summary = recorder
+        .record(LIVE_LINK, |event| {
+            if let RecorderEvent::Interrupted { session: 2, .. } = event {
+                stop.store(true, Ordering::SeqCst);
+            }
+            events.push(event);
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(summary.reason, StopReason::Stopped);
+    assert_eq!(summary.sessions, 2);
+    assert!(summary.segments.len() >= 4);
+    assert!(summary.segments.iter().all(|path| path.exists()));
+    for session in ["live_01_", "live_02_"] {
+        assert!(summary
+            .segments
+            .iter()
+            .any(|path| path.to_string_lossy().contains(session)));
+    }
+    let completed = events
+        .iter()
+        .filter(|e| matches!(e, RecorderEvent::SegmentCompleted { .. }))
+        .count();
+    assert_eq!(completed, summary.segments.len());
+}
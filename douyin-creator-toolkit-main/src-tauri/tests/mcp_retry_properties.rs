@@ -202,6 +202,7 @@ proptest! {
                 request_interval_ms: 100,
                 max_retries,
                 timeout_secs: 5,
+                ..McpConfig::default()
             };
 
             // 验证配置中的 max_retries
@@ -42,6 +42,7 @@ interface TaskState {
   pauseTask: (taskId: string) => Promise<void>;
   resumeTask: (taskId: string) => Promise<void>;
   cancelTask: (taskId: string) => Promise<void>;
+  retryTask: (taskId: string) => Promise<void>;
   clearHistory: () => Promise<void>;
   clearPending: () => Promise<void>;
   setupListeners: () => Promise<() => void>;
@@ -129,6 +130,16 @@ export const useTaskStore = create<TaskState>((set, get) => ({
     }
   },
 
+  retryTask: async (taskId: string) => {
+    try {
+      await invoke<string>('retry_task', { taskId });
+      await get().fetchTasks();
+      await get().fetchStats();
+    } catch (error) {
+      set({ error: String(error) });
+    }
+  },
+
   clearHistory: async () => {
     try {
       await invoke('clear_task_history');